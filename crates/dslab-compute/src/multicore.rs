@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use dslab_core::cast;
 use dslab_core::component::Id;
@@ -58,19 +58,57 @@ pub enum FailReason {
     },
 }
 
+/// NUMA node (socket) of a compute resource with its own cores and local memory.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NumaNode {
+    pub cores: u32,
+    pub memory: u64,
+}
+
+impl NumaNode {
+    pub fn new(cores: u32, memory: u64) -> Self {
+        Self { cores, memory }
+    }
+}
+
+/// NUMA topology of a compute resource.
+///
+/// Computations spanning several nodes access remote memory and are slowed down by `remote_access_penalty` times.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NumaTopology {
+    pub nodes: Vec<NumaNode>,
+    pub remote_access_penalty: f64,
+}
+
+impl NumaTopology {
+    pub fn new(nodes: Vec<NumaNode>, remote_access_penalty: f64) -> Self {
+        Self {
+            nodes,
+            remote_access_penalty,
+        }
+    }
+}
+
+// Part of a computation or allocation placed on a single NUMA node: (node index, cores, memory).
+type NumaShare = (usize, u32, u64);
+
 #[derive(Debug)]
 struct RunningComputation {
     cores: u32,
     memory: u64,
     requester: Id,
+    numa_placement: Vec<NumaShare>,
+    core_ids: Vec<u32>,
 }
 
 impl RunningComputation {
-    fn new(cores: u32, memory: u64, requester: Id) -> Self {
+    fn new(cores: u32, memory: u64, requester: Id, numa_placement: Vec<NumaShare>, core_ids: Vec<u32>) -> Self {
         RunningComputation {
             cores,
             memory,
             requester,
+            numa_placement,
+            core_ids,
         }
     }
 }
@@ -148,6 +186,13 @@ pub struct Compute {
     memory_available: u64,
     computations: HashMap<u64, RunningComputation>,
     allocations: HashMap<Id, Allocation>,
+    numa_nodes: Vec<NumaNode>,
+    numa_available: Vec<NumaNode>,
+    remote_access_penalty: f64,
+    core_speeds: Vec<f64>,
+    free_cores: Vec<bool>,
+    allocated_cores: HashMap<Id, Vec<u32>>,
+    allocated_numa: HashMap<Id, Vec<NumaShare>>,
     ctx: SimulationContext,
 }
 
@@ -161,14 +206,61 @@ impl Compute {
             memory_available: memory,
             computations: HashMap::new(),
             allocations: HashMap::new(),
+            numa_nodes: Vec::new(),
+            numa_available: Vec::new(),
+            remote_access_penalty: 1.,
+            core_speeds: Vec::new(),
+            free_cores: Vec::new(),
+            allocated_cores: HashMap::new(),
+            allocated_numa: HashMap::new(),
             ctx,
         }
     }
 
+    /// Creates compute resource with the specified NUMA topology.
+    ///
+    /// Computations are placed on a single NUMA node if possible, otherwise they are spread over several nodes
+    /// and slowed down by the remote access penalty.
+    pub fn with_numa(speed: f64, topology: NumaTopology, ctx: SimulationContext) -> Self {
+        assert!(
+            topology.remote_access_penalty >= 1.,
+            "Remote access penalty should be at least 1"
+        );
+        let cores = topology.nodes.iter().map(|n| n.cores).sum();
+        let memory = topology.nodes.iter().map(|n| n.memory).sum();
+        let mut compute = Self::new(speed, cores, memory, ctx);
+        compute.numa_available = topology.nodes.clone();
+        compute.numa_nodes = topology.nodes;
+        compute.remote_access_penalty = topology.remote_access_penalty;
+        compute
+    }
+
+    /// Creates compute resource with heterogeneous cores, e.g. big.LITTLE CPU.
+    ///
+    /// Computations are placed on the fastest free cores and run at the speed of the slowest of them.
+    /// Allocations take the slowest free cores. The speed of the resource is the speed of its fastest core.
+    pub fn with_core_speeds(core_speeds: Vec<f64>, memory: u64, ctx: SimulationContext) -> Self {
+        assert!(
+            !core_speeds.is_empty(),
+            "Compute resource should have at least one core"
+        );
+        assert!(core_speeds.iter().all(|&s| s > 0.), "Core speeds should be positive");
+        let speed = core_speeds.iter().copied().fold(0., f64::max);
+        let mut compute = Self::new(speed, core_speeds.len() as u32, memory, ctx);
+        compute.free_cores = vec![true; core_speeds.len()];
+        compute.core_speeds = core_speeds;
+        compute
+    }
+
     pub fn speed(&self) -> f64 {
         self.speed
     }
 
+    /// Returns the speeds of individual cores, or empty vector if all cores have the same speed.
+    pub fn core_speeds(&self) -> &Vec<f64> {
+        &self.core_speeds
+    }
+
     pub fn cores_total(&self) -> u32 {
         self.cores_total
     }
@@ -185,6 +277,113 @@ impl Compute {
         self.memory_available
     }
 
+    pub fn numa_nodes(&self) -> &Vec<NumaNode> {
+        &self.numa_nodes
+    }
+
+    /// Returns the currently available cores and memory on each NUMA node.
+    pub fn numa_available(&self) -> &Vec<NumaNode> {
+        &self.numa_available
+    }
+
+    // Places computation on a single NUMA node with enough free cores and memory, preferring the node
+    // with most free cores. Returns None if no such node exists.
+    fn place_local(&self, min_cores: u32, max_cores: u32, memory: u64) -> Option<Vec<NumaShare>> {
+        self.numa_available
+            .iter()
+            .enumerate()
+            .filter(|(_, node)| node.cores >= min_cores && node.memory >= memory)
+            .max_by(|(i1, n1), (i2, n2)| n1.cores.cmp(&n2.cores).then(i2.cmp(i1)))
+            .map(|(i, node)| vec![(i, node.cores.min(max_cores), memory)])
+    }
+
+    // Spreads cores and memory over NUMA nodes, starting from the nodes with most free resources.
+    fn place_spread(&self, cores: u32, memory: u64) -> Vec<NumaShare> {
+        let mut order = (0..self.numa_available.len()).collect::<Vec<_>>();
+        order.sort_by(|&a, &b| self.numa_available[b].cores.cmp(&self.numa_available[a].cores));
+        let mut placement = Vec::new();
+        let mut cores_left = cores;
+        let mut memory_left = memory;
+        for i in order {
+            let node = &self.numa_available[i];
+            let node_cores = node.cores.min(cores_left);
+            let node_memory = node.memory.min(memory_left);
+            if node_cores > 0 || node_memory > 0 {
+                placement.push((i, node_cores, node_memory));
+                cores_left -= node_cores;
+                memory_left -= node_memory;
+            }
+        }
+        placement
+    }
+
+    fn take_numa(&mut self, placement: &[NumaShare]) {
+        for &(i, cores, memory) in placement {
+            self.numa_available[i].cores -= cores;
+            self.numa_available[i].memory -= memory;
+        }
+    }
+
+    fn release_numa(&mut self, placement: &[NumaShare]) {
+        for &(i, cores, memory) in placement {
+            self.numa_available[i].cores += cores;
+            self.numa_available[i].memory += memory;
+        }
+    }
+
+    // Returns deallocated resources to the NUMA nodes they were allocated on, starting from the latest shares.
+    fn release_allocated_numa(
+        allocated_numa: &mut HashMap<Id, Vec<NumaShare>>,
+        available: &mut [NumaNode],
+        requester: Id,
+        mut cores: u32,
+        mut memory: u64,
+    ) {
+        let Some(shares) = allocated_numa.get_mut(&requester) else {
+            return;
+        };
+        for (i, share_cores, share_memory) in shares.iter_mut().rev() {
+            let node_cores = (*share_cores).min(cores);
+            let node_memory = (*share_memory).min(memory);
+            *share_cores -= node_cores;
+            *share_memory -= node_memory;
+            available[*i].cores += node_cores;
+            available[*i].memory += node_memory;
+            cores -= node_cores;
+            memory -= node_memory;
+        }
+        shares.retain(|&(_, cores, memory)| cores > 0 || memory > 0);
+        if shares.is_empty() {
+            allocated_numa.remove(&requester);
+        }
+    }
+
+    // Takes the given number of free cores, starting from the fastest or the slowest ones.
+    fn take_cores(&mut self, count: u32, fastest: bool) -> Vec<u32> {
+        let mut cores = (0..self.core_speeds.len() as u32)
+            .filter(|&core| self.free_cores[core as usize])
+            .collect::<Vec<_>>();
+        cores.sort_by(|&a, &b| {
+            let order = self.core_speeds[a as usize].total_cmp(&self.core_speeds[b as usize]);
+            if fastest {
+                order.reverse().then(a.cmp(&b))
+            } else {
+                order.then(a.cmp(&b))
+            }
+        });
+        cores.truncate(count as usize);
+        for &core in cores.iter() {
+            self.free_cores[core as usize] = false;
+        }
+        cores
+    }
+
+    fn release_cores(&mut self, cores: &[u32]) {
+        for &core in cores {
+            self.free_cores[core as usize] = true;
+        }
+    }
+
     pub fn run(
         &mut self,
         flops: f64,
@@ -247,17 +446,41 @@ impl EventHandler for Compute {
                         requester,
                     );
                 } else {
-                    let cores = self.cores_available.min(max_cores);
+                    let mut cores = self.cores_available.min(max_cores);
+                    let mut slowdown = 1.;
+                    let mut numa_placement = Vec::new();
+                    if !self.numa_nodes.is_empty() {
+                        numa_placement = match self.place_local(min_cores, max_cores, memory) {
+                            Some(placement) => placement,
+                            None => {
+                                slowdown = self.remote_access_penalty;
+                                self.place_spread(cores, memory)
+                            }
+                        };
+                        cores = numa_placement.iter().map(|(_, cores, _)| cores).sum();
+                        self.take_numa(&numa_placement);
+                    }
+                    let mut speed = self.speed;
+                    let mut core_ids = Vec::new();
+                    if !self.core_speeds.is_empty() {
+                        core_ids = self.take_cores(cores, true);
+                        speed = core_ids
+                            .iter()
+                            .map(|&core| self.core_speeds[core as usize])
+                            .fold(f64::INFINITY, f64::min);
+                    }
                     self.memory_available -= memory;
                     self.cores_available -= cores;
                     self.ctx.emit_now(CompStarted { id: event.id, cores }, requester);
 
                     let speedup = cores_dependency.speedup(cores);
 
-                    let compute_time = flops / self.speed / speedup;
+                    let compute_time = flops / speed / speedup * slowdown;
                     self.ctx.emit_self(CompFinished { id: event.id }, compute_time);
-                    self.computations
-                        .insert(event.id, RunningComputation::new(cores, memory, requester));
+                    self.computations.insert(
+                        event.id,
+                        RunningComputation::new(cores, memory, requester, numa_placement, core_ids),
+                    );
                 }
             }
             CompFinished { id } => {
@@ -267,6 +490,8 @@ impl EventHandler for Compute {
                     .expect("Unexpected CompFinished event in Compute");
                 self.memory_available += running_computation.memory;
                 self.cores_available += running_computation.cores;
+                self.release_numa(&running_computation.numa_placement);
+                self.release_cores(&running_computation.core_ids);
                 self.ctx.emit(CompFinished { id }, running_computation.requester, 0.);
             }
             AllocationRequest { allocation, requester } => {
@@ -292,6 +517,15 @@ impl EventHandler for Compute {
                     current_allocation.memory += allocation.memory;
                     self.cores_available -= allocation.cores;
                     self.memory_available -= allocation.memory;
+                    if !self.numa_nodes.is_empty() {
+                        let numa_placement = self.place_spread(allocation.cores, allocation.memory);
+                        self.take_numa(&numa_placement);
+                        self.allocated_numa.entry(requester).or_default().extend(numa_placement);
+                    }
+                    if !self.core_speeds.is_empty() {
+                        let core_ids = self.take_cores(allocation.cores, false);
+                        self.allocated_cores.entry(requester).or_default().extend(core_ids);
+                    }
                     self.ctx.emit(AllocationSuccess { id: event.id }, requester, 0.);
                 }
             }
@@ -305,6 +539,22 @@ impl EventHandler for Compute {
                     current_allocation.memory -= allocation.memory;
                     self.cores_available += allocation.cores;
                     self.memory_available += allocation.memory;
+                    Self::release_allocated_numa(
+                        &mut self.allocated_numa,
+                        &mut self.numa_available,
+                        requester,
+                        allocation.cores,
+                        allocation.memory,
+                    );
+                    if let Some(core_ids) = self.allocated_cores.get_mut(&requester) {
+                        let released = core_ids.split_off(core_ids.len() - allocation.cores as usize);
+                        for core in released {
+                            self.free_cores[core as usize] = true;
+                        }
+                        if core_ids.is_empty() {
+                            self.allocated_cores.remove(&requester);
+                        }
+                    }
                     self.ctx.emit(DeallocationSuccess { id: event.id }, requester, 0.);
                 } else {
                     self.ctx.emit_now(
@@ -0,0 +1,88 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use dslab_compute::multicore::*;
+use dslab_core::cast;
+use dslab_core::context::SimulationContext;
+use dslab_core::event::Event;
+use dslab_core::handler::EventHandler;
+use dslab_core::simulation::Simulation;
+
+struct Client {
+    finish_times: HashMap<u64, f64>,
+    ctx: SimulationContext,
+}
+
+impl EventHandler for Client {
+    fn on(&mut self, event: Event) {
+        cast!(match event.data {
+            CompStarted { .. } => {}
+            CompFinished { id } => {
+                self.finish_times.insert(id, self.ctx.time());
+            }
+        })
+    }
+}
+
+fn make_compute(sim: &mut Simulation) -> (Rc<RefCell<Compute>>, Rc<RefCell<Client>>) {
+    let compute = Rc::new(RefCell::new(Compute::with_core_speeds(
+        vec![10., 20., 10., 20.],
+        100,
+        sim.create_context("compute"),
+    )));
+    sim.add_handler("compute", compute.clone());
+    let client = Rc::new(RefCell::new(Client {
+        finish_times: HashMap::new(),
+        ctx: sim.create_context("client"),
+    }));
+    sim.add_handler("client", client.clone());
+    (compute, client)
+}
+
+#[test]
+fn test_fastest_cores_are_used() {
+    let mut sim = Simulation::new(123);
+    let (compute, client) = make_compute(&mut sim);
+    let client_id = client.borrow().ctx.id();
+    assert_eq!(compute.borrow().speed(), 20.);
+
+    // both fast cores are free
+    let fast = compute
+        .borrow_mut()
+        .run(100., 10, 2, 2, CoresDependency::Linear, client_id);
+    sim.step_until_no_events();
+    assert_eq!(client.borrow().finish_times[&fast], 2.5);
+
+    // one fast core is busy, so the computation runs at the speed of a slow core
+    let busy = compute
+        .borrow_mut()
+        .run(1000., 10, 1, 1, CoresDependency::Linear, client_id);
+    sim.step();
+    let mixed = compute
+        .borrow_mut()
+        .run(100., 10, 2, 2, CoresDependency::Linear, client_id);
+    sim.step_until_no_events();
+    assert_eq!(client.borrow().finish_times[&busy], 2.5 + 50.);
+    assert_eq!(client.borrow().finish_times[&mixed], 2.5 + 5.);
+    assert_eq!(compute.borrow().cores_available(), 4);
+}
+
+#[test]
+fn test_allocation_takes_slowest_cores() {
+    let mut sim = Simulation::new(123);
+    let (compute, client) = make_compute(&mut sim);
+    let client_id = client.borrow().ctx.id();
+
+    compute.borrow_mut().allocate(2, 10, client_id);
+    sim.step();
+    let comp = compute
+        .borrow_mut()
+        .run(100., 10, 1, 2, CoresDependency::Linear, client_id);
+    sim.step_until_no_events();
+    assert_eq!(client.borrow().finish_times[&comp], 2.5);
+
+    compute.borrow_mut().deallocate(2, 10, client_id);
+    sim.step_until_no_events();
+    assert_eq!(compute.borrow().cores_available(), 4);
+}
@@ -0,0 +1,125 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use dslab_compute::multicore::*;
+use dslab_core::cast;
+use dslab_core::context::SimulationContext;
+use dslab_core::event::Event;
+use dslab_core::handler::EventHandler;
+use dslab_core::simulation::Simulation;
+
+struct Client {
+    finish_times: HashMap<u64, f64>,
+    ctx: SimulationContext,
+}
+
+impl EventHandler for Client {
+    fn on(&mut self, event: Event) {
+        cast!(match event.data {
+            CompStarted { .. } => {}
+            AllocationSuccess { .. } => {}
+            DeallocationSuccess { .. } => {}
+            CompFinished { id } => {
+                self.finish_times.insert(id, self.ctx.time());
+            }
+        })
+    }
+}
+
+fn make_numa_compute(sim: &mut Simulation) -> (Rc<RefCell<Compute>>, Rc<RefCell<Client>>) {
+    let compute = Rc::new(RefCell::new(Compute::with_numa(
+        10.,
+        NumaTopology::new(vec![NumaNode::new(2, 100), NumaNode::new(2, 100)], 1.5),
+        sim.create_context("compute"),
+    )));
+    sim.add_handler("compute", compute.clone());
+    let client = Rc::new(RefCell::new(Client {
+        finish_times: HashMap::new(),
+        ctx: sim.create_context("client"),
+    }));
+    sim.add_handler("client", client.clone());
+    (compute, client)
+}
+
+#[test]
+fn test_numa_local_faster_than_cross_socket() {
+    // task fits into a single NUMA node
+    let mut sim = Simulation::new(123);
+    let (compute, client) = make_numa_compute(&mut sim);
+    let client_id = client.borrow().ctx.id();
+    let local = compute
+        .borrow_mut()
+        .run(100., 50, 2, 2, CoresDependency::Linear, client_id);
+    sim.step_until_no_events();
+    let local_time = client.borrow().finish_times[&local];
+    assert_eq!(local_time, 5.);
+
+    // same task is forced to span both nodes because each node has one core occupied
+    let mut sim = Simulation::new(123);
+    let (compute, client) = make_numa_compute(&mut sim);
+    let client_id = client.borrow().ctx.id();
+    compute
+        .borrow_mut()
+        .run(1000., 10, 1, 1, CoresDependency::Linear, client_id);
+    compute
+        .borrow_mut()
+        .run(1000., 10, 1, 1, CoresDependency::Linear, client_id);
+    sim.step();
+    sim.step();
+    assert_eq!(compute.borrow().numa_available()[0].cores, 1);
+    assert_eq!(compute.borrow().numa_available()[1].cores, 1);
+    let remote = compute
+        .borrow_mut()
+        .run(100., 50, 2, 2, CoresDependency::Linear, client_id);
+    sim.step_until_no_events();
+    let remote_time = client.borrow().finish_times[&remote];
+    assert_eq!(remote_time, 7.5);
+    assert!(local_time < remote_time);
+
+    for node in compute.borrow().numa_available() {
+        assert_eq!(node.cores, 2);
+        assert_eq!(node.memory, 100);
+    }
+}
+
+#[test]
+fn test_numa_deallocation_returns_own_nodes() {
+    let mut sim = Simulation::new(123);
+    let (compute, client) = make_numa_compute(&mut sim);
+    let client_id = client.borrow().ctx.id();
+    // the computation takes a core of node 0, the allocation takes a core of node 1
+    let first = compute
+        .borrow_mut()
+        .run(100., 10, 1, 1, CoresDependency::Linear, client_id);
+    sim.step_for_duration(1.);
+    compute.borrow_mut().allocate(1, 10, client_id);
+    sim.step_for_duration(1.);
+    assert_eq!(compute.borrow().numa_available()[0].cores, 1);
+    assert_eq!(compute.borrow().numa_available()[1].cores, 1);
+
+    // the deallocated core goes back to node 1 while the computation still holds its core on node 0
+    compute.borrow_mut().deallocate(1, 10, client_id);
+    sim.step_for_duration(1.);
+    assert_eq!(compute.borrow().numa_available()[0].cores, 1);
+    assert_eq!(compute.borrow().numa_available()[1].cores, 2);
+    assert_eq!(compute.borrow().numa_available()[1].memory, 100);
+
+    sim.step_until_no_events();
+    assert_eq!(client.borrow().finish_times[&first], 10.);
+    for node in compute.borrow().numa_available() {
+        assert_eq!(node.cores, 2);
+        assert_eq!(node.memory, 100);
+    }
+
+    // the whole compute is available again
+    let second = compute
+        .borrow_mut()
+        .run(400., 200, 4, 4, CoresDependency::Linear, client_id);
+    sim.step_until_no_events();
+    assert_eq!(client.borrow().finish_times[&second], 10. + 15.);
+    for node in compute.borrow().numa_available() {
+        assert_eq!(node.cores, 2);
+        assert_eq!(node.memory, 100);
+    }
+}
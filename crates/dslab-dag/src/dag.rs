@@ -1,6 +1,6 @@
 //! DAG model of computation.
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeSet, VecDeque};
 use std::path::Path;
 
 use dslab_compute::multicore::CoresDependency;
@@ -188,4 +188,80 @@ impl DAG {
     pub fn stats(&self) -> DagStats {
         DagStats::new(self)
     }
+
+    /// Returns ids of tasks in topological order, i.e. each task goes after all tasks producing its inputs.
+    ///
+    /// Uses Kahn's algorithm, among the tasks whose dependencies are satisfied the ones with smaller ids go first.
+    /// Returns an error if the task dependencies contain a cycle.
+    pub fn topological_order(&self) -> Result<Vec<usize>, String> {
+        let mut remaining_inputs: Vec<usize> = self
+            .tasks
+            .iter()
+            .map(|task| {
+                task.inputs
+                    .iter()
+                    .filter(|&&id| self.data_items[id].producer.is_some())
+                    .count()
+            })
+            .collect();
+        let mut queue: VecDeque<usize> = (0..self.tasks.len()).filter(|&id| remaining_inputs[id] == 0).collect();
+        let mut order = Vec::with_capacity(self.tasks.len());
+        while let Some(task_id) = queue.pop_front() {
+            order.push(task_id);
+            for &data_item_id in self.tasks[task_id].outputs.iter() {
+                for &consumer in self.data_items[data_item_id].consumers.iter() {
+                    remaining_inputs[consumer] -= 1;
+                    if remaining_inputs[consumer] == 0 {
+                        queue.push_back(consumer);
+                    }
+                }
+            }
+        }
+        if order.len() < self.tasks.len() {
+            let task_id = (0..self.tasks.len()).find(|&id| remaining_inputs[id] > 0).unwrap();
+            return Err(format!(
+                "task dependencies contain a cycle, e.g. task {} is never ready",
+                self.tasks[task_id].name
+            ));
+        }
+        Ok(order)
+    }
+
+    /// Returns the length of the critical path, i.e. the longest chain of dependent tasks, and its tasks.
+    ///
+    /// The length of a task is its flops divided by the given reference speed, data transfers are not counted.
+    /// Among several longest paths the first found in the [topological order](DAG::topological_order) is returned.
+    ///
+    /// Panics if the task dependencies contain a cycle.
+    pub fn critical_path(&self, speed: f64) -> (f64, Vec<usize>) {
+        let order = self.topological_order().unwrap();
+        // length of the longest path ending at each task and the previous task on this path
+        let mut length = vec![0.; self.tasks.len()];
+        let mut prev = vec![None; self.tasks.len()];
+        for &task_id in order.iter() {
+            let task = &self.tasks[task_id];
+            for &data_item_id in task.inputs.iter() {
+                if let Some(producer) = self.data_items[data_item_id].producer {
+                    if prev[task_id].is_none() || length[producer] > length[prev[task_id].unwrap()] {
+                        prev[task_id] = Some(producer);
+                    }
+                }
+            }
+            length[task_id] = prev[task_id].map_or(0., |p| length[p]) + task.flops / speed;
+        }
+        let mut last = None;
+        for &task_id in order.iter() {
+            if last.is_none() || length[task_id] > length[last.unwrap()] {
+                last = Some(task_id);
+            }
+        }
+        let mut path = Vec::new();
+        let mut current = last;
+        while let Some(task_id) = current {
+            path.push(task_id);
+            current = prev[task_id];
+        }
+        path.reverse();
+        (last.map_or(0., |t| length[t]), path)
+    }
 }
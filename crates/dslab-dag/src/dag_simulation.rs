@@ -3,12 +3,12 @@
 use std::cell::RefCell;
 use std::rc::Rc;
 
-use dslab_compute::multicore::Compute;
+use dslab_compute::multicore::{Compute, NumaTopology};
 use dslab_core::simulation::Simulation;
 
 use crate::dag::DAG;
 use crate::network::NetworkConfig;
-use crate::resource::{Resource, ResourceConfig};
+use crate::resource::{PowerModel, Resource, ResourceConfig};
 use crate::runner::{Config, DAGRunner, Start};
 use crate::scheduler::Scheduler;
 
@@ -46,9 +46,61 @@ impl DagSimulation {
             speed,
             cores,
             memory,
+            numa: None,
+            core_speeds: None,
+            gpus: 0,
+            gpu_memory: 0,
+            power: None,
         });
     }
 
+    /// Adds a resource with provided parameters and power model.
+    pub fn add_resource_with_power(&mut self, name: &str, speed: f64, cores: u32, memory: u64, power: PowerModel) {
+        self.resource_configs.push(ResourceConfig {
+            name: name.to_string(),
+            speed,
+            cores,
+            memory,
+            numa: None,
+            core_speeds: None,
+            gpus: 0,
+            gpu_memory: 0,
+            power: Some(power),
+        });
+    }
+
+    /// Adds a resource with provided parameters and NUMA topology.
+    pub fn add_numa_resource(&mut self, name: &str, speed: f64, numa: NumaTopology) {
+        self.resource_configs.push(ResourceConfig {
+            name: name.to_string(),
+            speed,
+            cores: numa.nodes.iter().map(|n| n.cores).sum(),
+            memory: numa.nodes.iter().map(|n| n.memory).sum(),
+            numa: Some(numa),
+            core_speeds: None,
+            gpus: 0,
+            gpu_memory: 0,
+            power: None,
+        });
+    }
+
+    /// Adds a resource with heterogeneous cores of provided speeds.
+    pub fn add_resource_with_core_speeds(&mut self, name: &str, core_speeds: Vec<f64>, memory: u64) {
+        let mut config = ResourceConfig {
+            name: name.to_string(),
+            speed: 0.,
+            cores: core_speeds.len() as u32,
+            memory,
+            numa: None,
+            core_speeds: Some(core_speeds),
+            gpus: 0,
+            gpu_memory: 0,
+            power: None,
+        };
+        config.speed = config.effective_speed();
+        self.resource_configs.push(config);
+    }
+
     /// Initializes DAG simulation.
     pub fn init(&mut self, dag: DAG) -> Rc<RefCell<DAGRunner>> {
         let net_ctx = self.sim.create_context("net");
@@ -58,20 +110,28 @@ impl DagSimulation {
             .resource_configs
             .iter()
             .map(|r| {
-                let compute = Rc::new(RefCell::new(Compute::new(
-                    r.speed,
-                    r.cores,
-                    r.memory,
-                    self.sim.create_context(&r.name),
-                )));
+                let ctx = self.sim.create_context(&r.name);
+                let compute = Rc::new(RefCell::new(match (&r.numa, &r.core_speeds) {
+                    (Some(numa), _) => Compute::with_numa(r.speed, numa.clone(), ctx),
+                    (None, Some(core_speeds)) => Compute::with_core_speeds(core_speeds.clone(), r.memory, ctx),
+                    (None, None) => Compute::new(r.speed, r.cores, r.memory, ctx),
+                }));
                 let id = self.sim.add_handler(&r.name, compute.clone());
                 Resource {
                     id,
                     name: r.name.clone(),
                     compute,
-                    speed: r.speed,
+                    speed: r.effective_speed(),
                     cores_available: r.cores,
                     memory_available: r.memory,
+                    cores_total: r.cores,
+                    memory_total: r.memory,
+                    gpus_available: r.gpus,
+                    gpu_memory_available: r.gpu_memory,
+                    gpus_total: r.gpus,
+                    gpu_memory_total: r.gpu_memory,
+                    numa: r.numa.clone(),
+                    power: r.power.clone(),
                 }
             })
             .collect::<Vec<_>>();
@@ -125,7 +125,7 @@ impl Experiment {
         let systems = get_all_files(&systems_paths).into_iter().map(|path| {
             let file_name = path.file_name().unwrap().to_str().unwrap().to_string();
             let file_stem = path.file_stem().unwrap().to_str().unwrap().to_string();
-            let resources = read_resource_configs(&path);
+            let resources = read_resource_configs(&path).unwrap_or_else(|e| panic!("{}", e));
             assert!(
                 !resources.is_empty(),
                 "Can't have empty list of resources: {}",
@@ -35,6 +35,11 @@ struct Task {
     min_cores: u32,
     #[serde(default = "one")]
     max_cores: u32,
+    #[serde(default)]
+    gpus: u32,
+    #[serde(default = "zero")]
+    // expected unit: MB
+    gpu_memory: u64,
     cores_dependency: Option<Value>,
     #[serde(default = "Vec::new")]
     inputs: Vec<String>,
@@ -78,6 +83,8 @@ impl DAG {
                     _ => CoresDependency::Linear,
                 },
             );
+            dag.get_task_mut(task_id).gpus = task.gpus;
+            dag.get_task_mut(task_id).gpu_memory = task.gpu_memory;
             for output in task.outputs.iter() {
                 data_items.insert(
                     output.name.clone(),
@@ -10,9 +10,13 @@ use dslab_core::component::Id;
 
 use dslab_compute::multicore::*;
 
+use crate::task::Task;
+
 /// Represents a computing resource that can execute DAG tasks.
 ///
 /// Described by the number of CPU cores, their speed in flop/s and amount of memory.
+/// Optionally, the resource can be split into several NUMA nodes (see [`NumaTopology`])
+/// and have a power model (see [`PowerModel`]) used for energy accounting.
 ///
 /// Supports execution of parallel tasks. The modeling of task execution is implemented by means of the
 /// [multicore](https://github.com/osukhoroslov/dslab/tree/main/crates/dslab-compute/src/multicore.rs)
@@ -24,20 +28,114 @@ pub struct Resource {
     pub compute: Rc<RefCell<Compute>>,
     /// CPU speed in Gflop/s.
     pub speed: f64,
+    /// Number of currently free CPU cores.
     pub cores_available: u32,
-    /// Memory size in MB.
+    /// Amount of currently free memory in MB.
     pub memory_available: u64,
+    /// Total number of CPU cores.
+    pub cores_total: u32,
+    /// Total memory size in MB.
+    pub memory_total: u64,
+    /// Number of currently free GPUs.
+    pub gpus_available: u32,
+    /// Amount of currently free GPU memory in MB.
+    pub gpu_memory_available: u64,
+    /// Total number of GPUs.
+    pub gpus_total: u32,
+    /// Total GPU memory size in MB.
+    pub gpu_memory_total: u64,
+    /// NUMA topology, if NUMA effects are modeled.
+    pub numa: Option<NumaTopology>,
+    /// Power model, if energy consumption is modeled.
+    pub power: Option<PowerModel>,
+}
+
+impl Resource {
+    /// Checks if the resource has enough GPUs and GPU memory to run the task.
+    pub fn has_gpus_for(&self, task: &Task) -> bool {
+        self.gpus_total >= task.gpus && self.gpu_memory_total >= task.gpu_memory
+    }
+
+    /// Checks if the resource currently has enough free GPUs and GPU memory to run the task.
+    pub fn has_free_gpus_for(&self, task: &Task) -> bool {
+        self.gpus_available >= task.gpus && self.gpu_memory_available >= task.gpu_memory
+    }
+
+    /// Returns the fractions of CPU cores and memory which are currently in use.
+    pub fn utilization(&self) -> (f64, f64) {
+        let fraction = |available: u64, total: u64| {
+            if total == 0 {
+                0.
+            } else {
+                (total - available) as f64 / total as f64
+            }
+        };
+        (
+            fraction(self.cores_available as u64, self.cores_total as u64),
+            fraction(self.memory_available, self.memory_total),
+        )
+    }
+}
+
+/// Power consumption model of a computing resource.
+///
+/// The resource constantly consumes `idle_power`, and a fully loaded resource additionally consumes `active_power`
+/// scaled by the speed factor, i.e. the ratio of the resource speed to `reference_speed`.
+/// Partially loaded resource consumes the share of active power proportional to the number of busy cores.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PowerModel {
+    /// Power consumption of idle resource in W.
+    pub idle_power: f64,
+    /// Additional power consumption of fully loaded resource running at reference speed in W.
+    pub active_power: f64,
+    /// CPU speed in Gflop/s at which the active power is specified.
+    pub reference_speed: f64,
+}
+
+impl PowerModel {
+    pub fn new(idle_power: f64, active_power: f64, reference_speed: f64) -> Self {
+        Self {
+            idle_power,
+            active_power,
+            reference_speed,
+        }
+    }
+
+    /// Returns energy in J consumed by the resource over the given time,
+    /// where `busy_core_time` is the total time its cores were busy.
+    pub fn energy(&self, resource: &Resource, time: f64, busy_core_time: f64) -> f64 {
+        let speed_factor = resource.speed / self.reference_speed;
+        let load = busy_core_time / resource.cores_total as f64;
+        self.idle_power * time + self.active_power * speed_factor * load
+    }
 }
 
 /// Contains parameters of computing resource, can be used later to create a compute resource instance.
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ResourceConfig {
     pub name: String,
-    /// CPU speed in Gflop/s.
+    /// CPU speed in Gflop/s. Can be omitted if `core_speeds` are specified.
+    #[serde(default)]
     pub speed: f64,
     pub cores: u32,
     /// Memory size in MB.
     pub memory: u64,
+    /// NUMA topology, if NUMA effects are modeled. Cores and memory of NUMA nodes must sum up to the resource totals.
+    #[serde(default)]
+    pub numa: Option<NumaTopology>,
+    /// Speeds of individual cores in Gflop/s, if cores are heterogeneous. Overrides `speed`, which is then
+    /// set to the fastest core speed for the schedulers, as reported by [`Compute::with_core_speeds`].
+    #[serde(default)]
+    pub core_speeds: Option<Vec<f64>>,
+    /// Number of GPUs.
+    #[serde(default)]
+    pub gpus: u32,
+    /// Total GPU memory size in MB.
+    #[serde(default)]
+    pub gpu_memory: u64,
+    /// Power model, if energy consumption is modeled.
+    #[serde(default)]
+    pub power: Option<PowerModel>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -45,14 +143,102 @@ struct Resources {
     resources: Vec<ResourceConfig>,
 }
 
+/// Error occurred while loading resource configurations.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LoadError {
+    /// Configuration file can't be read.
+    FileNotFound { path: String, message: String },
+    /// Configuration can't be parsed as YAML, the line is reported if known.
+    Parse { line: Option<usize>, message: String },
+    /// Resource parameters are invalid (e.g. zero cores).
+    InvalidResource { name: String, message: String },
+}
+
+impl std::fmt::Display for LoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoadError::FileNotFound { path, message } => write!(f, "Can't read file {}: {}", path, message),
+            LoadError::Parse {
+                line: Some(line),
+                message,
+            } => write!(f, "Can't parse resources from YAML at line {}: {}", line, message),
+            LoadError::Parse { line: None, message } => write!(f, "Can't parse resources from YAML: {}", message),
+            LoadError::InvalidResource { name, message } => write!(f, "Invalid resource {}: {}", name, message),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+impl ResourceConfig {
+    /// Returns the resource speed, i.e. the fastest core speed if cores are heterogeneous.
+    ///
+    /// Matches the speed reported by [`Compute::with_core_speeds`], since a single-core task is placed on the fastest core.
+    pub fn effective_speed(&self) -> f64 {
+        match &self.core_speeds {
+            Some(core_speeds) => core_speeds.iter().copied().fold(0., f64::max),
+            None => self.speed,
+        }
+    }
+
+    fn validate(&self) -> Result<(), LoadError> {
+        let error = |message: &str| {
+            Err(LoadError::InvalidResource {
+                name: self.name.clone(),
+                message: message.to_string(),
+            })
+        };
+        if self.cores == 0 {
+            return error("number of cores should be positive");
+        }
+        if let Some(core_speeds) = &self.core_speeds {
+            if core_speeds.len() != self.cores as usize {
+                return error("number of core speeds should be equal to the number of cores");
+            }
+            if core_speeds.iter().any(|&speed| speed <= 0.) {
+                return error("core speeds should be positive");
+            }
+            if self.numa.is_some() {
+                return error("core speeds can't be combined with NUMA topology");
+            }
+        } else if self.speed <= 0. {
+            return error("speed should be positive");
+        }
+        if let Some(numa) = &self.numa {
+            if numa.nodes.iter().map(|n| n.cores).sum::<u32>() != self.cores {
+                return error("cores of NUMA nodes should sum up to the number of cores");
+            }
+            if numa.nodes.iter().map(|n| n.memory).sum::<u64>() != self.memory {
+                return error("memory of NUMA nodes should sum up to the memory size");
+            }
+        }
+        Ok(())
+    }
+}
+
 /// Reads resource configurations from YAML file.
 ///
 /// Configuration file example:
 /// <https://github.com/osukhoroslov/dslab/blob/main/examples/dag-demo/systems/cluster-het-4-32cores.yaml>
-pub fn read_resource_configs<P: AsRef<Path>>(file: P) -> Vec<ResourceConfig> {
-    let resources: Resources = serde_yaml::from_str(
-        &std::fs::read_to_string(&file).unwrap_or_else(|_| panic!("Can't read file {}", file.as_ref().display())),
-    )
-    .unwrap_or_else(|_| panic!("Can't parse YAML from file {}", file.as_ref().display()));
-    resources.resources
+pub fn read_resource_configs<P: AsRef<Path>>(file: P) -> Result<Vec<ResourceConfig>, LoadError> {
+    let yaml = std::fs::read_to_string(&file).map_err(|e| LoadError::FileNotFound {
+        path: file.as_ref().display().to_string(),
+        message: e.to_string(),
+    })?;
+    parse_resource_configs(&yaml)
+}
+
+/// Parses resource configurations from YAML string in the same format as [`read_resource_configs`].
+///
+/// Useful when resources are generated programmatically or defined inline in tests.
+/// Returns an error if YAML is malformed or some resource has zero speed or cores.
+pub fn parse_resource_configs(yaml: &str) -> Result<Vec<ResourceConfig>, LoadError> {
+    let resources: Resources = serde_yaml::from_str(yaml).map_err(|e| LoadError::Parse {
+        line: e.location().map(|location| location.line()),
+        message: e.to_string(),
+    })?;
+    for resource in resources.resources.iter() {
+        resource.validate()?;
+    }
+    Ok(resources.resources)
 }
@@ -23,6 +23,11 @@ pub struct RunStats {
     pub memory_utilization_used: f64,
     pub cpu_utilization_active: f64,
     pub memory_utilization_active: f64,
+    /// Energy consumed by each resource in J, empty if no resource has a power model.
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub resource_energy: Vec<f64>,
+    #[serde(default)]
+    pub total_energy: f64,
 
     #[serde(skip)]
     task_starts: HashMap<usize, (u32, u64, f64)>,
@@ -40,6 +45,8 @@ pub struct RunStats {
     resource_first_used: HashMap<usize, f64>,
     #[serde(skip)]
     resource_last_used: HashMap<usize, f64>,
+    #[serde(skip)]
+    resource_busy_core_time: HashMap<usize, f64>,
 }
 
 impl RunStats {
@@ -73,7 +80,9 @@ impl RunStats {
         self.total_task_time += time - start_time;
         self.cpu_utilization += (time - start_time) * cores as f64;
         self.memory_utilization += (time - start_time) * memory as f64;
-        self.resource_last_used.insert(self.task_resource[&task], time);
+        let resource = self.task_resource[&task];
+        self.resource_last_used.insert(resource, time);
+        *self.resource_busy_core_time.entry(resource).or_default() += (time - start_time) * cores as f64;
     }
 
     pub fn set_transfer_start(&mut self, data_item: usize, size: f64, time: f64) {
@@ -96,15 +105,15 @@ impl RunStats {
         let mut total_cores_active = 0.;
         let mut total_memory_active = 0.;
         for (i, r) in system.resources.iter().enumerate() {
-            total_cores += r.cores_available;
-            total_memory += r.memory_available;
+            total_cores += r.cores_total;
+            total_memory += r.memory_total;
             if self.used_resources.contains(&i) {
-                total_cores_used += r.cores_available;
-                total_memory_used += r.memory_available;
+                total_cores_used += r.cores_total;
+                total_memory_used += r.memory_total;
                 total_cores_active +=
-                    r.cores_available as f64 * (self.resource_last_used[&i] - self.resource_first_used[&i]);
+                    r.cores_total as f64 * (self.resource_last_used[&i] - self.resource_first_used[&i]);
                 total_memory_active +=
-                    r.memory_available as f64 * (self.resource_last_used[&i] - self.resource_first_used[&i]);
+                    r.memory_total as f64 * (self.resource_last_used[&i] - self.resource_first_used[&i]);
             }
         }
 
@@ -117,5 +126,18 @@ impl RunStats {
         self.cpu_utilization /= time * total_cores as f64;
         self.memory_utilization /= time * total_memory as f64;
         self.used_resource_count = self.used_resources.len();
+
+        if system.resources.iter().any(|r| r.power.is_some()) {
+            self.resource_energy = system
+                .resources
+                .iter()
+                .enumerate()
+                .map(|(i, r)| match &r.power {
+                    Some(power) => power.energy(r, time, self.resource_busy_core_time.get(&i).cloned().unwrap_or(0.)),
+                    None => 0.,
+                })
+                .collect();
+            self.total_energy = self.resource_energy.iter().sum();
+        }
     }
 }
@@ -322,6 +322,10 @@ impl DAGRunner {
             );
             return;
         }
+        if !self.resources[resource].has_gpus_for(task) {
+            log_error!(self.ctx, "Wrong action, resource {} doesn't have enough GPUs", resource);
+            return;
+        }
         if need_cores < task.min_cores || task.max_cores < need_cores {
             log_error!(
                 self.ctx,
@@ -432,7 +436,9 @@ impl DAGRunner {
 
                 let queued_task = &self.resource_queue[resource_idx][core as usize][0];
                 let task = self.dag.get_task(queued_task.task_id);
-                if task.memory > self.resources[resource_idx].memory_available {
+                if task.memory > self.resources[resource_idx].memory_available
+                    || !self.resources[resource_idx].has_free_gpus_for(task)
+                {
                     continue;
                 }
                 if !task.inputs.iter().all(|x| {
@@ -458,7 +464,7 @@ impl DAGRunner {
                 let task_id = task_ids.remove(&action_id).unwrap();
                 let task = self.dag.get_task(task_id);
                 let mut resource = &mut self.resources[resource_idx];
-                if task.memory > resource.memory_available {
+                if task.memory > resource.memory_available || !resource.has_free_gpus_for(task) {
                     continue;
                 }
 
@@ -469,6 +475,8 @@ impl DAGRunner {
 
                 resource.cores_available -= need_cores;
                 resource.memory_available -= task.memory;
+                resource.gpus_available -= task.gpus;
+                resource.gpu_memory_available -= task.gpu_memory;
                 let resource = &self.resources[resource_idx];
                 self.task_inputs.insert(task_id, task.inputs.iter().cloned().collect());
                 self.task_cores.insert(task_id, ready_cores);
@@ -571,6 +579,8 @@ impl DAGRunner {
             self.available_cores[location].insert(core);
         }
         self.resources[location].memory_available += self.dag.get_task(task_id).memory;
+        self.resources[location].gpus_available += self.dag.get_task(task_id).gpus;
+        self.resources[location].gpu_memory_available += self.dag.get_task(task_id).gpu_memory;
         self.dag.update_task_state(task_id, TaskState::Done);
         let data_items = self.dag.get_task(task_id).outputs.clone();
 
@@ -17,6 +17,8 @@ use crate::task::*;
 struct Resource {
     cores_available: u32,
     memory_available: u64,
+    gpus_available: u32,
+    gpu_memory_available: u64,
     speed: f64,
 }
 
@@ -89,6 +91,8 @@ impl DynamicListScheduler {
             .map(|resource| Resource {
                 cores_available: resource.cores_available,
                 memory_available: resource.memory_available,
+                gpus_available: resource.gpus_available,
+                gpu_memory_available: resource.gpu_memory_available,
                 speed: resource.speed,
             })
             .collect();
@@ -130,6 +134,8 @@ impl DynamicListScheduler {
                 .filter(|&r| {
                     resources[r].cores_available >= dag.get_task(task).min_cores
                         && resources[r].memory_available >= dag.get_task(task).memory
+                        && resources[r].gpus_available >= dag.get_task(task).gpus
+                        && resources[r].gpu_memory_available >= dag.get_task(task).gpu_memory
                 })
                 .min_by(|&a, &b| match self.strategy.resource_criterion {
                     ResourceCriterion::Speed => resources[b].speed.total_cmp(&resources[a].speed),
@@ -164,6 +170,8 @@ impl DynamicListScheduler {
 
             resources[best_resource].cores_available -= cores;
             resources[best_resource].memory_available -= dag.get_task(task).memory;
+            resources[best_resource].gpus_available -= dag.get_task(task).gpus;
+            resources[best_resource].gpu_memory_available -= dag.get_task(task).gpu_memory;
             result.push(Action::ScheduleTask {
                 task,
                 resource: best_resource,
@@ -12,6 +12,11 @@ use crate::schedulers::common::*;
 use crate::schedulers::treap::Treap;
 use crate::system::System;
 
+/// Heterogeneous Earliest Finish Time (HEFT) static scheduler.
+///
+/// Tasks are ordered by their upward ranks computed with average execution and data transfer times,
+/// and each task is assigned to the resource and cores where it finishes earliest, taking into account
+/// data transfers from the parent tasks. The resulting schedule is returned from [`Scheduler::start`].
 pub struct HeftScheduler {
     data_transfer_strategy: DataTransferStrategy,
 }
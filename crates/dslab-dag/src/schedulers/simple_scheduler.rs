@@ -10,6 +10,8 @@ use crate::task::*;
 struct Resource {
     cores_available: u32,
     memory_available: u64,
+    gpus_available: u32,
+    gpu_memory_available: u64,
 }
 
 #[derive(Default)]
@@ -26,6 +28,8 @@ impl SimpleScheduler {
             .map(|resource| Resource {
                 cores_available: resource.cores_available,
                 memory_available: resource.memory_available,
+                gpus_available: resource.gpus_available,
+                gpu_memory_available: resource.gpu_memory_available,
             })
             .collect();
         let mut result: Vec<Action> = Vec::new();
@@ -33,12 +37,18 @@ impl SimpleScheduler {
         for task_id in ready_tasks {
             let task = dag.get_task(task_id);
             for (i, resource) in resources.iter_mut().enumerate() {
-                if resource.cores_available < task.min_cores || resource.memory_available < task.memory {
+                if resource.cores_available < task.min_cores
+                    || resource.memory_available < task.memory
+                    || resource.gpus_available < task.gpus
+                    || resource.gpu_memory_available < task.gpu_memory
+                {
                     continue;
                 }
                 let cores = resource.cores_available.min(task.max_cores);
                 resource.cores_available -= cores;
                 resource.memory_available -= task.memory;
+                resource.gpus_available -= task.gpus;
+                resource.gpu_memory_available -= task.gpu_memory;
                 result.push(Action::ScheduleTask {
                     task: task_id,
                     resource: i,
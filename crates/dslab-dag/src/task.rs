@@ -34,6 +34,10 @@ pub struct Task {
     pub flops: f64,
     /// Memory demand of this task in MB.
     pub memory: u64,
+    /// Number of GPUs used by this task.
+    pub gpus: u32,
+    /// GPU memory demand of this task in MB.
+    pub gpu_memory: u64,
     pub min_cores: u32,
     pub max_cores: u32,
     pub cores_dependency: CoresDependency,
@@ -57,6 +61,8 @@ impl Task {
             name: name.to_string(),
             flops,
             memory,
+            gpus: 0,
+            gpu_memory: 0,
             min_cores,
             max_cores,
             cores_dependency,
@@ -5,19 +5,22 @@ use rand::prelude::*;
 use rand_pcg::Pcg64;
 
 use dslab_compute::multicore::CoresDependency;
+use dslab_core::context::SimulationContext;
 
 use dslab_dag::dag::DAG;
 use dslab_dag::dag_simulation::DagSimulation;
 use dslab_dag::data_item::DataTransferMode;
 use dslab_dag::network::NetworkConfig;
-use dslab_dag::resource::ResourceConfig;
+use dslab_dag::resource::{parse_resource_configs, read_resource_configs, LoadError, PowerModel, ResourceConfig};
 use dslab_dag::runner::Config;
-use dslab_dag::scheduler::Scheduler;
+use dslab_dag::scheduler::{Action, Scheduler};
 use dslab_dag::schedulers::dls::DlsScheduler;
 use dslab_dag::schedulers::heft::HeftScheduler;
 use dslab_dag::schedulers::lookahead::LookaheadScheduler;
 use dslab_dag::schedulers::peft::PeftScheduler;
 use dslab_dag::schedulers::simple_scheduler::SimpleScheduler;
+use dslab_dag::system::System;
+use dslab_dag::task::TaskState;
 
 const PRECISION: f64 = 1. / ((1 << 20) as f64);
 
@@ -88,6 +91,11 @@ fn gen_resources(rng: &mut Pcg64, num_resources: usize, infinite_memory: bool) -
             } else {
                 rng.gen_range(32..1024)
             },
+            numa: None,
+            core_speeds: None,
+            gpus: 0,
+            gpu_memory: 0,
+            power: None,
         })
         .collect()
 }
@@ -405,3 +413,376 @@ fn test_fork_join() {
     let result = sim.time();
     assert_eq!(result, correct_result);
 }
+
+#[test]
+fn test_energy_fast_vs_slow() {
+    let run = |speed: f64| {
+        let mut dag = DAG::new();
+        let a = dag.add_task("A", 100., 0, 1, 1, CoresDependency::Linear);
+        let b = dag.add_task("B", 100., 0, 1, 1, CoresDependency::Linear);
+        let data_item = dag.add_task_output(a, "A_B", 0.);
+        dag.add_data_dependency(data_item, b);
+        let mut sim = DagSimulation::new(
+            123,
+            Vec::new(),
+            NetworkConfig::constant(1., 0.),
+            Rc::new(RefCell::new(SimpleScheduler::new())),
+            Config {
+                data_transfer_mode: DataTransferMode::Direct,
+            },
+        );
+        sim.add_resource_with_power("0", speed, 2, 1024, PowerModel::new(10., 100., 10.));
+        let runner = sim.init(dag);
+        sim.step_until_no_events();
+        assert!(runner.borrow().is_completed());
+        let stats = runner.borrow().run_stats().clone();
+        (sim.time(), stats)
+    };
+
+    // fast resource runs both tasks sequentially in 20 seconds with half of its cores busy
+    let (fast_time, fast) = run(10.);
+    assert_eq!(fast_time, 20.);
+    assert_eq!(fast.resource_energy.len(), 1);
+    assert_eq!(fast.total_energy, 10. * 20. + 100. * 0.5 * 20.);
+
+    // slow resource needs twice more time but consumes half of active power
+    let (slow_time, slow) = run(5.);
+    assert_eq!(slow_time, 40.);
+    assert_eq!(slow.total_energy, 10. * 40. + 50. * 0.5 * 40.);
+    assert!(fast.total_energy < slow.total_energy);
+}
+
+#[test]
+fn test_parse_resource_configs() {
+    let resources = parse_resource_configs(
+        "
+resources:
+  - name: fast
+    speed: 10
+    cores: 2
+    memory: 1024
+  - name: slow
+    speed: 5
+    cores: 4
+    memory: 2048
+",
+    )
+    .unwrap();
+    assert_eq!(resources.len(), 2);
+    assert_eq!(resources[0].name, "fast");
+    assert_eq!(resources[1].cores, 4);
+    assert!(resources[1].power.is_none());
+
+    let mut dag = DAG::new();
+    dag.add_task("task", 100., 0, 1, 1, CoresDependency::Linear);
+    let mut sim = DagSimulation::new(
+        123,
+        resources,
+        NetworkConfig::constant(1.0e+8, 0.),
+        Rc::new(RefCell::new(SimpleScheduler::new())),
+        Config {
+            data_transfer_mode: DataTransferMode::Direct,
+        },
+    );
+    let runner = sim.init(dag);
+    sim.step_until_no_events();
+    assert!(runner.borrow().is_completed());
+}
+
+#[test]
+fn test_resource_configs_errors() {
+    assert!(matches!(
+        read_resource_configs("no-such-file.yaml"),
+        Err(LoadError::FileNotFound { .. })
+    ));
+    assert!(matches!(
+        parse_resource_configs("resources:\n  - name: a\n    speed: fast\n    cores: 2\n    memory: 1024\n"),
+        Err(LoadError::Parse { line: Some(_), .. })
+    ));
+    assert_eq!(
+        parse_resource_configs("resources:\n  - name: a\n    speed: 10\n    cores: 0\n    memory: 1024\n").unwrap_err(),
+        LoadError::InvalidResource {
+            name: "a".to_string(),
+            message: "number of cores should be positive".to_string(),
+        }
+    );
+    assert!(matches!(
+        parse_resource_configs("resources:\n  - name: a\n    speed: 0\n    cores: 2\n    memory: 1024\n"),
+        Err(LoadError::InvalidResource { .. })
+    ));
+    assert_eq!(
+        parse_resource_configs("resources:\n  - name: a\n    core_speeds: [10, 5]\n    cores: 3\n    memory: 1024\n")
+            .unwrap_err(),
+        LoadError::InvalidResource {
+            name: "a".to_string(),
+            message: "number of core speeds should be equal to the number of cores".to_string(),
+        }
+    );
+    let numa_resource = |cores: u32, memory: u64| {
+        parse_resource_configs(&format!(
+            "resources:\n  - name: a\n    speed: 10\n    cores: {}\n    memory: {}\n    numa:\n      \
+             remote_access_penalty: 1.5\n      nodes:\n        - cores: 2\n          memory: 512\n        \
+             - cores: 2\n          memory: 512\n",
+            cores, memory
+        ))
+    };
+    assert!(numa_resource(4, 1024).is_ok());
+    assert_eq!(
+        numa_resource(8, 1024).unwrap_err(),
+        LoadError::InvalidResource {
+            name: "a".to_string(),
+            message: "cores of NUMA nodes should sum up to the number of cores".to_string(),
+        }
+    );
+    assert_eq!(
+        numa_resource(4, 2048).unwrap_err(),
+        LoadError::InvalidResource {
+            name: "a".to_string(),
+            message: "memory of NUMA nodes should sum up to the memory size".to_string(),
+        }
+    );
+}
+
+#[test]
+fn test_heterogeneous_cores() {
+    let resources =
+        parse_resource_configs("resources:\n  - name: a\n    core_speeds: [5, 20]\n    cores: 2\n    memory: 1024\n")
+            .unwrap();
+    assert_eq!(resources[0].core_speeds, Some(vec![5., 20.]));
+    assert_eq!(resources[0].effective_speed(), 20.);
+
+    let run = |tasks: u32| {
+        let mut dag = DAG::new();
+        for i in 0..tasks {
+            dag.add_task(&i.to_string(), 100., 0, 1, 1, CoresDependency::Linear);
+        }
+        let mut sim = DagSimulation::new(
+            123,
+            Vec::new(),
+            NetworkConfig::constant(1., 0.),
+            Rc::new(RefCell::new(SimpleScheduler::new())),
+            Config {
+                data_transfer_mode: DataTransferMode::Direct,
+            },
+        );
+        sim.add_resource_with_core_speeds("0", vec![5., 20.], 1024);
+        let runner = sim.init(dag);
+        sim.step_until_no_events();
+        assert!(runner.borrow().is_completed());
+        sim.time()
+    };
+    // single task runs on the fast core
+    assert_eq!(run(1), 5.);
+    // second task has to use the slow core
+    assert_eq!(run(2), 20.);
+}
+
+// Records resource utilization observed on task state changes.
+struct UtilizationRecorder {
+    inner: SimpleScheduler,
+    utilization: Vec<(TaskState, (f64, f64))>,
+}
+
+impl Scheduler for UtilizationRecorder {
+    fn start(&mut self, dag: &DAG, system: System, config: Config, ctx: &SimulationContext) -> Vec<Action> {
+        self.inner.start(dag, system, config, ctx)
+    }
+
+    fn on_task_state_changed(
+        &mut self,
+        task: usize,
+        task_state: TaskState,
+        dag: &DAG,
+        system: System,
+        ctx: &SimulationContext,
+    ) -> Vec<Action> {
+        self.utilization.push((task_state, system.resources[0].utilization()));
+        self.inner.on_task_state_changed(task, task_state, dag, system, ctx)
+    }
+
+    fn is_static(&self) -> bool {
+        false
+    }
+}
+
+#[test]
+fn test_resource_utilization() {
+    let mut dag = DAG::new();
+    dag.add_task("short", 100., 0, 1, 1, CoresDependency::Linear);
+    dag.add_task("long", 1000., 512, 1, 2, CoresDependency::Linear);
+    let scheduler = Rc::new(RefCell::new(UtilizationRecorder {
+        inner: SimpleScheduler::new(),
+        utilization: Vec::new(),
+    }));
+    let mut sim = DagSimulation::new(
+        123,
+        Vec::new(),
+        NetworkConfig::constant(1., 0.),
+        scheduler.clone(),
+        Config {
+            data_transfer_mode: DataTransferMode::Direct,
+        },
+    );
+    sim.add_resource("0", 10., 4, 1024);
+    let runner = sim.init(dag);
+    sim.step_until_no_events();
+    assert!(runner.borrow().is_completed());
+
+    let utilization = &scheduler.borrow().utilization;
+    // the long task is still running when the short one is completed
+    assert_eq!(
+        utilization,
+        &vec![(TaskState::Done, (0.5, 0.5)), (TaskState::Done, (0., 0.))]
+    );
+}
+
+#[test]
+fn test_gpu_resources() {
+    let resources = read_resource_configs("../../examples/dag-demo/systems/cluster-mixed-gpu.yaml").unwrap();
+    assert_eq!(resources.iter().map(|r| r.gpus).collect::<Vec<_>>(), vec![0, 0, 1]);
+
+    let mut dag = DAG::new();
+    for i in 0..2 {
+        let task = dag.add_task(&format!("gpu{}", i), 100., 0, 1, 1, CoresDependency::Linear);
+        dag.get_task_mut(task).gpus = 1;
+        dag.get_task_mut(task).gpu_memory = 8000;
+    }
+    dag.add_task("cpu", 100., 0, 1, 1, CoresDependency::Linear);
+
+    let mut sim = DagSimulation::new(
+        123,
+        resources,
+        NetworkConfig::constant(1000., 0.),
+        Rc::new(RefCell::new(SimpleScheduler::new())),
+        Config {
+            data_transfer_mode: DataTransferMode::Direct,
+        },
+    );
+    let runner = sim.init(dag);
+    sim.step_until_no_events();
+    assert!(runner.borrow().is_completed());
+    // GPU tasks run one after another on the only GPU node
+    assert_eq!(sim.time(), 20.);
+}
+
+#[test]
+fn test_topological_order() {
+    let mut rng = Pcg64::seed_from_u64(1);
+    let dag = gen_dag(&mut rng, 50, 100);
+    let order = dag.topological_order().unwrap();
+    assert_eq!(order.len(), 50);
+    let mut position = vec![0; 50];
+    for (i, &task_id) in order.iter().enumerate() {
+        position[task_id] = i;
+    }
+    for (task_id, task) in dag.get_tasks().iter().enumerate() {
+        for &data_item_id in task.inputs.iter() {
+            if let Some(producer) = dag.get_data_item(data_item_id).producer {
+                assert!(position[producer] < position[task_id]);
+            }
+        }
+    }
+
+    // a -> b -> c -> b
+    let mut dag = DAG::new();
+    let a = dag.add_task("a", 1., 0, 1, 1, CoresDependency::Linear);
+    let b = dag.add_task("b", 1., 0, 1, 1, CoresDependency::Linear);
+    let c = dag.add_task("c", 1., 0, 1, 1, CoresDependency::Linear);
+    let ab = dag.add_task_output(a, "ab", 1.);
+    dag.add_data_dependency(ab, b);
+    let bc = dag.add_task_output(b, "bc", 1.);
+    dag.add_data_dependency(bc, c);
+    assert_eq!(dag.topological_order(), Ok(vec![a, b, c]));
+    let cb = dag.add_task_output(c, "cb", 1.);
+    dag.add_data_dependency(cb, b);
+    let err = dag.topological_order().unwrap_err();
+    assert!(err.contains("cycle"), "{}", err);
+}
+
+#[test]
+fn test_critical_path() {
+    //   a(10) -> b(30) -> d(10)
+    //         -> c(20) ->
+    //   e(45)
+    let mut dag = DAG::new();
+    let a = dag.add_task("a", 10., 0, 1, 1, CoresDependency::Linear);
+    let b = dag.add_task("b", 30., 0, 1, 1, CoresDependency::Linear);
+    let c = dag.add_task("c", 20., 0, 1, 1, CoresDependency::Linear);
+    let d = dag.add_task("d", 10., 0, 1, 1, CoresDependency::Linear);
+    dag.add_task("e", 45., 0, 1, 1, CoresDependency::Linear);
+    let a_out = dag.add_task_output(a, "a_out", 1.);
+    dag.add_data_dependency(a_out, b);
+    dag.add_data_dependency(a_out, c);
+    let b_out = dag.add_task_output(b, "b_out", 1.);
+    dag.add_data_dependency(b_out, d);
+    let c_out = dag.add_task_output(c, "c_out", 1000.);
+    dag.add_data_dependency(c_out, d);
+
+    assert_eq!(dag.critical_path(10.), (5., vec![a, b, d]));
+    assert_eq!(DAG::new().critical_path(10.), (0., vec![]));
+}
+
+// Places each task on the resource with the given index.
+struct FixedAssignmentScheduler {
+    assignment: Vec<usize>,
+}
+
+impl Scheduler for FixedAssignmentScheduler {
+    fn start(&mut self, _dag: &DAG, _system: System, _config: Config, _ctx: &SimulationContext) -> Vec<Action> {
+        self.assignment
+            .iter()
+            .enumerate()
+            .map(|(task, &resource)| Action::ScheduleTask {
+                task,
+                resource,
+                cores: 1,
+                expected_span: None,
+            })
+            .collect()
+    }
+
+    fn on_task_state_changed(
+        &mut self,
+        _task: usize,
+        _task_state: TaskState,
+        _dag: &DAG,
+        _system: System,
+        _ctx: &SimulationContext,
+    ) -> Vec<Action> {
+        Vec::new()
+    }
+
+    fn is_static(&self) -> bool {
+        true
+    }
+}
+
+#[test]
+fn test_data_transfer_between_resources() {
+    let run = |assignment: Vec<usize>, data_transfer_mode: DataTransferMode| {
+        let mut dag = DAG::new();
+        let parent = dag.add_task("parent", 100., 0, 1, 1, CoresDependency::Linear);
+        let child = dag.add_task("child", 100., 0, 1, 1, CoresDependency::Linear);
+        let data_item = dag.add_task_output(parent, "data", 50.);
+        dag.add_data_dependency(data_item, child);
+        let mut sim = DagSimulation::new(
+            123,
+            Vec::new(),
+            NetworkConfig::constant(10., 0.),
+            Rc::new(RefCell::new(FixedAssignmentScheduler { assignment })),
+            Config { data_transfer_mode },
+        );
+        sim.add_resource("0", 10., 1, 1024);
+        sim.add_resource("1", 10., 1, 1024);
+        let runner = sim.init(dag);
+        sim.step_until_no_events();
+        assert!(runner.borrow().is_completed());
+        sim.time()
+    };
+    // no transfer is needed on the same resource
+    assert_eq!(run(vec![0, 0], DataTransferMode::Direct), 20.);
+    // the child waits for its input to be transferred with 10 MB/s
+    assert_eq!(run(vec![0, 1], DataTransferMode::Direct), 25.);
+    // transfer via the master node takes twice as long
+    assert_eq!(run(vec![0, 1], DataTransferMode::ViaMasterNode), 30.);
+}
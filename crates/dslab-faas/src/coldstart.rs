@@ -18,8 +18,12 @@ impl<T: 'static + ColdStartPolicy> ColdStartConvertHelper for T {
     }
 }
 
+/// Cold start policy decides how long idle containers are kept warm and whether they are prewarmed.
+/// Custom keepalive strategies can be plugged into the simulation by implementing this trait
+/// and passing it in [`Config`](crate::config::Config).
 pub trait ColdStartPolicy: ColdStartConvertHelper {
     /// Maximum allowed idle time until container destruction.
+    /// It is requested each time the container becomes idle.
     fn keepalive_window(&mut self, container: &Container) -> f64;
     /// Prewarm = x > 0 => destroy container, deploy new container after x time units since execution.
     /// Prewarm = 0 => do not destroy container immediately after execution.
@@ -32,6 +36,7 @@ pub trait ColdStartPolicy: ColdStartConvertHelper {
     }
 }
 
+/// Keeps idle containers for a fixed time.
 pub struct FixedTimeColdStartPolicy {
     keepalive_window: f64,
     prewarm_window: f64,
@@ -45,6 +50,11 @@ impl FixedTimeColdStartPolicy {
         }
     }
 
+    /// Creates a policy that never unloads idle containers.
+    pub fn no_unloading() -> Self {
+        Self::new(f64::MAX / 10.0, 0.0)
+    }
+
     pub fn from_options_map(options: &HashMap<String, String>) -> Self {
         let keepalive = options.get("keepalive").unwrap().parse::<f64>().unwrap();
         let prewarm = options.get("prewarm").unwrap().parse::<f64>().unwrap();
@@ -73,7 +83,7 @@ impl ColdStartPolicy for FixedTimeColdStartPolicy {
 
 pub fn default_coldstart_policy_resolver(s: &str) -> Box<dyn ColdStartPolicy> {
     if s == "No unloading" {
-        return Box::new(FixedTimeColdStartPolicy::new(f64::MAX / 10.0, 0.0));
+        return Box::new(FixedTimeColdStartPolicy::no_unloading());
     }
     if s.len() >= 26 && &s[0..25] == "FixedTimeColdStartPolicy[" && s.ends_with(']') {
         let opts = parse_options(&s[25..s.len() - 1]);
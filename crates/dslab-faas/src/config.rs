@@ -6,11 +6,13 @@ use std::path::Path;
 use serde::{Deserialize, Serialize};
 
 use crate::coldstart::{default_coldstart_policy_resolver, ColdStartPolicy, FixedTimeColdStartPolicy};
+use crate::container::{ContainerSelectionPolicy, ResourceReservationPolicy};
 use crate::cpu::{default_cpu_policy_resolver, ContendedCpuPolicy, CpuPolicy};
 use crate::deployer::{default_idle_deployer_resolver, BasicDeployer, IdleDeployer};
 use crate::invoker::{default_invoker_resolver, FIFOInvoker, Invoker};
 use crate::parallel::{ParallelConfig, ParallelHostConfig};
 use crate::scheduler::{default_scheduler_resolver, BasicScheduler, Scheduler};
+use crate::warmth::CacheWarmthModel;
 
 pub struct HostConfig {
     pub invoker: Box<dyn Invoker>,
@@ -46,6 +48,13 @@ impl From<ParallelConfig> for Config {
             cpu_policy: value.cpu_policy,
             idle_deployer: value.idle_deployer,
             scheduler: value.scheduler,
+            container_drain_delay: value.container_drain_delay,
+            container_selection: value.container_selection,
+            resource_reservation: value.resource_reservation,
+            wait_for_busy_containers: value.wait_for_busy_containers,
+            scheduler_tick: value.scheduler_tick,
+            container_status_log: value.container_status_log,
+            cache_warmth: value.cache_warmth,
             hosts: hosts.drain(..).map(HostConfig::from).collect(),
         }
     }
@@ -83,6 +92,20 @@ pub struct RawConfig {
     #[serde(default)]
     pub scheduler: String,
     #[serde(default)]
+    pub container_drain_delay: f64,
+    #[serde(default)]
+    pub container_selection: ContainerSelectionPolicy,
+    #[serde(default)]
+    pub resource_reservation: ResourceReservationPolicy,
+    #[serde(default)]
+    pub wait_for_busy_containers: bool,
+    #[serde(default)]
+    pub scheduler_tick: Option<f64>,
+    #[serde(default)]
+    pub container_status_log: bool,
+    #[serde(default)]
+    pub cache_warmth: Option<CacheWarmthModel>,
+    #[serde(default)]
     pub hosts: Vec<RawHostConfig>,
 }
 
@@ -102,7 +125,7 @@ pub struct ConfigParamResolvers {
     pub cpu_policy_resolver: Box<dyn Fn(&str) -> Box<dyn CpuPolicy> + Send + Sync>,
     pub idle_deployer_resolver: Box<dyn Fn(&str) -> Box<dyn IdleDeployer> + Send + Sync>,
     pub scheduler_resolver: Box<dyn Fn(&str) -> Box<dyn Scheduler> + Send + Sync>,
-    pub invoker_resolver: Box<dyn Fn(&str) -> Box<dyn Invoker> + Send + Sync>,
+    pub invoker_resolver: Box<dyn Fn(&str) -> Result<Box<dyn Invoker>, String> + Send + Sync>,
 }
 
 impl Default for ConfigParamResolvers {
@@ -124,6 +147,25 @@ pub struct Config {
     pub cpu_policy: Box<dyn CpuPolicy>,
     pub idle_deployer: Box<dyn IdleDeployer>,
     pub scheduler: Box<dyn Scheduler>,
+    /// Time a container marked for unloading keeps its resources after its last invocation finishes.
+    pub container_drain_delay: f64,
+    /// Defines which of the suitable containers is chosen by the invokers.
+    pub container_selection: ContainerSelectionPolicy,
+    /// Defines when host resources are committed to newly deployed containers.
+    pub resource_reservation: ResourceReservationPolicy,
+    /// If set, the invokers may queue an invocation to wait for a busy container of the application instead of
+    /// deploying a new one, when the container is expected to free up sooner than the deployment would finish.
+    pub wait_for_busy_containers: bool,
+    /// If set, the hosts start queued invocations only at the multiples of this interval, modeling a polling
+    /// scheduler, instead of doing it as soon as a container frees up or finishes deploying (event-driven mode).
+    /// Queued invocations may wait up to one interval longer, which increases the tail latency. On the other hand,
+    /// each tick is a separate simulation event, so very small intervals make the simulation noticeably slower
+    /// while the ticks are running, i.e. while some host has queued invocations.
+    pub scheduler_tick: Option<f64>,
+    /// If set, all container status transitions are recorded for debugging.
+    pub container_status_log: bool,
+    /// If set, invocations on containers reused after a long idle period run slower.
+    pub cache_warmth: Option<CacheWarmthModel>,
     pub hosts: Vec<HostConfig>,
 }
 
@@ -134,6 +176,13 @@ impl Default for Config {
             cpu_policy: Box::<ContendedCpuPolicy>::default(),
             idle_deployer: Box::new(BasicDeployer {}),
             scheduler: Box::new(BasicScheduler {}),
+            container_drain_delay: 0.0,
+            container_selection: Default::default(),
+            resource_reservation: Default::default(),
+            wait_for_busy_containers: false,
+            scheduler_tick: None,
+            container_status_log: false,
+            cache_warmth: None,
             hosts: Vec::new(),
         }
     }
@@ -157,7 +206,7 @@ impl Config {
         cpu_policy_resolver: &(dyn Fn(&str) -> Box<dyn CpuPolicy> + Send + Sync),
         idle_deployer_resolver: &(dyn Fn(&str) -> Box<dyn IdleDeployer> + Send + Sync),
         scheduler_resolver: &(dyn Fn(&str) -> Box<dyn Scheduler> + Send + Sync),
-        invoker_resolver: &(dyn Fn(&str) -> Box<dyn Invoker> + Send + Sync),
+        invoker_resolver: &(dyn Fn(&str) -> Result<Box<dyn Invoker>, String> + Send + Sync),
     ) -> Self {
         let mut me: Self = Default::default();
         if !raw.coldstart_policy.is_empty() {
@@ -172,6 +221,13 @@ impl Config {
         if !raw.scheduler.is_empty() {
             me.scheduler = scheduler_resolver(&raw.scheduler);
         }
+        me.container_drain_delay = raw.container_drain_delay;
+        me.container_selection = raw.container_selection;
+        me.resource_reservation = raw.resource_reservation;
+        me.wait_for_busy_containers = raw.wait_for_busy_containers;
+        me.scheduler_tick = raw.scheduler_tick;
+        me.container_status_log = raw.container_status_log;
+        me.cache_warmth = raw.cache_warmth;
         for host in raw.hosts {
             let mut resources = Vec::with_capacity(host.resources.len());
             for item in host.resources {
@@ -180,6 +236,7 @@ impl Config {
             for _ in 0..host.count {
                 let invoker = if !host.invoker.is_empty() {
                     invoker_resolver(&host.invoker)
+                        .unwrap_or_else(|e| panic!("Can't resolve invoker {}: {}", host.invoker, e))
                 } else {
                     Box::new(FIFOInvoker::new())
                 };
@@ -1,42 +1,157 @@
 use std::cell::RefCell;
+use std::collections::VecDeque;
 use std::rc::Rc;
 
+use serde::{Deserialize, Serialize};
+
 use dslab_core::context::SimulationContext;
 
-use crate::event::ContainerStartEvent;
+use crate::event::{ContainerStartEvent, DeploymentStartEvent};
 use crate::function::Application;
+use crate::invoker::RejectionReason;
 use crate::resource::{ResourceConsumer, ResourceProvider};
 use crate::util::{Counter, DefaultVecMap, FxIndexMap, FxIndexSet};
 
-#[derive(Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum ContainerStatus {
     Deploying,
     Running,
     Idle,
+    /// Container is marked for unloading: it finishes current invocations, accepts no new ones
+    /// and shuts down afterwards.
+    Draining,
+}
+
+/// Change of container status recorded by [`ContainerManager`] when the status log is enabled.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ContainerStatusTransition {
+    pub time: f64,
+    pub container_id: usize,
+    /// Status before the transition, `None` for newly deployed containers.
+    pub old_status: Option<ContainerStatus>,
+    /// Status after the transition, `None` for deleted containers.
+    pub new_status: Option<ContainerStatus>,
+}
+
+/// Defines which container is chosen by the invokers among several suitable ones.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ContainerSelectionPolicy {
+    /// Choose the container with the smallest wait time, ties are broken by container id.
+    #[default]
+    Nearest,
+    /// Same as `Nearest`, but among idle containers choose the one with the least remaining keepalive time,
+    /// so that the containers about to be unloaded are reused.
+    LeastRemainingKeepalive,
+}
+
+/// Defines when host resources are committed to a newly deployed container.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum ResourceReservationPolicy {
+    /// Resources are committed as soon as the invoker decides to deploy a container.
+    #[default]
+    OnDecision,
+    /// The deployment starts after the scheduling delay, and resources are committed only at this moment.
+    /// New deployments are admitted if the committed resources allow it, so concurrent cold starts may be admitted
+    /// beyond the host capacity. Such deployments wait for the resources to be released before starting,
+    /// in the order of admission. The deployment time reported to the invoker includes the scheduling delay,
+    /// but not the waiting time.
+    OnDeploymentStart { scheduling_delay: f64 },
 }
 
+#[derive(Clone)]
 pub struct Container {
     pub status: ContainerStatus,
     pub id: usize,
     pub deployment_time: f64,
+    /// Time when the deployment of the container was requested.
+    pub creation_time: f64,
     pub app_id: usize,
     pub invocations: FxIndexSet<usize>,
     pub resources: ResourceConsumer,
     pub started_invocations: usize,
     pub last_change: f64,
     pub cpu_share: f64,
+    /// Length of the idle period that preceded the current run of invocations.
+    pub idle_duration: f64,
+    /// Time when the container is scheduled to be unloaded, if it is idle or draining.
+    pub unload_time: Option<f64>,
+    /// Maximum number of invocations running in the container simultaneously.
+    pub max_concurrency: usize,
+    /// Total time during which the container was running at least one invocation.
+    pub busy_time: f64,
+    /// Integral of the number of running invocations over time.
+    pub busy_slot_time: f64,
+    /// Whether the container belongs to the application warm pool. Such containers are not unloaded by
+    /// the cold start policy and their idle time is not counted as wasted.
+    pub pooled: bool,
+    /// Expected end times of the running invocations, estimated without taking CPU contention into account.
+    pub expected_end_times: FxIndexMap<usize, f64>,
 }
 
 impl Container {
-    pub fn start_invocation(&mut self, id: usize) {
+    /// Accounts the time since the last change of running invocations in busy time metrics.
+    pub fn update_busy_time(&mut self, time: f64) {
+        if !self.invocations.is_empty() {
+            let delta = time - self.last_change;
+            self.busy_time += delta;
+            self.busy_slot_time += delta * self.invocations.len() as f64;
+            self.last_change = time;
+        }
+    }
+
+    /// Returns the average fraction of concurrency slots occupied while the container was running invocations,
+    /// or `None` if the container has not run any invocations yet.
+    pub fn concurrency_utilization(&self) -> Option<f64> {
+        if self.busy_time > 0. {
+            Some(self.busy_slot_time / (self.busy_time * self.max_concurrency as f64))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the time when the container is scheduled to be unloaded, or `None` if it is running
+    /// or deploying, or if its unloading is not scheduled (e.g. for warm pool containers).
+    pub fn unload_time(&self) -> Option<f64> {
+        match self.status {
+            ContainerStatus::Idle | ContainerStatus::Draining => self.unload_time,
+            ContainerStatus::Running | ContainerStatus::Deploying => None,
+        }
+    }
+
+    /// Records the expected end time of a running invocation, see [`Container::expected_end_time`].
+    pub fn set_expected_end_time(&mut self, invocation_id: usize, time: f64) {
+        self.expected_end_times.insert(invocation_id, time);
+    }
+
+    /// Returns the earliest expected end time among the invocations running in the container,
+    /// i.e. the time when a concurrency slot is expected to free up, or `None` if the container is not running.
+    pub fn expected_end_time(&self) -> Option<f64> {
+        if self.status != ContainerStatus::Running {
+            return None;
+        }
+        self.expected_end_times.values().copied().reduce(f64::min)
+    }
+
+    pub fn start_invocation(&mut self, id: usize, time: f64) {
+        self.update_busy_time(time);
+        self.idle_duration = if self.status == ContainerStatus::Idle {
+            time - self.last_change
+        } else {
+            0.
+        };
+        self.last_change = time;
+        self.unload_time = None;
+        self.status = ContainerStatus::Running;
         self.invocations.insert(id);
         self.started_invocations += 1;
     }
 
     pub fn end_invocation(&mut self, id: usize, curr_time: f64) {
+        self.update_busy_time(curr_time);
         self.last_change = curr_time;
         self.invocations.remove(&id);
-        if self.invocations.is_empty() {
+        self.expected_end_times.swap_remove(&id);
+        if self.invocations.is_empty() && self.status != ContainerStatus::Draining {
             self.status = ContainerStatus::Idle;
         }
     }
@@ -49,11 +164,28 @@ pub struct ContainerManager {
     containers_by_app: DefaultVecMap<FxIndexSet<usize>>,
     container_counter: Counter,
     reservations: FxIndexMap<usize, Vec<usize>>,
+    // number of slots taken by the decisions of the current bulk invocation, see `claim_container`
+    claims: FxIndexMap<usize, usize>,
+    drain_delay: f64,
+    selection_policy: ContainerSelectionPolicy,
+    status_log: Option<Vec<ContainerStatusTransition>>,
+    reservation_policy: ResourceReservationPolicy,
+    wait_for_busy: bool,
+    // containers which reached the deployment start but wait for resources, see `ResourceReservationPolicy`
+    waiting_deployments: VecDeque<usize>,
+    // containers removed by `evict`, not yet accounted in stats by the host
+    evicted: Vec<Container>,
+    detached: bool,
     ctx: Rc<RefCell<SimulationContext>>,
 }
 
 impl ContainerManager {
-    pub fn new(resources: ResourceProvider, ctx: Rc<RefCell<SimulationContext>>) -> Self {
+    pub fn new(
+        resources: ResourceProvider,
+        drain_delay: f64,
+        selection_policy: ContainerSelectionPolicy,
+        ctx: Rc<RefCell<SimulationContext>>,
+    ) -> Self {
         Self {
             active_invocations: 0,
             resources,
@@ -61,10 +193,42 @@ impl ContainerManager {
             containers_by_app: Default::default(),
             container_counter: Counter::default(),
             reservations: FxIndexMap::default(),
+            claims: FxIndexMap::default(),
+            drain_delay,
+            selection_policy,
+            status_log: None,
+            reservation_policy: Default::default(),
+            wait_for_busy: false,
+            waiting_deployments: VecDeque::new(),
+            evicted: Vec::new(),
+            detached: false,
             ctx,
         }
     }
 
+    /// Returns a copy of the manager that can be used to evaluate invoker decisions without affecting
+    /// the simulation: containers deployed by the copy do not emit any events.
+    pub fn detached_copy(&self) -> Self {
+        Self {
+            active_invocations: self.active_invocations,
+            resources: self.resources.clone(),
+            containers: self.containers.clone(),
+            containers_by_app: self.containers_by_app.clone(),
+            container_counter: self.container_counter.clone(),
+            reservations: self.reservations.clone(),
+            claims: self.claims.clone(),
+            drain_delay: self.drain_delay,
+            selection_policy: self.selection_policy,
+            status_log: None,
+            reservation_policy: self.reservation_policy,
+            wait_for_busy: self.wait_for_busy,
+            waiting_deployments: self.waiting_deployments.clone(),
+            evicted: Vec::new(),
+            detached: true,
+            ctx: self.ctx.clone(),
+        }
+    }
+
     pub fn can_allocate(&self, resources: &ResourceConsumer) -> bool {
         self.resources.can_allocate(resources)
     }
@@ -73,6 +237,11 @@ impl ContainerManager {
         self.resources.get_resource(id).unwrap().get_available()
     }
 
+    /// Returns the amount of the resource committed to containers.
+    pub fn get_used_resource(&self, id: usize) -> u64 {
+        self.resources.get_resource(id).unwrap().get_consumed()
+    }
+
     pub fn dec_active_invocations(&mut self) {
         self.active_invocations -= 1;
     }
@@ -85,6 +254,157 @@ impl ContainerManager {
         self.active_invocations
     }
 
+    /// Delay between the end of the last invocation in a draining container and its shutdown.
+    pub fn drain_delay(&self) -> f64 {
+        self.drain_delay
+    }
+
+    pub fn selection_policy(&self) -> ContainerSelectionPolicy {
+        self.selection_policy
+    }
+
+    pub fn reservation_policy(&self) -> ResourceReservationPolicy {
+        self.reservation_policy
+    }
+
+    /// Sets the resource reservation policy, should be called before any container is deployed.
+    pub fn set_reservation_policy(&mut self, policy: ResourceReservationPolicy) {
+        self.reservation_policy = policy;
+    }
+
+    /// Allows the invokers to wait for busy containers instead of deploying new ones,
+    /// see [`ContainerManager::nearest_busy_container`].
+    pub fn enable_waiting_for_busy_containers(&mut self) {
+        self.wait_for_busy = true;
+    }
+
+    pub fn waits_for_busy_containers(&self) -> bool {
+        self.wait_for_busy
+    }
+
+    /// Returns the running container of the application which is expected to free up a concurrency slot first
+    /// along with the expected wait time. Draining containers and containers without known expected end times
+    /// of their invocations are not considered. Ties are broken by container id.
+    pub fn nearest_busy_container(&self, app: &Application, time: f64) -> Option<(usize, f64)> {
+        let mut nearest: Option<(usize, f64)> = None;
+        for id in self.containers_by_app.get(app.id)?.iter() {
+            let Some(end_time) = self.containers[id].expected_end_time() else {
+                continue;
+            };
+            let wait = f64::max(0., end_time - time);
+            if nearest.is_none_or(|(n_id, n_wait)| wait < n_wait || (wait == n_wait && *id < n_id)) {
+                nearest = Some((*id, wait));
+            }
+        }
+        nearest
+    }
+
+    /// Starts the deployment of a container admitted with [`ResourceReservationPolicy::OnDeploymentStart`]
+    /// once the scheduling delay has passed. If there are not enough free resources, the container waits
+    /// until they are released.
+    pub fn start_deployment(&mut self, id: usize, time: f64) {
+        self.waiting_deployments.push_back(id);
+        self.start_waiting_deployments(time);
+    }
+
+    fn start_waiting_deployments(&mut self, time: f64) {
+        let scheduling_delay = match self.reservation_policy {
+            ResourceReservationPolicy::OnDecision => 0.,
+            ResourceReservationPolicy::OnDeploymentStart { scheduling_delay } => scheduling_delay,
+        };
+        while let Some(&id) = self.waiting_deployments.front() {
+            let container = self.containers.get_mut(&id).unwrap();
+            if !self.resources.can_allocate(&container.resources) {
+                break;
+            }
+            self.waiting_deployments.pop_front();
+            self.resources.allocate(&container.resources);
+            // from now on the deployment time counts from the actual deployment start
+            container.deployment_time -= scheduling_delay;
+            container.last_change = time;
+            if !self.detached {
+                self.ctx
+                    .borrow_mut()
+                    .emit_self(ContainerStartEvent { id }, container.deployment_time);
+            }
+        }
+    }
+
+    /// Enables recording of all container status transitions.
+    pub fn enable_status_log(&mut self) {
+        self.status_log.get_or_insert_with(Vec::new);
+    }
+
+    /// Returns recorded container status transitions in chronological order.
+    /// The log is empty unless it was enabled with [`ContainerManager::enable_status_log`].
+    pub fn status_log(&self) -> &[ContainerStatusTransition] {
+        self.status_log.as_deref().unwrap_or(&[])
+    }
+
+    fn log_transition(
+        &mut self,
+        time: f64,
+        container_id: usize,
+        old_status: Option<ContainerStatus>,
+        new_status: Option<ContainerStatus>,
+    ) {
+        if let Some(log) = self.status_log.as_mut() {
+            if old_status != new_status {
+                log.push(ContainerStatusTransition {
+                    time,
+                    container_id,
+                    old_status,
+                    new_status,
+                });
+            }
+        }
+    }
+
+    /// Starts the invocation in the container and returns the container.
+    pub fn start_invocation(&mut self, container_id: usize, invocation_id: usize, time: f64) -> &mut Container {
+        let container = self.containers.get_mut(&container_id).unwrap();
+        let old_status = container.status;
+        container.start_invocation(invocation_id, time);
+        let new_status = container.status;
+        self.log_transition(time, container_id, Some(old_status), Some(new_status));
+        self.containers.get_mut(&container_id).unwrap()
+    }
+
+    /// Removes the finished invocation from the container and returns the container.
+    pub fn end_invocation(&mut self, container_id: usize, invocation_id: usize, time: f64) -> &mut Container {
+        let container = self.containers.get_mut(&container_id).unwrap();
+        let old_status = container.status;
+        container.end_invocation(invocation_id, time);
+        let new_status = container.status;
+        self.log_transition(time, container_id, Some(old_status), Some(new_status));
+        self.containers.get_mut(&container_id).unwrap()
+    }
+
+    /// Moves a deployed container without reserved invocations to `Idle` state.
+    pub fn set_idle(&mut self, id: usize, time: f64) {
+        let container = self.containers.get_mut(&id).unwrap();
+        let old_status = container.status;
+        container.status = ContainerStatus::Idle;
+        self.log_transition(time, id, Some(old_status), Some(ContainerStatus::Idle));
+    }
+
+    /// Moves a running container to `Draining` state. Returns false if the container is not running.
+    pub fn start_draining(&mut self, id: usize, time: f64) -> bool {
+        if let Some(container) = self.containers.get_mut(&id) {
+            if container.status == ContainerStatus::Running {
+                container.status = ContainerStatus::Draining;
+                self.log_transition(
+                    time,
+                    id,
+                    Some(ContainerStatus::Running),
+                    Some(ContainerStatus::Draining),
+                );
+                return true;
+            }
+        }
+        false
+    }
+
     pub fn get_container(&self, id: usize) -> Option<&Container> {
         self.containers.get(&id)
     }
@@ -93,10 +413,44 @@ impl ContainerManager {
         self.containers.get_mut(&id)
     }
 
+    /// Returns the scheduled unload time of the container, see [`Container::unload_time`].
+    pub fn unload_time(&self, id: usize) -> Option<f64> {
+        self.containers.get(&id).and_then(|c| c.unload_time())
+    }
+
     pub fn get_containers(&mut self) -> &mut FxIndexMap<usize, Container> {
         &mut self.containers
     }
 
+    /// Iterates over all containers of the host in the order of their deployment.
+    pub fn iter_containers(&self) -> impl Iterator<Item = &Container> {
+        self.containers.values()
+    }
+
+    /// Returns the number of containers in each status, indexed in the order of [`ContainerStatus`] variants:
+    /// deploying, running, idle and draining.
+    pub fn container_count_by_status(&self) -> [usize; 4] {
+        let mut counts = [0; 4];
+        for container in self.containers.values() {
+            let idx = match container.status {
+                ContainerStatus::Deploying => 0,
+                ContainerStatus::Running => 1,
+                ContainerStatus::Idle => 2,
+                ContainerStatus::Draining => 3,
+            };
+            counts[idx] += 1;
+        }
+        counts
+    }
+
+    /// Returns the number of warm pool containers of the application.
+    pub fn pooled_container_count(&self, app_id: usize) -> usize {
+        match self.containers_by_app.get(app_id) {
+            Some(set) => set.iter().filter(|id| self.containers[*id].pooled).count(),
+            None => 0,
+        }
+    }
+
     pub fn get_possible_containers(&self, app: &Application, allow_deploying: bool) -> PossibleContainerIterator<'_> {
         let limit = app.get_concurrent_invocations();
         if let Some(set) = self.containers_by_app.get(app.id) {
@@ -104,19 +458,27 @@ impl ContainerManager {
                 Some(set.iter()),
                 &self.containers,
                 &self.reservations,
+                &self.claims,
                 limit,
                 allow_deploying,
             );
         }
-        PossibleContainerIterator::new(None, &self.containers, &self.reservations, limit, allow_deploying)
+        PossibleContainerIterator::new(
+            None,
+            &self.containers,
+            &self.reservations,
+            &self.claims,
+            limit,
+            allow_deploying,
+        )
     }
 
-    pub fn try_deploy(&mut self, app: &Application, time: f64) -> Option<(usize, f64)> {
+    /// Deploys a new container if there are enough free resources, returns its id and deployment time.
+    pub fn try_deploy(&mut self, app: &Application, time: f64) -> Result<(usize, f64), RejectionReason> {
         if self.resources.can_allocate(app.get_resources()) {
-            let id = self.deploy_container(app, time);
-            return Some((id, app.get_deployment_time()));
+            return Ok(self.deploy_container(app, time));
         }
-        None
+        Err(RejectionReason::InsufficientResources)
     }
 
     pub fn reserve_container(&mut self, id: usize, request: usize) {
@@ -127,32 +489,114 @@ impl ContainerManager {
         self.reservations.remove(&id)
     }
 
-    pub fn delete_container(&mut self, id: usize) {
+    /// Temporarily takes a slot of the container, so that it is not chosen for more invocations than it can run.
+    /// Used when several invocations are invoked at once, before their decisions are applied.
+    pub fn claim_container(&mut self, id: usize) {
+        *self.claims.entry(id).or_default() += 1;
+    }
+
+    /// Releases all slots taken by [`ContainerManager::claim_container`].
+    pub fn release_claims(&mut self) {
+        self.claims.clear();
+    }
+
+    pub fn delete_container(&mut self, id: usize, time: f64) {
+        self.remove_container(id, time);
+    }
+
+    /// Tears down an idle container and frees its resources, so that they can be reused by the next deployment.
+    /// Returns an error if the container does not exist or is not idle.
+    /// Evicted containers are accounted in stats by the host after the invoker call.
+    pub fn evict(&mut self, container_id: usize) -> Result<(), String> {
+        match self.containers.get(&container_id) {
+            Some(container) if container.status == ContainerStatus::Idle => {}
+            Some(container) => {
+                return Err(format!(
+                    "container {} is {:?}, only idle containers can be evicted",
+                    container_id, container.status
+                ))
+            }
+            None => return Err(format!("container {} does not exist", container_id)),
+        }
+        let time = self.ctx.borrow().time();
+        let container = self.remove_container(container_id, time);
+        if !self.detached {
+            self.evicted.push(container);
+        }
+        Ok(())
+    }
+
+    /// Returns containers evicted since the last call.
+    pub fn take_evicted(&mut self) -> Vec<Container> {
+        std::mem::take(&mut self.evicted)
+    }
+
+    fn remove_container(&mut self, id: usize, time: f64) -> Container {
         let container = self.containers.remove(&id).unwrap();
+        self.log_transition(time, id, Some(container.status), None);
         self.containers_by_app.get_mut(container.app_id).remove(&id);
         self.resources.release(&container.resources);
+        self.start_waiting_deployments(time);
+        container
     }
 
-    fn deploy_container(&mut self, app: &Application, time: f64) -> usize {
+    /// Deploys a new container and returns its id and deployment time.
+    /// The deployment time is drawn from the application distribution, detached copies use its mean instead
+    /// to leave the simulation RNG untouched.
+    /// With [`ResourceReservationPolicy::OnDeploymentStart`] the resources are not committed until the deployment
+    /// start, which is signaled by [`DeploymentStartEvent`].
+    fn deploy_container(&mut self, app: &Application, time: f64) -> (usize, f64) {
         let cont_id = self.container_counter.increment();
+        let mut deployment_time = if self.detached {
+            app.get_deployment_time()
+        } else {
+            app.get_deployment_time_distribution().sample(&self.ctx.borrow())
+        };
+        let scheduling_delay = match self.reservation_policy {
+            ResourceReservationPolicy::OnDecision => None,
+            ResourceReservationPolicy::OnDeploymentStart { scheduling_delay } => Some(scheduling_delay),
+        };
+        if let Some(delay) = scheduling_delay {
+            deployment_time += delay;
+        }
         let container = Container {
             status: ContainerStatus::Deploying,
             id: cont_id,
-            deployment_time: app.get_deployment_time(),
+            deployment_time,
+            creation_time: time,
             app_id: app.id,
             invocations: Default::default(),
             resources: app.get_resources().clone(),
             started_invocations: 0,
             last_change: time,
             cpu_share: app.get_cpu_share(),
+            idle_duration: 0.,
+            unload_time: None,
+            max_concurrency: app.get_concurrent_invocations(),
+            busy_time: 0.,
+            busy_slot_time: 0.,
+            pooled: false,
+            expected_end_times: FxIndexMap::default(),
         };
-        self.resources.allocate(&container.resources);
+        if scheduling_delay.is_none() {
+            self.resources.allocate(&container.resources);
+        }
         self.containers.insert(cont_id, container);
+        self.log_transition(time, cont_id, None, Some(ContainerStatus::Deploying));
         self.containers_by_app.get_mut(app.id).insert(cont_id);
-        self.ctx
-            .borrow_mut()
-            .emit_self(ContainerStartEvent { id: cont_id }, app.get_deployment_time());
-        cont_id
+        if !self.detached {
+            match scheduling_delay {
+                Some(delay) => self
+                    .ctx
+                    .borrow_mut()
+                    .emit_self(DeploymentStartEvent { id: cont_id }, delay),
+                None => self
+                    .ctx
+                    .borrow_mut()
+                    .emit_self(ContainerStartEvent { id: cont_id }, deployment_time),
+            };
+        }
+        (cont_id, deployment_time)
     }
 }
 
@@ -160,6 +604,7 @@ pub struct PossibleContainerIterator<'a> {
     inner: Option<indexmap::set::Iter<'a, usize>>,
     containers: &'a FxIndexMap<usize, Container>,
     reserve: &'a FxIndexMap<usize, Vec<usize>>,
+    claims: &'a FxIndexMap<usize, usize>,
     limit: usize,
     allow_deploying: bool,
 }
@@ -169,6 +614,7 @@ impl<'a> PossibleContainerIterator<'a> {
         inner: Option<indexmap::set::Iter<'a, usize>>,
         containers: &'a FxIndexMap<usize, Container>,
         reserve: &'a FxIndexMap<usize, Vec<usize>>,
+        claims: &'a FxIndexMap<usize, usize>,
         limit: usize,
         allow_deploying: bool,
     ) -> Self {
@@ -176,6 +622,7 @@ impl<'a> PossibleContainerIterator<'a> {
             inner,
             containers,
             reserve,
+            claims,
             limit,
             allow_deploying,
         }
@@ -188,13 +635,15 @@ impl<'a> Iterator for PossibleContainerIterator<'a> {
         if let Some(inner) = self.inner.as_mut() {
             for id in inner.by_ref() {
                 let c = self.containers.get(id).unwrap();
-                if c.status != ContainerStatus::Deploying && c.invocations.len() < self.limit {
+                if c.status == ContainerStatus::Draining {
+                    continue;
+                }
+                let claimed = self.claims.get(id).copied().unwrap_or(0);
+                if c.status != ContainerStatus::Deploying && c.invocations.len() + claimed < self.limit {
                     return Some(c);
                 }
-                if c.status == ContainerStatus::Deploying
-                    && self.allow_deploying
-                    && (!self.reserve.contains_key(id) || self.reserve.get(id).unwrap().len() < self.limit)
-                {
+                let reserved = self.reserve.get(id).map(|r| r.len()).unwrap_or(0);
+                if c.status == ContainerStatus::Deploying && self.allow_deploying && reserved + claimed < self.limit {
                     return Some(c);
                 }
             }
@@ -6,11 +6,12 @@ use dslab_core::cast;
 use dslab_core::event::Event;
 use dslab_core::handler::EventHandler;
 
+use crate::container::ContainerStatusTransition;
 use crate::deployer::IdleDeployer;
-use crate::event::{IdleDeployEvent, InvocationStartEvent, SimulationEndEvent};
+use crate::event::{ContainerReadyEvent, IdleDeployEvent, InvocationStartEvent, SimulationEndEvent};
 use crate::function::FunctionRegistry;
 use crate::host::Host;
-use crate::invoker::InvokerDecision;
+use crate::invoker::{InvokerDecision, RejectionReason};
 use crate::scheduler::Scheduler;
 
 pub struct Controller {
@@ -38,7 +39,8 @@ impl Controller {
         let reg = self.function_registry.borrow();
         let app = reg.get_app(app_id).unwrap();
         if let Some(host) = self.idle_deployer.deploy(app, &self.hosts) {
-            self.hosts[host].borrow_mut().try_deploy(app, time);
+            // prewarming is skipped if the host has no free resources
+            let _ = self.hosts[host].borrow_mut().try_deploy(app, time);
         }
     }
 
@@ -46,13 +48,58 @@ impl Controller {
         let reg = self.function_registry.borrow();
         let app = reg.get_app_by_function(func_id).unwrap();
         let host = self.scheduler.select_host(app, &self.hosts);
-        self.hosts[host].borrow_mut().invoke(id, time)
+        // forwarded invocations are re-routed to the next hosts in round-robin order,
+        // the invocation is rejected if all hosts forward it
+        for i in 0..self.hosts.len() {
+            let target = (host + i) % self.hosts.len();
+            let decision = self.hosts[target].borrow_mut().invoke(id, time);
+            if decision != InvokerDecision::Forward {
+                return decision;
+            }
+        }
+        self.hosts[host].borrow_mut().reject(id);
+        InvokerDecision::Rejected(RejectionReason::AllHostsForwarded)
+    }
+
+    /// Deploys warm pool containers of the application until there are at least `count` of them,
+    /// returns the resulting pool size, which may be less than `count` if the hosts run out of resources.
+    pub fn ensure_warm(&mut self, app_id: usize, count: usize, time: f64) -> usize {
+        let reg = self.function_registry.borrow();
+        let app = reg.get_app(app_id).unwrap();
+        let mut current: usize = self
+            .hosts
+            .iter()
+            .map(|host| host.borrow().pooled_container_count(app_id))
+            .sum();
+        while current < count {
+            match self.idle_deployer.deploy(app, &self.hosts) {
+                Some(host) if self.hosts[host].borrow_mut().try_deploy_pooled(app, time).is_ok() => current += 1,
+                _ => break,
+            }
+        }
+        current
     }
 
     pub fn add_host(&mut self, host: Rc<RefCell<Host>>) {
         self.hosts.push(host);
     }
 
+    pub fn unload_container(&self, host_id: usize, container_id: usize, time: f64) -> bool {
+        self.hosts[host_id].borrow_mut().unload_container(container_id, time)
+    }
+
+    pub fn queued_invocations(&self, host_id: usize) -> Vec<(usize, usize, f64)> {
+        self.hosts[host_id].borrow().queued_invocations()
+    }
+
+    pub fn container_status_log(&self, host_id: usize) -> Vec<ContainerStatusTransition> {
+        self.hosts[host_id].borrow().container_status_log()
+    }
+
+    pub fn container_unload_time(&self, host_id: usize, container_id: usize) -> Option<f64> {
+        self.hosts[host_id].borrow().container_unload_time(container_id)
+    }
+
     fn update_end_metrics(&mut self, time: f64) {
         for host in &mut self.hosts {
             host.borrow_mut().update_end_metrics(time);
@@ -63,6 +110,9 @@ impl Controller {
 impl EventHandler for Controller {
     fn on(&mut self, event: Event) {
         cast!(match event.data {
+            ContainerReadyEvent { host_id, id, app_id } => {
+                self.scheduler.on_container_ready(host_id, id, app_id, event.time);
+            }
             IdleDeployEvent { id } => {
                 self.idle_deploy(id, event.time);
             }
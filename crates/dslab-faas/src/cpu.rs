@@ -91,7 +91,10 @@ impl CpuPolicy for IgnoredCpuPolicy {
         if container.invocations.len() == 1 {
             self.load += container.cpu_share;
         }
-        ctx.emit_self(InvocationEndEvent { id: invocation.id }, invocation.duration);
+        ctx.emit_self(
+            InvocationEndEvent { id: invocation.id },
+            invocation.effective_duration(),
+        );
     }
 
     fn on_invocation_end(
@@ -144,16 +147,19 @@ impl CpuPolicy for IsolatedCpuPolicy {
         ctx: &mut SimulationContext,
     ) {
         if let Some(invs) = self.invocation_map.get_mut(&container.id) {
-            invs.push((invocation.id, invocation.duration));
+            invs.push((invocation.id, invocation.effective_duration()));
         } else if container.invocations.len() == 1 && self.load + container.cpu_share > self.cores + 1e-9 {
             self.invocation_map
-                .insert(container.id, vec![(invocation.id, invocation.duration)]);
+                .insert(container.id, vec![(invocation.id, invocation.effective_duration())]);
             self.queue.push_back((container.id, container.cpu_share));
         } else {
             if container.invocations.len() == 1 {
                 self.load += container.cpu_share;
             }
-            ctx.emit_self(InvocationEndEvent { id: invocation.id }, invocation.duration);
+            ctx.emit_self(
+                InvocationEndEvent { id: invocation.id },
+                invocation.effective_duration(),
+            );
         }
     }
 
@@ -285,7 +291,7 @@ impl CpuPolicy for ContendedCpuPolicy {
         }
         self.insert_invocation(
             invocation.id,
-            invocation.duration / self.cores * (container.invocations.len() as f64),
+            invocation.effective_duration() / self.cores * (container.invocations.len() as f64),
         );
         self.last_update = time;
         self.reschedule_end(ctx);
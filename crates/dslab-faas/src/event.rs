@@ -6,11 +6,27 @@ pub struct ContainerEndEvent {
     pub expected_count: usize,
 }
 
+/// Sent by the host to the controller when a container finishes deploying and can accept invocations.
+/// The host dequeues pending invocations at the same moment.
+#[derive(Clone, Serialize)]
+pub struct ContainerReadyEvent {
+    pub host_id: usize,
+    pub id: usize,
+    pub app_id: usize,
+}
+
 #[derive(Clone, Serialize)]
 pub struct ContainerStartEvent {
     pub id: usize,
 }
 
+/// Marks the end of the scheduling delay of a container deployed with
+/// [`crate::container::ResourceReservationPolicy::OnDeploymentStart`].
+#[derive(Clone, Serialize)]
+pub struct DeploymentStartEvent {
+    pub id: usize,
+}
+
 #[derive(Clone, Serialize)]
 pub struct IdleDeployEvent {
     pub id: usize,
@@ -27,5 +43,9 @@ pub struct InvocationStartEvent {
     pub func_id: usize,
 }
 
+/// Periodic trigger of the host scheduling loop, see [`crate::config::Config::scheduler_tick`].
+#[derive(Clone, Serialize)]
+pub struct SchedulerTickEvent {}
+
 #[derive(Clone, Serialize)]
 pub struct SimulationEndEvent {}
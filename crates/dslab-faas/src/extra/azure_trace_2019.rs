@@ -3,7 +3,7 @@
 use std::cmp::Ordering;
 use std::collections::{BTreeSet, HashMap};
 use std::fs::read_dir;
-use std::iter::repeat_with;
+use std::iter::{repeat_n, repeat_with};
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
@@ -26,6 +26,7 @@ pub struct ApplicationRecord {
     pub cold_start: f64,
 }
 
+/// Experiment generated from Azure Functions 2019 trace, can be loaded into the simulation as [`Trace`].
 #[derive(Default, Clone)]
 pub struct AzureTrace {
     pub concurrency_level: usize,
@@ -36,6 +37,35 @@ pub struct AzureTrace {
     pub app_records: Vec<ApplicationRecord>,
 }
 
+impl AzureTrace {
+    /// Scales the arrival rates of individual functions, `factors` maps function id to its scaling factor.
+    ///
+    /// Each request of a scaled function is replaced by `floor(factor)` copies with the same arrival time and duration,
+    /// plus one more copy with probability `factor - floor(factor)`. So the factors below 1 thin out the requests
+    /// of the function and the factors above 1 multiply them. Other functions are not affected,
+    /// the random choices are made with the generator initialized with `seed`.
+    pub fn scale_function_arrivals(&mut self, factors: &HashMap<usize, f64>, seed: u64) {
+        for (id, factor) in factors.iter() {
+            assert!(*factor >= 0., "arrival scaling factor of function {} is negative", id);
+        }
+        let mut gen = Pcg64::seed_from_u64(seed);
+        let mut records = Vec::with_capacity(self.trace_records.len());
+        for req in self.trace_records.drain(..) {
+            match factors.get(&req.id) {
+                Some(factor) => {
+                    let mut copies = factor.floor() as usize;
+                    if gen.gen_bool(factor - factor.floor()) {
+                        copies += 1;
+                    }
+                    records.extend(repeat_n(req, copies));
+                }
+                None => records.push(req),
+            }
+        }
+        self.trace_records = records;
+    }
+}
+
 impl Trace for AzureTrace {
     fn app_iter(&self) -> Box<dyn Iterator<Item = ApplicationData> + '_> {
         Box::new(self.app_records.iter().map(|x| {
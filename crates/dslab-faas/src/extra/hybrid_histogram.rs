@@ -4,6 +4,7 @@ use std::collections::hash_map::Entry;
 use std::collections::HashMap;
 
 use rand::prelude::*;
+use rand_pcg::Pcg64;
 
 use crate::coldstart::ColdStartPolicy;
 use crate::container::Container;
@@ -60,7 +61,8 @@ impl ApplicationData {
             Some(ma.as_slice()),
             1,
             &|_x, _y| 0.0,
-            &mut thread_rng(),
+            // the noise is zero, so the forecast does not depend on the generator state
+            &mut Pcg64::seed_from_u64(0),
         )
         .unwrap()[0]
     }
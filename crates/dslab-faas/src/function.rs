@@ -1,11 +1,52 @@
+use rand_distr::Normal;
+
+use dslab_core::context::SimulationContext;
+
 use crate::resource::ResourceConsumer;
 
+/// Distribution of container deployment time.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DeploymentTimeDistribution {
+    Constant(f64),
+    /// Normal distribution, negative draws are clamped to zero.
+    Normal {
+        mean: f64,
+        std_dev: f64,
+    },
+    /// Uniform choice among the observed samples.
+    Empirical(Vec<f64>),
+}
+
+impl DeploymentTimeDistribution {
+    /// Returns the mean of the distribution.
+    pub fn mean(&self) -> f64 {
+        match self {
+            Self::Constant(t) => *t,
+            Self::Normal { mean, .. } => *mean,
+            Self::Empirical(samples) => samples.iter().sum::<f64>() / samples.len() as f64,
+        }
+    }
+
+    /// Draws a deployment time using the simulation RNG. Constant distribution does not use the RNG.
+    pub fn sample(&self, ctx: &SimulationContext) -> f64 {
+        match self {
+            Self::Constant(t) => *t,
+            Self::Normal { mean, std_dev } => {
+                let dist = Normal::new(*mean, *std_dev).unwrap();
+                f64::max(0., ctx.sample_from_distribution(&dist))
+            }
+            Self::Empirical(samples) => samples[ctx.gen_range(0..samples.len())],
+        }
+    }
+}
+
 /// An application shares a common container image.
 /// Functions from the same application can be executed on the same container (limited by concurrent_invocations field).
 pub struct Application {
     pub id: usize,
     concurrent_invocations: usize,
-    container_deployment_time: f64,
+    priority: u32,
+    container_deployment_time: DeploymentTimeDistribution,
     container_cpu_share: f64,
     container_resources: ResourceConsumer,
 }
@@ -20,7 +61,8 @@ impl Application {
         Self {
             id: usize::MAX,
             concurrent_invocations,
-            container_deployment_time,
+            priority: 0,
+            container_deployment_time: DeploymentTimeDistribution::Constant(container_deployment_time),
             container_cpu_share,
             container_resources,
         }
@@ -30,8 +72,34 @@ impl Application {
         self.concurrent_invocations
     }
 
+    /// Priority of application invocations, which is used by [`crate::invoker::PriorityInvoker`].
+    /// Higher values mean higher priority, default is 0.
+    pub fn get_priority(&self) -> u32 {
+        self.priority
+    }
+
+    pub fn set_priority(&mut self, priority: u32) {
+        self.priority = priority;
+    }
+
+    /// Returns the mean container deployment time.
     pub fn get_deployment_time(&self) -> f64 {
-        self.container_deployment_time
+        self.container_deployment_time.mean()
+    }
+
+    pub fn get_deployment_time_distribution(&self) -> &DeploymentTimeDistribution {
+        &self.container_deployment_time
+    }
+
+    /// Sets the distribution of container deployment time, by default it is constant.
+    pub fn set_deployment_time_distribution(&mut self, dist: DeploymentTimeDistribution) {
+        if let DeploymentTimeDistribution::Empirical(samples) = &dist {
+            assert!(
+                !samples.is_empty(),
+                "empirical distribution requires at least one sample"
+            );
+        }
+        self.container_deployment_time = dist;
     }
 
     pub fn get_cpu_share(&self) -> f64 {
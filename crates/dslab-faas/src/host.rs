@@ -7,15 +7,22 @@ use dslab_core::event::Event;
 use dslab_core::handler::EventHandler;
 
 use crate::coldstart::ColdStartPolicy;
-use crate::container::{ContainerManager, ContainerStatus};
+use crate::container::{
+    Container, ContainerManager, ContainerSelectionPolicy, ContainerStatus, ContainerStatusTransition,
+    ResourceReservationPolicy,
+};
 use crate::cpu::{Cpu, CpuPolicy};
-use crate::event::{ContainerEndEvent, ContainerStartEvent, IdleDeployEvent, InvocationEndEvent};
+use crate::event::{
+    ContainerEndEvent, ContainerReadyEvent, ContainerStartEvent, DeploymentStartEvent, IdleDeployEvent,
+    InvocationEndEvent, SchedulerTickEvent,
+};
 use crate::function::{Application, FunctionRegistry};
 use crate::invocation::{InvocationRegistry, InvocationStatus};
-use crate::invoker::{Invoker, InvokerDecision};
+use crate::invoker::{Invoker, InvokerDecision, RejectionReason};
 use crate::resource::{ResourceConsumer, ResourceProvider};
 use crate::simulation::HandlerId;
 use crate::stats::Stats;
+use crate::warmth::CacheWarmthModel;
 
 pub struct Host {
     id: usize,
@@ -27,6 +34,10 @@ pub struct Host {
     coldstart: Rc<RefCell<dyn ColdStartPolicy>>,
     controller_id: HandlerId,
     stats: Rc<RefCell<Stats>>,
+    cache_warmth: Option<CacheWarmthModel>,
+    // interval of polling the invoker queue, `None` means that the queue is processed on every container release
+    scheduler_tick: Option<f64>,
+    tick_scheduled: bool,
     ctx: Rc<RefCell<SimulationContext>>,
 }
 
@@ -43,19 +54,25 @@ impl Host {
         coldstart: Rc<RefCell<dyn ColdStartPolicy>>,
         controller_id: HandlerId,
         stats: Rc<RefCell<Stats>>,
+        drain_delay: f64,
+        selection_policy: ContainerSelectionPolicy,
+        cache_warmth: Option<CacheWarmthModel>,
         ctx: SimulationContext,
     ) -> Self {
         let ctx = Rc::new(RefCell::new(ctx));
         Self {
             id,
             invoker,
-            container_manager: ContainerManager::new(resources, ctx.clone()),
+            container_manager: ContainerManager::new(resources, drain_delay, selection_policy, ctx.clone()),
             cpu: Cpu::new(cores, cpu_policy, ctx.clone()),
             function_registry,
             invocation_registry,
             coldstart,
             controller_id,
             stats,
+            cache_warmth,
+            scheduler_tick: None,
+            tick_scheduled: false,
             ctx,
         }
     }
@@ -79,6 +96,34 @@ impl Host {
         self.invoker.queue_len()
     }
 
+    /// Returns (invocation id, application id, arrival time) tuples of invocations queued by the invoker.
+    pub fn queued_invocations(&self) -> Vec<(usize, usize, f64)> {
+        self.invoker.queued_invocations()
+    }
+
+    pub fn enable_container_status_log(&mut self) {
+        self.container_manager.enable_status_log();
+    }
+
+    pub fn set_resource_reservation_policy(&mut self, policy: ResourceReservationPolicy) {
+        self.container_manager.set_reservation_policy(policy);
+    }
+
+    pub fn enable_waiting_for_busy_containers(&mut self) {
+        self.container_manager.enable_waiting_for_busy_containers();
+    }
+
+    /// Makes the host start queued invocations only at the multiples of `interval`, see
+    /// [`crate::config::Config::scheduler_tick`].
+    pub fn set_scheduler_tick(&mut self, interval: f64) {
+        assert!(interval > 0., "scheduler tick interval must be positive");
+        self.scheduler_tick = Some(interval);
+    }
+
+    pub fn container_status_log(&self) -> Vec<ContainerStatusTransition> {
+        self.container_manager.status_log().to_vec()
+    }
+
     pub fn total_invocation_count(&self) -> usize {
         self.active_invocation_count() + self.queued_invocation_count()
     }
@@ -106,10 +151,19 @@ impl Host {
             &mut self.container_manager,
             time,
         );
+        Self::record_evictions(&mut self.container_manager, &self.stats, time);
         let mut stats = self.stats.borrow_mut();
+        if status == InvokerDecision::Forward {
+            // the invocation is counted by the host which finally accepts or rejects it
+            invocation.host_id = None;
+            stats.on_forward(invocation.app_id, invocation.func_id);
+            self.container_manager.dec_active_invocations();
+            return status;
+        }
         stats.on_new_invocation(invocation.app_id, invocation.func_id);
         match status {
             InvokerDecision::Warm(container_id) => {
+                stats.on_warm_start(invocation.app_id, invocation.func_id);
                 drop(stats);
                 drop(ir);
                 self.start_invocation(container_id, id, time);
@@ -121,56 +175,143 @@ impl Host {
                 drop(stats);
                 self.container_manager.reserve_container(container_id, id);
             }
-            _ => {
+            InvokerDecision::Rejected(reason) => {
+                invocation.status = InvocationStatus::Rejected;
+                stats.on_rejection(invocation.app_id, invocation.func_id, reason);
+                self.container_manager.dec_active_invocations();
+            }
+            InvokerDecision::Queued => {
                 invocation.status = InvocationStatus::Queued;
+                drop(stats);
+                drop(ir);
+                self.schedule_tick();
             }
+            InvokerDecision::Forward => unreachable!(),
         }
         status
     }
 
-    pub fn try_deploy(&mut self, app: &Application, time: f64) -> Option<(usize, f64)> {
+    /// Rejects the invocation without passing it to the invoker, e.g. when it was forwarded by all hosts.
+    pub fn reject(&mut self, id: usize) {
+        let mut ir = self.invocation_registry.borrow_mut();
+        let invocation = &mut ir[id];
+        invocation.host_id = Some(self.id);
+        invocation.status = InvocationStatus::Rejected;
+        let mut stats = self.stats.borrow_mut();
+        stats.on_new_invocation(invocation.app_id, invocation.func_id);
+        stats.on_rejection(
+            invocation.app_id,
+            invocation.func_id,
+            RejectionReason::AllHostsForwarded,
+        );
+    }
+
+    pub fn try_deploy(&mut self, app: &Application, time: f64) -> Result<(usize, f64), RejectionReason> {
         self.container_manager.try_deploy(app, time)
     }
 
+    /// Deploys a container of the application warm pool.
+    pub fn try_deploy_pooled(&mut self, app: &Application, time: f64) -> Result<(usize, f64), RejectionReason> {
+        let (id, delay) = self.container_manager.try_deploy(app, time)?;
+        self.container_manager.get_container_mut(id).unwrap().pooled = true;
+        Ok((id, delay))
+    }
+
+    /// Returns the number of warm pool containers of the application on this host.
+    pub fn pooled_container_count(&self, app_id: usize) -> usize {
+        self.container_manager.pooled_container_count(app_id)
+    }
+
+    /// Iterates over all containers of this host, see [`ContainerManager::iter_containers`].
+    pub fn iter_containers(&self) -> impl Iterator<Item = &Container> {
+        self.container_manager.iter_containers()
+    }
+
+    /// Same as [`ContainerManager::unload_time`].
+    pub fn container_unload_time(&self, container_id: usize) -> Option<f64> {
+        self.container_manager.unload_time(container_id)
+    }
+
+    /// Same as [`ContainerManager::container_count_by_status`].
+    pub fn container_count_by_status(&self) -> [usize; 4] {
+        self.container_manager.container_count_by_status()
+    }
+
     pub fn update_end_metrics(&mut self, time: f64) {
         let mut stats = self.stats.borrow_mut();
         for (_, container) in self.container_manager.get_containers().iter_mut() {
-            if container.status == ContainerStatus::Idle {
+            let draining_empty = container.status == ContainerStatus::Draining && container.invocations.is_empty();
+            if container.status == ContainerStatus::Idle || draining_empty {
                 let delta = time - container.last_change;
-                stats.update_wasted_resources(delta, &container.resources);
+                stats.update_idle_container(delta, container);
                 container.last_change = time;
             }
+            container.update_busy_time(time);
+            if let Some(utilization) = container.concurrency_utilization() {
+                stats.update_concurrency_utilization(container.app_id, utilization);
+            }
+        }
+    }
+
+    /// Unloads the container: idle containers are deleted immediately, running ones start draining
+    /// and are deleted after their invocations finish and the drain delay passes.
+    /// Returns false if the container does not exist or is still deploying.
+    pub fn unload_container(&mut self, id: usize, time: f64) -> bool {
+        let (status, expected) = match self.container_manager.get_container(id) {
+            Some(cont) => (&cont.status, cont.started_invocations),
+            None => return false,
+        };
+        match status {
+            ContainerStatus::Idle => {
+                self.on_container_end(id, expected, time);
+                true
+            }
+            ContainerStatus::Running => self.container_manager.start_draining(id, time),
+            ContainerStatus::Draining => true,
+            ContainerStatus::Deploying => false,
         }
     }
 
     fn start_invocation(&mut self, cont_id: usize, id: usize, time: f64) {
-        let container = self.container_manager.get_container_mut(cont_id).unwrap();
+        let container = self.container_manager.get_container(cont_id).unwrap();
         if container.status == ContainerStatus::Idle {
             let delta = time - container.last_change;
-            self.stats
-                .borrow_mut()
-                .update_wasted_resources(delta, &container.resources);
+            self.stats.borrow_mut().update_idle_container(delta, container);
         }
-        container.last_change = time;
-        container.status = ContainerStatus::Running;
-        container.start_invocation(id);
+        let container = self.container_manager.start_invocation(cont_id, id, time);
         let mut ir = self.invocation_registry.borrow_mut();
         let mut invocation = &mut ir[id];
         invocation.start_time = Some(time);
         invocation.status = InvocationStatus::Running;
         invocation.container_id = Some(cont_id);
+        if let Some(model) = &self.cache_warmth {
+            invocation.warmup_penalty = model.penalty(container.idle_duration, invocation.duration);
+        }
+        container.set_expected_end_time(id, time + invocation.effective_duration());
         self.cpu.on_new_invocation(invocation, container, time);
     }
 
     fn on_container_start(&mut self, id: usize, time: f64) {
+        let app_id = self.container_manager.get_container(id).unwrap().app_id;
+        self.ctx.borrow_mut().emit(
+            ContainerReadyEvent {
+                host_id: self.id,
+                id,
+                app_id,
+            },
+            self.controller_id,
+            0.,
+        );
         if let Some(invocations) = self.container_manager.take_reservations(id) {
             for invocation in invocations {
                 self.start_invocation(id, invocation, time);
             }
         } else {
-            let container = self.container_manager.get_container_mut(id).unwrap();
-            container.status = ContainerStatus::Idle;
+            self.container_manager.set_idle(id, time);
             let immut_container = self.container_manager.get_container(id).unwrap();
+            if immut_container.pooled {
+                return;
+            }
             let keepalive = self.coldstart.borrow_mut().keepalive_window(immut_container);
             self.new_container_end_event(id, 0, keepalive);
         }
@@ -178,14 +319,44 @@ impl Host {
 
     pub fn on_container_end(&mut self, id: usize, expected: usize, time: f64) {
         if let Some(cont) = self.container_manager.get_container(id) {
-            if cont.status == ContainerStatus::Idle && cont.started_invocations == expected {
+            let unloadable = cont.status == ContainerStatus::Idle || cont.status == ContainerStatus::Draining;
+            if unloadable && cont.started_invocations == expected {
                 let delta = time - cont.last_change;
-                self.stats.borrow_mut().update_wasted_resources(delta, &cont.resources);
-                self.container_manager.delete_container(id);
+                let mut stats = self.stats.borrow_mut();
+                stats.update_idle_container(delta, cont);
+                if let Some(utilization) = cont.concurrency_utilization() {
+                    stats.update_concurrency_utilization(cont.app_id, utilization);
+                }
+                if cont.started_invocations == 0 {
+                    stats.on_wasted_deployment(cont.app_id);
+                }
+                stats.on_container_end(time, cont);
+                drop(stats);
+                self.container_manager.delete_container(id, time);
             }
         }
     }
 
+    /// Accounts containers evicted by the invoker in stats.
+    fn record_evictions(cm: &mut ContainerManager, stats: &RefCell<Stats>, time: f64) {
+        let evicted = cm.take_evicted();
+        if evicted.is_empty() {
+            return;
+        }
+        let mut stats = stats.borrow_mut();
+        for cont in evicted {
+            stats.update_idle_container(time - cont.last_change, &cont);
+            if let Some(utilization) = cont.concurrency_utilization() {
+                stats.update_concurrency_utilization(cont.app_id, utilization);
+            }
+            if cont.started_invocations == 0 {
+                stats.on_wasted_deployment(cont.app_id);
+            }
+            stats.on_container_end(time, &cont);
+            stats.on_eviction(cont.app_id);
+        }
+    }
+
     pub fn on_invocation_end(&mut self, id: usize, time: f64) {
         let ir = self.invocation_registry.clone();
         let fr = self.function_registry.clone();
@@ -201,13 +372,15 @@ impl Host {
             .borrow_mut()
             .update(invocation, self.function_registry.borrow().get_app(app_id).unwrap());
         self.container_manager.dec_active_invocations();
-        let container = self.container_manager.get_container_mut(cont_id).unwrap();
-        container.end_invocation(id, time);
+        let container = self.container_manager.end_invocation(cont_id, id, time);
         self.stats.borrow_mut().update_invocation_stats(invocation);
         self.cpu.on_invocation_end(invocation, container, time);
         let expect = container.started_invocations;
         let app = function_registry.get_app(app_id).unwrap();
-        if container.status == ContainerStatus::Idle {
+        if container.status == ContainerStatus::Idle && container.pooled {
+            // warm pool containers are kept regardless of the cold start policy
+            container.unload_time = None;
+        } else if container.status == ContainerStatus::Idle {
             let prewarm = f64::max(0.0, self.coldstart.borrow_mut().prewarm_window(app));
             if prewarm != 0. {
                 self.ctx
@@ -219,10 +392,18 @@ impl Host {
                 let keepalive = f64::max(0.0, self.coldstart.borrow_mut().keepalive_window(immut_container));
                 self.new_container_end_event(cont_id, expect, keepalive);
             }
+        } else if container.status == ContainerStatus::Draining && container.invocations.is_empty() {
+            let delay = self.container_manager.drain_delay();
+            self.new_container_end_event(cont_id, expect, delay);
         }
     }
 
     fn new_container_end_event(&mut self, container_id: usize, expected: usize, delay: f64) {
+        let unload_time = self.ctx.borrow().time() + delay;
+        self.container_manager
+            .get_container_mut(container_id)
+            .unwrap()
+            .unload_time = Some(unload_time);
         self.ctx.borrow_mut().emit_self(
             ContainerEndEvent {
                 id: container_id,
@@ -232,15 +413,46 @@ impl Host {
         );
     }
 
-    fn dequeue_requests(&mut self, time: f64) {
+    // In the event-driven mode the queue is processed right away, otherwise it waits for the next tick.
+    fn on_slot_released(&mut self, time: f64) {
+        if self.scheduler_tick.is_none() {
+            self.dequeue_requests(time);
+        } else {
+            self.schedule_tick();
+        }
+    }
+
+    // Ticks are aligned to the multiples of the interval and are scheduled only while there are queued invocations
+    // which may still be started, so that they do not keep the simulation running forever.
+    fn schedule_tick(&mut self) {
+        let Some(interval) = self.scheduler_tick else {
+            return;
+        };
+        if self.tick_scheduled || self.queued_invocation_count() == 0 {
+            return;
+        }
+        let time = self.ctx.borrow().time();
+        let next_tick = ((time / interval).floor() + 1.) * interval;
+        self.ctx.borrow_mut().emit_self(SchedulerTickEvent {}, next_tick - time);
+        self.tick_scheduled = true;
+    }
+
+    // Returns true if some queued invocations were started or dropped.
+    fn dequeue_requests(&mut self, time: f64) -> bool {
         let mut reqs = self.invoker.dequeue(
             self.function_registry.clone(),
             &mut self.container_manager,
             &mut self.stats.borrow_mut(),
             time,
         );
+        Self::record_evictions(&mut self.container_manager, &self.stats, time);
+        let dropped = self.invoker.take_dropped();
+        for &id in dropped.iter() {
+            self.invocation_registry.borrow_mut()[id].status = InvocationStatus::TimedOut;
+            self.container_manager.dec_active_invocations();
+        }
         if reqs.is_empty() {
-            return;
+            return !dropped.is_empty();
         }
         for req in reqs.drain(..) {
             let mut ir = self.invocation_registry.borrow_mut();
@@ -250,11 +462,23 @@ impl Host {
                 invocation.status = InvocationStatus::Running;
                 invocation.start_time = Some(time);
                 let container = self.container_manager.get_container_mut(req.container_id).unwrap();
+                if let Some(model) = &self.cache_warmth {
+                    invocation.warmup_penalty = model.penalty(container.idle_duration, invocation.duration);
+                }
+                container.set_expected_end_time(req.id, time + invocation.effective_duration());
                 self.cpu.on_new_invocation(invocation, container, time);
             } else {
                 invocation.status = InvocationStatus::WaitingForContainer;
             }
         }
+        true
+    }
+
+    // Returns true if some container is going to release a slot, which will schedule the next tick anyway.
+    fn has_busy_containers(&self) -> bool {
+        self.container_manager
+            .iter_containers()
+            .any(|c| c.status == ContainerStatus::Deploying || c.status == ContainerStatus::Running)
     }
 }
 
@@ -263,14 +487,25 @@ impl EventHandler for Host {
         cast!(match event.data {
             ContainerStartEvent { id } => {
                 self.on_container_start(id, event.time);
-                self.dequeue_requests(event.time);
+                self.on_slot_released(event.time);
+            }
+            DeploymentStartEvent { id } => {
+                self.container_manager.start_deployment(id, event.time);
             }
             ContainerEndEvent { id, expected_count } => {
                 self.on_container_end(id, expected_count, event.time);
             }
             InvocationEndEvent { id } => {
                 self.on_invocation_end(id, event.time);
-                self.dequeue_requests(event.time);
+                self.on_slot_released(event.time);
+            }
+            SchedulerTickEvent {} => {
+                self.tick_scheduled = false;
+                // stop ticking if the queue is stuck, e.g. queued invocations do not fit into the host,
+                // the ticks are resumed when a slot is released or a new invocation is queued
+                if self.dequeue_requests(event.time) || self.has_busy_containers() {
+                    self.schedule_tick();
+                }
             }
         });
     }
@@ -12,6 +12,10 @@ pub enum InvocationStatus {
     Running,
     /// Invocation is finished.
     Finished,
+    /// Invocation is rejected by the invoker, e.g. due to queue overflow.
+    Rejected,
+    /// Invocation is dropped from the invoker queue after waiting longer than the queueing timeout.
+    TimedOut,
 }
 
 #[derive(Copy, Clone)]
@@ -20,6 +24,8 @@ pub struct Invocation {
     pub app_id: usize,
     pub func_id: usize,
     pub duration: f64,
+    /// Extra execution time caused by cold runtime caches of a container reused after a long idle period.
+    pub warmup_penalty: f64,
     pub arrival_time: f64,
     pub status: InvocationStatus,
     pub host_id: Option<usize>,
@@ -29,6 +35,11 @@ pub struct Invocation {
 }
 
 impl Invocation {
+    /// Execution time of the invocation on a dedicated CPU, including the warmup penalty.
+    pub fn effective_duration(&self) -> f64 {
+        self.duration + self.warmup_penalty
+    }
+
     pub fn execution_time(&self) -> f64 {
         self.finish_time.unwrap() - self.start_time.unwrap()
     }
@@ -55,6 +66,7 @@ impl InvocationRegistry {
             app_id,
             func_id,
             duration,
+            warmup_penalty: 0.,
             arrival_time,
             status: InvocationStatus::NotArrived,
             host_id: None,
@@ -1,19 +1,56 @@
 use std::boxed::Box;
 use std::cell::RefCell;
-use std::collections::VecDeque;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
 use std::rc::Rc;
 
-use crate::container::{ContainerManager, ContainerStatus};
+use serde::Serialize;
+
+use crate::container::{Container, ContainerManager, ContainerSelectionPolicy, ContainerStatus};
 use crate::function::{Application, FunctionRegistry};
 use crate::invocation::Invocation;
+use crate::rng::SimulationRng;
 use crate::stats::Stats;
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum InvokerDecision {
+    /// The invocation starts immediately in an idle container or in a running container with free concurrency slots.
     Warm(usize),
     Cold((usize, f64)),
+    /// The invocation is queued, e.g. because the host has no capacity for it, or because waiting for a busy
+    /// container is expected to be faster than a cold start (see [`crate::config::Config::wait_for_busy_containers`]).
     Queued,
-    Rejected,
+    Rejected(RejectionReason),
+    /// The host has no capacity for the invocation, so it should be re-routed to another host by the controller.
+    /// Forwarded invocations are not put into the local queue.
+    Forward,
+}
+
+/// Cause of invocation rejection.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub enum RejectionReason {
+    /// There are no suitable containers and the host has not enough free resources to deploy a new one.
+    InsufficientResources,
+    /// The invoker queue is full.
+    QueueFull,
+    /// The invocation was forwarded by all hosts.
+    AllHostsForwarded,
+}
+
+impl RejectionReason {
+    pub const ALL: [RejectionReason; 3] = [
+        RejectionReason::InsufficientResources,
+        RejectionReason::QueueFull,
+        RejectionReason::AllHostsForwarded,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RejectionReason::InsufficientResources => "insufficient_resources",
+            RejectionReason::QueueFull => "queue_full",
+            RejectionReason::AllHostsForwarded => "all_hosts_forwarded",
+        }
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -33,8 +70,31 @@ impl DequeuedInvocation {
     }
 }
 
+// Compares idle containers by their scheduled unload time, containers without it go last.
+fn compare_unload_time(c: &Container, other: &Container) -> Ordering {
+    match (c.unload_time, other.unload_time) {
+        (Some(t), Some(other_t)) => t.total_cmp(&other_t),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+// Returns true if container `c` should be preferred over container `other` with the same wait time.
+// The remaining ties are broken by container id, so that the choice does not depend on the iteration order.
+fn is_preferred(c: &Container, other: &Container, policy: ContainerSelectionPolicy) -> bool {
+    let both_idle = c.status == ContainerStatus::Idle && other.status == ContainerStatus::Idle;
+    let order = if policy == ContainerSelectionPolicy::LeastRemainingKeepalive && both_idle {
+        compare_unload_time(c, other)
+    } else {
+        Ordering::Equal
+    };
+    order.then(c.id.cmp(&other.id)) == Ordering::Less
+}
+
 fn try_invoke(app: &Application, cm: &mut ContainerManager, time: f64) -> InvokerDecision {
-    let mut nearest: Option<usize> = None;
+    let policy = cm.selection_policy();
+    let mut nearest: Option<&Container> = None;
     let mut wait = 0.0;
     for c in cm.get_possible_containers(app, true) {
         let delay = if c.status == ContainerStatus::Deploying {
@@ -42,28 +102,77 @@ fn try_invoke(app: &Application, cm: &mut ContainerManager, time: f64) -> Invoke
         } else {
             0.0
         };
-        if nearest.is_none() || wait > delay {
+        let better = match nearest {
+            None => true,
+            Some(n) => delay < wait || (delay == wait && is_preferred(c, n, policy)),
+        };
+        if better {
             wait = delay;
-            nearest = Some(c.id);
+            nearest = Some(c);
         }
     }
-    if let Some(id) = nearest {
-        if cm.get_container(id).unwrap().status == ContainerStatus::Idle {
+    if let Some(id) = nearest.map(|c| c.id) {
+        // running containers are only returned if they have free concurrency slots
+        if cm.get_container(id).unwrap().status == ContainerStatus::Deploying {
+            return InvokerDecision::Cold((id, wait));
+        } else {
             return InvokerDecision::Warm(id);
+        }
+    }
+    if cm.waits_for_busy_containers() {
+        // the invocation is dequeued once a slot frees up, so waiting is a choice only if it beats the cold start
+        if let Some((_, wait)) = cm.nearest_busy_container(app, time) {
+            if wait < app.get_deployment_time() {
+                return InvokerDecision::Queued;
+            }
+        }
+    }
+    match cm.try_deploy(app, time) {
+        Ok((id, delay)) => InvokerDecision::Cold((id, delay)),
+        Err(reason) => InvokerDecision::Rejected(reason),
+    }
+}
+
+// Same as `try_invoke`, but picks a container uniformly at random among all suitable ones.
+fn try_invoke_random(app: &Application, cm: &mut ContainerManager, time: f64, rng: &SimulationRng) -> InvokerDecision {
+    let mut candidates: Vec<&Container> = cm.get_possible_containers(app, true).collect();
+    if !candidates.is_empty() {
+        // sort by id, so that the choice does not depend on the iteration order
+        candidates.sort_by_key(|c| c.id);
+        let c = candidates[rng.gen_range(0..candidates.len())];
+        // running containers are only returned if they have free concurrency slots
+        if c.status == ContainerStatus::Deploying {
+            return InvokerDecision::Cold((c.id, c.deployment_time + c.last_change - time));
         } else {
-            return InvokerDecision::Cold((id, wait));
+            return InvokerDecision::Warm(c.id);
         }
     }
-    if let Some((id, delay)) = cm.try_deploy(app, time) {
-        return InvokerDecision::Cold((id, delay));
+    match cm.try_deploy(app, time) {
+        Ok((id, delay)) => InvokerDecision::Cold((id, delay)),
+        Err(reason) => InvokerDecision::Rejected(reason),
+    }
+}
+
+// Returns true if the decision made by `try_invoke` defers the invocation, so that it should be queued.
+fn is_deferred(decision: &InvokerDecision) -> bool {
+    matches!(decision, InvokerDecision::Rejected(_) | InvokerDecision::Queued)
+}
+
+fn claim_container(decision: InvokerDecision, cm: &mut ContainerManager) {
+    match decision {
+        InvokerDecision::Warm(id) | InvokerDecision::Cold((id, _)) => cm.claim_container(id),
+        _ => {}
     }
-    InvokerDecision::Rejected
 }
 
 /// Invoker handles invocations at host level.
 /// It chooses containers for execution, deploys new containers and manages invocation queue.
 pub trait Invoker {
     /// Try to invoke some of the queued functions.
+    ///
+    /// Only the invocations accepted by [`Invoker::invoke`] with `Queued` decision are present in the queue.
+    /// Forwarded invocations belong to other hosts after re-routing, so they must not be re-queued
+    /// or dequeued locally.
     fn dequeue(
         &mut self,
         fr: Rc<RefCell<FunctionRegistry>>,
@@ -72,7 +181,8 @@ pub trait Invoker {
         time: f64,
     ) -> Vec<DequeuedInvocation>;
 
-    /// Invoke or queue new invocation.
+    /// Invoke or queue new invocation. Returns `Rejected` with the cause if the invocation can be neither invoked
+    /// nor queued, or `Forward` if the invoker asks the controller to re-route the invocation to another host.
     fn invoke(
         &mut self,
         invocation: &Invocation,
@@ -81,8 +191,46 @@ pub trait Invoker {
         time: f64,
     ) -> InvokerDecision;
 
+    /// Invokes or queues several invocations arriving at the same time, returns the decisions in the same order.
+    ///
+    /// The decisions are made one after another, and the containers chosen for the previous invocations
+    /// are claimed until the end of the call, so they are not chosen for more invocations than they can run.
+    /// The caller must apply the decisions in order, as if [`Invoker::invoke`] was called for each invocation.
+    fn invoke_many(
+        &mut self,
+        invocations: &[Invocation],
+        fr: Rc<RefCell<FunctionRegistry>>,
+        cm: &mut ContainerManager,
+        time: f64,
+    ) -> Vec<InvokerDecision> {
+        let mut decisions = Vec::with_capacity(invocations.len());
+        for invocation in invocations {
+            let decision = self.invoke(invocation, fr.clone(), cm, time);
+            claim_container(decision, cm);
+            decisions.push(decision);
+        }
+        cm.release_claims();
+        decisions
+    }
+
     fn queue_len(&self) -> usize;
 
+    /// Returns queued invocations as (invocation id, application id, arrival time) tuples.
+    /// The default implementation returns an empty vector, i.e. the queue contents are not exposed.
+    fn queued_invocations(&self) -> Vec<(usize, usize, f64)> {
+        Vec::new()
+    }
+
+    /// Returns ids of queued invocations that were dropped without invocation since the last call,
+    /// e.g. due to queueing timeout.
+    fn take_dropped(&mut self) -> Vec<usize> {
+        Vec::new()
+    }
+
+    /// Passes the simulation-wide random number generator, which should be used by randomized invokers.
+    /// Called once when the host with this invoker is added to the simulation.
+    fn set_rng(&mut self, _rng: SimulationRng) {}
+
     fn to_string(&self) -> String {
         "STUB INVOKER NAME".to_string()
     }
@@ -105,19 +253,85 @@ impl InvokerQueueItem {
             time,
         }
     }
+
+    fn as_tuple(&self) -> (usize, usize, f64) {
+        (self.invocation_id, self.app_id, self.time)
+    }
+}
+
+fn is_timed_out(item: &InvokerQueueItem, timeout: Option<f64>, time: f64) -> bool {
+    matches!(timeout, Some(timeout) if time - item.time > timeout)
+}
+
+// Drops a queued invocation which waited too long.
+fn drop_timed_out(item: &InvokerQueueItem, stats: &mut Stats, dropped: &mut Vec<usize>) {
+    stats.on_queue_timeout(item.app_id, item.func_id);
+    dropped.push(item.invocation_id);
+}
+
+// Starts a queued invocation according to the decision made by `try_invoke`.
+fn start_queued(
+    item: &InvokerQueueItem,
+    decision: InvokerDecision,
+    cm: &mut ContainerManager,
+    stats: &mut Stats,
+    time: f64,
+) -> DequeuedInvocation {
+    stats.update_queueing_time(item.app_id, item.func_id, time - item.time);
+    match decision {
+        InvokerDecision::Warm(id) => {
+            let container = cm.get_container(id).unwrap();
+            if container.status == ContainerStatus::Idle {
+                let delta = time - container.last_change;
+                stats.update_idle_container(delta, container);
+            }
+            stats.on_warm_start(item.app_id, item.func_id);
+            cm.start_invocation(id, item.invocation_id, time);
+            DequeuedInvocation::new(item.invocation_id, id, None)
+        }
+        InvokerDecision::Cold((id, delay)) => {
+            cm.reserve_container(id, item.invocation_id);
+            stats.on_cold_start(item.app_id, item.func_id, time - item.time + delay);
+            DequeuedInvocation::new(item.invocation_id, id, Some(delay))
+        }
+        _ => {
+            panic!("start_queued expects only Warm or Cold decision");
+        }
+    }
 }
 
 /// `NaiveInvoker` iterates over all queued invocations and tries to invoke each of them.
 /// In case of large queues it may be very slow, use [`FIFOInvoker`] instead.
+/// Within one pass over the queue the host state only gets more loaded, so once an invocation of some application
+/// is rejected, the remaining invocations of this application are kept in the queue without trying to invoke them.
+/// This makes a pass over the queue of a saturated host much cheaper without changing the invoker decisions.
+/// Optionally, the invocations which waited in the queue longer than the given timeout are dropped.
 #[derive(Default)]
 pub struct NaiveInvoker {
     queue: Vec<InvokerQueueItem>,
+    queueing_timeout: Option<f64>,
+    dropped: Vec<usize>,
 }
 
 impl NaiveInvoker {
     pub fn new() -> Self {
         Default::default()
     }
+
+    pub fn with_queueing_timeout(queueing_timeout: f64) -> Self {
+        Self {
+            queueing_timeout: Some(queueing_timeout),
+            ..Default::default()
+        }
+    }
+
+    /// Creates invoker from `timeout` option, which is optional.
+    pub fn from_options_map(options: &HashMap<String, String>) -> Result<Self, String> {
+        Ok(Self {
+            queueing_timeout: parse_option(options, "timeout")?,
+            ..Default::default()
+        })
+    }
 }
 
 impl Invoker for NaiveInvoker {
@@ -133,37 +347,25 @@ impl Invoker for NaiveInvoker {
         }
         let mut new_queue = Vec::new();
         let mut dequeued = Vec::new();
+        let mut rejected_apps = HashSet::new();
         for item in self.queue.drain(..) {
+            if is_timed_out(&item, self.queueing_timeout, time) {
+                drop_timed_out(&item, stats, &mut self.dropped);
+                continue;
+            }
+            if rejected_apps.contains(&item.app_id) {
+                new_queue.push(item);
+                continue;
+            }
             let fr_ref = fr.borrow();
             let app = fr_ref.get_app(item.app_id).unwrap();
             let decision = try_invoke(app, cm, time);
             drop(fr_ref);
-            match decision {
-                InvokerDecision::Warm(id) => {
-                    stats.update_queueing_time(item.app_id, item.func_id, time - item.time);
-                    let container = cm.get_container_mut(id).unwrap();
-                    if container.status == ContainerStatus::Idle {
-                        let delta = time - container.last_change;
-                        stats.update_wasted_resources(delta, &container.resources);
-                    }
-                    stats.on_cold_start(item.app_id, item.func_id, time - item.time);
-                    container.last_change = time;
-                    container.status = ContainerStatus::Running;
-                    container.start_invocation(item.invocation_id);
-                    dequeued.push(DequeuedInvocation::new(item.invocation_id, id, None));
-                }
-                InvokerDecision::Cold((id, delay)) => {
-                    stats.update_queueing_time(item.app_id, item.func_id, time - item.time);
-                    cm.reserve_container(id, item.invocation_id);
-                    stats.on_cold_start(item.app_id, item.func_id, time - item.time + delay);
-                    dequeued.push(DequeuedInvocation::new(item.invocation_id, id, Some(delay)));
-                }
-                InvokerDecision::Rejected => {
-                    new_queue.push(item);
-                }
-                _ => {
-                    panic!("try_invoke should only return Warm, Cold or Rejected");
-                }
+            if is_deferred(&decision) {
+                rejected_apps.insert(item.app_id);
+                new_queue.push(item);
+            } else {
+                dequeued.push(start_queued(&item, decision, cm, stats, time));
             }
         }
         self.queue = new_queue;
@@ -180,7 +382,7 @@ impl Invoker for NaiveInvoker {
         let fr_ref = fr.borrow();
         let app = fr_ref.get_app(invocation.app_id).unwrap();
         let decision = try_invoke(app, cm, time);
-        if decision == InvokerDecision::Rejected {
+        if is_deferred(&decision) {
             self.queue.push(InvokerQueueItem::new(
                 invocation.id,
                 invocation.func_id,
@@ -196,21 +398,100 @@ impl Invoker for NaiveInvoker {
         self.queue.len()
     }
 
+    fn queued_invocations(&self) -> Vec<(usize, usize, f64)> {
+        self.queue.iter().map(|item| item.as_tuple()).collect()
+    }
+
+    fn take_dropped(&mut self) -> Vec<usize> {
+        std::mem::take(&mut self.dropped)
+    }
+
     fn to_string(&self) -> String {
-        "NaiveInvoker".to_string()
+        if let Some(timeout) = self.queueing_timeout {
+            format!("NaiveInvoker[timeout={}]", timeout)
+        } else {
+            "NaiveInvoker".to_string()
+        }
     }
 }
 
 /// `FIFOInvoker` repeatedly tries to invoke the oldest queued invocation.
+/// Optionally, the queue length can be limited, in which case the invocations that do not fit into the queue
+/// are rejected, and the invocations which waited in the queue longer than the given timeout can be dropped.
+/// If forwarding is enabled, the invocations which can't be started immediately are forwarded
+/// to other hosts instead of being queued.
 #[derive(Default)]
 pub struct FIFOInvoker {
     queue: VecDeque<InvokerQueueItem>,
+    max_queue_len: Option<usize>,
+    queueing_timeout: Option<f64>,
+    forward: bool,
+    dropped: Vec<usize>,
 }
 
 impl FIFOInvoker {
     pub fn new() -> Self {
         Default::default()
     }
+
+    pub fn with_capacity(max_queue_len: usize) -> Self {
+        Self {
+            max_queue_len: Some(max_queue_len),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_queueing_timeout(queueing_timeout: f64) -> Self {
+        Self {
+            queueing_timeout: Some(queueing_timeout),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_forwarding() -> Self {
+        Self {
+            forward: true,
+            ..Default::default()
+        }
+    }
+
+    /// Creates invoker from `capacity`, `timeout` and `forward` options, which are optional.
+    pub fn from_options_map(options: &HashMap<String, String>) -> Result<Self, String> {
+        Ok(Self {
+            max_queue_len: parse_option(options, "capacity")?,
+            queueing_timeout: parse_option(options, "timeout")?,
+            forward: parse_option(options, "forward")?.unwrap_or(false),
+            ..Default::default()
+        })
+    }
+
+    fn invoke_app(
+        &mut self,
+        invocation: &Invocation,
+        app: &Application,
+        cm: &mut ContainerManager,
+        time: f64,
+    ) -> InvokerDecision {
+        let status = try_invoke(app, cm, time);
+        if is_deferred(&status) {
+            if self.forward {
+                return InvokerDecision::Forward;
+            }
+            if let Some(max) = self.max_queue_len {
+                if self.queue.len() >= max {
+                    return InvokerDecision::Rejected(RejectionReason::QueueFull);
+                }
+            }
+            self.queue.push_back(InvokerQueueItem::new(
+                invocation.id,
+                invocation.func_id,
+                invocation.app_id,
+                invocation.arrival_time,
+            ));
+            return InvokerDecision::Queued;
+        }
+        status
+    }
 }
 
 impl Invoker for FIFOInvoker {
@@ -223,38 +504,146 @@ impl Invoker for FIFOInvoker {
     ) -> Vec<DequeuedInvocation> {
         let mut dequeued = Vec::new();
         while let Some(item) = self.queue.front().copied() {
+            if is_timed_out(&item, self.queueing_timeout, time) {
+                drop_timed_out(&item, stats, &mut self.dropped);
+                self.queue.pop_front();
+                continue;
+            }
             let fr_ref = fr.borrow();
             let app = fr_ref.get_app(item.app_id).unwrap();
             let status = try_invoke(app, cm, time);
-            match status {
-                InvokerDecision::Warm(id) => {
-                    stats.update_queueing_time(item.app_id, item.func_id, time - item.time);
-                    let container = cm.get_container_mut(id).unwrap();
-                    if container.status == ContainerStatus::Idle {
-                        let delta = time - container.last_change;
-                        stats.update_wasted_resources(delta, &container.resources);
-                    }
-                    stats.on_cold_start(item.app_id, item.func_id, time - item.time);
-                    container.last_change = time;
-                    container.status = ContainerStatus::Running;
-                    container.start_invocation(item.invocation_id);
-                    dequeued.push(DequeuedInvocation::new(item.invocation_id, id, None));
-                    self.queue.pop_front();
-                }
-                InvokerDecision::Cold((id, delay)) => {
-                    stats.update_queueing_time(item.app_id, item.func_id, time - item.time);
-                    cm.reserve_container(id, item.invocation_id);
-                    stats.on_cold_start(item.app_id, item.func_id, time - item.time + delay);
-                    dequeued.push(DequeuedInvocation::new(item.invocation_id, id, Some(delay)));
-                    self.queue.pop_front();
-                }
-                InvokerDecision::Rejected => {
-                    break;
-                }
-                _ => {
-                    panic!("try_invoke should only return Warm, Cold or Rejected");
-                }
+            if is_deferred(&status) {
+                break;
             }
+            dequeued.push(start_queued(&item, status, cm, stats, time));
+            self.queue.pop_front();
+        }
+        dequeued
+    }
+
+    fn invoke(
+        &mut self,
+        invocation: &Invocation,
+        fr: Rc<RefCell<FunctionRegistry>>,
+        cm: &mut ContainerManager,
+        time: f64,
+    ) -> InvokerDecision {
+        let fr_ref = fr.borrow();
+        let app = fr_ref.get_app(invocation.app_id).unwrap();
+        self.invoke_app(invocation, app, cm, time)
+    }
+
+    fn invoke_many(
+        &mut self,
+        invocations: &[Invocation],
+        fr: Rc<RefCell<FunctionRegistry>>,
+        cm: &mut ContainerManager,
+        time: f64,
+    ) -> Vec<InvokerDecision> {
+        let fr_ref = fr.borrow();
+        let mut decisions = Vec::with_capacity(invocations.len());
+        for invocation in invocations {
+            let app = fr_ref.get_app(invocation.app_id).unwrap();
+            let decision = self.invoke_app(invocation, app, cm, time);
+            claim_container(decision, cm);
+            decisions.push(decision);
+        }
+        cm.release_claims();
+        decisions
+    }
+
+    fn queue_len(&self) -> usize {
+        self.queue.len()
+    }
+
+    fn queued_invocations(&self) -> Vec<(usize, usize, f64)> {
+        self.queue.iter().map(|item| item.as_tuple()).collect()
+    }
+
+    fn take_dropped(&mut self) -> Vec<usize> {
+        std::mem::take(&mut self.dropped)
+    }
+
+    fn to_string(&self) -> String {
+        let mut options = Vec::new();
+        if let Some(max) = self.max_queue_len {
+            options.push(format!("capacity={}", max));
+        }
+        if let Some(timeout) = self.queueing_timeout {
+            options.push(format!("timeout={}", timeout));
+        }
+        if self.forward {
+            options.push("forward=true".to_string());
+        }
+        if options.is_empty() {
+            "FIFOInvoker".to_string()
+        } else {
+            format!("FIFOInvoker[{}]", options.join(","))
+        }
+    }
+}
+
+struct PriorityQueueItem {
+    priority: u32,
+    item: InvokerQueueItem,
+}
+
+impl PartialEq for PriorityQueueItem {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for PriorityQueueItem {}
+
+impl PartialOrd for PriorityQueueItem {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PriorityQueueItem {
+    // higher priority first, then earlier arrival, then smaller invocation id
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.priority
+            .cmp(&other.priority)
+            .then(other.item.time.total_cmp(&self.item.time))
+            .then(other.item.invocation_id.cmp(&self.item.invocation_id))
+    }
+}
+
+/// `PriorityInvoker` repeatedly tries to invoke the queued invocation with the highest application priority
+/// (see [`Application::get_priority`]), invocations with equal priorities are handled in FIFO order.
+#[derive(Default)]
+pub struct PriorityInvoker {
+    queue: BinaryHeap<PriorityQueueItem>,
+}
+
+impl PriorityInvoker {
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+impl Invoker for PriorityInvoker {
+    fn dequeue(
+        &mut self,
+        fr: Rc<RefCell<FunctionRegistry>>,
+        cm: &mut ContainerManager,
+        stats: &mut Stats,
+        time: f64,
+    ) -> Vec<DequeuedInvocation> {
+        let mut dequeued = Vec::new();
+        while let Some(top) = self.queue.peek() {
+            let item = top.item;
+            let fr_ref = fr.borrow();
+            let app = fr_ref.get_app(item.app_id).unwrap();
+            let status = try_invoke(app, cm, time);
+            if is_deferred(&status) {
+                break;
+            }
+            dequeued.push(start_queued(&item, status, cm, stats, time));
+            self.queue.pop();
         }
         dequeued
     }
@@ -269,7 +658,91 @@ impl Invoker for FIFOInvoker {
         let fr_ref = fr.borrow();
         let app = fr_ref.get_app(invocation.app_id).unwrap();
         let status = try_invoke(app, cm, time);
-        if status == InvokerDecision::Rejected {
+        if is_deferred(&status) {
+            self.queue.push(PriorityQueueItem {
+                priority: app.get_priority(),
+                item: InvokerQueueItem::new(
+                    invocation.id,
+                    invocation.func_id,
+                    invocation.app_id,
+                    invocation.arrival_time,
+                ),
+            });
+            return InvokerDecision::Queued;
+        }
+        status
+    }
+
+    fn queue_len(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Returns queued invocations in the order of their priority.
+    fn queued_invocations(&self) -> Vec<(usize, usize, f64)> {
+        let mut items: Vec<&PriorityQueueItem> = self.queue.iter().collect();
+        items.sort_by(|a, b| b.cmp(a));
+        items.iter().map(|p| p.item.as_tuple()).collect()
+    }
+
+    fn to_string(&self) -> String {
+        "PriorityInvoker".to_string()
+    }
+}
+
+/// `RandomInvoker` is a baseline invoker, which picks a container uniformly at random among all idle, deploying
+/// and running containers able to accept the invocation, and deploys a new container only if there are none.
+/// Rejected invocations are queued and dequeued in FIFO order, like in [`FIFOInvoker`].
+/// The choices are made with the simulation-wide random number generator, so the runs are reproducible.
+#[derive(Default)]
+pub struct RandomInvoker {
+    queue: VecDeque<InvokerQueueItem>,
+    rng: Option<SimulationRng>,
+}
+
+impl RandomInvoker {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    fn try_invoke(&self, app: &Application, cm: &mut ContainerManager, time: f64) -> InvokerDecision {
+        let rng = self.rng.as_ref().expect("simulation RNG is not set");
+        try_invoke_random(app, cm, time, rng)
+    }
+}
+
+impl Invoker for RandomInvoker {
+    fn dequeue(
+        &mut self,
+        fr: Rc<RefCell<FunctionRegistry>>,
+        cm: &mut ContainerManager,
+        stats: &mut Stats,
+        time: f64,
+    ) -> Vec<DequeuedInvocation> {
+        let mut dequeued = Vec::new();
+        while let Some(item) = self.queue.front().copied() {
+            let fr_ref = fr.borrow();
+            let app = fr_ref.get_app(item.app_id).unwrap();
+            let status = self.try_invoke(app, cm, time);
+            if matches!(status, InvokerDecision::Rejected(_)) {
+                break;
+            }
+            dequeued.push(start_queued(&item, status, cm, stats, time));
+            self.queue.pop_front();
+        }
+        dequeued
+    }
+
+    fn invoke(
+        &mut self,
+        invocation: &Invocation,
+        fr: Rc<RefCell<FunctionRegistry>>,
+        cm: &mut ContainerManager,
+        time: f64,
+    ) -> InvokerDecision {
+        let fr_ref = fr.borrow();
+        let app = fr_ref.get_app(invocation.app_id).unwrap();
+        let status = self.try_invoke(app, cm, time);
+        if matches!(status, InvokerDecision::Rejected(_)) {
             self.queue.push_back(InvokerQueueItem::new(
                 invocation.id,
                 invocation.func_id,
@@ -285,17 +758,282 @@ impl Invoker for FIFOInvoker {
         self.queue.len()
     }
 
+    fn queued_invocations(&self) -> Vec<(usize, usize, f64)> {
+        self.queue.iter().map(|item| item.as_tuple()).collect()
+    }
+
+    fn set_rng(&mut self, rng: SimulationRng) {
+        self.rng = Some(rng);
+    }
+
     fn to_string(&self) -> String {
-        "FIFOInvoker".to_string()
+        "RandomInvoker".to_string()
     }
 }
 
-pub fn default_invoker_resolver(s: &str) -> Box<dyn Invoker> {
-    if s == "NaiveInvoker" {
-        Box::new(NaiveInvoker::new())
-    } else if s == "FIFOInvoker" {
-        Box::new(FIFOInvoker::new())
-    } else {
-        panic!("Can't resolve: {}", s);
+/// Decisions made by primary and shadow invokers for the same invocation.
+#[derive(Clone, Copy)]
+pub struct ShadowRecord {
+    pub invocation_id: usize,
+    pub time: f64,
+    pub primary: InvokerDecision,
+    pub shadow: InvokerDecision,
+}
+
+/// Invocations dequeued by primary and shadow invokers at the same moment.
+#[derive(Clone)]
+pub struct ShadowDequeueRecord {
+    pub time: f64,
+    pub primary: Vec<DequeuedInvocation>,
+    pub shadow: Vec<DequeuedInvocation>,
+}
+
+/// `ShadowInvoker` allows comparing two invokers under identical conditions in a single run.
+/// The primary invoker drives the execution, while the shadow invoker makes its decisions on a detached copy
+/// of the container manager, so they are only recorded and do not affect the simulation.
+///
+/// Limitations:
+/// - The copy is taken anew for each decision, so its cost grows with the number of containers on the host.
+/// - The shadow decisions are evaluated against the state produced by the primary invoker, i.e. the containers
+///   deployed or reserved by the shadow invoker are discarded after each decision. Therefore the shadow records
+///   show what the shadow invoker would do at this moment, not how a whole run driven by it would look like.
+///   The shadow queue is kept across decisions, so it may contain invocations already started by the primary.
+pub struct ShadowInvoker {
+    primary: Box<dyn Invoker>,
+    shadow: Box<dyn Invoker>,
+    records: Rc<RefCell<Vec<ShadowRecord>>>,
+    dequeue_records: Rc<RefCell<Vec<ShadowDequeueRecord>>>,
+}
+
+impl ShadowInvoker {
+    pub fn new(primary: Box<dyn Invoker>, shadow: Box<dyn Invoker>) -> Self {
+        Self {
+            primary,
+            shadow,
+            records: Default::default(),
+            dequeue_records: Default::default(),
+        }
+    }
+
+    /// Returns a shared handle to the recorded decisions, so that they can be inspected after the invoker
+    /// is moved into the simulation.
+    pub fn records(&self) -> Rc<RefCell<Vec<ShadowRecord>>> {
+        self.records.clone()
+    }
+
+    /// Returns a shared handle to the recorded dequeue decisions, which are only recorded
+    /// if at least one of the invokers dequeued something.
+    pub fn dequeue_records(&self) -> Rc<RefCell<Vec<ShadowDequeueRecord>>> {
+        self.dequeue_records.clone()
+    }
+}
+
+impl Invoker for ShadowInvoker {
+    fn dequeue(
+        &mut self,
+        fr: Rc<RefCell<FunctionRegistry>>,
+        cm: &mut ContainerManager,
+        stats: &mut Stats,
+        time: f64,
+    ) -> Vec<DequeuedInvocation> {
+        // the copy is only needed if the shadow invoker has something to dequeue
+        let shadow = if self.shadow.queue_len() > 0 {
+            let mut shadow_cm = cm.detached_copy();
+            self.shadow
+                .dequeue(fr.clone(), &mut shadow_cm, &mut Stats::default(), time)
+        } else {
+            Vec::new()
+        };
+        let primary = self.primary.dequeue(fr, cm, stats, time);
+        if !primary.is_empty() || !shadow.is_empty() {
+            self.dequeue_records.borrow_mut().push(ShadowDequeueRecord {
+                time,
+                primary: primary.clone(),
+                shadow,
+            });
+        }
+        primary
+    }
+
+    fn invoke(
+        &mut self,
+        invocation: &Invocation,
+        fr: Rc<RefCell<FunctionRegistry>>,
+        cm: &mut ContainerManager,
+        time: f64,
+    ) -> InvokerDecision {
+        let mut shadow_cm = cm.detached_copy();
+        let shadow = self.shadow.invoke(invocation, fr.clone(), &mut shadow_cm, time);
+        let primary = self.primary.invoke(invocation, fr, cm, time);
+        self.records.borrow_mut().push(ShadowRecord {
+            invocation_id: invocation.id,
+            time,
+            primary,
+            shadow,
+        });
+        primary
+    }
+
+    fn queue_len(&self) -> usize {
+        self.primary.queue_len()
+    }
+
+    fn queued_invocations(&self) -> Vec<(usize, usize, f64)> {
+        self.primary.queued_invocations()
+    }
+
+    fn take_dropped(&mut self) -> Vec<usize> {
+        // the shadow invoker works on a detached copy, so only the primary one drops real invocations
+        self.shadow.take_dropped();
+        self.primary.take_dropped()
+    }
+
+    fn set_rng(&mut self, rng: SimulationRng) {
+        // the shadow invoker draws from its own generator, so that it does not shift the random sequence
+        // observed by the primary invoker and the rest of the simulation
+        self.shadow.set_rng(rng.fork());
+        self.primary.set_rng(rng);
+    }
+
+    fn to_string(&self) -> String {
+        format!(
+            "ShadowInvoker[{}, {}]",
+            self.primary.to_string(),
+            self.shadow.to_string()
+        )
+    }
+}
+
+/// Parsed invoker description of the form `Name`, `Name(arg1,arg2,...)` or `Name[arg1,arg2,...]`.
+///
+/// Arguments may contain nested descriptions with their own arguments, e.g. `ShadowInvoker(FIFOInvoker, NaiveInvoker)`.
+/// Square brackets are accepted for consistency with the descriptions of other policies, which use them for options.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InvokerSpec {
+    pub name: String,
+    pub args: Vec<String>,
+}
+
+impl InvokerSpec {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let s = s.trim();
+        let (name, args) = match s.find(['(', '[']) {
+            None => (s, Vec::new()),
+            Some(pos) => {
+                let close = Self::closing_bracket(s[pos..].chars().next().unwrap());
+                if !s.ends_with(close) {
+                    return Err(format!("expected '{}' at the end of {}", close, s));
+                }
+                (&s[..pos], Self::split_args(&s[pos + 1..s.len() - 1])?)
+            }
+        };
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(format!("invalid name in {}", s));
+        }
+        Ok(Self {
+            name: name.to_string(),
+            args,
+        })
+    }
+
+    fn closing_bracket(open: char) -> char {
+        if open == '(' {
+            ')'
+        } else {
+            ']'
+        }
+    }
+
+    // Splits the arguments by commas which are not enclosed in brackets of nested descriptions.
+    fn split_args(s: &str) -> Result<Vec<String>, String> {
+        if s.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut args = Vec::new();
+        let mut open = Vec::new();
+        let mut start = 0;
+        for (i, c) in s.char_indices() {
+            match c {
+                '(' | '[' => open.push(c),
+                ')' | ']' if open.pop().map(Self::closing_bracket) != Some(c) => {
+                    return Err(format!("unbalanced brackets in {}", s));
+                }
+                ',' if open.is_empty() => {
+                    args.push(s[start..i].trim().to_string());
+                    start = i + 1;
+                }
+                _ => {}
+            }
+        }
+        if !open.is_empty() {
+            return Err(format!("unbalanced brackets in {}", s));
+        }
+        args.push(s[start..].trim().to_string());
+        if args.iter().any(|arg| arg.is_empty()) {
+            return Err(format!("empty argument in {}", s));
+        }
+        Ok(args)
+    }
+
+    /// Interprets the arguments as `key=value` options, only the keys from `allowed` are accepted.
+    pub fn options(&self, allowed: &[&str]) -> Result<HashMap<String, String>, String> {
+        let mut options = HashMap::new();
+        for arg in &self.args {
+            let (key, value) = arg
+                .split_once('=')
+                .ok_or(format!("expected key=value option of {}, got {}", self.name, arg))?;
+            if !allowed.contains(&key) {
+                return Err(format!("unknown option of {}: {}", self.name, key));
+            }
+            options.insert(key.to_string(), value.to_string());
+        }
+        Ok(options)
+    }
+}
+
+fn parse_option<T: std::str::FromStr>(options: &HashMap<String, String>, key: &str) -> Result<Option<T>, String> {
+    options
+        .get(key)
+        .map(|value| {
+            value
+                .parse::<T>()
+                .map_err(|_| format!("invalid value of option {}: {}", key, value))
+        })
+        .transpose()
+}
+
+/// Creates invoker from its description, see [`InvokerSpec`].
+///
+/// Supported invokers are `NaiveInvoker(timeout=...)`, `FIFOInvoker(capacity=...,timeout=...,forward=...)`
+/// (all options are optional), `PriorityInvoker`, `RandomInvoker` and `ShadowInvoker(primary, shadow)`.
+pub fn default_invoker_resolver(s: &str) -> Result<Box<dyn Invoker>, String> {
+    let spec = InvokerSpec::parse(s)?;
+    match spec.name.as_str() {
+        "NaiveInvoker" => {
+            let options = spec.options(&["timeout"])?;
+            Ok(Box::new(NaiveInvoker::from_options_map(&options)?))
+        }
+        "FIFOInvoker" => {
+            let options = spec.options(&["capacity", "timeout", "forward"])?;
+            Ok(Box::new(FIFOInvoker::from_options_map(&options)?))
+        }
+        "PriorityInvoker" => {
+            spec.options(&[])?;
+            Ok(Box::new(PriorityInvoker::new()))
+        }
+        "RandomInvoker" => {
+            spec.options(&[])?;
+            Ok(Box::new(RandomInvoker::new()))
+        }
+        "ShadowInvoker" => {
+            if spec.args.len() != 2 {
+                return Err(format!("ShadowInvoker expects 2 arguments, got {}", spec.args.len()));
+            }
+            Ok(Box::new(ShadowInvoker::new(
+                default_invoker_resolver(&spec.args[0])?,
+                default_invoker_resolver(&spec.args[1])?,
+            )))
+        }
+        _ => Err(format!("unknown invoker: {}", spec.name)),
     }
 }
@@ -14,8 +14,10 @@ pub mod invocation;
 pub mod invoker;
 pub mod parallel;
 pub mod resource;
+pub mod rng;
 pub mod scheduler;
 pub mod simulation;
 pub mod stats;
 pub mod trace;
 pub mod util;
+pub mod warmth;
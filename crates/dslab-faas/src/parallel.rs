@@ -11,6 +11,7 @@ use dslab_core::simulation::Simulation;
 
 use crate::coldstart::{ColdStartPolicy, FixedTimeColdStartPolicy};
 use crate::config::{Config, ConfigParamResolvers, RawConfig};
+use crate::container::{ContainerSelectionPolicy, ResourceReservationPolicy};
 use crate::cpu::{ContendedCpuPolicy, CpuPolicy};
 use crate::deployer::{BasicDeployer, IdleDeployer};
 use crate::invoker::{FIFOInvoker, Invoker};
@@ -18,6 +19,25 @@ use crate::scheduler::{BasicScheduler, Scheduler};
 use crate::simulation::ServerlessSimulation;
 use crate::stats::Stats;
 use crate::trace::Trace;
+use crate::warmth::CacheWarmthModel;
+
+/// Objective used to compare results of different simulation runs, lower values are better.
+pub type Objective<T> = Box<dyn Fn(&T) -> f64>;
+
+/// Returns indices of results that are Pareto-optimal with respect to the given objectives (all minimized),
+/// i.e. results that are not dominated by any other result. Indices are returned in increasing order.
+pub fn pareto_frontier<T>(results: &[T], objectives: &[Objective<T>]) -> Vec<usize> {
+    let values: Vec<Vec<f64>> = results
+        .iter()
+        .map(|r| objectives.iter().map(|f| f(r)).collect())
+        .collect();
+    let dominates = |a: &[f64], b: &[f64]| {
+        a.iter().zip(b.iter()).all(|(x, y)| x <= y) && a.iter().zip(b.iter()).any(|(x, y)| x < y)
+    };
+    (0..values.len())
+        .filter(|&i| !values.iter().any(|other| dominates(other, &values[i])))
+        .collect()
+}
 
 pub struct ParallelHostConfig {
     pub invoker: Box<dyn Invoker + Send>,
@@ -40,6 +60,13 @@ pub struct ParallelConfig {
     pub cpu_policy: Box<dyn CpuPolicy + Send>,
     pub idle_deployer: Box<dyn IdleDeployer + Send>,
     pub scheduler: Box<dyn Scheduler + Send>,
+    pub container_drain_delay: f64,
+    pub container_selection: ContainerSelectionPolicy,
+    pub resource_reservation: ResourceReservationPolicy,
+    pub wait_for_busy_containers: bool,
+    pub scheduler_tick: Option<f64>,
+    pub container_status_log: bool,
+    pub cache_warmth: Option<CacheWarmthModel>,
     pub hosts: Vec<ParallelHostConfig>,
 }
 
@@ -50,6 +77,13 @@ impl Default for ParallelConfig {
             cpu_policy: Box::<ContendedCpuPolicy>::default(),
             idle_deployer: Box::new(BasicDeployer {}),
             scheduler: Box::new(BasicScheduler {}),
+            container_drain_delay: 0.0,
+            container_selection: Default::default(),
+            resource_reservation: Default::default(),
+            wait_for_busy_containers: false,
+            scheduler_tick: None,
+            container_status_log: false,
+            cache_warmth: None,
             hosts: Vec::new(),
         }
     }
@@ -150,7 +184,8 @@ pub fn parallel_simulation_raw_n_workers(
         Arc::from(resolvers.idle_deployer_resolver);
     let scheduler_resolver1: Arc<dyn Fn(&str) -> Box<dyn Scheduler> + Send + Sync> =
         Arc::from(resolvers.scheduler_resolver);
-    let invoker_resolver1: Arc<dyn Fn(&str) -> Box<dyn Invoker> + Send + Sync> = Arc::from(resolvers.invoker_resolver);
+    let invoker_resolver1: Arc<dyn Fn(&str) -> Result<Box<dyn Invoker>, String> + Send + Sync> =
+        Arc::from(resolvers.invoker_resolver);
     let pool = ThreadPool::new(n_workers);
     let (tx, rx) = channel();
     let len = configs.len();
@@ -56,6 +56,10 @@ impl Resource {
     pub fn get_available(&self) -> u64 {
         self.available
     }
+
+    pub fn get_consumed(&self) -> u64 {
+        self.consumed
+    }
 }
 
 #[derive(Clone)]
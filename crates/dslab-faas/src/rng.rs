@@ -0,0 +1,77 @@
+//! Access to the simulation-wide random number generator.
+//!
+//! All stochastic decisions in the simulation, e.g. sampling of container deployment times or random host
+//! selection, draw from the single generator owned by the simulation, which is seeded by [`Simulation::new`].
+//! Therefore runs with the same seed, configuration and trace produce identical results.
+//! Components that must not perturb this sequence, e.g. the shadow invoker, use a forked generator
+//! seeded once from the simulation-wide one.
+//!
+//! [`Simulation::new`]: dslab_core::simulation::Simulation::new
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use rand::distributions::uniform::{SampleRange, SampleUniform};
+use rand::prelude::Distribution;
+use rand::{Rng, SeedableRng};
+use rand_pcg::Pcg64;
+
+use dslab_core::context::SimulationContext;
+
+#[derive(Clone)]
+enum RngSource {
+    Simulation(Rc<SimulationContext>),
+    Forked(Rc<RefCell<Pcg64>>),
+}
+
+/// Cloneable handle to the simulation-wide random number generator
+/// or to a generator forked from it with [`SimulationRng::fork`].
+#[derive(Clone)]
+pub struct SimulationRng {
+    source: RngSource,
+}
+
+impl SimulationRng {
+    pub fn new(ctx: Rc<SimulationContext>) -> Self {
+        Self {
+            source: RngSource::Simulation(ctx),
+        }
+    }
+
+    /// Returns an independent generator seeded with a single number drawn from this one.
+    /// Draws from the returned generator do not affect the sequence of this one.
+    pub fn fork(&self) -> Self {
+        let seed = self.gen_range(0..u64::MAX);
+        Self {
+            source: RngSource::Forked(Rc::new(RefCell::new(Pcg64::seed_from_u64(seed)))),
+        }
+    }
+
+    /// Returns a random float in the range _[0, 1)_.
+    pub fn rand(&self) -> f64 {
+        match &self.source {
+            RngSource::Simulation(ctx) => ctx.rand(),
+            RngSource::Forked(rng) => rng.borrow_mut().gen_range(0.0..1.0),
+        }
+    }
+
+    /// Returns a random number in the specified range.
+    pub fn gen_range<T, R>(&self, range: R) -> T
+    where
+        T: SampleUniform,
+        R: SampleRange<T>,
+    {
+        match &self.source {
+            RngSource::Simulation(ctx) => ctx.gen_range(range),
+            RngSource::Forked(rng) => rng.borrow_mut().gen_range(range),
+        }
+    }
+
+    /// Returns a random value from the specified distribution.
+    pub fn sample<T, Dist: Distribution<T>>(&self, dist: &Dist) -> T {
+        match &self.source {
+            RngSource::Simulation(ctx) => ctx.sample_from_distribution(dist),
+            RngSource::Forked(rng) => dist.sample(&mut *rng.borrow_mut()),
+        }
+    }
+}
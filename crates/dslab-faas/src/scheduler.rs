@@ -11,11 +11,19 @@ use rand_pcg::Pcg64;
 use crate::config::parse_options;
 use crate::function::Application;
 use crate::host::Host;
+use crate::rng::SimulationRng;
 
 /// Scheduler chooses an invoker to run new invocation of some function from given application.
 pub trait Scheduler {
     fn select_host(&mut self, app: &Application, hosts: &[Rc<RefCell<Host>>]) -> usize;
 
+    /// Passes the simulation-wide random number generator, which should be used by randomized schedulers.
+    /// Called once when the scheduler is added to the simulation.
+    fn set_rng(&mut self, _rng: SimulationRng) {}
+
+    /// Called when a container of the application finishes deploying on the host and can accept invocations.
+    fn on_container_ready(&mut self, _host_id: usize, _container_id: usize, _app_id: usize, _time: f64) {}
+
     fn to_string(&self) -> String {
         "STUB SCHEDULER NAME".to_string()
     }
@@ -151,30 +159,57 @@ impl Scheduler for LocalityBasedScheduler {
 }
 
 /// RandomScheduler picks a host uniformly at random.
+/// It uses either a separate generator with its own seed ([`RandomScheduler::new`])
+/// or the simulation-wide random number generator ([`RandomScheduler::with_simulation_rng`]).
+#[derive(Default)]
 pub struct RandomScheduler {
-    rng: Pcg64,
+    seeded_rng: Option<(u64, Pcg64)>,
+    sim_rng: Option<SimulationRng>,
 }
 
 impl RandomScheduler {
     pub fn new(seed: u64) -> Self {
         Self {
-            rng: Pcg64::seed_from_u64(seed),
+            seeded_rng: Some((seed, Pcg64::seed_from_u64(seed))),
+            sim_rng: None,
         }
     }
 
+    /// Creates scheduler drawing from the simulation-wide random number generator,
+    /// so that its choices depend on the simulation seed.
+    pub fn with_simulation_rng() -> Self {
+        Default::default()
+    }
+
     pub fn from_options_map(options: &HashMap<String, String>) -> Self {
-        let seed = options.get("seed").unwrap().parse::<u64>().unwrap();
-        Self::new(seed)
+        match options.get("seed") {
+            Some(seed) => Self::new(seed.parse::<u64>().unwrap()),
+            None => Self::with_simulation_rng(),
+        }
     }
 }
 
 impl Scheduler for RandomScheduler {
     fn select_host(&mut self, _app: &Application, hosts: &[Rc<RefCell<Host>>]) -> usize {
-        self.rng.gen::<usize>() % hosts.len()
+        if let Some((_, rng)) = self.seeded_rng.as_mut() {
+            rng.gen::<usize>() % hosts.len()
+        } else {
+            self.sim_rng
+                .as_ref()
+                .expect("simulation RNG is not set")
+                .gen_range(0..hosts.len())
+        }
+    }
+
+    fn set_rng(&mut self, rng: SimulationRng) {
+        self.sim_rng = Some(rng);
     }
 
     fn to_string(&self) -> String {
-        "RandomScheduler".to_string()
+        match self.seeded_rng {
+            Some((seed, _)) => format!("RandomScheduler[seed={}]", seed),
+            None => "RandomScheduler".to_string(),
+        }
     }
 }
 
@@ -284,6 +319,9 @@ pub fn default_scheduler_resolver(s: &str) -> Box<dyn Scheduler> {
     if s == "RoundRobinScheduler" {
         return Box::new(RoundRobinScheduler::new());
     }
+    if s == "RandomScheduler" {
+        return Box::new(RandomScheduler::with_simulation_rng());
+    }
     if s.len() >= 17 && &s[0..16] == "RandomScheduler[" && s.ends_with(']') {
         let opts = parse_options(&s[16..s.len() - 1]);
         return Box::new(RandomScheduler::from_options_map(&opts));
@@ -8,6 +8,7 @@ use dslab_core::simulation::Simulation;
 
 use crate::coldstart::ColdStartPolicy;
 use crate::config::Config;
+use crate::container::{ContainerSelectionPolicy, ContainerStatusTransition, ResourceReservationPolicy};
 use crate::controller::Controller;
 use crate::cpu::CpuPolicy;
 use crate::event::{InvocationStartEvent, SimulationEndEvent};
@@ -16,9 +17,11 @@ use crate::host::Host;
 use crate::invocation::{Invocation, InvocationRegistry};
 use crate::invoker::{FIFOInvoker, Invoker};
 use crate::resource::{Resource, ResourceConsumer, ResourceNameResolver, ResourceProvider, ResourceRequirement};
+use crate::rng::SimulationRng;
 use crate::stats::{GlobalStats, InvocationStats, Stats};
 use crate::trace::{RequestData, Trace};
 use crate::util::Counter;
+use crate::warmth::CacheWarmthModel;
 
 pub type HandlerId = dslab_core::component::Id;
 
@@ -27,10 +30,17 @@ pub struct ServerlessSimulation {
     controller: Rc<RefCell<Controller>>,
     controller_id: HandlerId,
     cpu_policy: Box<dyn CpuPolicy>,
+    container_drain_delay: f64,
+    container_selection: ContainerSelectionPolicy,
+    resource_reservation: ResourceReservationPolicy,
+    wait_for_busy_containers: bool,
+    scheduler_tick: Option<f64>,
+    container_status_log: bool,
+    cache_warmth: Option<CacheWarmthModel>,
     function_registry: Rc<RefCell<FunctionRegistry>>,
     host_ctr: Counter,
     invocation_registry: Rc<RefCell<InvocationRegistry>>,
-    ctx: SimulationContext,
+    ctx: Rc<SimulationContext>,
     resource_name_resolver: ResourceNameResolver,
     sim: Simulation,
     stats: Rc<RefCell<Stats>>,
@@ -39,13 +49,15 @@ pub struct ServerlessSimulation {
 impl ServerlessSimulation {
     pub fn new(mut sim: Simulation, config: Config) -> Self {
         let stats = Rc::new(RefCell::new(Default::default()));
-        let ctx = sim.create_context("entry point");
+        let ctx = Rc::new(sim.create_context("entry point"));
+        let mut scheduler = config.scheduler;
+        scheduler.set_rng(SimulationRng::new(ctx.clone()));
         let function_registry: Rc<RefCell<FunctionRegistry>> = Rc::new(RefCell::new(Default::default()));
         let invocation_registry: Rc<RefCell<InvocationRegistry>> = Rc::new(RefCell::new(Default::default()));
         let controller = Rc::new(RefCell::new(Controller::new(
             function_registry.clone(),
             config.idle_deployer,
-            config.scheduler,
+            scheduler,
         )));
         let controller_id = sim.add_handler("controller", controller.clone());
         let mut this_sim = Self {
@@ -53,6 +65,13 @@ impl ServerlessSimulation {
             controller,
             controller_id,
             cpu_policy: config.cpu_policy,
+            container_drain_delay: config.container_drain_delay,
+            container_selection: config.container_selection,
+            resource_reservation: config.resource_reservation,
+            wait_for_busy_containers: config.wait_for_busy_containers,
+            scheduler_tick: config.scheduler_tick,
+            container_status_log: config.container_status_log,
+            cache_warmth: config.cache_warmth,
             function_registry,
             host_ctr: Default::default(),
             invocation_registry,
@@ -72,6 +91,11 @@ impl ServerlessSimulation {
         this_sim
     }
 
+    /// Returns a handle to the simulation-wide random number generator, see [`crate::rng`].
+    pub fn rng(&self) -> SimulationRng {
+        SimulationRng::new(self.ctx.clone())
+    }
+
     pub fn try_resolve_resource_name(&self, name: &str) -> Option<usize> {
         self.resource_name_resolver.try_resolve(name)
     }
@@ -96,6 +120,11 @@ impl ServerlessSimulation {
         self.stats.borrow().clone()
     }
 
+    /// Discards the stats collected so far, see [`Stats::reset`].
+    pub fn reset_stats(&mut self) {
+        self.stats.borrow_mut().reset();
+    }
+
     pub fn global_stats(&self) -> GlobalStats {
         self.stats.borrow().global_stats.clone()
     }
@@ -106,7 +135,8 @@ impl ServerlessSimulation {
 
     pub fn add_host(&mut self, invoker: Option<Box<dyn Invoker>>, resources: ResourceProvider, cores: u32) {
         let id = self.host_ctr.increment();
-        let real_invoker = invoker.unwrap_or_else(|| Box::new(FIFOInvoker::new()));
+        let mut real_invoker = invoker.unwrap_or_else(|| Box::new(FIFOInvoker::new()));
+        real_invoker.set_rng(self.rng());
         let ctx = self.sim.create_context(format!("host_{}", id));
         let host = Rc::new(RefCell::new(Host::new(
             id,
@@ -119,12 +149,59 @@ impl ServerlessSimulation {
             self.coldstart.clone(),
             self.controller_id,
             self.stats.clone(),
+            self.container_drain_delay,
+            self.container_selection,
+            self.cache_warmth,
             ctx,
         )));
+        host.borrow_mut()
+            .set_resource_reservation_policy(self.resource_reservation);
+        if self.wait_for_busy_containers {
+            host.borrow_mut().enable_waiting_for_busy_containers();
+        }
+        if let Some(interval) = self.scheduler_tick {
+            host.borrow_mut().set_scheduler_tick(interval);
+        }
+        if self.container_status_log {
+            host.borrow_mut().enable_container_status_log();
+        }
         self.sim.add_handler(format!("host_{}", id), host.clone());
         self.controller.borrow_mut().add_host(host);
     }
 
+    /// Marks container on the given host for unloading. Running containers finish their current
+    /// invocations before shutting down. Returns false if there is no such container or it is still deploying.
+    pub fn unload_container(&mut self, host_id: usize, container_id: usize) -> bool {
+        let time = self.sim.time();
+        self.controller.borrow().unload_container(host_id, container_id, time)
+    }
+
+    /// Keeps at least `count` containers of the application deployed regardless of the traffic, deploying
+    /// missing ones now. Pool containers are never unloaded by the cold start policy.
+    /// Returns the resulting pool size, which may be less than `count` if the hosts run out of resources.
+    pub fn ensure_warm(&mut self, app_id: usize, count: usize) -> usize {
+        let time = self.sim.time();
+        self.controller.borrow_mut().ensure_warm(app_id, count, time)
+    }
+
+    /// Returns (invocation id, application id, arrival time) tuples of invocations queued on the given host.
+    pub fn queued_invocations(&self, host_id: usize) -> Vec<(usize, usize, f64)> {
+        self.controller.borrow().queued_invocations(host_id)
+    }
+
+    /// Returns container status transitions recorded on the given host.
+    /// The log is empty unless `container_status_log` is enabled in the config.
+    pub fn container_status_log(&self, host_id: usize) -> Vec<ContainerStatusTransition> {
+        self.controller.borrow().container_status_log(host_id)
+    }
+
+    /// Returns the time when the given container on the given host is scheduled to be unloaded.
+    /// Returns `None` for running and deploying containers, for unknown containers and for containers
+    /// which are kept regardless of the cold start policy.
+    pub fn container_unload_time(&self, host_id: usize, container_id: usize) -> Option<f64> {
+        self.controller.borrow().container_unload_time(host_id, container_id)
+    }
+
     pub fn add_function(&mut self, f: Function) -> usize {
         self.function_registry.borrow_mut().add_function(f)
     }
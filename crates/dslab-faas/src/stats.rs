@@ -1,8 +1,12 @@
+use std::collections::BTreeMap;
+
 use order_stat::kth_by;
 use serde::ser::{SerializeSeq, Serializer};
 use serde::Serialize;
 
+use crate::container::Container;
 use crate::invocation::Invocation;
+use crate::invoker::RejectionReason;
 use crate::resource::ResourceConsumer;
 use crate::util::DefaultVecMap;
 
@@ -127,11 +131,38 @@ impl Serialize for SampleMetric {
 #[derive(Clone, Default, Serialize)]
 pub struct InvocationStats {
     pub invocations: u64,
+    /// Number of invocations which waited for a container deployment, including the ones dequeued
+    /// from the invoker queue into a new container.
     pub cold_starts: u64,
+    /// Number of invocations started in an already deployed container, right away or after queueing.
+    pub warm_starts: u64,
+    /// Number of invocations rejected by the invokers, e.g. due to queue overflow.
+    pub rejections: u64,
+    /// Breakdown of rejections by their cause.
+    pub rejection_reasons: BTreeMap<RejectionReason, u64>,
+    /// Number of invocations dropped from the invoker queues after waiting longer than the queueing timeout.
+    pub queue_timeouts: u64,
+    /// Number of times the invocations were forwarded by the invokers to other hosts.
+    pub forwards: u64,
+    /// Number of containers unloaded without serving any invocation, e.g. after speculative prewarming.
+    pub wasted_deployments: u64,
+    /// Number of idle containers evicted with [`crate::container::ContainerManager::evict`].
+    pub evictions: u64,
+    /// Resource time spent by idle containers outside of warm pools, indexed by resource id.
+    /// Is tracked for applications only, the aggregate over all applications is stored in [`GlobalStats`].
+    pub wasted_resource_time: DefaultVecMap<SampleMetric>,
     /// This metric counts latency of cold starts only, warm starts are not counted as zero.
     pub cold_start_latency: SampleMetric,
     /// Measures queueing time of requests stuck in the invoker queue (other requests are not counted at all).
     pub queueing_time: SampleMetric,
+    /// Average fraction of concurrency slots occupied while the container was running invocations,
+    /// one sample per container.
+    pub concurrency_utilization: SampleMetric,
+    /// Time from the deployment request to the unloading of the container, one sample per unloaded container.
+    /// The sum of the sample is the total lifetime of unloaded containers.
+    pub container_lifetime: SampleMetric,
+    /// Number of invocations served by the container before it was unloaded, one sample per unloaded container.
+    pub invocations_per_container: SampleMetric,
     pub abs_exec_slowdown: SampleMetric,
     pub rel_exec_slowdown: SampleMetric,
     pub abs_total_slowdown: SampleMetric,
@@ -144,10 +175,35 @@ impl InvocationStats {
         self.cold_starts += 1;
     }
 
+    pub fn on_warm_start(&mut self) {
+        self.warm_starts += 1;
+    }
+
     pub fn on_new_invocation(&mut self) {
         self.invocations += 1;
     }
 
+    pub fn on_rejection(&mut self, reason: RejectionReason) {
+        self.rejections += 1;
+        *self.rejection_reasons.entry(reason).or_default() += 1;
+    }
+
+    pub fn on_queue_timeout(&mut self) {
+        self.queue_timeouts += 1;
+    }
+
+    pub fn on_forward(&mut self) {
+        self.forwards += 1;
+    }
+
+    pub fn on_wasted_deployment(&mut self) {
+        self.wasted_deployments += 1;
+    }
+
+    pub fn on_eviction(&mut self) {
+        self.evictions += 1;
+    }
+
     pub fn update(&mut self, invocation: &Invocation) {
         let len = invocation.execution_time();
         let total_len = invocation.response_time();
@@ -162,12 +218,122 @@ impl InvocationStats {
     pub fn update_queueing_time(&mut self, queueing_time: f64) {
         self.queueing_time.add(queueing_time);
     }
+
+    pub fn update_concurrency_utilization(&mut self, utilization: f64) {
+        self.concurrency_utilization.add(utilization);
+    }
+
+    pub fn update_wasted_resources(&mut self, time: f64, resource: &ResourceConsumer) {
+        for (_, req) in resource.iter() {
+            let delta = time * (req.quantity as f64);
+            self.wasted_resource_time.get_mut(req.id).add(delta);
+        }
+    }
+
+    pub fn update_container_lifetime(&mut self, lifetime: f64, invocations: usize) {
+        self.container_lifetime.add(lifetime);
+        self.invocations_per_container.add(invocations as f64);
+    }
+
+    /// Returns the mean lifetime of unloaded containers, or `None` if no container was unloaded.
+    pub fn mean_container_lifetime(&self) -> Option<f64> {
+        (!self.container_lifetime.is_empty()).then(|| self.container_lifetime.mean())
+    }
+
+    /// Returns p-th percentile (0 <= p <= 100) of container lifetime, or `None` if no container was unloaded.
+    pub fn container_lifetime_percentile(&self, p: f64) -> Option<f64> {
+        (!self.container_lifetime.is_empty()).then(|| self.container_lifetime.quantile(p / 100.))
+    }
+
+    /// Returns the mean number of invocations served by unloaded containers,
+    /// or `None` if no container was unloaded.
+    pub fn mean_invocations_per_container(&self) -> Option<f64> {
+        (!self.invocations_per_container.is_empty()).then(|| self.invocations_per_container.mean())
+    }
+
+    /// Returns p-th percentile (0 <= p <= 100) of the number of invocations served by unloaded containers,
+    /// or `None` if no container was unloaded.
+    pub fn invocations_per_container_percentile(&self, p: f64) -> Option<f64> {
+        (!self.invocations_per_container.is_empty()).then(|| self.invocations_per_container.quantile(p / 100.))
+    }
+
+    /// Returns p-th percentile (0 <= p <= 100) of relative slowdown, i.e. the ratio of invocation response time
+    /// to invocation duration.
+    pub fn relative_slowdown_percentile(&self, p: f64) -> f64 {
+        self.rel_total_slowdown.quantile(p / 100.) + 1.
+    }
+
+    /// Appends (column name, value) pairs describing these stats to `fields`.
+    /// Counters are written as is, sample metrics are represented by their means (empty if there are no samples).
+    fn csv_fields(&self, prefix: &str, fields: &mut Vec<(String, String)>) {
+        let counters = [
+            ("invocations", self.invocations),
+            ("cold_starts", self.cold_starts),
+            ("warm_starts", self.warm_starts),
+            ("rejections", self.rejections),
+            ("queue_timeouts", self.queue_timeouts),
+            ("forwards", self.forwards),
+            ("wasted_deployments", self.wasted_deployments),
+            ("evictions", self.evictions),
+        ];
+        for (name, value) in counters {
+            fields.push((format!("{}{}", prefix, name), value.to_string()));
+        }
+        for reason in RejectionReason::ALL {
+            let value = self.rejection_reasons.get(&reason).copied().unwrap_or(0);
+            fields.push((format!("{}rejections_{}", prefix, reason.as_str()), value.to_string()));
+        }
+        let metrics = [
+            ("cold_start_latency", &self.cold_start_latency),
+            ("queueing_time", &self.queueing_time),
+            ("concurrency_utilization", &self.concurrency_utilization),
+            ("container_lifetime", &self.container_lifetime),
+            ("invocations_per_container", &self.invocations_per_container),
+            ("abs_exec_slowdown", &self.abs_exec_slowdown),
+            ("rel_exec_slowdown", &self.rel_exec_slowdown),
+            ("abs_total_slowdown", &self.abs_total_slowdown),
+            ("rel_total_slowdown", &self.rel_total_slowdown),
+        ];
+        for (name, metric) in metrics {
+            let value = if metric.is_empty() {
+                String::new()
+            } else {
+                metric.mean().to_string()
+            };
+            fields.push((format!("{}{}_mean", prefix, name), value));
+        }
+        for (id, metric) in self.wasted_resource_time.iter().enumerate() {
+            fields.push((
+                format!("{}wasted_resource_time_{}", prefix, id),
+                metric.sum().to_string(),
+            ));
+        }
+    }
+
+    /// Returns the fraction of invocations that caused a cold start.
+    pub fn cold_start_fraction(&self) -> f64 {
+        if self.invocations == 0 {
+            0.
+        } else {
+            self.cold_starts as f64 / self.invocations as f64
+        }
+    }
+
+    /// Returns the fraction of cold starts among the started invocations, i.e. excluding the rejected ones,
+    /// or `None` if no invocation was started.
+    pub fn cold_start_ratio(&self) -> Option<f64> {
+        let starts = self.cold_starts + self.warm_starts;
+        (starts > 0).then(|| self.cold_starts as f64 / starts as f64)
+    }
 }
 
 #[derive(Clone, Default, Serialize)]
 pub struct GlobalStats {
     pub invocation_stats: InvocationStats,
     pub wasted_resource_time: DefaultVecMap<SampleMetric>,
+    /// Resource time spent by idle containers of warm pools, which is not counted as wasted.
+    /// See [`crate::simulation::ServerlessSimulation::ensure_warm`].
+    pub pool_resource_time: DefaultVecMap<SampleMetric>,
 }
 
 impl GlobalStats {
@@ -175,10 +341,34 @@ impl GlobalStats {
         self.invocation_stats.on_cold_start(delay);
     }
 
+    pub fn on_warm_start(&mut self) {
+        self.invocation_stats.on_warm_start();
+    }
+
     pub fn on_new_invocation(&mut self) {
         self.invocation_stats.on_new_invocation();
     }
 
+    pub fn on_rejection(&mut self, reason: RejectionReason) {
+        self.invocation_stats.on_rejection(reason);
+    }
+
+    pub fn on_queue_timeout(&mut self) {
+        self.invocation_stats.on_queue_timeout();
+    }
+
+    pub fn on_forward(&mut self) {
+        self.invocation_stats.on_forward();
+    }
+
+    pub fn on_wasted_deployment(&mut self) {
+        self.invocation_stats.on_wasted_deployment();
+    }
+
+    pub fn on_eviction(&mut self) {
+        self.invocation_stats.on_eviction();
+    }
+
     pub fn update_invocation_stats(&mut self, invocation: &Invocation) {
         self.invocation_stats.update(invocation);
     }
@@ -187,6 +377,10 @@ impl GlobalStats {
         self.invocation_stats.update_queueing_time(queueing_time);
     }
 
+    pub fn update_concurrency_utilization(&mut self, utilization: f64) {
+        self.invocation_stats.update_concurrency_utilization(utilization);
+    }
+
     pub fn update_wasted_resources(&mut self, time: f64, resource: &ResourceConsumer) {
         for (_, req) in resource.iter() {
             let delta = time * (req.quantity as f64);
@@ -194,6 +388,17 @@ impl GlobalStats {
         }
     }
 
+    pub fn update_container_lifetime(&mut self, lifetime: f64, invocations: usize) {
+        self.invocation_stats.update_container_lifetime(lifetime, invocations);
+    }
+
+    pub fn update_pool_resources(&mut self, time: f64, resource: &ResourceConsumer) {
+        for (_, req) in resource.iter() {
+            let delta = time * (req.quantity as f64);
+            self.pool_resource_time.get_mut(req.id).add(delta);
+        }
+    }
+
     pub fn print_summary(&self, name: &str) {
         println!("describing {}", name);
         println!("{} successful invocations", self.invocation_stats.invocations);
@@ -231,12 +436,46 @@ impl Stats {
         self.func_stats.get_mut(func_id).on_cold_start(delay);
     }
 
+    pub fn on_warm_start(&mut self, app_id: usize, func_id: usize) {
+        self.global_stats.on_warm_start();
+        self.app_stats.get_mut(app_id).on_warm_start();
+        self.func_stats.get_mut(func_id).on_warm_start();
+    }
+
     pub fn on_new_invocation(&mut self, app_id: usize, func_id: usize) {
         self.global_stats.on_new_invocation();
         self.app_stats.get_mut(app_id).on_new_invocation();
         self.func_stats.get_mut(func_id).on_new_invocation();
     }
 
+    pub fn on_rejection(&mut self, app_id: usize, func_id: usize, reason: RejectionReason) {
+        self.global_stats.on_rejection(reason);
+        self.app_stats.get_mut(app_id).on_rejection(reason);
+        self.func_stats.get_mut(func_id).on_rejection(reason);
+    }
+
+    pub fn on_queue_timeout(&mut self, app_id: usize, func_id: usize) {
+        self.global_stats.on_queue_timeout();
+        self.app_stats.get_mut(app_id).on_queue_timeout();
+        self.func_stats.get_mut(func_id).on_queue_timeout();
+    }
+
+    pub fn on_forward(&mut self, app_id: usize, func_id: usize) {
+        self.global_stats.on_forward();
+        self.app_stats.get_mut(app_id).on_forward();
+        self.func_stats.get_mut(func_id).on_forward();
+    }
+
+    pub fn on_wasted_deployment(&mut self, app_id: usize) {
+        self.global_stats.on_wasted_deployment();
+        self.app_stats.get_mut(app_id).on_wasted_deployment();
+    }
+
+    pub fn on_eviction(&mut self, app_id: usize) {
+        self.global_stats.on_eviction();
+        self.app_stats.get_mut(app_id).on_eviction();
+    }
+
     pub fn update_invocation_stats(&mut self, invocation: &Invocation) {
         self.global_stats.update_invocation_stats(invocation);
         self.app_stats.get_mut(invocation.app_id).update(invocation);
@@ -249,7 +488,111 @@ impl Stats {
         self.func_stats.get_mut(func_id).update_queueing_time(queueing_time);
     }
 
-    pub fn update_wasted_resources(&mut self, time: f64, resource: &ResourceConsumer) {
+    pub fn update_wasted_resources(&mut self, app_id: usize, time: f64, resource: &ResourceConsumer) {
         self.global_stats.update_wasted_resources(time, resource);
+        self.app_stats.get_mut(app_id).update_wasted_resources(time, resource);
+    }
+
+    /// Accounts the time the container spent idle, idle time of warm pool containers is not considered wasted.
+    pub fn update_idle_container(&mut self, time: f64, container: &Container) {
+        if container.pooled {
+            self.global_stats.update_pool_resources(time, &container.resources);
+        } else {
+            self.update_wasted_resources(container.app_id, time, &container.resources);
+        }
+    }
+
+    /// Accounts the lifetime and the number of served invocations of the container being unloaded.
+    pub fn on_container_end(&mut self, time: f64, container: &Container) {
+        let lifetime = time - container.creation_time;
+        let invocations = container.started_invocations;
+        self.global_stats.update_container_lifetime(lifetime, invocations);
+        self.app_stats
+            .get_mut(container.app_id)
+            .update_container_lifetime(lifetime, invocations);
+    }
+
+    /// Discards all collected metrics, e.g. at the end of warmup period.
+    ///
+    /// Note that the time of idle containers is accounted when they leave the idle state,
+    /// so it may include the time before the reset.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Returns a copy of the current state of all metrics.
+    pub fn snapshot(&self) -> Stats {
+        self.clone()
+    }
+
+    /// Same as [`InvocationStats::relative_slowdown_percentile`] computed over all invocations.
+    pub fn relative_slowdown_percentile(&self, p: f64) -> f64 {
+        self.global_stats.invocation_stats.relative_slowdown_percentile(p)
+    }
+
+    /// Same as [`InvocationStats::mean_container_lifetime`] computed over all containers.
+    pub fn mean_container_lifetime(&self) -> Option<f64> {
+        self.global_stats.invocation_stats.mean_container_lifetime()
+    }
+
+    /// Same as [`InvocationStats::container_lifetime_percentile`] computed over all containers.
+    pub fn container_lifetime_percentile(&self, p: f64) -> Option<f64> {
+        self.global_stats.invocation_stats.container_lifetime_percentile(p)
+    }
+
+    /// Same as [`InvocationStats::mean_invocations_per_container`] computed over all containers.
+    pub fn mean_invocations_per_container(&self) -> Option<f64> {
+        self.global_stats.invocation_stats.mean_invocations_per_container()
+    }
+
+    /// Same as [`InvocationStats::invocations_per_container_percentile`] computed over all containers.
+    pub fn invocations_per_container_percentile(&self, p: f64) -> Option<f64> {
+        self.global_stats
+            .invocation_stats
+            .invocations_per_container_percentile(p)
+    }
+
+    /// Same as [`InvocationStats::cold_start_fraction`] computed over all invocations.
+    pub fn cold_start_fraction(&self) -> f64 {
+        self.global_stats.invocation_stats.cold_start_fraction()
+    }
+
+    /// Same as [`InvocationStats::cold_start_ratio`] computed over the invocations of the given application.
+    pub fn cold_start_ratio(&self, app_id: usize) -> Option<f64> {
+        self.app_stats.get(app_id).and_then(|stats| stats.cold_start_ratio())
+    }
+
+    /// Returns all stats serialized to JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+
+    /// Returns CSV header line and values line with the summary of stats.
+    /// Global stats go first, followed by per-application and per-function stats
+    /// with `app_<id>_` and `func_<id>_` prefixes. Sample metrics are represented by their means.
+    pub fn to_csv_row(&self) -> String {
+        let mut fields = Vec::new();
+        self.global_stats.invocation_stats.csv_fields("", &mut fields);
+        for (id, metric) in self.global_stats.wasted_resource_time.iter().enumerate() {
+            fields.push((format!("wasted_resource_time_{}", id), metric.sum().to_string()));
+        }
+        for (id, metric) in self.global_stats.pool_resource_time.iter().enumerate() {
+            fields.push((format!("pool_resource_time_{}", id), metric.sum().to_string()));
+        }
+        for (id, stats) in self.app_stats.iter().enumerate() {
+            stats.csv_fields(&format!("app_{}_", id), &mut fields);
+        }
+        for (id, stats) in self.func_stats.iter().enumerate() {
+            stats.csv_fields(&format!("func_{}_", id), &mut fields);
+        }
+        let (header, values): (Vec<_>, Vec<_>) = fields.into_iter().unzip();
+        format!("{}\n{}\n", header.join(","), values.join(","))
+    }
+
+    pub fn update_concurrency_utilization(&mut self, app_id: usize, utilization: f64) {
+        self.global_stats.update_concurrency_utilization(utilization);
+        self.app_stats
+            .get_mut(app_id)
+            .update_concurrency_utilization(utilization);
     }
 }
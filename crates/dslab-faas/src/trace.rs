@@ -51,6 +51,10 @@ impl Ord for RequestData {
     }
 }
 
+/// Source of applications, functions and invocation requests replayed by
+/// [`ServerlessSimulation::load_trace`](crate::simulation::ServerlessSimulation::load_trace).
+///
+/// See [`AzureTrace`](crate::extra::azure_trace_2019::AzureTrace) for the Azure Functions 2019 trace.
 pub trait Trace {
     fn app_iter(&self) -> Box<dyn Iterator<Item = ApplicationData> + '_>;
     fn request_iter(&self) -> Box<dyn Iterator<Item = RequestData> + '_>;
@@ -6,7 +6,7 @@ use rustc_hash::FxHasher;
 use serde::ser::{SerializeSeq, Serializer};
 use serde::Serialize;
 
-#[derive(Default)]
+#[derive(Clone, Default)]
 pub struct Counter {
     value: usize,
 }
@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+/// Models decay of container runtime caches (JIT code, file caches, etc.) while the container is idle.
+/// The first invocation after an idle period of length `t` gets an execution time penalty equal to
+/// `max_penalty * (1 - exp(-t / decay_time))` of its duration, so quickly reused containers are almost unaffected.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct CacheWarmthModel {
+    /// Relative penalty for a container with completely cold caches.
+    pub max_penalty: f64,
+    /// Characteristic time of cache warmth decay.
+    pub decay_time: f64,
+}
+
+impl CacheWarmthModel {
+    pub fn new(max_penalty: f64, decay_time: f64) -> Self {
+        assert!(max_penalty >= 0., "Cache warmth penalty should be non-negative");
+        assert!(decay_time > 0., "Cache warmth decay time should be positive");
+        Self {
+            max_penalty,
+            decay_time,
+        }
+    }
+
+    /// Returns extra execution time of an invocation with given duration that starts after given idle period.
+    pub fn penalty(&self, idle_duration: f64, duration: f64) -> f64 {
+        if idle_duration <= 0. {
+            return 0.;
+        }
+        duration * self.max_penalty * (1. - (-idle_duration / self.decay_time).exp())
+    }
+}
@@ -1,3 +1,54 @@
+// not every test file uses all helpers
+#![allow(dead_code)]
+
+use std::boxed::Box;
+
+use dslab_core::simulation::Simulation;
+use dslab_faas::coldstart::FixedTimeColdStartPolicy;
+use dslab_faas::config::Config;
+use dslab_faas::cpu::IgnoredCpuPolicy;
+use dslab_faas::function::Application;
+use dslab_faas::invoker::Invoker;
+use dslab_faas::resource::{ResourceConsumer, ResourceProvider};
+use dslab_faas::simulation::ServerlessSimulation;
+
 pub fn assert_float_eq(x: f64, y: f64, eps: f64) {
     assert!(x > y - eps && x < y + eps);
 }
+
+/// Returns config with fixed keepalive window, no prewarming and ignored CPU sharing.
+/// Other fields can be overridden with struct update syntax.
+pub fn fixed_keepalive_config(keepalive: f64) -> Config {
+    Config {
+        coldstart_policy: Box::new(FixedTimeColdStartPolicy::new(keepalive, 0.0)),
+        cpu_policy: Box::new(IgnoredCpuPolicy::default()),
+        ..Default::default()
+    }
+}
+
+/// Returns config which never unloads idle containers.
+pub fn no_unloading_config() -> Config {
+    fixed_keepalive_config(f64::MAX / 10.)
+}
+
+pub fn make_sim(seed: u64, config: Config) -> ServerlessSimulation {
+    ServerlessSimulation::new(Simulation::new(seed), config)
+}
+
+/// Adds single-core host with the given amount of memory.
+pub fn add_host(sim: &mut ServerlessSimulation, invoker: Option<Box<dyn Invoker>>, mem: u64) {
+    let host_mem = sim.create_resource("mem", mem);
+    sim.add_host(invoker, ResourceProvider::new(vec![host_mem]), 1);
+}
+
+/// Creates application whose containers require the given amount of memory.
+pub fn make_app(sim: &mut ServerlessSimulation, concurrency: usize, deployment_time: f64, mem: u64) -> Application {
+    let fn_mem = sim.create_resource_requirement("mem", mem);
+    Application::new(concurrency, deployment_time, 1., ResourceConsumer::new(vec![fn_mem]))
+}
+
+/// Adds application with a single function, see [`make_app`], and returns the function id.
+pub fn add_function(sim: &mut ServerlessSimulation, concurrency: usize, deployment_time: f64, mem: u64) -> usize {
+    let app = make_app(sim, concurrency, deployment_time, mem);
+    sim.add_app_with_single_function(app)
+}
@@ -0,0 +1,52 @@
+use std::collections::HashMap;
+
+use dslab_faas::extra::azure_trace_2019::{ApplicationRecord, AzureTrace, FunctionRecord};
+use dslab_faas::trace::{RequestData, Trace};
+
+fn make_trace() -> AzureTrace {
+    let trace_records = (0..200)
+        .map(|i| RequestData {
+            id: i % 2,
+            duration: 1.0,
+            time: i as f64,
+        })
+        .collect();
+    AzureTrace {
+        concurrency_level: 1,
+        memory_name: "mem".to_string(),
+        sim_end: None,
+        trace_records,
+        function_records: vec![FunctionRecord { app_id: 0 }; 2],
+        app_records: vec![ApplicationRecord {
+            mem: 1,
+            cold_start: 1.0,
+        }],
+    }
+}
+
+fn count(trace: &AzureTrace, id: usize) -> usize {
+    trace.request_iter().filter(|r| r.id == id).count()
+}
+
+#[test]
+fn test_scale_function_arrivals() {
+    let mut trace = make_trace();
+    trace.scale_function_arrivals(&HashMap::from([(0, 2.0), (1, 0.5)]), 1);
+    assert_eq!(count(&trace, 0), 200);
+    let thinned = count(&trace, 1);
+    assert!(thinned > 20 && thinned < 80);
+    // the requests stay ordered by time
+    let times: Vec<f64> = trace.request_iter().map(|r| r.time).collect();
+    assert!(times.windows(2).all(|w| w[0] <= w[1]));
+
+    let mut same = make_trace();
+    same.scale_function_arrivals(&HashMap::from([(0, 2.0), (1, 0.5)]), 1);
+    let ids: Vec<usize> = trace.request_iter().map(|r| r.id).collect();
+    let same_ids: Vec<usize> = same.request_iter().map(|r| r.id).collect();
+    assert_eq!(ids, same_ids);
+
+    let mut unchanged = make_trace();
+    unchanged.scale_function_arrivals(&HashMap::from([(0, 1.0)]), 1);
+    assert_eq!(count(&unchanged, 0), 100);
+    assert_eq!(count(&unchanged, 1), 100);
+}
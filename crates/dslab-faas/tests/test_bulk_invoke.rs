@@ -0,0 +1,57 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use dslab_core::simulation::Simulation;
+use dslab_faas::container::{ContainerManager, ContainerSelectionPolicy};
+use dslab_faas::function::{Application, FunctionRegistry};
+use dslab_faas::invocation::{Invocation, InvocationStatus};
+use dslab_faas::invoker::{FIFOInvoker, Invoker, InvokerDecision, NaiveInvoker};
+use dslab_faas::resource::{Resource, ResourceConsumer, ResourceProvider, ResourceRequirement};
+
+fn invoke_burst(invoker: &mut dyn Invoker) -> Vec<InvokerDecision> {
+    let mut sim = Simulation::new(1);
+    let ctx = Rc::new(RefCell::new(sim.create_context("host")));
+    let mut cm = ContainerManager::new(
+        ResourceProvider::new(vec![Resource::new(0, 2)]),
+        0.,
+        ContainerSelectionPolicy::Nearest,
+        ctx,
+    );
+    let mut fr = FunctionRegistry::default();
+    let f = fr.add_app_with_single_function(Application::new(
+        1,
+        1.,
+        1.,
+        ResourceConsumer::new(vec![ResourceRequirement::new(0, 1)]),
+    ));
+    let app_id = fr.get_function(f).unwrap().app_id;
+    let invocations: Vec<_> = (0..3)
+        .map(|id| Invocation {
+            id,
+            app_id,
+            func_id: f,
+            duration: 1.,
+            warmup_penalty: 0.,
+            arrival_time: 0.,
+            status: InvocationStatus::NotArrived,
+            host_id: None,
+            container_id: None,
+            start_time: None,
+            finish_time: None,
+        })
+        .collect();
+    invoker.invoke_many(&invocations, Rc::new(RefCell::new(fr)), &mut cm, 0.)
+}
+
+#[test]
+fn test_invoke_many_does_not_overbook_containers() {
+    // each container runs one invocation, so the burst deploys two containers and the last invocation is queued
+    let expected = vec![
+        InvokerDecision::Cold((0, 1.)),
+        InvokerDecision::Cold((1, 1.)),
+        InvokerDecision::Queued,
+    ];
+    assert_eq!(invoke_burst(&mut FIFOInvoker::new()), expected);
+    // the default implementation gives the same decisions
+    assert_eq!(invoke_burst(&mut NaiveInvoker::new()), expected);
+}
@@ -0,0 +1,43 @@
+use std::boxed::Box;
+
+use dslab_core::simulation::Simulation;
+use dslab_faas::coldstart::FixedTimeColdStartPolicy;
+use dslab_faas::config::Config;
+use dslab_faas::cpu::IgnoredCpuPolicy;
+use dslab_faas::function::Application;
+use dslab_faas::invocation::InvocationStatus;
+use dslab_faas::resource::{ResourceConsumer, ResourceProvider};
+use dslab_faas::simulation::ServerlessSimulation;
+
+mod common;
+use common::assert_float_eq;
+
+#[test]
+fn test_concurrent_invocations_share_container() {
+    let config = Config {
+        coldstart_policy: Box::new(FixedTimeColdStartPolicy::new(0.0, 0.0)),
+        cpu_policy: Box::new(IgnoredCpuPolicy::default()),
+        ..Default::default()
+    };
+    let mut sim = ServerlessSimulation::new(Simulation::new(1), config);
+    let host_mem = sim.create_resource("mem", 1);
+    sim.add_host(None, ResourceProvider::new(vec![host_mem]), 1);
+    let fn_mem = sim.create_resource_requirement("mem", 1);
+    let f = sim.add_app_with_single_function(Application::new(2, 0., 1., ResourceConsumer::new(vec![fn_mem])));
+    sim.send_invocation_request(f, 2.0, 0.0);
+    sim.send_invocation_request(f, 2.0, 0.5);
+    sim.step_until_no_events();
+
+    // the second invocation starts immediately in the running container
+    let second = sim.get_invocation(1);
+    assert_eq!(second.status, InvocationStatus::Finished);
+    assert_eq!(second.container_id, sim.get_invocation(0).container_id);
+    assert_float_eq(second.start_time.unwrap(), 0.5, 1e-9);
+    assert_float_eq(second.finish_time.unwrap(), 2.5, 1e-9);
+    assert_eq!(sim.invocation_stats().cold_starts, 1);
+
+    // both slots are occupied during 1.5 of 2.5 busy time units, one slot during the rest
+    let utilization = &sim.invocation_stats().concurrency_utilization;
+    assert_eq!(utilization.len(), 1);
+    assert_float_eq(utilization.mean(), 0.8, 1e-9);
+}
@@ -0,0 +1,51 @@
+use std::boxed::Box;
+
+use dslab_core::simulation::Simulation;
+use dslab_faas::coldstart::FixedTimeColdStartPolicy;
+use dslab_faas::config::Config;
+use dslab_faas::cpu::IgnoredCpuPolicy;
+use dslab_faas::function::Application;
+use dslab_faas::invocation::InvocationStatus;
+use dslab_faas::resource::{ResourceConsumer, ResourceProvider};
+use dslab_faas::simulation::ServerlessSimulation;
+
+mod common;
+use common::assert_float_eq;
+
+#[test]
+fn test_draining_container_finishes_invocation() {
+    let config = Config {
+        coldstart_policy: Box::new(FixedTimeColdStartPolicy::new(0.0, 0.0)),
+        cpu_policy: Box::new(IgnoredCpuPolicy::default()),
+        container_drain_delay: 0.5,
+        ..Default::default()
+    };
+    let mut sim = ServerlessSimulation::new(Simulation::new(1), config);
+    let host_mem = sim.create_resource("mem", 2);
+    sim.add_host(None, ResourceProvider::new(vec![host_mem]), 1);
+    let fn_mem = sim.create_resource_requirement("mem", 1);
+    let f = sim.add_app_with_single_function(Application::new(1, 1., 1., ResourceConsumer::new(vec![fn_mem])));
+    sim.send_invocation_request(f, 2.0, 0.0);
+    sim.send_invocation_request(f, 2.0, 2.5);
+    sim.step_for_duration(2.0);
+    assert_eq!(sim.get_invocation(0).status, InvocationStatus::Running);
+    let container = sim.get_invocation(0).container_id.unwrap();
+    assert!(sim.unload_container(0, container));
+    sim.step_until_no_events();
+
+    // the draining container finished its invocation instead of being killed
+    let first = sim.get_invocation(0);
+    assert_eq!(first.status, InvocationStatus::Finished);
+    assert_float_eq(first.finish_time.unwrap(), 3.0, 1e-9);
+
+    // the new invocation was refused by the draining container and caused a cold start
+    let second = sim.get_invocation(1);
+    assert_eq!(second.status, InvocationStatus::Finished);
+    assert_ne!(second.container_id.unwrap(), container);
+    assert_float_eq(second.start_time.unwrap(), 3.5, 1e-9);
+    assert_eq!(sim.invocation_stats().cold_starts, 2);
+
+    // only the drain delay is counted as wasted time
+    assert_float_eq(sim.global_stats().wasted_resource_time[0].sum(), 0.5, 1e-9);
+    assert!(!sim.unload_container(0, container));
+}
@@ -0,0 +1,118 @@
+use std::boxed::Box;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use dslab_core::simulation::Simulation;
+use dslab_faas::coldstart::FixedTimeColdStartPolicy;
+use dslab_faas::config::Config;
+use dslab_faas::container::{ContainerManager, ContainerStatus};
+use dslab_faas::cpu::IgnoredCpuPolicy;
+use dslab_faas::function::{Application, FunctionRegistry};
+use dslab_faas::invocation::{Invocation, InvocationStatus};
+use dslab_faas::invoker::{DequeuedInvocation, Invoker, InvokerDecision, RejectionReason};
+use dslab_faas::resource::{ResourceConsumer, ResourceProvider};
+use dslab_faas::simulation::ServerlessSimulation;
+use dslab_faas::stats::Stats;
+
+/// Evicts containers of other applications when there are not enough resources for a new container.
+struct EvictingInvoker {
+    eviction_results: Rc<RefCell<Vec<Result<(), String>>>>,
+}
+
+impl Invoker for EvictingInvoker {
+    fn dequeue(
+        &mut self,
+        _fr: Rc<RefCell<FunctionRegistry>>,
+        _cm: &mut ContainerManager,
+        _stats: &mut Stats,
+        _time: f64,
+    ) -> Vec<DequeuedInvocation> {
+        Vec::new()
+    }
+
+    fn invoke(
+        &mut self,
+        invocation: &Invocation,
+        fr: Rc<RefCell<FunctionRegistry>>,
+        cm: &mut ContainerManager,
+        time: f64,
+    ) -> InvokerDecision {
+        let fr_ref = fr.borrow();
+        let app = fr_ref.get_app(invocation.app_id).unwrap();
+        if let Some(c) = cm.get_possible_containers(app, false).next() {
+            return InvokerDecision::Warm(c.id);
+        }
+        if !cm.can_allocate(app.get_resources()) {
+            let others: Vec<usize> = cm
+                .get_containers()
+                .values()
+                .filter(|c| c.app_id != app.id)
+                .map(|c| c.id)
+                .collect();
+            for id in others {
+                let result = cm.evict(id);
+                self.eviction_results.borrow_mut().push(result);
+            }
+        }
+        match cm.try_deploy(app, time) {
+            Ok((id, delay)) => InvokerDecision::Cold((id, delay)),
+            Err(reason) => InvokerDecision::Rejected(reason),
+        }
+    }
+
+    fn queue_len(&self) -> usize {
+        0
+    }
+}
+
+#[test]
+fn test_evicted_container_frees_resources() {
+    let config = Config {
+        coldstart_policy: Box::new(FixedTimeColdStartPolicy::new(100.0, 0.0)),
+        cpu_policy: Box::new(IgnoredCpuPolicy::default()),
+        container_status_log: true,
+        ..Default::default()
+    };
+    let mut sim = ServerlessSimulation::new(Simulation::new(1), config);
+    let host_mem = sim.create_resource("mem", 1);
+    let eviction_results = Rc::new(RefCell::new(Vec::new()));
+    let invoker = EvictingInvoker {
+        eviction_results: eviction_results.clone(),
+    };
+    sim.add_host(Some(Box::new(invoker)), ResourceProvider::new(vec![host_mem]), 1);
+    let fn_mem = sim.create_resource_requirement("mem", 1);
+    let f1 = sim.add_app_with_single_function(Application::new(1, 0., 1., ResourceConsumer::new(vec![fn_mem])));
+    let fn_mem = sim.create_resource_requirement("mem", 1);
+    let f2 = sim.add_app_with_single_function(Application::new(1, 0., 1., ResourceConsumer::new(vec![fn_mem])));
+    sim.send_invocation_request(f1, 1.0, 0.0);
+    sim.send_invocation_request(f2, 10.0, 5.0);
+    sim.send_invocation_request(f1, 1.0, 6.0);
+    sim.step_until_no_events();
+
+    // the idle container of the first app is evicted to deploy the second app
+    assert_eq!(sim.get_invocation(1).status, InvocationStatus::Finished);
+    assert_eq!(sim.get_invocation(1).container_id, Some(1));
+    // the running container of the second app can't be evicted
+    assert_eq!(sim.get_invocation(2).status, InvocationStatus::Rejected);
+    let results = eviction_results.borrow();
+    assert_eq!(results.len(), 2);
+    assert!(results[0].is_ok());
+    assert!(results[1].is_err());
+
+    let log = sim.container_status_log(0);
+    assert!(log.iter().any(|t| t.container_id == 0
+        && t.time == 5.0
+        && t.old_status == Some(ContainerStatus::Idle)
+        && t.new_status.is_none()));
+
+    let stats = sim.stats();
+    assert_eq!(stats.global_stats.invocation_stats.evictions, 1);
+    assert_eq!(stats.app_stats[0].evictions, 1);
+    assert_eq!(stats.app_stats[1].evictions, 0);
+    assert_eq!(
+        stats.app_stats[0].rejection_reasons[&RejectionReason::InsufficientResources],
+        1
+    );
+    // the evicted container was idle from 1 to 5, the other one is unloaded after 100 idle seconds
+    assert_eq!(stats.global_stats.wasted_resource_time[0].sum(), 104.0);
+}
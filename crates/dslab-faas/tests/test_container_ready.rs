@@ -0,0 +1,59 @@
+use std::boxed::Box;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use dslab_core::simulation::Simulation;
+use dslab_faas::coldstart::FixedTimeColdStartPolicy;
+use dslab_faas::config::Config;
+use dslab_faas::cpu::IgnoredCpuPolicy;
+use dslab_faas::function::Application;
+use dslab_faas::host::Host;
+use dslab_faas::resource::{ResourceConsumer, ResourceProvider};
+use dslab_faas::scheduler::{BasicScheduler, Scheduler};
+use dslab_faas::simulation::ServerlessSimulation;
+
+/// (host id, container id, app id, time)
+type ReadyRecord = (usize, usize, usize, f64);
+
+/// Records ready containers.
+struct ReadyRecorder {
+    inner: BasicScheduler,
+    ready: Rc<RefCell<Vec<ReadyRecord>>>,
+}
+
+impl Scheduler for ReadyRecorder {
+    fn select_host(&mut self, app: &Application, hosts: &[Rc<RefCell<Host>>]) -> usize {
+        self.inner.select_host(app, hosts)
+    }
+
+    fn on_container_ready(&mut self, host_id: usize, container_id: usize, app_id: usize, time: f64) {
+        self.ready.borrow_mut().push((host_id, container_id, app_id, time));
+    }
+}
+
+#[test]
+fn test_container_ready_event() {
+    let ready = Rc::new(RefCell::new(Vec::new()));
+    let config = Config {
+        coldstart_policy: Box::new(FixedTimeColdStartPolicy::new(10.0, 0.0)),
+        cpu_policy: Box::new(IgnoredCpuPolicy::default()),
+        scheduler: Box::new(ReadyRecorder {
+            inner: BasicScheduler {},
+            ready: ready.clone(),
+        }),
+        ..Default::default()
+    };
+    let mut sim = ServerlessSimulation::new(Simulation::new(1), config);
+    let host_mem = sim.create_resource("mem", 2);
+    sim.add_host(None, ResourceProvider::new(vec![host_mem]), 1);
+    let fn_mem = sim.create_resource_requirement("mem", 1);
+    let f1 = sim.add_app_with_single_function(Application::new(1, 2., 1., ResourceConsumer::new(vec![fn_mem])));
+    let fn_mem = sim.create_resource_requirement("mem", 1);
+    let f2 = sim.add_app_with_single_function(Application::new(1, 0.5, 1., ResourceConsumer::new(vec![fn_mem])));
+    sim.send_invocation_request(f1, 1.0, 0.0);
+    sim.send_invocation_request(f2, 1.0, 1.0);
+    sim.step_until_no_events();
+
+    assert_eq!(*ready.borrow(), vec![(0, 1, 1, 1.5), (0, 0, 0, 2.0)]);
+    assert_eq!(sim.get_invocation(0).start_time, Some(2.0));
+}
@@ -0,0 +1,43 @@
+use std::boxed::Box;
+
+use dslab_core::simulation::Simulation;
+use dslab_faas::coldstart::FixedTimeColdStartPolicy;
+use dslab_faas::config::Config;
+use dslab_faas::cpu::IgnoredCpuPolicy;
+use dslab_faas::function::Application;
+use dslab_faas::invocation::InvocationStatus;
+use dslab_faas::invoker::FIFOInvoker;
+use dslab_faas::resource::{ResourceConsumer, ResourceProvider};
+use dslab_faas::simulation::ServerlessSimulation;
+
+#[test]
+fn test_small_host_rejects_third_large_container() {
+    let config = Config {
+        coldstart_policy: Box::new(FixedTimeColdStartPolicy::new(0.0, 0.0)),
+        cpu_policy: Box::new(IgnoredCpuPolicy::default()),
+        ..Default::default()
+    };
+    let mut sim = ServerlessSimulation::new(Simulation::new(1), config);
+    let host_mem = sim.create_resource("mem", 4);
+    let host_cpu = sim.create_resource("cpu", 2);
+    sim.add_host(
+        Some(Box::new(FIFOInvoker::with_capacity(0))),
+        ResourceProvider::new(vec![host_mem, host_cpu]),
+        1,
+    );
+    let fn_mem = sim.create_resource_requirement("mem", 1);
+    let fn_cpu = sim.create_resource_requirement("cpu", 1);
+    let f = sim.add_app_with_single_function(Application::new(1, 0., 1., ResourceConsumer::new(vec![fn_mem, fn_cpu])));
+    for _ in 0..3 {
+        sim.send_invocation_request(f, 1.0, 0.0);
+    }
+    sim.step_until_no_events();
+
+    // there is enough memory for the third container, but the host runs out of cpu
+    assert_eq!(sim.get_invocation(0).status, InvocationStatus::Finished);
+    assert_eq!(sim.get_invocation(1).status, InvocationStatus::Finished);
+    assert_ne!(sim.get_invocation(0).container_id, sim.get_invocation(1).container_id);
+    assert_eq!(sim.get_invocation(2).status, InvocationStatus::Rejected);
+    assert_eq!(sim.invocation_stats().cold_starts, 2);
+    assert_eq!(sim.invocation_stats().rejections, 1);
+}
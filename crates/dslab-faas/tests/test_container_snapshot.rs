@@ -0,0 +1,45 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use dslab_core::simulation::Simulation;
+use dslab_faas::container::{ContainerManager, ContainerSelectionPolicy, ContainerStatus};
+use dslab_faas::function::Application;
+use dslab_faas::resource::{Resource, ResourceConsumer, ResourceProvider, ResourceRequirement};
+
+#[test]
+fn test_container_snapshot() {
+    let mut sim = Simulation::new(1);
+    let ctx = Rc::new(RefCell::new(sim.create_context("host")));
+    let mut cm = ContainerManager::new(
+        ResourceProvider::new(vec![Resource::new(0, 4)]),
+        0.,
+        ContainerSelectionPolicy::Nearest,
+        ctx,
+    );
+    let mut app = Application::new(1, 1., 1., ResourceConsumer::new(vec![ResourceRequirement::new(0, 1)]));
+    // applications get their ids when registered, the manager is used here without the registry
+    app.id = 0;
+    assert_eq!(cm.iter_containers().count(), 0);
+    assert_eq!(cm.container_count_by_status(), [0; 4]);
+
+    for _ in 0..3 {
+        cm.try_deploy(&app, 0.).unwrap();
+    }
+    cm.set_idle(0, 1.);
+    cm.start_invocation(1, 0, 1.);
+    cm.start_invocation(2, 1, 1.);
+    cm.start_draining(2, 1.);
+
+    let statuses: Vec<_> = cm.iter_containers().map(|c| (c.id, c.status)).collect();
+    assert_eq!(
+        statuses,
+        vec![
+            (0, ContainerStatus::Idle),
+            (1, ContainerStatus::Running),
+            (2, ContainerStatus::Draining),
+        ]
+    );
+    assert_eq!(cm.container_count_by_status(), [0, 1, 1, 1]);
+    cm.try_deploy(&app, 1.).unwrap();
+    assert_eq!(cm.container_count_by_status(), [1, 1, 1, 1]);
+}
@@ -0,0 +1,298 @@
+use std::boxed::Box;
+
+use dslab_faas::coldstart::{ColdStartPolicy, FixedTimeColdStartPolicy};
+use dslab_faas::config::Config;
+use dslab_faas::container::ContainerStatus::*;
+use dslab_faas::container::{Container, ContainerSelectionPolicy, ContainerStatusTransition};
+use dslab_faas::cpu::IgnoredCpuPolicy;
+use dslab_faas::function::{Application, DeploymentTimeDistribution, Function};
+use dslab_faas::invocation::Invocation;
+use dslab_faas::stats::Stats;
+use dslab_faas::warmth::CacheWarmthModel;
+
+mod common;
+use common::{
+    add_function, add_host, assert_float_eq, fixed_keepalive_config, make_app, make_sim, no_unloading_config,
+};
+
+fn run_status_log(container_status_log: bool) -> Vec<ContainerStatusTransition> {
+    let config = Config {
+        container_status_log,
+        ..fixed_keepalive_config(1.0)
+    };
+    let mut sim = make_sim(1, config);
+    add_host(&mut sim, None, 1);
+    let f = add_function(&mut sim, 1, 1., 1);
+    sim.send_invocation_request(f, 1.0, 0.0);
+    sim.step_until_no_events();
+    sim.container_status_log(0)
+}
+
+#[test]
+fn test_container_status_log() {
+    let transition = |time, old_status, new_status| ContainerStatusTransition {
+        time,
+        container_id: 0,
+        old_status,
+        new_status,
+    };
+    assert_eq!(
+        run_status_log(true),
+        vec![
+            transition(0., None, Some(Deploying)),
+            transition(1., Some(Deploying), Some(Running)),
+            transition(2., Some(Running), Some(Idle)),
+            transition(3., Some(Idle), None),
+        ]
+    );
+    assert!(run_status_log(false).is_empty());
+}
+
+/// Keeps containers of the first application forever and unloads other containers immediately.
+struct FirstAppOnly {}
+
+impl ColdStartPolicy for FirstAppOnly {
+    fn keepalive_window(&mut self, container: &Container) -> f64 {
+        if container.app_id == 0 {
+            f64::MAX / 10.
+        } else {
+            0.
+        }
+    }
+
+    fn prewarm_window(&mut self, _app: &Application) -> f64 {
+        0.
+    }
+
+    fn update(&mut self, _invocation: &Invocation, _app: &Application) {}
+}
+
+fn keepalive_cold_starts(coldstart_policy: Box<dyn ColdStartPolicy>) -> u64 {
+    let config = Config {
+        coldstart_policy,
+        cpu_policy: Box::new(IgnoredCpuPolicy::default()),
+        ..Default::default()
+    };
+    let mut sim = make_sim(1, config);
+    add_host(&mut sim, None, 2);
+    for _ in 0..2 {
+        let f = add_function(&mut sim, 1, 1., 1);
+        sim.send_invocation_request(f, 1.0, 0.0);
+        sim.send_invocation_request(f, 1.0, 100.0);
+    }
+    sim.step_until_no_events();
+    sim.invocation_stats().cold_starts
+}
+
+#[test]
+fn test_keepalive_policies() {
+    assert_eq!(
+        keepalive_cold_starts(Box::new(FixedTimeColdStartPolicy::new(10.0, 0.0))),
+        4
+    );
+    assert_eq!(
+        keepalive_cold_starts(Box::new(FixedTimeColdStartPolicy::no_unloading())),
+        2
+    );
+    assert_eq!(keepalive_cold_starts(Box::new(FirstAppOnly {})), 3);
+}
+
+#[test]
+fn test_container_unload_time() {
+    let mut sim = make_sim(1, fixed_keepalive_config(10.0));
+    add_host(&mut sim, None, 1);
+    let f = add_function(&mut sim, 1, 1., 1);
+    sim.send_invocation_request(f, 1.0, 0.0);
+    sim.step_for_duration(0.5);
+    // deploying
+    assert_eq!(sim.container_unload_time(0, 0), None);
+    sim.step_for_duration(1.0);
+    // running
+    assert_eq!(sim.container_unload_time(0, 0), None);
+    sim.step_for_duration(1.0);
+    // idle, finished the invocation at 2.0
+    assert_eq!(sim.container_unload_time(0, 0), Some(12.0));
+    assert_eq!(sim.container_unload_time(0, 1), None);
+    sim.step_until_no_events();
+    assert_eq!(sim.container_unload_time(0, 0), None);
+}
+
+fn selection_cold_starts(policy: ContainerSelectionPolicy) -> u64 {
+    let config = Config {
+        container_selection: policy,
+        ..fixed_keepalive_config(10.0)
+    };
+    let mut sim = make_sim(1, config);
+    add_host(&mut sim, None, 4);
+    let f = add_function(&mut sim, 1, 0., 1);
+    // the first burst deploys two containers: the first one is unloaded at 12, the second one at 11
+    sim.send_invocation_request(f, 2.0, 0.0);
+    sim.send_invocation_request(f, 0.5, 0.5);
+    // a single invocation between the bursts extends the keepalive of the chosen container
+    sim.send_invocation_request(f, 1.0, 5.0);
+    // the second burst needs two containers again
+    sim.send_invocation_request(f, 1.0, 11.5);
+    sim.send_invocation_request(f, 1.0, 11.5);
+    sim.step_until_no_events();
+    sim.invocation_stats().cold_starts
+}
+
+#[test]
+fn test_least_remaining_keepalive_reduces_cold_starts() {
+    // the first container is always reused, so the second one expires before the next burst
+    assert_eq!(selection_cold_starts(ContainerSelectionPolicy::Nearest), 3);
+    // the container closest to unloading is reused and both containers survive until the next burst
+    assert_eq!(
+        selection_cold_starts(ContainerSelectionPolicy::LeastRemainingKeepalive),
+        2
+    );
+}
+
+fn run_warm_pool(pool_size: usize) -> Stats {
+    // containers without invocations are unloaded right away
+    let mut sim = make_sim(1, fixed_keepalive_config(0.0));
+    add_host(&mut sim, None, 4);
+    let app = make_app(&mut sim, 1, 1., 1);
+    let app = sim.add_app(app);
+    let f = sim.add_function(Function::new(app));
+    assert_eq!(sim.ensure_warm(app, pool_size), pool_size);
+    // two overlapping invocations are running at any moment
+    for i in 0..20 {
+        sim.send_invocation_request(f, 1.5, 2.0 + i as f64);
+    }
+    sim.step_until_no_events();
+    sim.stats()
+}
+
+#[test]
+fn test_warm_pool_covering_arrival_rate_avoids_cold_starts() {
+    assert!(run_warm_pool(0).global_stats.invocation_stats.cold_starts > 0);
+
+    let stats = run_warm_pool(2);
+    assert_eq!(stats.global_stats.invocation_stats.invocations, 20);
+    assert_eq!(stats.global_stats.invocation_stats.cold_starts, 0);
+    // pool containers are idle between invocations, but their idle time is not wasted
+    assert!(stats.global_stats.pool_resource_time[0].sum() > 0.);
+    assert!(stats.global_stats.wasted_resource_time.get(0).is_none());
+}
+
+#[test]
+fn test_ensure_warm_is_limited_by_resources() {
+    let mut sim = make_sim(1, fixed_keepalive_config(0.0));
+    add_host(&mut sim, None, 3);
+    let app = make_app(&mut sim, 1, 1., 1);
+    let app = sim.add_app(app);
+    assert_eq!(sim.ensure_warm(app, 2), 2);
+    // the existing pool containers are reused
+    assert_eq!(sim.ensure_warm(app, 2), 2);
+    assert_eq!(sim.ensure_warm(app, 5), 3);
+}
+
+fn run_wait_for_busy(wait_for_busy_containers: bool, first_duration: f64, second_arrival: f64) -> Vec<Invocation> {
+    let config = Config {
+        wait_for_busy_containers,
+        ..fixed_keepalive_config(10.0)
+    };
+    let mut sim = make_sim(1, config);
+    add_host(&mut sim, None, 2);
+    let f = add_function(&mut sim, 1, 5., 1);
+    // the first invocation starts in the container at 5
+    sim.send_invocation_request(f, first_duration, 0.0);
+    sim.send_invocation_request(f, 1.0, second_arrival);
+    sim.step_until_no_events();
+    sim.get_invocations(0..2)
+}
+
+#[test]
+fn test_waiting_beats_cold_start() {
+    // without waiting the second invocation deploys a new container
+    let invocations = run_wait_for_busy(false, 2.0, 6.0);
+    assert_eq!(invocations[1].container_id, Some(1));
+    assert_eq!(invocations[1].start_time, Some(11.0));
+
+    // the busy container frees up at 7, which is sooner than the deployment of a new one
+    let invocations = run_wait_for_busy(true, 2.0, 6.0);
+    assert_eq!(invocations[1].container_id, Some(0));
+    assert_eq!(invocations[1].start_time, Some(7.0));
+    assert_eq!(invocations[1].finish_time, Some(8.0));
+}
+
+#[test]
+fn test_cold_start_beats_waiting() {
+    // the busy container frees up at 15, while a new one is ready at 11
+    let invocations = run_wait_for_busy(true, 10.0, 6.0);
+    assert_eq!(invocations[1].container_id, Some(1));
+    assert_eq!(invocations[1].start_time, Some(11.0));
+}
+
+/// Runs invocations that never reuse containers and returns their start times.
+fn deployment_times(dist: DeploymentTimeDistribution, seed: u64) -> Vec<f64> {
+    let mut sim = make_sim(seed, fixed_keepalive_config(0.0));
+    add_host(&mut sim, None, 1);
+    let mut app = make_app(&mut sim, 1, 1., 1);
+    app.set_deployment_time_distribution(dist);
+    let f = sim.add_app_with_single_function(app);
+    for i in 0..10 {
+        sim.send_invocation_request(f, 1.0, i as f64 * 100.);
+    }
+    sim.step_until_no_events();
+    (0..10)
+        .map(|i| sim.get_invocation(i).start_time.unwrap() - i as f64 * 100.)
+        .collect()
+}
+
+#[test]
+fn test_constant_deployment_time() {
+    assert_eq!(
+        deployment_times(DeploymentTimeDistribution::Constant(2.), 1),
+        vec![2.; 10]
+    );
+}
+
+#[test]
+fn test_normal_deployment_time_is_deterministic() {
+    let dist = DeploymentTimeDistribution::Normal { mean: 5., std_dev: 1. };
+    let times = deployment_times(dist.clone(), 123);
+    assert_eq!(times, deployment_times(dist, 123));
+    assert!(times.iter().all(|&t| t >= 0.));
+    assert!(times.iter().any(|&t| t != times[0]));
+}
+
+#[test]
+fn test_empirical_deployment_time() {
+    let samples = vec![0.5, 1.5, 4.];
+    let times = deployment_times(DeploymentTimeDistribution::Empirical(samples.clone()), 7);
+    assert!(times.iter().all(|t| samples.contains(t)));
+    assert_eq!(
+        times,
+        deployment_times(DeploymentTimeDistribution::Empirical(samples), 7)
+    );
+}
+
+fn reuse_execution_time(idle: f64) -> f64 {
+    let config = Config {
+        cache_warmth: Some(CacheWarmthModel::new(0.2, 100.)),
+        ..no_unloading_config()
+    };
+    let mut sim = make_sim(1, config);
+    add_host(&mut sim, None, 1);
+    let f = add_function(&mut sim, 1, 1., 1);
+    // the first invocation runs on a freshly deployed container and finishes at time 2
+    sim.send_invocation_request(f, 1.0, 0.0);
+    sim.send_invocation_request(f, 1.0, 2.0 + idle);
+    sim.step_until_no_events();
+    let first = sim.get_invocation(0);
+    assert_float_eq(first.execution_time(), 1.0, 1e-9);
+    let second = sim.get_invocation(1);
+    assert_eq!(first.container_id, second.container_id);
+    second.execution_time()
+}
+
+#[test]
+fn test_warmth_decay_penalty() {
+    let quick = reuse_execution_time(1.);
+    let long = reuse_execution_time(10000.);
+    assert!(quick < 1.01);
+    assert_float_eq(long, 1.2, 1e-6);
+    assert!(quick < long);
+}
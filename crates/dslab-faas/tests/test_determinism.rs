@@ -0,0 +1,74 @@
+use std::boxed::Box;
+
+use dslab_faas::config::Config;
+use dslab_faas::invoker::NaiveInvoker;
+use dslab_faas::resource::ResourceProvider;
+use dslab_faas::scheduler::RandomScheduler;
+
+mod common;
+use common::{add_function, add_host, fixed_keepalive_config, make_sim};
+
+fn run_stats() -> String {
+    let mut sim = make_sim(1, fixed_keepalive_config(3.0));
+    let host_mem = sim.create_resource("mem", 4);
+    sim.add_host(
+        Some(Box::new(NaiveInvoker::new())),
+        ResourceProvider::new(vec![host_mem]),
+        2,
+    );
+    let f1 = add_function(&mut sim, 1, 0.5, 1);
+    let f2 = add_function(&mut sim, 2, 1., 2);
+    // bursts of simultaneous invocations produce many ties between equally suitable containers
+    for i in 0..20 {
+        let time = (i / 4) as f64 * 2.;
+        sim.send_invocation_request(f1, 1.0 + (i % 3) as f64, time);
+        sim.send_invocation_request(f2, 0.5 + (i % 2) as f64, time);
+    }
+    sim.step_until_no_events();
+    serde_yaml::to_string(&sim.stats()).unwrap()
+}
+
+#[test]
+fn test_same_scenario_gives_identical_stats() {
+    assert_eq!(run_stats(), run_stats());
+}
+
+fn random_hosts(seed: u64, scheduler: RandomScheduler) -> Vec<Option<usize>> {
+    let config = Config {
+        scheduler: Box::new(scheduler),
+        ..fixed_keepalive_config(0.0)
+    };
+    let mut sim = make_sim(seed, config);
+    for _ in 0..4 {
+        add_host(&mut sim, None, 10);
+    }
+    let f = add_function(&mut sim, 1, 1., 1);
+    for i in 0..20 {
+        sim.send_invocation_request(f, 1.0, i as f64);
+    }
+    sim.step_until_no_events();
+    (0..20).map(|i| sim.get_invocation(i).host_id).collect()
+}
+
+#[test]
+fn test_random_scheduler_uses_simulation_rng() {
+    let hosts = random_hosts(42, RandomScheduler::with_simulation_rng());
+    assert_eq!(hosts, random_hosts(42, RandomScheduler::with_simulation_rng()));
+    assert!(hosts.iter().any(|h| *h != hosts[0]));
+}
+
+#[test]
+fn test_seeded_random_scheduler_ignores_simulation_seed() {
+    let hosts = random_hosts(42, RandomScheduler::new(5));
+    assert_eq!(hosts, random_hosts(43, RandomScheduler::new(5)));
+    assert!(hosts.iter().any(|h| *h != hosts[0]));
+}
+
+#[test]
+fn test_simulation_rng_is_seeded() {
+    let sim1 = make_sim(7, Config::default());
+    let sim2 = make_sim(7, Config::default());
+    let a: Vec<f64> = (0..5).map(|_| sim1.rng().rand()).collect();
+    let b: Vec<f64> = (0..5).map(|_| sim2.rng().rand()).collect();
+    assert_eq!(a, b);
+}
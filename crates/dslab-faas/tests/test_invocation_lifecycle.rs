@@ -31,6 +31,10 @@ fn test_invocation_lifecycle() {
     sim.step();
     assert_eq!(sim.get_invocation(0).status, InvocationStatus::Running);
     assert_eq!(sim.get_invocation(1).status, InvocationStatus::NotArrived);
+    // the controller is notified that the container is ready
+    sim.step();
+    assert_eq!(sim.get_invocation(0).status, InvocationStatus::Running);
+    assert_eq!(sim.get_invocation(1).status, InvocationStatus::NotArrived);
     sim.step();
     assert_eq!(sim.get_invocation(0).status, InvocationStatus::Running);
     assert_eq!(sim.get_invocation(1).status, InvocationStatus::Queued);
@@ -0,0 +1,226 @@
+use std::boxed::Box;
+
+use dslab_core::simulation::Simulation;
+use dslab_faas::coldstart::FixedTimeColdStartPolicy;
+use dslab_faas::config::Config;
+use dslab_faas::cpu::IgnoredCpuPolicy;
+use dslab_faas::function::Application;
+use dslab_faas::invocation::InvocationStatus;
+use dslab_faas::invoker::{
+    default_invoker_resolver, FIFOInvoker, Invoker, InvokerSpec, NaiveInvoker, PriorityInvoker, RejectionReason,
+};
+use dslab_faas::resource::{ResourceConsumer, ResourceProvider};
+use dslab_faas::simulation::ServerlessSimulation;
+
+mod common;
+use common::assert_float_eq;
+
+#[test]
+fn test_fifo_invoker_rejects_on_full_queue() {
+    let config = Config {
+        coldstart_policy: Box::new(FixedTimeColdStartPolicy::new(f64::MAX / 10., 0.0)),
+        cpu_policy: Box::new(IgnoredCpuPolicy::default()),
+        ..Default::default()
+    };
+    let mut sim = ServerlessSimulation::new(Simulation::new(1), config);
+    let host_mem = sim.create_resource("mem", 1);
+    sim.add_host(
+        Some(Box::new(FIFOInvoker::with_capacity(2))),
+        ResourceProvider::new(vec![host_mem]),
+        1,
+    );
+    let fn_mem = sim.create_resource_requirement("mem", 1);
+    let f = sim.add_app_with_single_function(Application::new(1, 1., 1., ResourceConsumer::new(vec![fn_mem])));
+    for _ in 0..4 {
+        sim.send_invocation_request(f, 2.0, 0.0);
+    }
+    sim.step_until_no_events();
+
+    // the first invocation occupies the only container, the next two are queued and the last one is rejected
+    for id in 0..3 {
+        assert_eq!(sim.get_invocation(id).status, InvocationStatus::Finished);
+    }
+    assert_float_eq(sim.get_invocation(2).finish_time.unwrap(), 7.0, 1e-9);
+    let rejected = sim.get_invocation(3);
+    assert_eq!(rejected.status, InvocationStatus::Rejected);
+    assert!(rejected.start_time.is_none());
+    assert_eq!(sim.invocation_stats().invocations, 4);
+    assert_eq!(sim.invocation_stats().rejections, 1);
+    assert_eq!(sim.invocation_stats().rejection_reasons[&RejectionReason::QueueFull], 1);
+}
+
+fn check_queueing_timeout(invoker: Box<dyn Invoker>) {
+    let config = Config {
+        coldstart_policy: Box::new(FixedTimeColdStartPolicy::new(f64::MAX / 10., 0.0)),
+        cpu_policy: Box::new(IgnoredCpuPolicy::default()),
+        ..Default::default()
+    };
+    let mut sim = ServerlessSimulation::new(Simulation::new(1), config);
+    let host_mem = sim.create_resource("mem", 1);
+    sim.add_host(Some(invoker), ResourceProvider::new(vec![host_mem]), 1);
+    let fn_mem = sim.create_resource_requirement("mem", 1);
+    let f = sim.add_app_with_single_function(Application::new(1, 0., 1., ResourceConsumer::new(vec![fn_mem])));
+    sim.send_invocation_request(f, 2.0, 0.0);
+    sim.send_invocation_request(f, 1.0, 0.1);
+    sim.send_invocation_request(f, 1.0, 1.0);
+    sim.step_until_no_events();
+
+    // when the container becomes free at time 2, the first queued invocation has waited for too long
+    assert_eq!(sim.get_invocation(1).status, InvocationStatus::TimedOut);
+    assert!(sim.get_invocation(1).start_time.is_none());
+    assert_eq!(sim.get_invocation(2).status, InvocationStatus::Finished);
+    assert_float_eq(sim.get_invocation(2).start_time.unwrap(), 2.0, 1e-9);
+    assert_eq!(sim.invocation_stats().queue_timeouts, 1);
+}
+
+#[test]
+fn test_queueing_timeout() {
+    check_queueing_timeout(Box::new(FIFOInvoker::with_queueing_timeout(1.5)));
+    check_queueing_timeout(Box::new(NaiveInvoker::with_queueing_timeout(1.5)));
+}
+
+#[test]
+fn test_fifo_invoker_resolver() {
+    assert_eq!(
+        default_invoker_resolver("FIFOInvoker").unwrap().to_string(),
+        "FIFOInvoker"
+    );
+    assert_eq!(
+        default_invoker_resolver("FIFOInvoker[capacity=1000]")
+            .unwrap()
+            .to_string(),
+        "FIFOInvoker[capacity=1000]"
+    );
+    assert_eq!(
+        default_invoker_resolver("FIFOInvoker[capacity=10,timeout=2.5]")
+            .unwrap()
+            .to_string(),
+        "FIFOInvoker[capacity=10,timeout=2.5]"
+    );
+    assert_eq!(
+        default_invoker_resolver("NaiveInvoker[timeout=2.5]")
+            .unwrap()
+            .to_string(),
+        "NaiveInvoker[timeout=2.5]"
+    );
+}
+
+#[test]
+fn test_invoker_resolver_specs() {
+    assert_eq!(
+        InvokerSpec::parse("ShadowInvoker[FIFOInvoker[capacity=1,timeout=2], NaiveInvoker]"),
+        Ok(InvokerSpec {
+            name: "ShadowInvoker".to_string(),
+            args: vec![
+                "FIFOInvoker[capacity=1,timeout=2]".to_string(),
+                "NaiveInvoker".to_string()
+            ],
+        })
+    );
+    assert_eq!(
+        InvokerSpec::parse("ShadowInvoker(FIFOInvoker(capacity=1,timeout=2), NaiveInvoker[timeout=1])"),
+        Ok(InvokerSpec {
+            name: "ShadowInvoker".to_string(),
+            args: vec![
+                "FIFOInvoker(capacity=1,timeout=2)".to_string(),
+                "NaiveInvoker[timeout=1]".to_string()
+            ],
+        })
+    );
+    assert_eq!(
+        default_invoker_resolver("FIFOInvoker(capacity=10,timeout=2.5)")
+            .unwrap()
+            .to_string(),
+        "FIFOInvoker[capacity=10,timeout=2.5]"
+    );
+    assert_eq!(
+        default_invoker_resolver("ShadowInvoker[FIFOInvoker[capacity=1], NaiveInvoker]")
+            .unwrap()
+            .to_string(),
+        "ShadowInvoker[FIFOInvoker[capacity=1], NaiveInvoker]"
+    );
+    for invalid in [
+        "FIFOInvokr",
+        "FIFOInvoker[capacity=1",
+        "FIFOInvoker[capacity=1]]",
+        "FIFOInvoker[capacity=-1]",
+        "FIFOInvoker[size=1]",
+        "FIFOInvoker[capacity=1,]",
+        "PriorityInvoker[timeout=1]",
+        "ShadowInvoker[FIFOInvoker]",
+        "FIFOInvoker(capacity=1]",
+        "ShadowInvoker(FIFOInvoker[capacity=1), NaiveInvoker]",
+        "NaiveInvoker(timeout=x)",
+        "",
+    ] {
+        assert!(default_invoker_resolver(invalid).is_err(), "{} is resolved", invalid);
+    }
+}
+
+fn queued_after_arrivals(invoker: Box<dyn Invoker>) -> Vec<(usize, usize, f64)> {
+    let config = Config {
+        coldstart_policy: Box::new(FixedTimeColdStartPolicy::new(f64::MAX / 10., 0.0)),
+        cpu_policy: Box::new(IgnoredCpuPolicy::default()),
+        ..Default::default()
+    };
+    let mut sim = ServerlessSimulation::new(Simulation::new(1), config);
+    let host_mem = sim.create_resource("mem", 1);
+    sim.add_host(Some(invoker), ResourceProvider::new(vec![host_mem]), 1);
+    let fn_mem = sim.create_resource_requirement("mem", 1);
+    let low = sim.add_app_with_single_function(Application::new(1, 0., 1., ResourceConsumer::new(vec![fn_mem])));
+    let fn_mem = sim.create_resource_requirement("mem", 1);
+    let mut high_app = Application::new(1, 0., 1., ResourceConsumer::new(vec![fn_mem]));
+    high_app.set_priority(1);
+    let high = sim.add_app_with_single_function(high_app);
+    sim.send_invocation_request(low, 5.0, 0.0);
+    sim.send_invocation_request(low, 1.0, 0.5);
+    sim.send_invocation_request(high, 1.0, 1.0);
+    sim.send_invocation_request(low, 1.0, 1.5);
+    sim.step_for_duration(2.0);
+    sim.queued_invocations(0)
+}
+
+#[test]
+fn test_queued_invocations() {
+    assert_eq!(
+        queued_after_arrivals(Box::new(FIFOInvoker::new())),
+        vec![(1, 0, 0.5), (2, 1, 1.0), (3, 0, 1.5)]
+    );
+    assert_eq!(
+        queued_after_arrivals(Box::new(PriorityInvoker::new())),
+        vec![(2, 1, 1.0), (1, 0, 0.5), (3, 0, 1.5)]
+    );
+}
+
+#[test]
+fn test_naive_invoker_skips_rejected_apps() {
+    let config = Config {
+        coldstart_policy: Box::new(FixedTimeColdStartPolicy::new(f64::MAX / 10., 0.0)),
+        cpu_policy: Box::new(IgnoredCpuPolicy::default()),
+        ..Default::default()
+    };
+    let mut sim = ServerlessSimulation::new(Simulation::new(1), config);
+    let host_mem = sim.create_resource("mem", 2);
+    sim.add_host(
+        Some(Box::new(NaiveInvoker::new())),
+        ResourceProvider::new(vec![host_mem]),
+        1,
+    );
+    let small_mem = sim.create_resource_requirement("mem", 1);
+    let small = sim.add_app_with_single_function(Application::new(1, 0., 1., ResourceConsumer::new(vec![small_mem])));
+    let big_mem = sim.create_resource_requirement("mem", 2);
+    let big = sim.add_app_with_single_function(Application::new(1, 0., 1., ResourceConsumer::new(vec![big_mem])));
+    sim.send_invocation_request(small, 2.0, 0.0);
+    sim.send_invocation_request(small, 5.0, 0.1);
+    sim.send_invocation_request(big, 1.0, 0.2);
+    sim.send_invocation_request(big, 1.0, 0.3);
+    sim.send_invocation_request(small, 1.0, 0.4);
+    sim.step_until_no_events();
+
+    // the invocations of the big application can never be started on the host occupied by two small containers,
+    // but they do not block the invocation of the small application queued after them
+    assert!(sim.get_invocation(2).start_time.is_none());
+    assert!(sim.get_invocation(3).start_time.is_none());
+    assert_eq!(sim.get_invocation(4).status, InvocationStatus::Finished);
+    assert_float_eq(sim.get_invocation(4).start_time.unwrap(), 2.0, 1e-9);
+}
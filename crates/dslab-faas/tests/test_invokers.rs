@@ -0,0 +1,166 @@
+use std::boxed::Box;
+
+use dslab_faas::invocation::InvocationStatus;
+use dslab_faas::invoker::{
+    default_invoker_resolver, FIFOInvoker, Invoker, PriorityInvoker, RandomInvoker, RejectionReason, ShadowInvoker,
+};
+use dslab_faas::simulation::ServerlessSimulation;
+
+mod common;
+use common::{
+    add_function, add_host, assert_float_eq, fixed_keepalive_config, make_app, make_sim, no_unloading_config,
+};
+
+/// Returns start times of the queued low and high priority invocations.
+fn run_priorities(invoker: Box<dyn Invoker>) -> (f64, f64) {
+    let mut sim = make_sim(1, no_unloading_config());
+    add_host(&mut sim, Some(invoker), 2);
+    let low = add_function(&mut sim, 1, 0., 1);
+    let mut high_app = make_app(&mut sim, 1, 0., 1);
+    high_app.set_priority(1);
+    let high = sim.add_app_with_single_function(high_app);
+    // both containers are busy when the next invocations arrive
+    sim.send_invocation_request(low, 2.0, 0.0);
+    sim.send_invocation_request(high, 4.0, 0.0);
+    sim.send_invocation_request(low, 1.0, 0.1);
+    sim.send_invocation_request(high, 1.0, 0.2);
+    sim.step_until_no_events();
+    (
+        sim.get_invocation(2).start_time.unwrap(),
+        sim.get_invocation(3).start_time.unwrap(),
+    )
+}
+
+#[test]
+fn test_priority_invoker_serves_high_priority_first() {
+    // FIFO order starts the earlier low priority invocation as soon as its container is free
+    let (low, high) = run_priorities(Box::new(FIFOInvoker::new()));
+    assert_float_eq(low, 2.0, 1e-9);
+    assert_float_eq(high, 4.0, 1e-9);
+
+    // the high priority invocation is at the head of the queue and blocks the low priority one
+    let (low, high) = run_priorities(Box::new(PriorityInvoker::new()));
+    assert_float_eq(low, 4.0, 1e-9);
+    assert_float_eq(high, 4.0, 1e-9);
+}
+
+#[test]
+fn test_priority_invoker_resolver() {
+    assert_eq!(
+        default_invoker_resolver("PriorityInvoker").unwrap().to_string(),
+        "PriorityInvoker"
+    );
+}
+
+/// Returns containers chosen for the invocations sent after four containers are warmed up.
+fn run_container_choices(seed: u64, invoker: Box<dyn Invoker>) -> Vec<Option<usize>> {
+    let mut sim = make_sim(seed, fixed_keepalive_config(100.0));
+    add_host(&mut sim, Some(invoker), 4);
+    let f = add_function(&mut sim, 1, 1., 1);
+    for _ in 0..4 {
+        sim.send_invocation_request(f, 1.0, 0.0);
+    }
+    for i in 0..20 {
+        sim.send_invocation_request(f, 0.5, 3.0 + i as f64);
+    }
+    // the host has room for four containers only, so the last invocation is queued
+    for _ in 0..5 {
+        sim.send_invocation_request(f, 1.0, 30.0);
+    }
+    sim.step_until_no_events();
+    for id in 0..29 {
+        assert_eq!(sim.get_invocation(id).status, InvocationStatus::Finished);
+    }
+    (4..24).map(|id| sim.get_invocation(id).container_id).collect()
+}
+
+#[test]
+fn test_random_invoker_uses_simulation_rng() {
+    // with ties broken by container id the same idle container is chosen every time
+    let containers = run_container_choices(1, Box::new(FIFOInvoker::new()));
+    assert!(containers.iter().all(|c| *c == containers[0]));
+
+    let containers = run_container_choices(42, Box::new(RandomInvoker::new()));
+    assert!(containers.iter().all(|c| c.unwrap() < 4));
+    assert!(containers.iter().any(|c| *c != containers[0]));
+    assert_eq!(containers, run_container_choices(42, Box::new(RandomInvoker::new())));
+    assert_ne!(containers, run_container_choices(43, Box::new(RandomInvoker::new())));
+}
+
+#[test]
+fn test_random_shadow_invoker_does_not_affect_primary() {
+    let shadow = |shadow: Box<dyn Invoker>| -> Box<dyn Invoker> {
+        Box::new(ShadowInvoker::new(Box::new(RandomInvoker::new()), shadow))
+    };
+    let containers = run_container_choices(42, shadow(Box::new(FIFOInvoker::new())));
+    assert!(containers.iter().any(|c| *c != containers[0]));
+    assert_eq!(
+        containers,
+        run_container_choices(42, shadow(Box::new(RandomInvoker::new())))
+    );
+}
+
+#[test]
+fn test_random_invoker_resolver() {
+    assert_eq!(
+        default_invoker_resolver("RandomInvoker").unwrap().to_string(),
+        "RandomInvoker"
+    );
+    assert!(default_invoker_resolver("RandomInvoker[capacity=1]").is_err());
+}
+
+fn run_forwarding(forward_on_second_host: bool) -> ServerlessSimulation {
+    let mut sim = make_sim(1, no_unloading_config());
+    add_host(&mut sim, Some(Box::new(FIFOInvoker::with_forwarding())), 1);
+    let second = if forward_on_second_host {
+        FIFOInvoker::with_forwarding()
+    } else {
+        FIFOInvoker::new()
+    };
+    add_host(&mut sim, Some(Box::new(second)), 1);
+    let f = add_function(&mut sim, 1, 0., 1);
+    sim.send_invocation_request(f, 10.0, 0.0);
+    sim.send_invocation_request(f, 2.0, 0.5);
+    // both hosts are busy, the scheduler selects the first one
+    sim.send_invocation_request(f, 1.0, 1.0);
+    sim.step_until_no_events();
+    sim
+}
+
+#[test]
+fn test_forwarded_invocation_is_rerouted() {
+    let sim = run_forwarding(false);
+    let forwarded = sim.get_invocation(2);
+    assert_eq!(forwarded.status, InvocationStatus::Finished);
+    assert_eq!(forwarded.host_id, Some(1));
+    assert_float_eq(forwarded.start_time.unwrap(), 2.5, 1e-9);
+    assert_eq!(sim.invocation_stats().invocations, 3);
+    assert_eq!(sim.invocation_stats().forwards, 1);
+    assert_eq!(sim.invocation_stats().rejections, 0);
+}
+
+#[test]
+fn test_invocation_forwarded_by_all_hosts_is_rejected() {
+    let sim = run_forwarding(true);
+    let forwarded = sim.get_invocation(2);
+    assert_eq!(forwarded.status, InvocationStatus::Rejected);
+    assert_eq!(forwarded.host_id, Some(0));
+    assert!(forwarded.start_time.is_none());
+    assert_eq!(sim.invocation_stats().invocations, 3);
+    assert_eq!(sim.invocation_stats().forwards, 2);
+    assert_eq!(sim.invocation_stats().rejections, 1);
+    assert_eq!(
+        sim.invocation_stats().rejection_reasons[&RejectionReason::AllHostsForwarded],
+        1
+    );
+}
+
+#[test]
+fn test_forwarding_invoker_resolver() {
+    assert_eq!(
+        default_invoker_resolver("FIFOInvoker[forward=true]")
+            .unwrap()
+            .to_string(),
+        "FIFOInvoker[forward=true]"
+    );
+}
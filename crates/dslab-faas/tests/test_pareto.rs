@@ -0,0 +1,19 @@
+use dslab_faas::parallel::{pareto_frontier, Objective};
+
+#[test]
+fn test_pareto_frontier() {
+    // (cold start %, wasted memory, cost)
+    let points = vec![
+        (10., 100., 5.),
+        (20., 50., 5.),
+        (15., 120., 6.),
+        (5., 200., 5.),
+        (20., 50., 5.),
+        (25., 60., 4.),
+        (30., 70., 4.),
+    ];
+    let objectives: Vec<Objective<(f64, f64, f64)>> = vec![Box::new(|p| p.0), Box::new(|p| p.1), Box::new(|p| p.2)];
+    assert_eq!(pareto_frontier(&points, &objectives), vec![0, 1, 3, 4, 5]);
+    let objectives: Vec<Objective<(f64, f64, f64)>> = vec![Box::new(|p| p.0), Box::new(|p| p.1)];
+    assert_eq!(pareto_frontier(&points, &objectives), vec![0, 1, 3, 4]);
+}
@@ -0,0 +1,120 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use dslab_core::simulation::Simulation;
+use dslab_faas::coldstart::FixedTimeColdStartPolicy;
+use dslab_faas::config::Config;
+use dslab_faas::container::{ContainerManager, ContainerSelectionPolicy, ResourceReservationPolicy};
+use dslab_faas::cpu::IgnoredCpuPolicy;
+use dslab_faas::function::{Application, FunctionRegistry};
+use dslab_faas::invocation::{Invocation, InvocationStatus};
+use dslab_faas::invoker::{FIFOInvoker, Invoker, InvokerDecision};
+use dslab_faas::resource::{Resource, ResourceConsumer, ResourceProvider, ResourceRequirement};
+use dslab_faas::simulation::ServerlessSimulation;
+
+mod common;
+use common::assert_float_eq;
+
+// Returns the decisions for a burst of three invocations on a host with room for two containers
+// and the amount of memory committed right after the burst.
+fn invoke_burst(policy: ResourceReservationPolicy) -> (Vec<InvokerDecision>, u64) {
+    let mut sim = Simulation::new(1);
+    let ctx = Rc::new(RefCell::new(sim.create_context("host")));
+    let mut cm = ContainerManager::new(
+        ResourceProvider::new(vec![Resource::new(0, 2)]),
+        0.,
+        ContainerSelectionPolicy::Nearest,
+        ctx,
+    );
+    cm.set_reservation_policy(policy);
+    let mut fr = FunctionRegistry::default();
+    let f = fr.add_app_with_single_function(Application::new(
+        1,
+        1.,
+        1.,
+        ResourceConsumer::new(vec![ResourceRequirement::new(0, 1)]),
+    ));
+    let app_id = fr.get_function(f).unwrap().app_id;
+    let invocations: Vec<_> = (0..3)
+        .map(|id| Invocation {
+            id,
+            app_id,
+            func_id: f,
+            duration: 1.,
+            warmup_penalty: 0.,
+            arrival_time: 0.,
+            status: InvocationStatus::NotArrived,
+            host_id: None,
+            container_id: None,
+            start_time: None,
+            finish_time: None,
+        })
+        .collect();
+    let decisions = FIFOInvoker::new().invoke_many(&invocations, Rc::new(RefCell::new(fr)), &mut cm, 0.);
+    (decisions, cm.get_used_resource(0))
+}
+
+#[test]
+fn test_burst_reservation() {
+    // resources are committed at decision time, so the last invocation of the burst has to wait
+    let (decisions, used) = invoke_burst(ResourceReservationPolicy::OnDecision);
+    assert_eq!(
+        decisions,
+        vec![
+            InvokerDecision::Cold((0, 1.)),
+            InvokerDecision::Cold((1, 1.)),
+            InvokerDecision::Queued,
+        ]
+    );
+    assert_eq!(used, 2);
+
+    // nothing is committed during the scheduling delay, so the whole burst is admitted
+    let (decisions, used) = invoke_burst(ResourceReservationPolicy::OnDeploymentStart { scheduling_delay: 0.5 });
+    assert_eq!(
+        decisions,
+        vec![
+            InvokerDecision::Cold((0, 1.5)),
+            InvokerDecision::Cold((1, 1.5)),
+            InvokerDecision::Cold((2, 1.5)),
+        ]
+    );
+    assert_eq!(used, 0);
+}
+
+fn run_burst(policy: ResourceReservationPolicy) -> ServerlessSimulation {
+    let config = Config {
+        coldstart_policy: Box::new(FixedTimeColdStartPolicy::new(0.0, 0.0)),
+        cpu_policy: Box::new(IgnoredCpuPolicy::default()),
+        resource_reservation: policy,
+        ..Default::default()
+    };
+    let mut sim = ServerlessSimulation::new(Simulation::new(1), config);
+    let mem = sim.create_resource("mem", 2);
+    sim.add_host(None, ResourceProvider::new(vec![mem]), 1);
+    let fn_mem = sim.create_resource_requirement("mem", 1);
+    let f = sim.add_app_with_single_function(Application::new(1, 1., 1., ResourceConsumer::new(vec![fn_mem])));
+    for _ in 0..3 {
+        sim.send_invocation_request(f, 1.0, 0.0);
+    }
+    sim.step_until_no_events();
+    sim
+}
+
+#[test]
+fn test_deployment_waits_for_resources() {
+    // the last invocation is queued and reuses one of the first containers once it becomes idle
+    let sim = run_burst(ResourceReservationPolicy::OnDecision);
+    assert_float_eq(sim.get_invocation(2).start_time.unwrap(), 2.0, 1e-9);
+    assert!(sim.get_invocation(2).container_id.unwrap() < 2);
+
+    // the third container is admitted, but its deployment starts only after the first containers are unloaded
+    let sim = run_burst(ResourceReservationPolicy::OnDeploymentStart { scheduling_delay: 0.5 });
+    for id in 0..2 {
+        assert_float_eq(sim.get_invocation(id).start_time.unwrap(), 1.5, 1e-9);
+    }
+    assert_float_eq(sim.get_invocation(2).start_time.unwrap(), 3.5, 1e-9);
+    assert_eq!(sim.get_invocation(2).container_id, Some(2));
+    for id in 0..3 {
+        assert_eq!(sim.get_invocation(id).status, InvocationStatus::Finished);
+    }
+}
@@ -0,0 +1,60 @@
+use dslab_faas::config::Config;
+use dslab_faas::invocation::{Invocation, InvocationStatus};
+
+mod common;
+use common::{add_function, add_host, fixed_keepalive_config, make_sim};
+
+fn run(scheduler_tick: Option<f64>) -> Vec<Invocation> {
+    let config = Config {
+        scheduler_tick,
+        ..fixed_keepalive_config(10.0)
+    };
+    let mut sim = make_sim(1, config);
+    add_host(&mut sim, None, 1);
+    let f = add_function(&mut sim, 1, 0.5, 1);
+    // the first invocation occupies the only container until 1.5, the other ones are queued
+    sim.send_invocation_request(f, 1.0, 0.0);
+    sim.send_invocation_request(f, 1.0, 0.1);
+    sim.send_invocation_request(f, 1.0, 0.2);
+    sim.step_until_no_events();
+    sim.get_invocations(0..3)
+}
+
+#[test]
+fn test_event_driven_dequeue() {
+    let invocations = run(None);
+    assert_eq!(invocations[1].start_time, Some(1.5));
+    assert_eq!(invocations[2].start_time, Some(2.5));
+}
+
+#[test]
+fn test_polling_dequeue() {
+    // queued invocations wait for the next tick after the container frees up
+    let invocations = run(Some(2.0));
+    assert_eq!(invocations[0].start_time, Some(0.5));
+    assert_eq!(invocations[1].start_time, Some(2.0));
+    assert_eq!(invocations[1].finish_time, Some(3.0));
+    assert_eq!(invocations[2].start_time, Some(4.0));
+    assert_eq!(invocations[2].finish_time, Some(5.0));
+}
+
+#[test]
+fn test_polling_stops_without_progress() {
+    let config = Config {
+        scheduler_tick: Some(1.0),
+        ..fixed_keepalive_config(10.0)
+    };
+    let mut sim = make_sim(1, config);
+    add_host(&mut sim, None, 1);
+    let small = add_function(&mut sim, 1, 0.5, 1);
+    let big = add_function(&mut sim, 1, 0.5, 2);
+    // the second invocation never fits into the host, so it stays queued after the first one finishes
+    sim.send_invocation_request(small, 1.0, 0.0);
+    sim.send_invocation_request(big, 1.0, 0.0);
+    sim.step_until_no_events();
+
+    let invocations = sim.get_invocations(0..2);
+    assert_eq!(invocations[0].finish_time, Some(1.5));
+    assert_eq!(invocations[1].status, InvocationStatus::Queued);
+    assert_eq!(sim.queued_invocations(0).len(), 1);
+}
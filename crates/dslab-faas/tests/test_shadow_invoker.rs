@@ -0,0 +1,123 @@
+use std::boxed::Box;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use dslab_core::simulation::Simulation;
+use dslab_faas::coldstart::FixedTimeColdStartPolicy;
+use dslab_faas::config::Config;
+use dslab_faas::container::ContainerManager;
+use dslab_faas::cpu::IgnoredCpuPolicy;
+use dslab_faas::function::{Application, FunctionRegistry};
+use dslab_faas::invocation::Invocation;
+use dslab_faas::invoker::{DequeuedInvocation, FIFOInvoker, Invoker, InvokerDecision, ShadowInvoker};
+use dslab_faas::resource::{ResourceConsumer, ResourceProvider};
+use dslab_faas::simulation::ServerlessSimulation;
+use dslab_faas::stats::Stats;
+
+/// Deploys a new container for each invocation, ignoring idle ones.
+struct AlwaysColdInvoker {}
+
+impl Invoker for AlwaysColdInvoker {
+    fn dequeue(
+        &mut self,
+        _fr: Rc<RefCell<FunctionRegistry>>,
+        _cm: &mut ContainerManager,
+        _stats: &mut Stats,
+        _time: f64,
+    ) -> Vec<DequeuedInvocation> {
+        Vec::new()
+    }
+
+    fn invoke(
+        &mut self,
+        invocation: &Invocation,
+        fr: Rc<RefCell<FunctionRegistry>>,
+        cm: &mut ContainerManager,
+        time: f64,
+    ) -> InvokerDecision {
+        let fr_ref = fr.borrow();
+        let app = fr_ref.get_app(invocation.app_id).unwrap();
+        match cm.try_deploy(app, time) {
+            Ok((id, delay)) => {
+                cm.reserve_container(id, invocation.id);
+                InvokerDecision::Cold((id, delay))
+            }
+            Err(reason) => InvokerDecision::Rejected(reason),
+        }
+    }
+
+    fn queue_len(&self) -> usize {
+        0
+    }
+}
+
+#[test]
+fn test_shadow_invoker_does_not_affect_simulation() {
+    let config = Config {
+        coldstart_policy: Box::new(FixedTimeColdStartPolicy::new(f64::MAX / 10., 0.0)),
+        cpu_policy: Box::new(IgnoredCpuPolicy::default()),
+        ..Default::default()
+    };
+    let mut sim = ServerlessSimulation::new(Simulation::new(1), config);
+    let host_mem = sim.create_resource("mem", 2);
+    let invoker = ShadowInvoker::new(Box::new(FIFOInvoker::new()), Box::new(AlwaysColdInvoker {}));
+    let records = invoker.records();
+    sim.add_host(Some(Box::new(invoker)), ResourceProvider::new(vec![host_mem]), 1);
+    let fn_mem = sim.create_resource_requirement("mem", 1);
+    let f = sim.add_app_with_single_function(Application::new(1, 1., 1., ResourceConsumer::new(vec![fn_mem])));
+    sim.send_invocation_request(f, 1.0, 0.0);
+    sim.send_invocation_request(f, 1.0, 5.0);
+    sim.step_until_no_events();
+
+    let records = records.borrow();
+    assert_eq!(records.len(), 2);
+    assert!(records[0].primary == InvokerDecision::Cold((0, 1.0)));
+    assert!(records[0].shadow == InvokerDecision::Cold((0, 1.0)));
+    // primary reuses the idle container, while shadow would have deployed a new one
+    assert!(records[1].primary == InvokerDecision::Warm(0));
+    assert!(records[1].shadow == InvokerDecision::Cold((1, 1.0)));
+
+    // real execution follows the primary decisions only
+    let second = sim.get_invocation(1);
+    assert_eq!(second.container_id, Some(0));
+    assert_eq!(second.start_time, Some(5.0));
+    assert_eq!(second.finish_time, Some(6.0));
+    assert_eq!(sim.invocation_stats().cold_starts, 1);
+}
+
+#[test]
+fn test_shadow_invoker_records_dequeue_decisions() {
+    let config = Config {
+        coldstart_policy: Box::new(FixedTimeColdStartPolicy::new(f64::MAX / 10., 0.0)),
+        cpu_policy: Box::new(IgnoredCpuPolicy::default()),
+        ..Default::default()
+    };
+    let mut sim = ServerlessSimulation::new(Simulation::new(1), config);
+    let host_mem = sim.create_resource("mem", 1);
+    let invoker = ShadowInvoker::new(Box::new(FIFOInvoker::new()), Box::new(FIFOInvoker::new()));
+    let records = invoker.records();
+    let dequeue_records = invoker.dequeue_records();
+    sim.add_host(Some(Box::new(invoker)), ResourceProvider::new(vec![host_mem]), 1);
+    let fn_mem = sim.create_resource_requirement("mem", 1);
+    let f = sim.add_app_with_single_function(Application::new(1, 1., 1., ResourceConsumer::new(vec![fn_mem])));
+    sim.send_invocation_request(f, 1.0, 0.0);
+    sim.send_invocation_request(f, 1.0, 0.0);
+    sim.step_until_no_events();
+
+    let records = records.borrow();
+    assert_eq!(records.len(), 2);
+    assert!(records[1].primary == InvokerDecision::Queued);
+    assert!(records[1].shadow == InvokerDecision::Queued);
+
+    // both invokers start the queued invocation on the container released by the first one
+    let dequeue_records = dequeue_records.borrow();
+    assert_eq!(dequeue_records.len(), 1);
+    assert_eq!(dequeue_records[0].time, 2.0);
+    for dequeued in [&dequeue_records[0].primary, &dequeue_records[0].shadow] {
+        assert_eq!(dequeued.len(), 1);
+        assert_eq!(dequeued[0].id, 1);
+        assert_eq!(dequeued[0].container_id, 0);
+        assert_eq!(dequeued[0].delay, None);
+    }
+    assert_eq!(sim.get_invocation(1).finish_time, Some(3.0));
+}
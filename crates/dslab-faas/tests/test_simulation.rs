@@ -40,12 +40,39 @@ fn test_simulation() {
     assert_float_eq(inv_stats.abs_total_slowdown.mean(), 4.0 / 3.0, 1e-9);
     assert_float_eq(inv_stats.rel_total_slowdown.mean(), 4.0 / 3.0, 1e-9);
     assert_float_eq(stats.global_stats.wasted_resource_time[0].sum(), 3.0 - 1e-9, 1e-9);
+    assert_float_eq(stats.app_stats[0].wasted_resource_time[0].sum(), 2.0 - 1e-9, 1e-9);
+    assert_float_eq(stats.app_stats[1].wasted_resource_time[0].sum(), 1.0, 1e-9);
     let f1_stats = &stats.func_stats[0];
     assert_float_eq(f1_stats.abs_total_slowdown.mean(), 1.0, 1e-9);
     let f2_stats = &stats.func_stats[1];
     assert_float_eq(f2_stats.abs_total_slowdown.mean(), 2.0, 1e-9);
 }
 
+#[test]
+fn test_simulation_warmup() {
+    let config = Config {
+        coldstart_policy: Box::new(FixedTimeColdStartPolicy::new(1.0, 0.0)),
+        ..Default::default()
+    };
+    let mut sim = ServerlessSimulation::new(Simulation::new(1), config);
+    let mem = sim.create_resource("mem", 2);
+    sim.add_host(None, ResourceProvider::new(vec![mem]), 1);
+    let mem1 = sim.create_resource_requirement("mem", 1);
+    let f1 = sim.add_app_with_single_function(Application::new(1, 1., 1., ResourceConsumer::new(vec![mem1])));
+    sim.send_invocation_request(f1, 1.0, 0.0);
+    sim.send_invocation_request(f1, 1.0, 3.0);
+    sim.step_for_duration(2.5);
+    let warmup_stats = sim.stats();
+    sim.reset_stats();
+    sim.step_until_no_events();
+    assert_eq!(warmup_stats.global_stats.invocation_stats.invocations, 1);
+    assert_eq!(warmup_stats.global_stats.invocation_stats.cold_starts, 1);
+    let inv_stats = sim.invocation_stats();
+    assert_eq!(inv_stats.invocations, 1);
+    assert_eq!(inv_stats.cold_starts, 0);
+    assert_float_eq(inv_stats.cold_start_fraction(), 0.0, 1e-9);
+}
+
 #[test]
 fn test_simulation_with_invoker_queueing() {
     let config = Config {
@@ -68,9 +95,11 @@ fn test_simulation_with_invoker_queueing() {
     let stats = sim.stats();
     let inv_stats = stats.global_stats.invocation_stats;
     assert_eq!(inv_stats.invocations, 3);
-    assert_eq!(inv_stats.cold_starts, 3);
+    // the queued invocation reuses the container of the first one, which counts as a warm start
+    assert_eq!(inv_stats.cold_starts, 2);
+    assert_eq!(inv_stats.warm_starts, 1);
     assert_eq!(inv_stats.queueing_time.len(), 1);
-    assert_float_eq(inv_stats.cold_start_latency.min().unwrap(), 0.1, 1e-9);
+    assert_float_eq(inv_stats.cold_start_latency.min().unwrap(), 1.0, 1e-9);
     assert_float_eq(inv_stats.cold_start_latency.max().unwrap(), 1.0, 1e-9);
     assert_float_eq(inv_stats.abs_exec_slowdown.mean(), 2.0 / 3.0, 1e-9);
     assert_float_eq(inv_stats.rel_exec_slowdown.mean(), 2.0 / 3.0, 1e-9);
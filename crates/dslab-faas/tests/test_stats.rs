@@ -1,8 +1,15 @@
 mod common;
 use common::assert_float_eq;
 
+use dslab_core::simulation::Simulation;
+use dslab_faas::coldstart::FixedTimeColdStartPolicy;
+use dslab_faas::config::Config;
+use dslab_faas::cpu::IgnoredCpuPolicy;
+use dslab_faas::function::Application;
 use dslab_faas::invocation::{Invocation, InvocationStatus};
-use dslab_faas::stats::InvocationStats;
+use dslab_faas::resource::{ResourceConsumer, ResourceProvider};
+use dslab_faas::simulation::ServerlessSimulation;
+use dslab_faas::stats::{InvocationStats, Stats};
 
 #[test]
 fn test_invocation_stats() {
@@ -12,6 +19,7 @@ fn test_invocation_stats() {
         app_id: 0,
         func_id: 0,
         duration: 1.0,
+        warmup_penalty: 0.0,
         arrival_time: 0.0,
         status: InvocationStatus::Finished,
         host_id: Some(0),
@@ -24,6 +32,7 @@ fn test_invocation_stats() {
         app_id: 0,
         func_id: 0,
         duration: 1.2,
+        warmup_penalty: 0.0,
         arrival_time: 0.0,
         status: InvocationStatus::Finished,
         host_id: Some(0),
@@ -36,6 +45,7 @@ fn test_invocation_stats() {
         app_id: 0,
         func_id: 0,
         duration: 1.0,
+        warmup_penalty: 0.0,
         arrival_time: 2.0,
         status: InvocationStatus::Finished,
         host_id: Some(0),
@@ -50,4 +60,133 @@ fn test_invocation_stats() {
     assert_float_eq(stats.rel_total_slowdown.mean(), (1.0 + 0.8 / 1.2) / 3.0, 1e-9);
     assert_float_eq(stats.abs_exec_slowdown.mean(), 0.8 / 3.0, 1e-9);
     assert_float_eq(stats.rel_exec_slowdown.mean(), (0.5 + 0.3 / 1.2) / 3.0, 1e-9);
+    assert_float_eq(stats.relative_slowdown_percentile(50.), 2.0 / 1.2, 1e-9);
+    assert_float_eq(stats.relative_slowdown_percentile(100.), 2.0, 1e-9);
+    assert_float_eq(
+        stats.relative_slowdown_percentile(25.),
+        1.0 + (2.0 / 1.2 - 1.0) / 2.0,
+        1e-9,
+    );
+}
+
+#[test]
+fn test_cold_start_fraction() {
+    let mut stats: InvocationStats = Default::default();
+    assert_float_eq(stats.cold_start_fraction(), 0.0, 1e-9);
+    for _ in 0..4 {
+        stats.on_new_invocation();
+    }
+    stats.on_cold_start(1.0);
+    assert_float_eq(stats.cold_start_fraction(), 0.25, 1e-9);
+}
+
+#[test]
+fn test_stats_export() {
+    let mut stats: Stats = Default::default();
+    stats.on_new_invocation(0, 0);
+    stats.on_new_invocation(1, 1);
+    stats.on_cold_start(1, 1, 0.5);
+
+    let json: serde_json::Value = serde_json::from_str(&stats.to_json()).unwrap();
+    assert_eq!(json["global_stats"]["invocation_stats"]["invocations"], 2);
+    assert_eq!(json["app_stats"][1]["cold_starts"], 1);
+
+    let csv = stats.to_csv_row();
+    let lines: Vec<_> = csv.lines().collect();
+    assert_eq!(lines.len(), 2);
+    let header: Vec<_> = lines[0].split(',').collect();
+    let values: Vec<_> = lines[1].split(',').collect();
+    assert_eq!(header.len(), values.len());
+    let value = |name: &str| values[header.iter().position(|h| *h == name).unwrap()];
+    assert_eq!(value("invocations"), "2");
+    assert_eq!(value("app_0_cold_starts"), "0");
+    assert_eq!(value("app_1_cold_starts"), "1");
+    assert_eq!(value("func_1_cold_start_latency_mean"), "0.5");
+    assert_eq!(value("app_0_cold_start_latency_mean"), "");
+}
+
+#[test]
+fn test_stats_reset() {
+    let mut stats: Stats = Default::default();
+    stats.on_new_invocation(0, 0);
+    stats.on_new_invocation(1, 1);
+    stats.on_cold_start(1, 1, 0.5);
+    stats.update_queueing_time(1, 1, 0.25);
+
+    let snapshot = stats.snapshot();
+    stats.reset();
+    assert_eq!(snapshot.global_stats.invocation_stats.invocations, 2);
+    assert_eq!(snapshot.app_stats.get(1).unwrap().cold_starts, 1);
+    assert_eq!(stats.global_stats.invocation_stats.invocations, 0);
+    assert!(stats.global_stats.invocation_stats.queueing_time.is_empty());
+    assert_eq!(stats.app_stats.iter().count(), 0);
+    assert_eq!(stats.func_stats.iter().count(), 0);
+
+    stats.on_new_invocation(0, 0);
+    assert_float_eq(stats.cold_start_fraction(), 0.0, 1e-9);
+}
+
+#[test]
+fn test_container_lifetime_stats() {
+    let config = Config {
+        coldstart_policy: Box::new(FixedTimeColdStartPolicy::new(10.0, 0.0)),
+        cpu_policy: Box::new(IgnoredCpuPolicy::default()),
+        ..Default::default()
+    };
+    let mut sim = ServerlessSimulation::new(Simulation::new(1), config);
+    let host_mem = sim.create_resource("mem", 1);
+    sim.add_host(None, ResourceProvider::new(vec![host_mem]), 1);
+    let fn_mem = sim.create_resource_requirement("mem", 1);
+    let f = sim.add_app_with_single_function(Application::new(1, 1., 1., ResourceConsumer::new(vec![fn_mem])));
+    assert_eq!(sim.stats().mean_container_lifetime(), None);
+    // the first container lives from 0 to 16 and serves two invocations
+    sim.send_invocation_request(f, 1.0, 0.0);
+    sim.send_invocation_request(f, 1.0, 5.0);
+    // the second container lives from 100 to 112 and serves one invocation
+    sim.send_invocation_request(f, 1.0, 100.0);
+    sim.step_until_no_events();
+    let stats = sim.stats();
+    assert_eq!(stats.global_stats.invocation_stats.container_lifetime.len(), 2);
+    assert_float_eq(stats.global_stats.invocation_stats.container_lifetime.sum(), 28.0, 1e-9);
+    assert_float_eq(stats.mean_container_lifetime().unwrap(), 14.0, 1e-9);
+    assert_float_eq(stats.container_lifetime_percentile(100.).unwrap(), 16.0, 1e-9);
+    assert_float_eq(stats.container_lifetime_percentile(0.).unwrap(), 12.0, 1e-9);
+    assert_float_eq(stats.mean_invocations_per_container().unwrap(), 1.5, 1e-9);
+    assert_float_eq(stats.invocations_per_container_percentile(100.).unwrap(), 2.0, 1e-9);
+    assert_float_eq(stats.app_stats[0].mean_container_lifetime().unwrap(), 14.0, 1e-9);
+}
+
+#[test]
+fn test_cold_start_ratio() {
+    let config = Config {
+        coldstart_policy: Box::new(FixedTimeColdStartPolicy::new(10.0, 0.0)),
+        cpu_policy: Box::new(IgnoredCpuPolicy::default()),
+        ..Default::default()
+    };
+    let mut sim = ServerlessSimulation::new(Simulation::new(1), config);
+    let host_mem = sim.create_resource("mem", 2);
+    sim.add_host(None, ResourceProvider::new(vec![host_mem]), 1);
+    let fn_mem = sim.create_resource_requirement("mem", 1);
+    let f0 = sim.add_app_with_single_function(Application::new(1, 1., 1., ResourceConsumer::new(vec![fn_mem])));
+    let fn_mem = sim.create_resource_requirement("mem", 1);
+    let f1 = sim.add_app_with_single_function(Application::new(1, 1., 1., ResourceConsumer::new(vec![fn_mem])));
+    // the first invocation of each application deploys a container, the next ones reuse it
+    sim.send_invocation_request(f0, 1.0, 0.0);
+    sim.send_invocation_request(f0, 1.0, 5.0);
+    sim.send_invocation_request(f0, 1.0, 7.0);
+    // the host has no room for another container, so this one is queued until the container frees up at 8
+    let queued = sim.send_invocation_request(f0, 1.0, 7.5);
+    sim.send_invocation_request(f1, 1.0, 0.0);
+    sim.step_until_no_events();
+    let stats = sim.stats();
+    assert_eq!(sim.get_invocation(queued).start_time, Some(8.0));
+    assert_eq!(stats.app_stats[0].queueing_time.len(), 1);
+    assert_eq!(stats.app_stats[0].cold_starts, 1);
+    assert_eq!(stats.app_stats[0].warm_starts, 3);
+    assert_eq!(stats.app_stats[0].cold_start_latency.len(), 1);
+    assert_eq!(stats.func_stats[f0].warm_starts, 3);
+    assert_eq!(stats.global_stats.invocation_stats.warm_starts, 3);
+    assert_float_eq(stats.cold_start_ratio(0).unwrap(), 1.0 / 4.0, 1e-9);
+    assert_float_eq(stats.cold_start_ratio(1).unwrap(), 1.0, 1e-9);
+    assert_eq!(stats.cold_start_ratio(2), None);
 }
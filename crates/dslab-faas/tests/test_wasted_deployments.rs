@@ -0,0 +1,31 @@
+use std::boxed::Box;
+
+use dslab_core::simulation::Simulation;
+use dslab_faas::coldstart::FixedTimeColdStartPolicy;
+use dslab_faas::config::Config;
+use dslab_faas::cpu::IgnoredCpuPolicy;
+use dslab_faas::function::Application;
+use dslab_faas::resource::{ResourceConsumer, ResourceProvider};
+use dslab_faas::simulation::ServerlessSimulation;
+
+#[test]
+fn test_prewarmed_container_without_invocations_is_wasted() {
+    let config = Config {
+        coldstart_policy: Box::new(FixedTimeColdStartPolicy::new(1.0, 1.0)),
+        cpu_policy: Box::new(IgnoredCpuPolicy::default()),
+        ..Default::default()
+    };
+    let mut sim = ServerlessSimulation::new(Simulation::new(1), config);
+    let host_mem = sim.create_resource("mem", 1);
+    sim.add_host(None, ResourceProvider::new(vec![host_mem]), 1);
+    let fn_mem = sim.create_resource_requirement("mem", 1);
+    let f = sim.add_app_with_single_function(Application::new(1, 0., 1., ResourceConsumer::new(vec![fn_mem])));
+    sim.send_invocation_request(f, 1.0, 0.0);
+    sim.step_until_no_events();
+
+    // the container which served the invocation is unloaded right away,
+    // the prewarmed one is deployed at 2 and unloaded at 3 without serving any invocation
+    let stats = sim.stats();
+    assert_eq!(stats.global_stats.invocation_stats.wasted_deployments, 1);
+    assert_eq!(stats.app_stats[0].wasted_deployments, 1);
+}
@@ -15,12 +15,18 @@ const TOTAL_WORK_MAX_VALUE: f64 = 1e12;
 struct Activity<T> {
     id: u64,
     item: T,
+    start_work: f64,
     finish_work: f64,
 }
 
 impl<T> Activity<T> {
-    fn new(id: u64, item: T, finish_work: f64) -> Self {
-        Self { id, item, finish_work }
+    fn new(id: u64, item: T, start_work: f64, finish_work: f64) -> Self {
+        Self {
+            id,
+            item,
+            start_work,
+            finish_work,
+        }
     }
 }
 
@@ -90,12 +96,166 @@ impl<T> FairThroughputSharingModel<T> {
         }
     }
 
+    /// Returns the number of activities in the model.
+    pub fn len(&self) -> usize {
+        self.activities.len()
+    }
+
+    /// Returns `true` if there are no activities in the model.
+    pub fn is_empty(&self) -> bool {
+        self.activities.is_empty()
+    }
+
+    /// Returns an iterator over activity items in arbitrary order.
+    pub fn items(&self) -> impl Iterator<Item = &T> {
+        self.activities.iter().map(|a| &a.item)
+    }
+
+    /// Returns the time needed to complete a new activity with given volume if it is inserted at `time`,
+    /// assuming that no other activities are inserted afterwards.
+    ///
+    /// The factor function is not applied to the new activity, since its item is not known.
+    pub fn estimate_completion_time(&self, volume: f64, time: f64) -> f64 {
+        let current_work = self.total_work + (time - self.last_update) * self.throughput_per_activity;
+        let mut remaining: Vec<f64> = self
+            .activities
+            .iter()
+            .map(|a| (a.finish_work - current_work).max(0.))
+            .collect();
+        remaining.sort_by(|a, b| a.total_cmp(b));
+        let mut count = remaining.len() + 1;
+        let mut done = 0.;
+        let mut duration = 0.;
+        for work in remaining {
+            if work >= volume {
+                break;
+            }
+            duration += (work - done) / ((self.throughput_function)(count) / count as f64);
+            done = work;
+            count -= 1;
+        }
+        duration + (volume - done) / ((self.throughput_function)(count) / count as f64)
+    }
+
+    /// Returns an iterator over activity items in arbitrary order along with the completed fraction
+    /// of their volumes at `time`.
+    pub fn progress(&self, time: f64) -> impl Iterator<Item = (&T, f64)> {
+        let current_work = self.total_work + (time - self.last_update) * self.throughput_per_activity;
+        self.activities.iter().map(move |a| {
+            let volume = a.finish_work - a.start_work;
+            let done = if volume > 0. {
+                ((current_work - a.start_work) / volume).clamp(0., 1.)
+            } else {
+                1.
+            };
+            (&a.item, done)
+        })
+    }
+
+    /// Recomputes the throughput at `time`.
+    ///
+    /// Should be called when the result of throughput function changes due to external factors,
+    /// e.g. the resource is shared with another model.
+    pub fn update_throughput(&mut self, time: f64) {
+        if !self.activities.is_empty() {
+            self.increment_total_work((time - self.last_update) * self.throughput_per_activity);
+            let count = self.activities.len();
+            self.throughput_per_activity = (self.throughput_function)(count) / count as f64;
+        }
+        self.last_update = time;
+    }
+
+    /// Removes all activities from the model and returns them along with their remaining volumes at `time`.
+    ///
+    /// Activities are returned in the order of their completion. The returned volumes are already scaled by
+    /// the activity factors, so re-inserting them into a model with a different factor function scales them again.
+    pub fn drain(&mut self, time: f64) -> Vec<(T, f64)> {
+        let current_work = self.total_work + (time - self.last_update) * self.throughput_per_activity;
+        let mut result = Vec::with_capacity(self.activities.len());
+        while let Some(activity) = self.activities.pop() {
+            result.push((activity.item, (activity.finish_work - current_work).max(0.)));
+        }
+        self.throughput_per_activity = 0.;
+        self.total_work = 0.;
+        self.last_update = time;
+        result
+    }
+
+    /// Removes the first activity matching `predicate` from the model at `time`.
+    ///
+    /// Returns the removed activity along with its remaining volume (scaled by the activity factor),
+    /// or `None` if there is no matching activity. The remaining activities share the freed throughput from `time`.
+    pub fn remove<P>(&mut self, predicate: P, time: f64) -> Option<(T, f64)>
+    where
+        P: Fn(&T) -> bool,
+    {
+        let mut activities = std::mem::take(&mut self.activities).into_vec();
+        let pos = activities.iter().position(|a| predicate(&a.item));
+        let removed = pos.map(|pos| activities.swap_remove(pos));
+        self.activities = activities.into();
+        let removed = removed?;
+        self.increment_total_work((time - self.last_update) * self.throughput_per_activity);
+        self.last_update = time;
+        let remaining = (removed.finish_work - self.total_work).max(0.);
+        let count = self.activities.len();
+        if count > 0 {
+            self.throughput_per_activity = (self.throughput_function)(count) / count as f64;
+        } else {
+            self.throughput_per_activity = 0.;
+        }
+        Some((removed.item, remaining))
+    }
+
+    /// Returns the next activity completion time (if any) along with all activities completing at this time,
+    /// i.e. the activities with exactly the same amount of remaining work, in the order of their insertion.
+    ///
+    /// In contrast to `pop_all_next`, the returned activities are not removed from the model.
+    pub fn peek_all_next(&self) -> Vec<(f64, &T)> {
+        let Some((time, _)) = self.peek() else {
+            return Vec::new();
+        };
+        let finish_work = self.activities.peek().unwrap().finish_work;
+        let mut next: Vec<&Activity<T>> = self
+            .activities
+            .iter()
+            .filter(|a| a.finish_work == finish_work)
+            .collect();
+        next.sort_by_key(|a| a.id);
+        next.into_iter().map(|a| (time, &a.item)).collect()
+    }
+
+    /// Removes the next completed activity along with all activities completing at the same time
+    /// and returns them in the order of their insertion.
+    ///
+    /// Allows to process simultaneous completions together instead of one by one.
+    pub fn pop_all_next(&mut self) -> Vec<(f64, T)> {
+        let Some(finish_work) = self.activities.peek().map(|a| a.finish_work) else {
+            return Vec::new();
+        };
+        let remaining_work = finish_work - self.total_work;
+        let finish_time = self.last_update + remaining_work / self.throughput_per_activity;
+        let mut result = Vec::new();
+        while self.activities.peek().is_some_and(|a| a.finish_work == finish_work) {
+            result.push((finish_time, self.activities.pop().unwrap().item));
+        }
+        self.increment_total_work(remaining_work);
+        let count = self.activities.len();
+        if count > 0 {
+            self.throughput_per_activity = (self.throughput_function)(count) / count as f64;
+        } else {
+            self.throughput_per_activity = 0.;
+        }
+        self.last_update = finish_time;
+        result
+    }
+
     fn increment_total_work(&mut self, delta: f64) {
         self.total_work += delta;
         if self.total_work > TOTAL_WORK_MAX_VALUE {
             let mut entries_vec = Vec::new();
             while !self.activities.is_empty() {
                 let mut activity = self.activities.pop().unwrap();
+                activity.start_work -= self.total_work;
                 activity.finish_work -= self.total_work;
                 entries_vec.push(activity);
             }
@@ -113,7 +273,7 @@ impl<T> ThroughputSharingModel<T> for FairThroughputSharingModel<T> {
         let volume = volume / self.factor_function.get_factor(&item, ctx);
         let finish_work = self.total_work + volume;
         self.activities
-            .push(Activity::<T>::new(self.next_id, item, finish_work));
+            .push(Activity::<T>::new(self.next_id, item, self.total_work, finish_work));
         self.next_id += 1;
         let count = self.activities.len();
         self.throughput_per_activity = (self.throughput_function)(count) / count as f64;
@@ -1,3 +1,6 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
 use sugars::boxed;
 
 use dslab_core::{Simulation, SimulationContext};
@@ -241,3 +244,93 @@ fn throughput_factor_and_degradation() {
     assert_eq!(model.pop(), Some((4.125, 0)));
     assert_eq!(model.pop(), Some((5.125, 1)));
 }
+
+#[test]
+fn drain_remaining_work() {
+    let mut sim = Simulation::new(123);
+    let mut ctx = sim.create_context("test");
+    let mut model: FairThroughputSharingModel<u32> = FairThroughputSharingModel::with_fixed_throughput(100.);
+    model.insert(0, 100., &mut ctx);
+    model.insert(1, 300., &mut ctx);
+    sim.step_until_time(1.);
+    assert_eq!(model.drain(ctx.time()), vec![(0, 50.), (1, 250.)]);
+    assert_eq!(model.peek(), None);
+    model.insert(2, 100., &mut ctx);
+    assert_eq!(model.pop(), Some((2., 2)));
+}
+
+#[test]
+fn remove_activity() {
+    let mut sim = Simulation::new(123);
+    let mut ctx = sim.create_context("test");
+    let mut model: FairThroughputSharingModel<u32> = FairThroughputSharingModel::with_fixed_throughput(100.);
+    model.insert(0, 100., &mut ctx);
+    model.insert(1, 300., &mut ctx);
+    sim.step_until_time(1.);
+    assert_eq!(model.remove(|x| *x == 2, ctx.time()), None);
+    assert_eq!(model.remove(|x| *x == 1, ctx.time()), Some((1, 250.)));
+    assert_eq!(model.pop(), Some((1.5, 0)));
+    assert_eq!(model.pop(), None);
+}
+
+#[test]
+fn simultaneous_completions() {
+    let mut sim = Simulation::new(123);
+    let mut ctx = sim.create_context("test");
+    let mut model: FairThroughputSharingModel<u32> = FairThroughputSharingModel::with_fixed_throughput(100.);
+    model.insert(0, 100., &mut ctx);
+    model.insert(1, 50., &mut ctx);
+    model.insert(2, 100., &mut ctx);
+    model.insert(3, 50., &mut ctx);
+    assert_eq!(model.peek_all_next(), vec![(2., &1), (2., &3)]);
+    assert_eq!(model.pop_all_next(), vec![(2., 1), (2., 3)]);
+    assert_eq!(model.peek_all_next(), vec![(3., &0), (3., &2)]);
+    assert_eq!(model.pop_all_next(), vec![(3., 0), (3., 2)]);
+    assert!(model.pop_all_next().is_empty());
+    sim.step_until_time(3.);
+    model.insert(4, 100., &mut ctx);
+    assert_eq!(model.pop(), Some((4., 4)));
+}
+
+#[test]
+fn update_throughput() {
+    let mut sim = Simulation::new(123);
+    let mut ctx = sim.create_context("test");
+    let throughput = Rc::new(Cell::new(100.));
+    let throughput_fn = throughput.clone();
+    let mut model: FairThroughputSharingModel<u32> =
+        FairThroughputSharingModel::with_dynamic_throughput(boxed!(move |_| throughput_fn.get()));
+    model.insert(0, 200., &mut ctx);
+    sim.step_until_time(1.);
+    throughput.set(50.);
+    model.update_throughput(ctx.time());
+    assert_eq!(model.pop(), Some((3., 0)));
+}
+
+#[test]
+fn estimate_completion_time() {
+    let mut sim = Simulation::new(123);
+    let mut ctx = sim.create_context("test");
+    let mut model: FairThroughputSharingModel<u32> = FairThroughputSharingModel::with_fixed_throughput(100.);
+    model.insert(0, 200., &mut ctx);
+    model.insert(1, 400., &mut ctx);
+    sim.step_until_time(1.);
+    assert_eq!(model.estimate_completion_time(200., ctx.time()), 5.5);
+    model.insert(2, 200., &mut ctx);
+    assert_eq!(model.pop(), Some((5.5, 0)));
+    assert_eq!(model.pop(), Some((6.5, 2)));
+}
+
+#[test]
+fn activity_progress() {
+    let mut sim = Simulation::new(123);
+    let mut ctx = sim.create_context("test");
+    let mut model: FairThroughputSharingModel<u32> = FairThroughputSharingModel::with_fixed_throughput(100.);
+    model.insert(0, 100., &mut ctx);
+    sim.step_until_time(0.5);
+    model.insert(1, 100., &mut ctx);
+    sim.step_until_time(1.);
+    let mut progress: Vec<_> = model.progress(ctx.time()).map(|(item, done)| (*item, done)).collect();
+    progress.sort_by_key(|p| p.0);
+    assert_eq!(progress, vec![(0, 0.75), (1, 0.25)]);
+}
@@ -1,5 +1,7 @@
 use std::collections::{BTreeMap, BTreeSet, VecDeque};
 
+use serde::{Deserialize, Serialize};
+
 use crate::mc::events::{McEventId, McTime};
 use crate::message::Message;
 
@@ -15,33 +17,83 @@ use crate::message::Message;
 /// in the order of their activation).
 /// The inverse is generally not true - a new timer cannot block any existing timer with larger delay, because
 /// we do not know exactly the time moments when these timers were activated.
-#[derive(Default, Clone, Hash, Eq, PartialEq, Debug)]
+///
+/// A timer can also fire at any moment within a range of delays. In this case it is blocked only by the timers
+/// whose maximum delay is less or equal to its minimum delay, i.e. the timers with overlapping delay ranges
+/// are not ordered. A timer with a single delay is treated as a range consisting of one point.
+///
+/// Identical messages between the same pair of processes are delivered in the order they were sent.
+/// Different messages are not ordered unless the maximum reordering is set with
+/// [`DependencyResolver::with_max_reorder`]: then a message can overtake at most `K` earlier pending messages
+/// between the same pair of processes, i.e. only the first `K + 1` messages of each channel are available,
+/// and `K = 0` means strict FIFO delivery.
+///
+/// The resolver can be serialized and deserialized along with [`PendingEvents`](crate::mc::pending_events::PendingEvents)
+/// to save and restore the model checking state.
+#[derive(Default, Clone, Hash, Eq, PartialEq, Debug, Serialize, Deserialize)]
 pub struct DependencyResolver {
     timers: BTreeMap<McEventId, TimerInfo>,
+    #[serde(with = "crate::mc::serde_map")]
     messages: BTreeMap<(Message, String, String), VecDeque<McEventId>>,
     proc_timers: BTreeMap<String, BTreeSet<McEventId>>,
+    // pending messages of each (src, dest) pair in the order they were sent, tracked only if max_reorder is set
+    #[serde(with = "crate::mc::serde_map")]
+    channels: BTreeMap<(String, String), VecDeque<(McEventId, Message)>>,
+    max_reorder: Option<usize>,
 }
 
-#[derive(Clone, Hash, Eq, PartialEq, Debug)]
+#[derive(Clone, Hash, Eq, PartialEq, Debug, Serialize, Deserialize)]
 struct TimerInfo {
     proc: String,
-    delay: McTime,
+    min_delay: McTime,
+    max_delay: McTime,
     blockers: BTreeSet<McEventId>,
 }
 
 impl DependencyResolver {
+    /// Creates a resolver which allows a message to overtake at most `max_reorder` earlier messages
+    /// between the same pair of processes.
+    pub fn with_max_reorder(max_reorder: usize) -> Self {
+        Self {
+            max_reorder: Some(max_reorder),
+            ..Default::default()
+        }
+    }
+
     pub fn add_timer(&mut self, proc: String, delay: McTime, event_id: McEventId) -> bool {
+        self.add_timer_with_range(proc, delay, delay, event_id)
+    }
+
+    pub fn add_timer_with_range(
+        &mut self,
+        proc: String,
+        min_delay: McTime,
+        max_delay: McTime,
+        event_id: McEventId,
+    ) -> bool {
+        assert!(
+            min_delay <= max_delay,
+            "min timer delay should not exceed max timer delay"
+        );
         let proc_timers = self.proc_timers.entry(proc.clone()).or_default();
         let mut blockers = BTreeSet::default();
         for id in proc_timers.iter() {
-            if self.timers[id].delay <= delay {
+            if self.timers[id].max_delay <= min_delay {
                 blockers.insert(*id);
             }
         }
         let is_available = blockers.is_empty();
         assert!(
             self.timers
-                .insert(event_id, TimerInfo { proc, delay, blockers })
+                .insert(
+                    event_id,
+                    TimerInfo {
+                        proc,
+                        min_delay,
+                        max_delay,
+                        blockers
+                    }
+                )
                 .is_none(),
             "event with such id already exists"
         );
@@ -70,29 +122,137 @@ impl DependencyResolver {
         unblocked
     }
 
+    /// Returns the pending events which currently block the given event, in the order of their ids.
+    ///
+    /// For a timer, these are the timers of the same process which must fire before it. For a message,
+    /// these are the earlier copies of the identical message and, if the maximum reordering is set,
+    /// the earlier messages of the same channel. The result is empty for available or unknown events.
+    pub fn explain_block(&self, blocked: McEventId) -> Vec<McEventId> {
+        if let Some(timer) = self.timers.get(&blocked) {
+            return timer.blockers.iter().copied().collect();
+        }
+        let mut blockers = BTreeSet::new();
+        for ((_, src, dest), ids) in &self.messages {
+            let Some(pos) = ids.iter().position(|id| *id == blocked) else {
+                continue;
+            };
+            blockers.extend(ids.iter().take(pos));
+            if let (Some(max_reorder), Some(channel)) =
+                (self.max_reorder, self.channels.get(&(src.clone(), dest.clone())))
+            {
+                let pos = channel.iter().position(|(id, _)| *id == blocked).unwrap();
+                if pos > max_reorder {
+                    blockers.extend(channel.iter().take(pos).map(|(id, _)| *id));
+                }
+            }
+            break;
+        }
+        blockers.into_iter().collect()
+    }
+
+    /// Returns a copy of the resolver with event ids replaced according to the mapping.
+    /// The mapping must contain all tracked events.
+    pub fn remap_ids(&self, mapping: &BTreeMap<McEventId, McEventId>) -> Self {
+        let timers = self
+            .timers
+            .iter()
+            .map(|(id, timer)| {
+                let timer = TimerInfo {
+                    blockers: timer.blockers.iter().map(|b| mapping[b]).collect(),
+                    ..timer.clone()
+                };
+                (mapping[id], timer)
+            })
+            .collect();
+        let messages = self
+            .messages
+            .iter()
+            .map(|(key, ids)| (key.clone(), ids.iter().map(|id| mapping[id]).collect()))
+            .collect();
+        let proc_timers = self
+            .proc_timers
+            .iter()
+            .map(|(proc, ids)| (proc.clone(), ids.iter().map(|id| mapping[id]).collect()))
+            .collect();
+        let channels = self
+            .channels
+            .iter()
+            .map(|(key, msgs)| {
+                let msgs = msgs.iter().map(|(id, msg)| (mapping[id], msg.clone())).collect();
+                (key.clone(), msgs)
+            })
+            .collect();
+        Self {
+            timers,
+            messages,
+            proc_timers,
+            channels,
+            max_reorder: self.max_reorder,
+        }
+    }
+
     pub fn add_message(&mut self, msg: Message, src: String, dest: String, event_id: McEventId) -> bool {
+        if let Some(max_reorder) = self.max_reorder {
+            let channel = self.channels.entry((src.clone(), dest.clone())).or_default();
+            channel.push_back((event_id, msg.clone()));
+            let in_window = channel.len() <= max_reorder + 1;
+            let vec_ref = self.messages.entry((msg, src, dest)).or_default();
+            vec_ref.push_back(event_id);
+            return vec_ref.len() == 1 && in_window;
+        }
         let vec_ref = self.messages.entry((msg, src, dest)).or_default();
         vec_ref.push_back(event_id);
         vec_ref.len() == 1
     }
 
-    pub fn remove_message(&mut self, msg: Message, src: String, dest: String) -> Option<McEventId> {
-        let ids = self
-            .messages
-            .get_mut(&(msg.clone(), src.clone(), dest.clone()))
-            .unwrap();
-        ids.pop_front();
-        if !ids.is_empty() {
-            Some(ids[0])
-        } else {
-            self.messages.remove(&(msg, src, dest));
-            None
+    /// Removes the earliest pending copy of the message and returns the messages unblocked by its removal.
+    pub fn remove_message(&mut self, msg: Message, src: String, dest: String) -> BTreeSet<McEventId> {
+        let channel_key = (src.clone(), dest.clone());
+        let available_before = self.available_channel_messages(&channel_key);
+        let key = (msg, src, dest);
+        let ids = self.messages.get_mut(&key).unwrap();
+        let removed = ids.pop_front().unwrap();
+        let next = ids.front().copied();
+        if ids.is_empty() {
+            self.messages.remove(&key);
         }
+        if self.max_reorder.is_none() {
+            return next.into_iter().collect();
+        }
+        let channel = self.channels.get_mut(&channel_key).unwrap();
+        let pos = channel.iter().position(|(id, _)| *id == removed).unwrap();
+        channel.remove(pos);
+        if channel.is_empty() {
+            self.channels.remove(&channel_key);
+        }
+        self.available_channel_messages(&channel_key)
+            .difference(&available_before)
+            .copied()
+            .collect()
+    }
+
+    // Returns the available messages of the channel, i.e. the first copies of identical messages
+    // among the first max_reorder + 1 messages. Used only if max_reorder is set.
+    fn available_channel_messages(&self, channel_key: &(String, String)) -> BTreeSet<McEventId> {
+        let (max_reorder, channel) = match (self.max_reorder, self.channels.get(channel_key)) {
+            (Some(max_reorder), Some(channel)) => (max_reorder, channel),
+            _ => return BTreeSet::new(),
+        };
+        channel
+            .iter()
+            .take(max_reorder.saturating_add(1))
+            .filter(|(id, msg)| {
+                let key = (msg.clone(), channel_key.0.clone(), channel_key.1.clone());
+                self.messages[&key].front() == Some(id)
+            })
+            .map(|(id, _)| *id)
+            .collect()
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use ordered_float::OrderedFloat;
     use serde::Serialize;
 
     use crate::{mc::dependency::DependencyResolver, message::Message};
@@ -150,24 +310,95 @@ mod tests {
                 if proc_to == proc_from {
                     continue;
                 }
+                assert!(
+                    resolver
+                        .remove_message(
+                            Message::json("MSG", &EmptyMessage {}),
+                            proc_from.to_owned(),
+                            proc_to.to_owned()
+                        )
+                        .len()
+                        == 1
+                );
                 assert!(resolver
                     .remove_message(
                         Message::json("MSG", &EmptyMessage {}),
                         proc_from.to_owned(),
                         proc_to.to_owned()
                     )
-                    .is_some());
-                assert!(resolver
-                    .remove_message(
-                        Message::json("MSG", &EmptyMessage {}),
-                        proc_from.to_owned(),
-                        proc_to.to_owned()
-                    )
-                    .is_none());
+                    .is_empty());
             }
         }
         assert!(resolver.messages.is_empty());
         assert!(resolver.timers.is_empty());
         assert!(resolver.proc_timers.is_empty());
     }
+
+    #[test]
+    fn test_dependency_resolver_timer_ranges() {
+        let mut resolver = DependencyResolver::default();
+        let proc = "proc".to_owned();
+        assert!(resolver.add_timer_with_range(proc.clone(), OrderedFloat(1.0), OrderedFloat(3.0), 1));
+        // overlapping ranges are not ordered
+        assert!(resolver.add_timer_with_range(proc.clone(), OrderedFloat(2.0), OrderedFloat(4.0), 2));
+        assert!(resolver.add_timer(proc.clone(), OrderedFloat(2.5), 3));
+        // range which starts after the end of other ranges is blocked by them
+        assert!(!resolver.add_timer_with_range(proc.clone(), OrderedFloat(3.0), OrderedFloat(5.0), 4));
+        assert_eq!(resolver.timers[&4].blockers, [1, 3].into());
+        assert!(!resolver.add_timer(proc.clone(), OrderedFloat(4.0), 5));
+        assert_eq!(resolver.timers[&5].blockers, [1, 2, 3].into());
+
+        for id in 1..4 {
+            resolver.remove_timer(id);
+        }
+        assert!(resolver.timers[&4].blockers.is_empty());
+        assert!(resolver.timers[&5].blockers.is_empty());
+    }
+
+    #[test]
+    fn test_dependency_resolver_max_reorder() {
+        let available_count = |max_reorder: Option<usize>| {
+            let mut resolver = match max_reorder {
+                Some(k) => DependencyResolver::with_max_reorder(k),
+                None => DependencyResolver::default(),
+            };
+            let mut count = 0;
+            for i in 0..5 {
+                let msg = Message::new("MSG", &i.to_string());
+                if resolver.add_message(msg, "proc-0".to_owned(), "proc-1".to_owned(), i) {
+                    count += 1;
+                }
+            }
+            // messages of other channels are not affected
+            assert!(resolver.add_message(Message::new("MSG", "0"), "proc-1".to_owned(), "proc-0".to_owned(), 5));
+            count
+        };
+        assert_eq!(available_count(None), 5);
+        assert_eq!(available_count(Some(3)), 4);
+        assert_eq!(available_count(Some(1)), 2);
+        assert_eq!(available_count(Some(0)), 1);
+
+        // with strict FIFO the next message is unblocked when the first one is delivered
+        let mut resolver = DependencyResolver::with_max_reorder(0);
+        for i in 0..3 {
+            resolver.add_message(Message::new("MSG", &i.to_string()), "a".to_owned(), "b".to_owned(), i);
+        }
+        assert_eq!(
+            resolver.remove_message(Message::new("MSG", "0"), "a".to_owned(), "b".to_owned()),
+            [1].into()
+        );
+
+        // a message outside the window is not available even if it overtakes only identical messages
+        let mut resolver = DependencyResolver::with_max_reorder(1);
+        assert!(resolver.add_message(Message::new("MSG", "x"), "a".to_owned(), "b".to_owned(), 0));
+        assert!(!resolver.add_message(Message::new("MSG", "x"), "a".to_owned(), "b".to_owned(), 1));
+        assert!(!resolver.add_message(Message::new("MSG", "y"), "a".to_owned(), "b".to_owned(), 2));
+        assert_eq!(resolver.explain_block(1), vec![0]);
+        assert_eq!(resolver.explain_block(2), vec![0, 1]);
+        assert_eq!(
+            resolver.remove_message(Message::new("MSG", "x"), "a".to_owned(), "b".to_owned()),
+            [1, 2].into()
+        );
+        assert!(resolver.channels.values().all(|c| c.len() == 2));
+    }
 }
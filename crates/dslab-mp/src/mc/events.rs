@@ -1,9 +1,9 @@
 use ordered_float::OrderedFloat;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use crate::message::Message;
 
-#[derive(Serialize, Clone, PartialEq, Eq, Hash, Debug)]
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Hash, Debug)]
 pub enum DeliveryOptions {
     /// Message will be received exactly once without corruption with specified max delay
     NoFailures(McTime),
@@ -20,7 +20,7 @@ pub enum DeliveryOptions {
 pub type McTime = OrderedFloat<f64>;
 pub type McEventId = usize;
 
-#[derive(Serialize, Clone, Eq, Hash, PartialEq, Debug)]
+#[derive(Serialize, Deserialize, Clone, Eq, Hash, PartialEq, Debug)]
 pub enum McEvent {
     MessageReceived {
         msg: Message,
@@ -32,6 +32,8 @@ pub enum McEvent {
         proc: String,
         timer: String,
         timer_delay: McTime,
+        /// If set, the timer can fire at any moment with delay in range `[timer_delay, max_timer_delay]`.
+        max_timer_delay: Option<McTime>,
     },
     TimerCancelled {
         proc: String,
@@ -42,6 +44,12 @@ pub enum McEvent {
         src: String,
         dest: String,
     },
+    /// Directive which makes the message delivered one more time (see `PendingEvents::pop`).
+    MessageDuplicated {
+        msg: Message,
+        src: String,
+        dest: String,
+    },
 }
 
 impl McEvent {
@@ -71,3 +79,23 @@ impl McEvent {
         }
     }
 }
+
+/// Kind of directive event, used to order pending directives (see `PendingEvents::set_directive_order`).
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum DirectiveKind {
+    TimerCancelled,
+    MessageDropped,
+    MessageDuplicated,
+}
+
+impl McEvent {
+    /// Returns the kind of directive if the event is a directive.
+    pub fn directive_kind(&self) -> Option<DirectiveKind> {
+        match self {
+            McEvent::TimerCancelled { .. } => Some(DirectiveKind::TimerCancelled),
+            McEvent::MessageDropped { .. } => Some(DirectiveKind::MessageDropped),
+            McEvent::MessageDuplicated { .. } => Some(DirectiveKind::MessageDuplicated),
+            _ => None,
+        }
+    }
+}
@@ -8,6 +8,7 @@ pub mod model_checker;
 mod network;
 mod node;
 mod pending_events;
+mod serde_map;
 pub mod state;
 pub mod strategies;
 pub mod strategy;
@@ -28,6 +28,12 @@ impl ModelChecker {
     /// Creates a new model checker with the specified strategy
     /// and initial state equal to the current state of the system.
     pub fn new<S: Strategy + 'static>(sys: &System, strategy_config: StrategyConfig) -> Self {
+        let mut events = match strategy_config.max_message_reorder {
+            Some(max_reorder) => PendingEvents::with_max_reorder(max_reorder),
+            None => PendingEvents::new(),
+        };
+        events.set_directive_order(strategy_config.directive_order.clone());
+
         // Setup strategy which specifies rules for state exploration
         let strategy = boxed!(S::build(strategy_config));
 
@@ -45,7 +51,6 @@ impl ModelChecker {
             );
         }
 
-        let mut events = PendingEvents::new();
         for event in sim.dump_events() {
             if let Some(value) = event.data.downcast_ref::<MessageReceived>() {
                 events.push(
@@ -58,6 +63,7 @@ impl ModelChecker {
                     proc: value.proc.clone(),
                     timer: value.timer.clone(),
                     timer_delay: McTime::from(0.0),
+                    max_timer_delay: None,
                 });
             }
         }
@@ -156,6 +156,7 @@ impl McNode {
                             timer: name.clone(),
                             proc: proc.clone(),
                             timer_delay: McTime::from(delay),
+                            max_timer_delay: None,
                         };
                         new_events.push(event);
                         // event_id is 0 since it is not used in model checking
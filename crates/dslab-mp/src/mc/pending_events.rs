@@ -1,16 +1,37 @@
+use std::collections::hash_map::DefaultHasher;
 use std::collections::BTreeMap;
 use std::collections::BTreeSet;
+use std::hash::{Hash, Hasher};
+
+use serde::{Deserialize, Serialize};
 
 use crate::mc::dependency::DependencyResolver;
-use crate::mc::events::{McEvent, McEventId};
+use crate::mc::events::{DeliveryOptions, DirectiveKind, McEvent, McEventId, McTime};
+
+/// Counts of pending events of each kind.
+#[derive(Default, Clone, Copy, Eq, PartialEq, Debug)]
+pub struct PendingEventsStats {
+    /// Number of messages waiting for delivery.
+    pub messages: usize,
+    /// Number of timers waiting for firing.
+    pub timers: usize,
+    /// Number of directives (cancelled timers, dropped and duplicated messages).
+    pub directives: usize,
+}
 
-/// Stores pending events and provides a convenient interface for working with them.  
-#[derive(Default, Clone, Hash, Eq, PartialEq, Debug)]
+/// Stores pending events and provides a convenient interface for working with them.
+///
+/// The whole pending state, including the dependency resolver, can be serialized (e.g. to JSON)
+/// and restored later to resume model checking.
+#[derive(Default, Clone, Hash, Eq, PartialEq, Debug, Serialize, Deserialize)]
 pub struct PendingEvents {
     events: BTreeMap<McEventId, McEvent>,
+    #[serde(with = "crate::mc::serde_map")]
     timer_mapping: BTreeMap<(String, String), usize>,
     available_events: BTreeSet<McEventId>,
     directives: BTreeSet<McEventId>,
+    #[serde(default)]
+    directive_order: Vec<DirectiveKind>,
     resolver: DependencyResolver,
     id_counter: McEventId,
 }
@@ -23,11 +44,30 @@ impl PendingEvents {
             timer_mapping: BTreeMap::default(),
             available_events: BTreeSet::default(),
             directives: BTreeSet::default(),
+            directive_order: Vec::new(),
             resolver: DependencyResolver::default(),
             id_counter: 0,
         }
     }
 
+    /// Creates a new empty PendingEvents instance, in which a message can be delivered before at most
+    /// `max_reorder` earlier messages between the same pair of processes (see [`DependencyResolver`]).
+    pub fn with_max_reorder(max_reorder: usize) -> Self {
+        PendingEvents {
+            resolver: DependencyResolver::with_max_reorder(max_reorder),
+            ..Self::new()
+        }
+    }
+
+    /// Sets the order in which pending directives are processed.
+    ///
+    /// Directives of the kinds listed earlier in `order` go first, the kinds missing from `order` go last.
+    /// Directives of the same kind are processed in the order of their ids, i.e. in the order of insertion.
+    /// By default (with empty `order`) all directives are processed in the order of insertion.
+    pub fn set_directive_order(&mut self, order: Vec<DirectiveKind>) {
+        self.directive_order = order;
+    }
+
     /// Stores the passed event and returns id assigned to it.
     pub fn push(&mut self, event: McEvent) -> McEventId {
         let id = self.id_counter;
@@ -47,10 +87,18 @@ impl PendingEvents {
             McEvent::TimerFired {
                 proc,
                 timer_delay,
+                max_timer_delay,
                 timer,
             } => {
                 self.timer_mapping.insert((proc.clone(), timer.clone()), id);
-                if self.resolver.add_timer(proc.clone(), *timer_delay, id) {
+                let is_available = match max_timer_delay {
+                    Some(max_timer_delay) => {
+                        self.resolver
+                            .add_timer_with_range(proc.clone(), *timer_delay, *max_timer_delay, id)
+                    }
+                    None => self.resolver.add_timer(proc.clone(), *timer_delay, id),
+                };
+                if is_available {
                     self.available_events.insert(id);
                 }
             }
@@ -60,6 +108,9 @@ impl PendingEvents {
             McEvent::MessageDropped { .. } => {
                 self.directives.insert(id);
             }
+            McEvent::MessageDuplicated { .. } => {
+                self.directives.insert(id);
+            }
         };
         self.events.insert(id, event);
         id
@@ -70,13 +121,53 @@ impl PendingEvents {
         self.events.get(&id)
     }
 
+    /// Returns the pending events which currently block the given event (see [`DependencyResolver::explain_block`]).
+    ///
+    /// Helps to find out why an event is missing from [`PendingEvents::available_events`].
+    pub fn explain_block(&self, event_id: McEventId) -> Vec<McEventId> {
+        self.resolver.explain_block(event_id)
+    }
+
     /// Returns currently available events, i.e. not blocked by other events (see DependencyResolver).
+    ///
+    /// If there are pending directives, only the first of them in the directive order is available
+    /// (see [`PendingEvents::set_directive_order`]).
+    /// The ids are guaranteed to be iterated in ascending order, so strategies relying on this order are reproducible.
     pub fn available_events(&self) -> BTreeSet<McEventId> {
-        if let Some(directive) = self.directives.iter().next() {
-            BTreeSet::from_iter(vec![*directive])
+        self.available_events_iter().collect()
+    }
+
+    /// Same as [`PendingEvents::available_events`], but iterates over the events without copying them.
+    /// The ids are yielded in ascending order.
+    pub fn available_events_iter(&self) -> impl Iterator<Item = McEventId> + '_ {
+        let directive = self.next_directive();
+        let events = if directive.is_none() {
+            Some(self.available_events.iter().copied())
         } else {
-            self.available_events.clone()
+            None
+        };
+        directive.into_iter().chain(events.into_iter().flatten())
+    }
+
+    // Returns the directive to be processed first according to the directive order.
+    fn next_directive(&self) -> Option<McEventId> {
+        if self.directive_order.is_empty() {
+            return self.directives.iter().next().copied();
         }
+        self.directives.iter().copied().min_by_key(|id| {
+            let kind = self.events[id].directive_kind();
+            let rank = self
+                .directive_order
+                .iter()
+                .position(|k| Some(*k) == kind)
+                .unwrap_or(self.directive_order.len());
+            (rank, *id)
+        })
+    }
+
+    /// Returns the smallest id among currently available events, if any.
+    pub fn first_available(&self) -> Option<McEventId> {
+        self.available_events_iter().next()
     }
 
     /// Returns the number of currently available events
@@ -87,6 +178,69 @@ impl PendingEvents {
         self.available_events.len()
     }
 
+    /// Returns the total number of currently stored events, including the unavailable ones and directives.
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Returns true if there are no stored events.
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Returns the number of currently stored events of each kind.
+    pub fn stats(&self) -> PendingEventsStats {
+        let mut stats = PendingEventsStats {
+            directives: self.directives.len(),
+            ..Default::default()
+        };
+        for event in self.events.values() {
+            match event {
+                McEvent::MessageReceived { .. } => stats.messages += 1,
+                McEvent::TimerFired { .. } => stats.timers += 1,
+                _ => {}
+            }
+        }
+        stats
+    }
+
+    /// Returns the canonical form of the pending events, in which the concrete id values do not matter.
+    ///
+    /// The stored events are renumbered with consecutive ids starting from 0 preserving their relative order
+    /// (which matters, e.g., for the order of directives), the id counter is set to the number of events
+    /// and stale mappings of already fired timers are dropped. So the canonical forms of two instances
+    /// which differ only by id assignment are equal.
+    pub fn canonical_form(&self) -> Self {
+        let mapping: BTreeMap<McEventId, McEventId> = self
+            .events
+            .keys()
+            .enumerate()
+            .map(|(new_id, old_id)| (*old_id, new_id))
+            .collect();
+        let remap_set = |ids: &BTreeSet<McEventId>| ids.iter().map(|id| mapping[id]).collect();
+        PendingEvents {
+            events: self.events.iter().map(|(id, e)| (mapping[id], e.clone())).collect(),
+            timer_mapping: self
+                .timer_mapping
+                .iter()
+                .filter_map(|(timer, id)| mapping.get(id).map(|new_id| (timer.clone(), *new_id)))
+                .collect(),
+            available_events: remap_set(&self.available_events),
+            directives: remap_set(&self.directives),
+            directive_order: self.directive_order.clone(),
+            resolver: self.resolver.remap_ids(&mapping),
+            id_counter: self.events.len(),
+        }
+    }
+
+    /// Returns the hash of the [canonical form](PendingEvents::canonical_form), which can be used to collapse
+    /// equivalent states reached via different paths.
+    pub fn canonical_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.canonical_form().hash(&mut hasher);
+        hasher.finish()
+    }
+
     /// Cancels given timer and recalculates available events.
     pub fn cancel_timer(&mut self, proc: String, timer: String) {
         let id = self.timer_mapping.remove(&(proc, timer));
@@ -95,7 +249,29 @@ impl PendingEvents {
         }
     }
 
+    /// Cancels all pending timers of the given process (e.g. on the process crash) and recalculates available events.
+    pub fn cancel_all_timers(&mut self, proc: &str) {
+        let timers: Vec<(String, String)> = self
+            .timer_mapping
+            .keys()
+            .filter(|(timer_proc, _)| timer_proc == proc)
+            .cloned()
+            .collect();
+        for timer in timers {
+            let id = self.timer_mapping.remove(&timer).unwrap();
+            if self.events.contains_key(&id) {
+                self.pop(id);
+            }
+        }
+    }
+
     /// Removes available event by its id.
+    ///
+    /// Popping a `MessageDuplicated` directive stores a copy of the duplicated message as a new `MessageReceived`
+    /// event. The copy is passed through the DependencyResolver like any newly sent message, i.e. it is blocked
+    /// by the pending copies of the same message between the same processes and is delivered after them.
+    /// If there are no such copies (e.g. the original message is already delivered), the copy is available at once.
+    /// The copy itself is not subject to further network failures.
     pub fn pop(&mut self, event_id: McEventId) -> McEvent {
         let result = self.events.remove(&event_id).unwrap();
         self.directives.remove(&event_id);
@@ -105,9 +281,16 @@ impl PendingEvents {
             self.available_events.extend(unblocked_events);
         }
         if let McEvent::MessageReceived { msg, src, dest, .. } = result.clone() {
-            if let Some(unblocked_event) = self.resolver.remove_message(msg, src, dest) {
-                self.available_events.insert(unblocked_event);
-            }
+            let unblocked_events = self.resolver.remove_message(msg, src, dest);
+            self.available_events.extend(unblocked_events);
+        }
+        if let McEvent::MessageDuplicated { msg, src, dest } = result.clone() {
+            self.push(McEvent::MessageReceived {
+                msg,
+                src,
+                dest,
+                options: DeliveryOptions::NoFailures(McTime::from(0.0)),
+            });
         }
         result
     }
@@ -116,9 +299,12 @@ impl PendingEvents {
 #[cfg(test)]
 mod tests {
     use rand::prelude::IteratorRandom;
+    use rand::SeedableRng;
+    use rand_pcg::Pcg64;
 
-    use crate::mc::events::{McEvent, McTime};
-    use crate::mc::pending_events::PendingEvents;
+    use crate::mc::events::{DeliveryOptions, DirectiveKind, McEvent, McTime};
+    use crate::mc::pending_events::{PendingEvents, PendingEventsStats};
+    use crate::message::Message;
 
     #[test]
     fn test_mc_time() {
@@ -132,6 +318,7 @@ mod tests {
     #[test]
     fn test_dependency_resolver_simple() {
         let mut pending_events = PendingEvents::new();
+        let mut rng = Pcg64::seed_from_u64(123);
         let mut sequence = Vec::new();
         let mut rev_id = vec![0; 9];
         for node_id in 0..3 {
@@ -141,12 +328,13 @@ mod tests {
                     proc: node_id.to_string(),
                     timer: format!("{}", event_time),
                     timer_delay: McTime::from(event_time as f64),
+                    max_timer_delay: None,
                 };
                 rev_id[pending_events.push(event)] = event_time * 3 + node_id;
             }
         }
         println!("{:?}", rev_id);
-        while let Some(id) = pending_events.available_events().iter().choose(&mut rand::thread_rng()) {
+        while let Some(id) = pending_events.available_events().iter().choose(&mut rng) {
             let id = *id;
             sequence.push(rev_id[id]);
             pending_events.pop(id);
@@ -165,6 +353,7 @@ mod tests {
     #[test]
     fn test_dependency_resolver_pop() {
         let mut pending_events = PendingEvents::new();
+        let mut rng = Pcg64::seed_from_u64(123);
         let mut sequence = Vec::new();
         let mut rev_id = vec![0; 12];
 
@@ -175,6 +364,7 @@ mod tests {
                     proc: node_id.to_string(),
                     timer: format!("{}", event_time),
                     timer_delay: McTime::from(1.0 + event_time as f64),
+                    max_timer_delay: None,
                 };
                 rev_id[pending_events.push(event)] = event_time * 3 + node_id;
             }
@@ -186,11 +376,7 @@ mod tests {
         // - one timer with delay 3
         // - two timers with delays 2 and 3
         for _ in 0..7 {
-            let id = *pending_events
-                .available_events()
-                .iter()
-                .choose(&mut rand::thread_rng())
-                .unwrap();
+            let id = *pending_events.available_events().iter().choose(&mut rng).unwrap();
             sequence.push(rev_id[id]);
             pending_events.pop(id);
         }
@@ -206,10 +392,11 @@ mod tests {
                 proc: node_id.to_string(),
                 timer: format!("{}", node_id),
                 timer_delay: McTime::from(3.),
+                max_timer_delay: None,
             };
             rev_id[pending_events.push(event)] = 9 + node_id;
         }
-        while let Some(id) = pending_events.available_events().iter().choose(&mut rand::thread_rng()) {
+        while let Some(id) = pending_events.available_events().iter().choose(&mut rng) {
             let id = *id;
             sequence.push(rev_id[id]);
             pending_events.pop(id);
@@ -224,4 +411,329 @@ mod tests {
             timers[node as usize] += 1;
         }
     }
+
+    #[test]
+    fn test_explain_block() {
+        let mut pending_events = PendingEvents::new();
+        let mut ids = vec![];
+        for node_id in 0..3 {
+            for event_time in 0..3 {
+                let event = McEvent::TimerFired {
+                    proc: node_id.to_string(),
+                    timer: format!("{}", event_time),
+                    timer_delay: McTime::from(1.0 + event_time as f64),
+                    max_timer_delay: None,
+                };
+                ids.push(pending_events.push(event));
+            }
+        }
+        // each timer is blocked only by the timers of the same process with smaller delays
+        assert!(pending_events.explain_block(ids[0]).is_empty());
+        assert_eq!(pending_events.explain_block(ids[1]), vec![ids[0]]);
+        assert_eq!(pending_events.explain_block(ids[2]), vec![ids[0], ids[1]]);
+        assert_eq!(pending_events.explain_block(ids[8]), vec![ids[6], ids[7]]);
+
+        pending_events.pop(ids[0]);
+        assert!(pending_events.explain_block(ids[1]).is_empty());
+        assert_eq!(pending_events.explain_block(ids[2]), vec![ids[1]]);
+
+        // a new timer with equal delay is blocked by the remaining timers
+        let event = McEvent::TimerFired {
+            proc: "0".to_string(),
+            timer: "new".to_string(),
+            timer_delay: McTime::from(3.),
+            max_timer_delay: None,
+        };
+        let new_id = pending_events.push(event);
+        assert_eq!(pending_events.explain_block(new_id), vec![ids[1], ids[2]]);
+        for id in pending_events.available_events() {
+            assert!(pending_events.explain_block(id).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_available_events_iter() {
+        let mut pending_events = PendingEvents::new();
+        for node_id in 0..3 {
+            let event = McEvent::TimerFired {
+                proc: node_id.to_string(),
+                timer: "timer".to_string(),
+                timer_delay: McTime::from(1.0),
+                max_timer_delay: None,
+            };
+            pending_events.push(event);
+        }
+        assert_eq!(
+            pending_events.available_events_iter().collect::<Vec<_>>(),
+            vec![0, 1, 2]
+        );
+
+        // directive is the only available event until it is processed
+        let directive = pending_events.push(McEvent::TimerCancelled {
+            proc: "0".to_string(),
+            timer: "other".to_string(),
+        });
+        assert_eq!(
+            pending_events.available_events_iter().collect::<Vec<_>>(),
+            vec![directive]
+        );
+        assert_eq!(pending_events.first_available(), Some(directive));
+        assert_eq!(
+            pending_events.available_events(),
+            pending_events.available_events_iter().collect()
+        );
+        pending_events.pop(directive);
+        assert_eq!(
+            pending_events.available_events_iter().collect::<Vec<_>>(),
+            vec![0, 1, 2]
+        );
+        assert_eq!(pending_events.first_available(), Some(0));
+        for id in 0..3 {
+            pending_events.pop(id);
+        }
+        assert_eq!(pending_events.first_available(), None);
+    }
+
+    #[test]
+    fn test_directive_order() {
+        let mut pending_events = PendingEvents::new();
+        let dropped = pending_events.push(McEvent::MessageDropped {
+            msg: Message::new("PING", "some_data"),
+            src: "0".to_string(),
+            dest: "1".to_string(),
+        });
+        let cancelled = pending_events.push(McEvent::TimerCancelled {
+            proc: "0".to_string(),
+            timer: "timer".to_string(),
+        });
+        // by default directives are processed in the order of insertion
+        assert_eq!(pending_events.first_available(), Some(dropped));
+
+        pending_events.set_directive_order(vec![DirectiveKind::TimerCancelled]);
+        assert_eq!(
+            pending_events.available_events_iter().collect::<Vec<_>>(),
+            vec![cancelled]
+        );
+        assert_eq!(pending_events.canonical_form().first_available(), Some(cancelled));
+        pending_events.pop(cancelled);
+        assert_eq!(pending_events.first_available(), Some(dropped));
+    }
+
+    #[test]
+    fn test_message_duplicated() {
+        let mut pending_events = PendingEvents::new();
+        let msg = Message::new("PING", "data");
+        let original = pending_events.push(McEvent::MessageReceived {
+            msg: msg.clone(),
+            src: "0".to_string(),
+            dest: "1".to_string(),
+            options: DeliveryOptions::NoFailures(McTime::from(1.0)),
+        });
+        let directive = pending_events.push(McEvent::MessageDuplicated {
+            msg: msg.clone(),
+            src: "0".to_string(),
+            dest: "1".to_string(),
+        });
+        assert_eq!(pending_events.available_events(), [directive].into());
+
+        // the copy is queued behind the original message
+        pending_events.pop(directive);
+        assert_eq!(pending_events.available_events(), [original].into());
+        pending_events.pop(original);
+        let copy = *pending_events.available_events().iter().next().unwrap();
+        assert_ne!(copy, original);
+        match pending_events.pop(copy) {
+            McEvent::MessageReceived { msg: copy_msg, .. } => assert_eq!(copy_msg, msg),
+            _ => panic!("expected MessageReceived"),
+        }
+        assert_eq!(pending_events.available_events_num(), 0);
+
+        // the copy of already delivered message is available at once
+        let directive = pending_events.push(McEvent::MessageDuplicated {
+            msg,
+            src: "0".to_string(),
+            dest: "1".to_string(),
+        });
+        pending_events.pop(directive);
+        assert_eq!(pending_events.available_events_num(), 1);
+    }
+
+    #[test]
+    fn test_cancel_all_timers() {
+        let mut pending_events = PendingEvents::new();
+        for proc in ["0", "1"] {
+            for delay in 1..4 {
+                pending_events.push(McEvent::TimerFired {
+                    proc: proc.to_string(),
+                    timer: format!("{}", delay),
+                    timer_delay: McTime::from(delay as f64),
+                    max_timer_delay: None,
+                });
+            }
+        }
+        assert_eq!(pending_events.available_events(), [0, 3].into());
+
+        pending_events.cancel_all_timers("0");
+        assert_eq!(pending_events.available_events(), [3].into());
+        for id in 0..3 {
+            assert!(pending_events.get(id).is_none());
+        }
+
+        // new timer of the process is not blocked by the cancelled ones
+        let timer = pending_events.push(McEvent::TimerFired {
+            proc: "0".to_string(),
+            timer: "new".to_string(),
+            timer_delay: McTime::from(5.0),
+            max_timer_delay: None,
+        });
+        assert_eq!(pending_events.available_events(), [3, timer].into());
+
+        // timers of other processes are still ordered
+        pending_events.pop(3);
+        assert_eq!(pending_events.available_events(), [4, timer].into());
+        pending_events.cancel_all_timers("1");
+        assert_eq!(pending_events.available_events(), [timer].into());
+    }
+
+    #[test]
+    fn test_stats() {
+        let mut pending_events = PendingEvents::new();
+        assert_eq!(pending_events.stats(), PendingEventsStats::default());
+        for delay in 0..3 {
+            pending_events.push(McEvent::TimerFired {
+                proc: "0".to_string(),
+                timer: format!("{}", delay),
+                timer_delay: McTime::from(delay as f64),
+                max_timer_delay: None,
+            });
+        }
+        let msg = pending_events.push(McEvent::MessageReceived {
+            msg: Message::new("PING", "data"),
+            src: "0".to_string(),
+            dest: "1".to_string(),
+            options: DeliveryOptions::NoFailures(McTime::from(1.0)),
+        });
+        let directive = pending_events.push(McEvent::TimerCancelled {
+            proc: "0".to_string(),
+            timer: "2".to_string(),
+        });
+        assert_eq!(
+            pending_events.stats(),
+            PendingEventsStats {
+                messages: 1,
+                timers: 3,
+                directives: 1,
+            }
+        );
+
+        pending_events.pop(directive);
+        pending_events.cancel_timer("0".to_string(), "2".to_string());
+        pending_events.pop(msg);
+        assert_eq!(
+            pending_events.stats(),
+            PendingEventsStats {
+                messages: 0,
+                timers: 2,
+                directives: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_serialization_round_trip() {
+        let mut pending_events = PendingEvents::new();
+        for proc in ["0", "1"] {
+            for delay in 1..4 {
+                pending_events.push(McEvent::TimerFired {
+                    proc: proc.to_string(),
+                    timer: format!("{}", delay),
+                    timer_delay: McTime::from(delay as f64),
+                    max_timer_delay: Some(McTime::from(delay as f64 + 0.5)),
+                });
+            }
+        }
+        for _ in 0..3 {
+            pending_events.push(McEvent::MessageReceived {
+                msg: Message::new("PING", "data"),
+                src: "0".to_string(),
+                dest: "1".to_string(),
+                options: DeliveryOptions::NoFailures(McTime::from(1.0)),
+            });
+        }
+        pending_events.pop(0);
+        pending_events.pop(6);
+        pending_events.cancel_timer("1".to_string(), "2".to_string());
+
+        let json = serde_json::to_string(&pending_events).unwrap();
+        let mut restored: PendingEvents = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, pending_events);
+        assert_eq!(restored.available_events(), pending_events.available_events());
+
+        // both copies evolve identically after the restore
+        while let Some(id) = pending_events.first_available() {
+            assert_eq!(restored.pop(id), pending_events.pop(id));
+            assert_eq!(restored.available_events(), pending_events.available_events());
+        }
+        assert_eq!(restored, pending_events);
+    }
+
+    #[test]
+    fn test_canonical_form() {
+        let timer = |proc: &str, name: &str, delay: f64| McEvent::TimerFired {
+            proc: proc.to_string(),
+            timer: name.to_string(),
+            timer_delay: McTime::from(delay),
+            max_timer_delay: None,
+        };
+        let message = McEvent::MessageReceived {
+            msg: Message::new("PING", "data"),
+            src: "0".to_string(),
+            dest: "1".to_string(),
+            options: DeliveryOptions::NoFailures(McTime::from(1.0)),
+        };
+
+        // the same pending events reached via different paths
+        let mut first = PendingEvents::new();
+        first.push(timer("0", "a", 1.0));
+        first.push(timer("0", "b", 2.0));
+        first.push(message.clone());
+        let fired = first.first_available().unwrap();
+        first.pop(fired);
+
+        let mut second = PendingEvents::new();
+        second.push(message.clone());
+        let delivered = second.first_available().unwrap();
+        second.pop(delivered);
+        second.push(timer("0", "b", 2.0));
+        second.push(message);
+
+        assert_ne!(first, second);
+        assert_eq!(first.canonical_form(), second.canonical_form());
+        assert_eq!(first.canonical_hash(), second.canonical_hash());
+        assert_eq!(
+            first.canonical_form().available_events(),
+            second.canonical_form().available_events()
+        );
+
+        // order of events is preserved by the canonical form
+        let mut third = PendingEvents::new();
+        third.push(McEvent::TimerCancelled {
+            proc: "0".to_string(),
+            timer: "b".to_string(),
+        });
+        third.push(McEvent::TimerCancelled {
+            proc: "0".to_string(),
+            timer: "a".to_string(),
+        });
+        let mut fourth = PendingEvents::new();
+        fourth.push(McEvent::TimerCancelled {
+            proc: "0".to_string(),
+            timer: "a".to_string(),
+        });
+        fourth.push(McEvent::TimerCancelled {
+            proc: "0".to_string(),
+            timer: "b".to_string(),
+        });
+        assert_ne!(third.canonical_hash(), fourth.canonical_hash());
+    }
 }
@@ -11,6 +11,7 @@ use crate::mc::system::McSystem;
 /// The search strategy based on the [BFS](https://en.wikipedia.org/wiki/Breadth-first_search) algorithm.
 pub struct Bfs {
     prune: PruneFn,
+    max_pending_events: Option<usize>,
     goal: GoalFn,
     invariant: InvariantFn,
     collect: CollectFn,
@@ -48,6 +49,7 @@ impl Strategy for Bfs {
     fn build(config: StrategyConfig) -> Self {
         Bfs {
             prune: config.prune,
+            max_pending_events: config.max_pending_events,
             goal: config.goal,
             invariant: config.invariant,
             collect: config.collect,
@@ -83,6 +85,10 @@ impl Strategy for Bfs {
         &mut self.prune
     }
 
+    fn max_pending_events(&self) -> Option<usize> {
+        self.max_pending_events
+    }
+
     fn goal(&mut self) -> &mut GoalFn {
         &mut self.goal
     }
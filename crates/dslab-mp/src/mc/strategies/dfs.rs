@@ -9,6 +9,7 @@ use crate::mc::system::McSystem;
 /// The search strategy based on the [DFS](https://en.wikipedia.org/wiki/Depth-first_search) algorithm.
 pub struct Dfs {
     prune: PruneFn,
+    max_pending_events: Option<usize>,
     goal: GoalFn,
     invariant: InvariantFn,
     collect: CollectFn,
@@ -36,6 +37,7 @@ impl Strategy for Dfs {
     fn build(config: StrategyConfig) -> Self {
         Dfs {
             prune: config.prune,
+            max_pending_events: config.max_pending_events,
             goal: config.goal,
             invariant: config.invariant,
             collect: config.collect,
@@ -71,6 +73,10 @@ impl Strategy for Dfs {
         &mut self.prune
     }
 
+    fn max_pending_events(&self) -> Option<usize> {
+        self.max_pending_events
+    }
+
     fn goal(&mut self) -> &mut GoalFn {
         &mut self.goal
     }
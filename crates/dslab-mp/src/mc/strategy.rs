@@ -9,8 +9,8 @@ use lazy_static::lazy_static;
 use regex::Regex;
 use sugars::boxed;
 
-use crate::mc::events::McEvent::{MessageDropped, MessageReceived, TimerCancelled, TimerFired};
-use crate::mc::events::{DeliveryOptions, McEvent, McEventId};
+use crate::mc::events::McEvent::{MessageDropped, MessageDuplicated, MessageReceived, TimerCancelled, TimerFired};
+use crate::mc::events::{DeliveryOptions, DirectiveKind, McEvent, McEventId};
 use crate::mc::state::McState;
 use crate::mc::system::McSystem;
 use crate::message::Message;
@@ -24,6 +24,9 @@ pub struct StrategyConfig {
     pub(crate) collect: CollectFn,
     pub(crate) execution_mode: ExecutionMode,
     pub(crate) visited_states: VisitedStates,
+    pub(crate) max_message_reorder: Option<usize>,
+    pub(crate) max_pending_events: Option<usize>,
+    pub(crate) directive_order: Vec<DirectiveKind>,
 }
 
 impl Default for StrategyConfig {
@@ -35,6 +38,9 @@ impl Default for StrategyConfig {
             collect: boxed!(default_collect),
             execution_mode: ExecutionMode::Default,
             visited_states: VisitedStates::Partial(HashSet::default()),
+            max_message_reorder: None,
+            max_pending_events: None,
+            directive_order: Vec::new(),
         }
     }
 }
@@ -75,6 +81,31 @@ impl StrategyConfig {
         self.visited_states = visited_states;
         self
     }
+
+    /// Limits message reordering: a message can be delivered before at most `max_reorder` earlier messages
+    /// between the same pair of processes, `0` means FIFO delivery. By default the reordering is not limited.
+    pub fn max_message_reorder(mut self, max_reorder: usize) -> Self {
+        self.max_message_reorder = Some(max_reorder);
+        self
+    }
+
+    /// Limits the number of pending events (see [`PendingEvents::len`](crate::mc::pending_events::PendingEvents::len)).
+    ///
+    /// The execution branches in which this number exceeds `max_pending_events`, e.g. due to runaway accumulation
+    /// of timers or messages, are not explored further and are counted in [`McStats::pending_events_limit_exceeded`]
+    /// instead of being reported as errors. By default the number of pending events is not limited.
+    pub fn max_pending_events(mut self, max_pending_events: usize) -> Self {
+        self.max_pending_events = Some(max_pending_events);
+        self
+    }
+
+    /// Sets the order in which pending directives of different kinds are processed, e.g. to process timer
+    /// cancellations before message drops. By default the directives are processed in the order of their creation.
+    /// See [`PendingEvents::set_directive_order`](crate::mc::pending_events::PendingEvents::set_directive_order).
+    pub fn directive_order(mut self, order: Vec<DirectiveKind>) -> Self {
+        self.directive_order = order;
+        self
+    }
 }
 
 pub(crate) fn default_prune(_: &McState) -> Option<String> {
@@ -137,11 +168,14 @@ pub struct McStats {
     pub statuses: HashMap<String, u32>,
     /// States that were collected with Collect predicate
     pub collected_states: HashSet<McState>,
+    /// Number of execution branches cut because of exceeding the pending events limit
+    pub pending_events_limit_exceeded: u32,
 }
 
 impl McStats {
     pub(crate) fn combine(&mut self, other: McStats) {
         self.collected_states.extend(other.collected_states.into_iter());
+        self.pending_events_limit_exceeded += other.pending_events_limit_exceeded;
         for (state, cnt) in other.statuses {
             let entry = self.statuses.entry(state).or_insert(0);
             *entry += cnt;
@@ -228,7 +262,7 @@ pub trait Strategy {
                 system.events.cancel_timer(proc, timer);
                 self.apply_event(system, event_id, false, false)?;
             }
-            MessageDropped { .. } => {
+            MessageDropped { .. } | MessageDuplicated { .. } => {
                 self.apply_event(system, event_id, false, false)?;
             }
         }
@@ -362,6 +396,9 @@ pub trait Strategy {
                     )
                     .red());
                 }
+                MessageDuplicated { msg, src, dest } => {
+                    self.log_message(depth, msg, src, dest, LogContext::Duplicated);
+                }
             }
         }
     }
@@ -437,6 +474,10 @@ pub trait Strategy {
             // Execution branch is pruned
             self.on_final_state_reached(status);
             Some(Ok(()))
+        } else if matches!(self.max_pending_events(), Some(max) if state.events.len() > max) {
+            // Execution branch is cut to keep the search tractable
+            self.stats().pending_events_limit_exceeded += 1;
+            Some(Ok(()))
         } else if state.events.available_events_num() == 0 {
             // exhausted without goal completed
             Some(Err("nothing left to do to reach the goal".to_owned()))
@@ -459,6 +500,12 @@ pub trait Strategy {
     /// Returns the prune function.
     fn prune(&mut self) -> &mut PruneFn;
 
+    /// Returns the limit of pending events number, see [`StrategyConfig::max_pending_events`].
+    /// By default the number of pending events is not limited.
+    fn max_pending_events(&self) -> Option<usize> {
+        None
+    }
+
     /// Returns the goal function.
     fn goal(&mut self) -> &mut GoalFn;
 
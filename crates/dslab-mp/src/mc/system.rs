@@ -69,6 +69,11 @@ impl McSystem {
         }
     }
 
+    /// Makes the network deliver one more copy of the message from `src` to `dest`.
+    pub fn duplicate_message(&mut self, msg: Message, src: String, dest: String) {
+        self.events.push(McEvent::MessageDuplicated { msg, src, dest });
+    }
+
     pub fn get_state(&self) -> McState {
         let mut state = McState::new(self.events.clone(), self.depth);
         for (name, node) in &self.nodes {
@@ -1,8 +1,8 @@
 use std::fmt::{Error, Formatter};
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Clone, Eq, Hash, PartialEq, PartialOrd, Ord)]
+#[derive(Serialize, Deserialize, Clone, Eq, Hash, PartialEq, PartialOrd, Ord)]
 pub struct Message {
     pub tip: String,
     pub data: String,
@@ -638,3 +638,67 @@ fn collect_mode(#[case] strategy_name: String) {
     });
     assert!(res.is_ok());
 }
+
+#[rstest]
+#[case("dfs")]
+#[case("bfs")]
+fn naive_receiver_fails_under_duplication(#[case] strategy_name: String) {
+    let mut sys = build_ping_system();
+    sys.send_local_message("process1", Message::new("PING", "some_data"));
+
+    // first stage: collect the initial state with the message in flight
+    let invariant = boxed!(|_: &McState| Ok(()));
+    let prune = boxed!(|_: &McState| None);
+    let goal = boxed!(|_: &McState| Some("final".to_string()));
+    let collect = boxed!(|_: &McState| true);
+    let config = build_strategy_config(prune, goal, invariant).collect(collect);
+    let mut mc = build_mc_from_config(&sys, strategy_name.clone(), config);
+    let states = mc.run().expect("run failed but shouldn't").collected_states;
+    assert_eq!(states.len(), 1);
+
+    // second stage: the network delivers the message twice and the receiver processes it twice
+    let invariant = boxed!(|state: &McState| {
+        if state.node_states["node2"]["process2"].local_outbox.len() > 1 {
+            Err("message processed twice".to_string())
+        } else {
+            Ok(())
+        }
+    });
+    let prune = boxed!(|_: &McState| None);
+    let goal = build_no_events_left_goal();
+    let mut mc = build_mc(&sys, strategy_name, prune, goal, invariant);
+    let result = mc.run_from_states_with_change(states, |sys| {
+        sys.duplicate_message(
+            Message::new("PING", "some_data"),
+            "process1".to_string(),
+            "process2".to_string(),
+        );
+    });
+    assert_eq!(result, Err("message processed twice".to_string()));
+}
+
+#[rstest]
+#[case("dfs")]
+#[case("bfs")]
+fn max_pending_events(#[case] strategy_name: String) {
+    let mut sys = build_spammer_delivery_system();
+    sys.send_local_message("process1", Message::new("START", "start spamming!!!"));
+
+    // all 10 messages are pending in the initial state, so the search stops right away
+    let invariant = boxed!(|_: &McState| Ok(()));
+    let prune = boxed!(|_: &McState| None);
+    let goal = build_no_events_left_goal();
+    let config = build_strategy_config(prune, goal, invariant).max_pending_events(9);
+    let mut mc = build_mc_from_config(&sys, strategy_name.clone(), config);
+    let stats = mc.run().expect("exceeding the limit should not be reported as error");
+    assert_eq!(stats.pending_events_limit_exceeded, 1);
+
+    let invariant = boxed!(|_: &McState| Ok(()));
+    let prune = boxed!(|_: &McState| None);
+    // the number of pending events only decreases, so the limit is never exceeded
+    let goal = boxed!(|state: &McState| (state.depth >= 2).then(|| "final".to_string()));
+    let config = build_strategy_config(prune, goal, invariant).max_pending_events(10);
+    let mut mc = build_mc_from_config(&sys, strategy_name, config);
+    let stats = mc.run().expect("run failed but shouldn't");
+    assert_eq!(stats.pending_events_limit_exceeded, 0);
+}
@@ -0,0 +1,386 @@
+//! In-memory cache over storage.
+//!
+//! The cache keeps recently accessed data items identified by user-defined keys and is limited by the total size
+//! of cached items. When there is no space for a new item, the least recently used items are evicted.
+//! Reads of cached items (hits) are completed after a fixed latency without accessing the backing storage,
+//! while the other reads (misses) are served by the backing storage and populate the cache upon completion.
+//!
+//! Writes are handled according to [`WritePolicy`]: either passed to the backing storage immediately
+//! (write-through), or completed in the cache and written to the backing storage only when the item is evicted
+//! or [`CachedStorage::flush()`] is called (write-back).
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::rc::Rc;
+
+use serde::{Deserialize, Serialize};
+
+use dslab_core::cast;
+use dslab_core::component::Id;
+use dslab_core::context::SimulationContext;
+use dslab_core::event::{Event, EventData};
+use dslab_core::handler::EventHandler;
+use dslab_core::{log_debug, log_error};
+
+use crate::events::{DataReadCompleted, DataReadFailed, DataWriteCompleted, DataWriteFailed};
+use crate::storage::Storage;
+
+/// Defines how writes are handled by the cache.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WritePolicy {
+    /// Writes are passed to the backing storage and are completed when the backing storage completes them.
+    WriteThrough,
+    /// Writes are completed in the cache, and the data is written to the backing storage on eviction.
+    WriteBack,
+}
+
+struct CacheEntry {
+    size: u64,
+    dirty: bool,
+    last_access: u64,
+}
+
+// Request passed to the backing storage on behalf of the requester.
+struct BackingRequest {
+    request_id: u64,
+    requester: Id,
+    key: u64,
+    start_time: f64,
+}
+
+/// Represents a cache with LRU eviction over backing storage.
+pub struct CachedStorage<S: Storage> {
+    backing: Rc<RefCell<S>>,
+    capacity: u64,
+    used: u64,
+    hit_latency: f64,
+    write_policy: WritePolicy,
+    entries: HashMap<u64, CacheEntry>,
+    // mapping access counter -> key, ordered from the least recently used item
+    lru: BTreeMap<u64, u64>,
+    access_counter: u64,
+    // mapping backing request id -> request, separately for reads and writes
+    pending_reads: HashMap<u64, BackingRequest>,
+    pending_writes: HashMap<u64, BackingRequest>,
+    hits: u64,
+    misses: u64,
+    next_request_id: u64,
+    ctx: SimulationContext,
+}
+
+impl<S: Storage> CachedStorage<S> {
+    /// Creates cache of given capacity (total size of cached items) over given backing storage.
+    ///
+    /// Cache hits are completed after `hit_latency`. The backing storage should be registered as simulation handler.
+    pub fn new(
+        backing: Rc<RefCell<S>>,
+        capacity: u64,
+        hit_latency: f64,
+        write_policy: WritePolicy,
+        ctx: SimulationContext,
+    ) -> Self {
+        Self {
+            backing,
+            capacity,
+            used: 0,
+            hit_latency,
+            write_policy,
+            entries: HashMap::new(),
+            lru: BTreeMap::new(),
+            access_counter: 0,
+            pending_reads: HashMap::new(),
+            pending_writes: HashMap::new(),
+            hits: 0,
+            misses: 0,
+            next_request_id: 0,
+            ctx,
+        }
+    }
+
+    fn make_unique_request_id(&mut self) -> u64 {
+        let request_id = self.next_request_id;
+        self.next_request_id += 1;
+        request_id
+    }
+
+    /// Submits read request for data item with given key and returns unique request id.
+    ///
+    /// The component specified in `requester` will receive `DataReadCompleted` event upon the read completion,
+    /// or `DataReadFailed` event if the backing storage fails the read.
+    /// The read is a hit if the item is cached with at least the requested size.
+    pub fn read(&mut self, key: u64, size: u64, requester: Id) -> u64 {
+        log_debug!(
+            self.ctx,
+            "Received read request, key: {}, size: {}, requester: {}",
+            key,
+            size,
+            requester
+        );
+        let request_id = self.make_unique_request_id();
+        let hit = match self.entries.get(&key) {
+            Some(entry) => entry.size >= size,
+            None => false,
+        };
+        if hit {
+            self.hits += 1;
+            self.touch(key);
+            self.complete_in_cache(
+                DataReadCompleted {
+                    request_id,
+                    size,
+                    start_time: self.ctx.time(),
+                    completion_time: self.ctx.time() + self.hit_latency,
+                    tag: None,
+                },
+                requester,
+            );
+        } else {
+            self.misses += 1;
+            let backing_id = self.backing.borrow_mut().read(size, self.ctx.id());
+            self.pending_reads
+                .insert(backing_id, self.backing_request(request_id, requester, key));
+        }
+        request_id
+    }
+
+    /// Submits write request for data item with given key and returns unique request id.
+    ///
+    /// The component specified in `requester` will receive `DataWriteCompleted` event upon the write completion,
+    /// or `DataWriteFailed` event if the backing storage fails the write-through.
+    pub fn write(&mut self, key: u64, size: u64, requester: Id) -> u64 {
+        log_debug!(
+            self.ctx,
+            "Received write request, key: {}, size: {}, requester: {}",
+            key,
+            size,
+            requester
+        );
+        let request_id = self.make_unique_request_id();
+        match self.write_policy {
+            WritePolicy::WriteThrough => {
+                let backing_id = self.backing.borrow_mut().write(size, self.ctx.id());
+                self.pending_writes
+                    .insert(backing_id, self.backing_request(request_id, requester, key));
+            }
+            WritePolicy::WriteBack => {
+                if self.insert(key, size, true) {
+                    self.complete_in_cache(
+                        DataWriteCompleted {
+                            request_id,
+                            size,
+                            start_time: self.ctx.time(),
+                            completion_time: self.ctx.time() + self.hit_latency,
+                            tag: None,
+                        },
+                        requester,
+                    );
+                } else {
+                    // the item does not fit into the cache, so it is written directly to the backing storage
+                    let backing_id = self.backing.borrow_mut().write(size, self.ctx.id());
+                    self.pending_writes
+                        .insert(backing_id, self.backing_request(request_id, requester, key));
+                }
+            }
+        }
+        request_id
+    }
+
+    /// Writes all dirty items to the backing storage.
+    pub fn flush(&mut self) {
+        let mut dirty: Vec<u64> = self
+            .entries
+            .iter()
+            .filter(|(_, entry)| entry.dirty)
+            .map(|(key, _)| *key)
+            .collect();
+        dirty.sort();
+        for key in dirty {
+            let entry = self.entries.get_mut(&key).unwrap();
+            entry.dirty = false;
+            let size = entry.size;
+            self.write_back(key, size);
+        }
+    }
+
+    /// Returns the fraction of reads served from the cache, or zero if there were no reads.
+    pub fn hit_rate(&self) -> f64 {
+        if self.hits + self.misses == 0 {
+            0.
+        } else {
+            self.hits as f64 / (self.hits + self.misses) as f64
+        }
+    }
+
+    /// Returns the number of reads served from the cache.
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    /// Returns the number of reads served by the backing storage.
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+
+    /// Returns the total size of cached items.
+    pub fn used_space(&self) -> u64 {
+        self.used
+    }
+
+    /// Returns whether the item with given key is cached.
+    pub fn contains(&self, key: u64) -> bool {
+        self.entries.contains_key(&key)
+    }
+
+    /// Returns the backing storage.
+    pub fn backing(&self) -> Rc<RefCell<S>> {
+        self.backing.clone()
+    }
+
+    fn backing_request(&self, request_id: u64, requester: Id, key: u64) -> BackingRequest {
+        BackingRequest {
+            request_id,
+            requester,
+            key,
+            start_time: self.ctx.time(),
+        }
+    }
+
+    fn complete_in_cache<T: EventData>(&mut self, data: T, requester: Id) {
+        self.ctx.emit(data, requester, self.hit_latency);
+    }
+
+    fn touch(&mut self, key: u64) {
+        let entry = self.entries.get_mut(&key).unwrap();
+        self.lru.remove(&entry.last_access);
+        entry.last_access = self.access_counter;
+        self.lru.insert(self.access_counter, key);
+        self.access_counter += 1;
+    }
+
+    // Puts the item into the cache evicting the least recently used items if needed.
+    // Returns false if the item is larger than the cache.
+    // The incoming item itself is never written back here, the caller is responsible for it.
+    fn insert(&mut self, key: u64, size: u64, dirty: bool) -> bool {
+        let mut dirty = dirty;
+        if let Some(entry) = self.entries.remove(&key) {
+            self.lru.remove(&entry.last_access);
+            self.used -= entry.size;
+            if size > self.capacity && entry.dirty {
+                // the old version of the item is dropped from the cache, so it is written back
+                self.write_back(key, entry.size);
+            }
+            dirty |= entry.dirty;
+        }
+        if size > self.capacity {
+            return false;
+        }
+        while self.used + size > self.capacity {
+            let (_, evicted) = self.lru.pop_first().unwrap();
+            let entry = self.entries.remove(&evicted).unwrap();
+            self.used -= entry.size;
+            log_debug!(self.ctx, "Evicted item {} of size {}", evicted, entry.size);
+            if entry.dirty {
+                self.write_back(evicted, entry.size);
+            }
+        }
+        self.entries.insert(
+            key,
+            CacheEntry {
+                size,
+                dirty,
+                last_access: self.access_counter,
+            },
+        );
+        self.lru.insert(self.access_counter, key);
+        self.access_counter += 1;
+        self.used += size;
+        true
+    }
+
+    // Writes the dirty item to the backing storage. The completion of such writes is not reported to anyone.
+    fn write_back(&mut self, key: u64, size: u64) {
+        log_debug!(self.ctx, "Writing back item {} of size {}", key, size);
+        self.backing.borrow_mut().write(size, self.ctx.id());
+    }
+
+    fn on_read_completed(&mut self, backing_id: u64, size: u64) {
+        if let Some(request) = self.pending_reads.remove(&backing_id) {
+            self.insert(request.key, size, false);
+            self.ctx.emit_now(
+                DataReadCompleted {
+                    request_id: request.request_id,
+                    size,
+                    start_time: request.start_time,
+                    completion_time: self.ctx.time(),
+                    tag: None,
+                },
+                request.requester,
+            );
+        }
+    }
+
+    fn on_write_completed(&mut self, backing_id: u64, size: u64) {
+        if let Some(request) = self.pending_writes.remove(&backing_id) {
+            if self.write_policy == WritePolicy::WriteThrough {
+                self.insert(request.key, size, false);
+            }
+            self.ctx.emit_now(
+                DataWriteCompleted {
+                    request_id: request.request_id,
+                    size,
+                    start_time: request.start_time,
+                    completion_time: self.ctx.time(),
+                    tag: None,
+                },
+                request.requester,
+            );
+        }
+    }
+
+    fn on_read_failed(&mut self, backing_id: u64, error: String) {
+        if let Some(request) = self.pending_reads.remove(&backing_id) {
+            log_error!(self.ctx, "Failed reading item {}: {}", request.key, error);
+            self.ctx.emit_now(
+                DataReadFailed {
+                    request_id: request.request_id,
+                    error,
+                },
+                request.requester,
+            );
+        }
+    }
+
+    fn on_write_failed(&mut self, backing_id: u64, error: String) {
+        if let Some(request) = self.pending_writes.remove(&backing_id) {
+            log_error!(self.ctx, "Failed writing item {}: {}", request.key, error);
+            self.ctx.emit_now(
+                DataWriteFailed {
+                    request_id: request.request_id,
+                    error,
+                },
+                request.requester,
+            );
+        } else {
+            log_error!(self.ctx, "Failed writing back: {}", error);
+        }
+    }
+}
+
+impl<S: Storage> EventHandler for CachedStorage<S> {
+    fn on(&mut self, event: Event) {
+        cast!(match event.data {
+            DataReadCompleted { request_id, size, .. } => {
+                self.on_read_completed(request_id, size);
+            }
+            DataWriteCompleted { request_id, size, .. } => {
+                self.on_write_completed(request_id, size);
+            }
+            DataReadFailed { request_id, error } => {
+                self.on_read_failed(request_id, error);
+            }
+            DataWriteFailed { request_id, error } => {
+                self.on_write_failed(request_id, error);
+            }
+        })
+    }
+}
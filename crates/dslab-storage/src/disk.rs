@@ -5,9 +5,34 @@
 //! and dependence on operation properties by means of user-defined throughput and factor functions. For detailed
 //! information about these functions, please refer to documentation in `dslab-models` crate.
 //!
+//! Optionally, a fixed per-operation latency can be added to completion time of each read or write operation,
+//! the combined read and write throughput can be capped, and the throughput can be shared equally between
+//! requesters instead of operations (see [`FairnessMode`]) or weighted by operation priorities
+//! (see [`DiskBuilder::enable_priorities()`]). The disk can also periodically notify requesters
+//! about the progress of their operations (see [`DiskBuilder::progress_interval()`]). For resilience testing,
+//! random operation failures and device death can be injected (see [`DiskBuilder::failure_rate()`]
+//! and [`DiskBuilder::fail_after()`]). The difference between sequential and random access can be modeled
+//! by submitting operations with known offsets and setting the seek penalty (see [`DiskBuilder::seek_penalty_fn()`]).
+//! Random variability of disk performance can be added with [`DiskBuilder::bw_with_jitter()`].
+//! Disks that slow down as they fill up can be modeled with [`DiskBuilder::utilization_factor_fn()`].
+//! Sequential I/O logic can be written with futures returned by [`Disk::read_async()`] and [`Disk::write_async()`].
+//! Free space can be monitored with watermark events (see [`Disk::set_watermark()`]).
+//! The number of operations served concurrently can be limited to model the hardware queue depth
+//! (see [`DiskBuilder::max_queue_depth()`]).
+//! Timeout-driven I/O can be modeled with reads which fail if not completed by a deadline
+//! (see [`Disk::read_with_deadline()`]).
+//!
 //! Note that this model is quite generic and can be used to model other types of storage as well.
 
-use serde::Serialize;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll, Waker};
+
+use rand::distributions::Uniform;
+use serde::{Deserialize, Serialize};
 use sugars::boxed;
 
 use dslab_core::cast;
@@ -16,11 +41,16 @@ use dslab_core::event::Event;
 use dslab_core::handler::EventHandler;
 use dslab_core::{context::SimulationContext, log_debug, log_error};
 use dslab_models::throughput_sharing::{
-    make_constant_throughput_fn, ActivityFactorFn, ConstantFactorFn, FairThroughputSharingModel, ResourceThroughputFn,
-    ThroughputSharingModel,
+    make_constant_throughput_fn, ActivityFactorFn, ConstantFactorFn, RandomizedFactorFn, ResourceThroughputFn,
 };
 
-use crate::events::{DataReadCompleted, DataReadFailed, DataWriteCompleted, DataWriteFailed};
+use crate::disk_throughput::DiskThroughputModel;
+pub use crate::disk_throughput::FairnessMode;
+use crate::events::{
+    DataBatchReadCompleted, DataBatchReadFailed, DataBatchWriteCompleted, DataBatchWriteFailed, DataFlushCompleted,
+    DataReadCompleted, DataReadFailed, DataReadModifyWriteCompleted, DataReadModifyWriteFailed, DataReadProgress,
+    DataWriteCompleted, DataWriteFailed, DataWriteProgress, StorageLowSpace, StorageSpaceRecovered,
+};
 use crate::storage::{Storage, StorageInfo};
 
 /// Describes a disk operation.
@@ -32,6 +62,174 @@ pub struct DiskActivity {
     pub requester: Id,
     /// Size.
     pub size: u64,
+    /// Priority. Is taken into account only if priorities are enabled for the disk.
+    pub priority: u8,
+    /// Time when the operation was submitted.
+    pub submit_time: f64,
+    /// Position on the disk where the operation starts, if known.
+    ///
+    /// Is taken into account only if seek penalty is set for the disk (see [`DiskBuilder::seek_penalty_fn()`]).
+    pub offset: Option<u64>,
+    /// User-defined tag which is passed to the completion event, see [`Disk::read_tagged()`].
+    pub tag: Option<u64>,
+}
+
+/// Throughput factor function which takes into account the position of the disk head.
+///
+/// Is called with the position after the previous operation with known offset (its offset plus size)
+/// and the new operation with known offset. The returned factor is applied on top of the read or write factor
+/// function, for example, the factor below 1 for the operations not starting at the current position
+/// models the lower throughput of random access compared to sequential one.
+pub type SeekPenaltyFn = Box<dyn Fn(u64, &DiskActivity) -> f64>;
+
+/// Throughput factor function which takes into account the disk space usage.
+///
+/// Is called with the disk capacity and usage at the time an operation is started, including the space reserved
+/// by this operation if it is a write. The returned factor is applied on top of the read or write factor function.
+pub type UtilizationFactorFn = Box<dyn Fn(&StorageInfo) -> f64>;
+
+/// Type of disk operation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DiskOperation {
+    /// Read operation.
+    Read,
+    /// Write operation.
+    Write,
+}
+
+/// Describes an outstanding disk operation exported from one disk to be continued on another disk.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OutstandingDiskActivity {
+    /// Operation type.
+    pub operation: DiskOperation,
+    /// Request Id.
+    pub request_id: u64,
+    /// Requester.
+    pub requester: Id,
+    /// Total size of operation.
+    pub size: u64,
+    /// Remaining amount of data to process, regardless of the activity factors of the source disk.
+    pub remaining_size: f64,
+    /// Priority.
+    #[serde(default)]
+    pub priority: u8,
+    /// Time when the operation was originally submitted.
+    #[serde(default)]
+    pub submit_time: f64,
+    /// Position on the disk where the operation starts, if known.
+    #[serde(default)]
+    pub offset: Option<u64>,
+    /// Whether the operation is a phase of read-modify-write request.
+    ///
+    /// The read phase is followed by the write phase, and the write phase does not reserve disk space.
+    #[serde(default)]
+    pub read_modify_write: bool,
+    /// Whether the operation is an overwrite of existing data, which does not reserve disk space.
+    #[serde(default)]
+    pub overwrite: bool,
+    /// User-defined tag of the operation.
+    #[serde(default)]
+    pub tag: Option<u64>,
+    /// Batch the operation belongs to, if it was submitted with [`Storage::read_batch()`]
+    /// or [`Storage::write_batch()`].
+    #[serde(default)]
+    pub batch: Option<OutstandingDiskBatch>,
+}
+
+/// Describes a batch of an exported disk operation, see [`OutstandingDiskActivity`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OutstandingDiskBatch {
+    /// Request ids of all operations of the batch, including the already completed ones.
+    pub request_ids: Vec<u64>,
+    /// Total size of all operations of the batch.
+    pub total_size: u64,
+}
+
+// Batch of operations submitted with a single request, which is completed when all its operations are completed.
+struct DiskBatch {
+    operation: DiskOperation,
+    requester: Id,
+    request_ids: Vec<u64>,
+    total_size: u64,
+    remaining: usize,
+}
+
+// Flush request, which is completed when all writes outstanding at the time of its submission are finished.
+struct DiskFlush {
+    request_id: u64,
+    requester: Id,
+    pending: HashSet<u64>,
+}
+
+// Watermarks of used space, see `Disk::set_watermark()`.
+struct DiskWatermark {
+    low: u64,
+    high: u64,
+    listener: Id,
+    // whether the used space has risen above the high watermark and not yet dropped below the low one
+    low_space: bool,
+}
+
+// Deadline of read request, see `Disk::read_with_deadline()`.
+struct DiskDeadline {
+    requester: Id,
+    deadline: f64,
+    event_id: u64,
+}
+
+/// Callback invoked on completion of disk operation with the operation size or on its failure with the error.
+pub type DiskCallback = Box<dyn FnOnce(Result<u64, String>)>;
+
+#[derive(Default)]
+struct DiskFutureState {
+    result: Option<Result<u64, String>>,
+    waker: Option<Waker>,
+}
+
+/// Future which resolves with the operation size on completion of disk operation or with the error on its failure.
+///
+/// Is returned by [`Disk::read_async()`] and [`Disk::write_async()`]. The future does not borrow the disk,
+/// so it can be awaited while the disk is processing events. It is resolved by the disk callback
+/// (see [`Disk::read_with_callback()`]), i.e. at the same simulation time as the corresponding event is delivered
+/// to the requester, and the task awaiting it is woken up via its waker. Polling the future does not advance
+/// the simulation, so the task should be driven by an executor which polls it between simulation steps.
+/// If the operation is cancelled, the future is never resolved.
+pub struct DiskFuture {
+    request_id: u64,
+    state: Rc<RefCell<DiskFutureState>>,
+}
+
+impl DiskFuture {
+    /// Returns id of the corresponding request.
+    pub fn request_id(&self) -> u64 {
+        self.request_id
+    }
+}
+
+// Stores the operation result and wakes up the task awaiting the future.
+fn make_future_callback(state: Rc<RefCell<DiskFutureState>>) -> impl FnOnce(Result<u64, String>) + 'static {
+    move |result| {
+        let mut state = state.borrow_mut();
+        state.result.replace(result);
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl Future for DiskFuture {
+    type Output = Result<u64, String>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.state.borrow_mut();
+        match state.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                state.waker.replace(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
 }
 
 #[derive(Clone, Serialize)]
@@ -40,7 +238,33 @@ struct DiskReadActivityCompleted {}
 #[derive(Clone, Serialize)]
 struct DiskWriteActivityCompleted {}
 
-type DiskThroughputModel = FairThroughputSharingModel<DiskActivity>;
+#[derive(Clone, Serialize)]
+struct DiskProgressTick {}
+
+#[derive(Clone, Serialize)]
+struct DiskDeath {}
+
+#[derive(Clone, Serialize)]
+struct DiskWriteBackDue {
+    request_id: u64,
+}
+
+#[derive(Clone, Serialize)]
+struct DiskDeadlineExpired {
+    request_id: u64,
+}
+
+struct QueuedDiskActivity {
+    operation: DiskOperation,
+    activity: DiskActivity,
+    remaining_size: f64,
+}
+
+#[derive(Clone, Serialize)]
+struct DiskCallbackDue {
+    request_id: u64,
+    result: Result<u64, String>,
+}
 
 ///////////////////////////////////////////////////////////////////////////////
 
@@ -53,6 +277,18 @@ pub struct DiskBuilder {
     write_throughput_fn: Option<ResourceThroughputFn>,
     read_factor_fn: Box<dyn ActivityFactorFn<DiskActivity>>,
     write_factor_fn: Box<dyn ActivityFactorFn<DiskActivity>>,
+    seek_penalty_fn: Option<SeekPenaltyFn>,
+    utilization_factor_fn: Option<UtilizationFactorFn>,
+    read_latency: f64,
+    write_latency: f64,
+    total_bw: Option<f64>,
+    fairness: FairnessMode,
+    priorities: bool,
+    progress_interval: Option<f64>,
+    failure_rate: f64,
+    fail_after: Option<f64>,
+    track_written_bytes: bool,
+    max_queue_depth: Option<usize>,
 }
 
 impl Default for DiskBuilder {
@@ -66,6 +302,18 @@ impl Default for DiskBuilder {
             write_throughput_fn: None,
             read_factor_fn: boxed!(ConstantFactorFn::new(1.)),
             write_factor_fn: boxed!(ConstantFactorFn::new(1.)),
+            seek_penalty_fn: None,
+            utilization_factor_fn: None,
+            read_latency: 0.,
+            write_latency: 0.,
+            total_bw: None,
+            fairness: FairnessMode::PerActivity,
+            priorities: false,
+            progress_interval: None,
+            failure_rate: 0.,
+            fail_after: None,
+            track_written_bytes: false,
+            max_queue_depth: None,
         }
     }
 }
@@ -112,6 +360,38 @@ impl DiskBuilder {
         self
     }
 
+    /// Sets read and write bandwidth with random per-operation variability.
+    ///
+    /// The throughput of each operation is multiplied by a factor drawn from the uniform distribution with mean 1
+    /// and coefficient of variation `cv`. The factors are sampled from the simulation random engine, so the results
+    /// are reproducible for a given seed. With `cv = 0` the disk has constant bandwidth, as with
+    /// [`DiskBuilder::simple()`].
+    ///
+    /// Replaces the read and write factor functions set before.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cv` is not in `[0, 1/sqrt(3))` range, since otherwise the factor may be non-positive.
+    pub fn bw_with_jitter(self, read_bw: f64, write_bw: f64, cv: f64) -> Self {
+        let half_width = cv * 3f64.sqrt();
+        assert!(
+            (0. ..1.).contains(&half_width),
+            "coefficient of variation must be in [0, 1/sqrt(3)) range, got {}",
+            cv
+        );
+        let builder = self.constant_read_bw(read_bw).constant_write_bw(write_bw);
+        if half_width == 0. {
+            builder
+                .read_factor_fn(boxed!(ConstantFactorFn::new(1.)))
+                .write_factor_fn(boxed!(ConstantFactorFn::new(1.)))
+        } else {
+            let dist = Uniform::new_inclusive(1. - half_width, 1. + half_width);
+            builder
+                .read_factor_fn(boxed!(RandomizedFactorFn::new(dist)))
+                .write_factor_fn(boxed!(RandomizedFactorFn::new(dist)))
+        }
+    }
+
     /// Sets custom throughput function for read operations.
     pub fn read_throughput_fn(mut self, read_throughput_fn: ResourceThroughputFn) -> Self {
         self.read_throughput_fn.replace(read_throughput_fn);
@@ -136,29 +416,277 @@ impl DiskBuilder {
         self
     }
 
+    /// Sets seek penalty function applied to both read and write operations with known offset.
+    ///
+    /// Such operations are submitted with [`Disk::read_at()`] and [`Disk::write_at()`].
+    /// The position of the disk head is shared by reads and writes, and is updated when an operation is started.
+    pub fn seek_penalty_fn(mut self, seek_penalty_fn: SeekPenaltyFn) -> Self {
+        self.seek_penalty_fn.replace(seek_penalty_fn);
+        self
+    }
+
+    /// Sets utilization factor function applied to both read and write operations.
+    ///
+    /// The factor is computed once when an operation is started, so the throughput of outstanding operations
+    /// is not affected by subsequent changes of disk usage. By default the disk usage has no effect.
+    pub fn utilization_factor_fn(mut self, utilization_factor_fn: UtilizationFactorFn) -> Self {
+        self.utilization_factor_fn.replace(utilization_factor_fn);
+        self
+    }
+
+    /// Sets fixed latency added to completion time of each read operation.
+    pub fn read_latency(mut self, read_latency: f64) -> Self {
+        self.read_latency = read_latency;
+        self
+    }
+
+    /// Sets fixed latency added to completion time of each write operation.
+    pub fn write_latency(mut self, write_latency: f64) -> Self {
+        self.write_latency = write_latency;
+        self
+    }
+
+    /// Sets the cap on combined read and write throughput, e.g. imposed by a shared bus or controller.
+    ///
+    /// When both reads and writes are in progress and their throughputs sum up above the cap, the cap is divided
+    /// between reads and writes in proportion to the number of active operations of each type.
+    /// If one of the types cannot use its share because of its own bandwidth limit, the rest goes to the other type.
+    pub fn total_bw(mut self, total_bw: f64) -> Self {
+        self.total_bw.replace(total_bw);
+        self
+    }
+
+    /// Sets how throughput is shared between concurrent operations, see [`FairnessMode`].
+    ///
+    /// Default is [`FairnessMode::PerActivity`].
+    pub fn fairness(mut self, fairness: FairnessMode) -> Self {
+        self.fairness = fairness;
+        self
+    }
+
+    /// Enables weighted sharing of throughput between operations with different priorities.
+    ///
+    /// An operation with priority `p` receives a share of throughput proportional to `p + 1`,
+    /// see [`Disk::read_with_priority()`] and [`Disk::write_with_priority()`].
+    /// Priorities are supported only with [`FairnessMode::PerActivity`].
+    pub fn enable_priorities(mut self) -> Self {
+        self.priorities = true;
+        self
+    }
+
+    /// Enables periodic progress notifications for outstanding operations.
+    ///
+    /// While there are outstanding operations, each `interval` the disk sends [`DataReadProgress`] or
+    /// [`DataWriteProgress`] event with the amount of processed data to the requester of each operation.
+    /// Disabled by default.
+    pub fn progress_interval(mut self, interval: f64) -> Self {
+        assert!(interval > 0., "progress interval must be positive");
+        self.progress_interval.replace(interval);
+        self
+    }
+
+    /// Sets the probability of injected failure of each submitted operation.
+    ///
+    /// The failures are drawn from the simulation random generator, so they are reproducible with the same seed.
+    pub fn failure_rate(mut self, failure_rate: f64) -> Self {
+        assert!((0. ..=1.).contains(&failure_rate), "failure rate must be in [0, 1]");
+        self.failure_rate = failure_rate;
+        self
+    }
+
+    /// Makes the disk fail at given simulation time, modeling the death of the device.
+    ///
+    /// At this time all outstanding operations fail, and all operations submitted afterwards fail immediately.
+    pub fn fail_after(mut self, time: f64) -> Self {
+        self.fail_after.replace(time);
+        self
+    }
+
+    /// Makes read operations fail if their size exceeds the amount of used disk space.
+    ///
+    /// Helps to catch modeling bugs where a client reads more data than was written to the disk.
+    /// By default, reads are only limited by the disk capacity.
+    pub fn track_written_bytes(mut self) -> Self {
+        self.track_written_bytes = true;
+        self
+    }
+
+    /// Limits the number of operations served by the disk concurrently, modeling the hardware queue depth.
+    ///
+    /// When this many reads and writes are in flight, newly submitted operations are held in the internal queue
+    /// and do not consume disk bandwidth. The queued operations are started in the order of submission
+    /// as the in-flight operations complete. By default, the number of in-flight operations is unbounded.
+    pub fn max_queue_depth(mut self, depth: usize) -> Self {
+        assert!(depth > 0, "queue depth must be positive");
+        self.max_queue_depth.replace(depth);
+        self
+    }
+
     /// Builds disk from given builder and simulation context.
     ///
     /// Panics on invalid or incomplete disk settings.
     pub fn build(self, ctx: SimulationContext) -> Disk {
+        assert!(
+            !self.priorities || self.fairness == FairnessMode::PerActivity,
+            "priorities are supported only with per-activity fairness"
+        );
+        let mut read_throughput_fn = self.read_throughput_fn.unwrap();
+        let mut write_throughput_fn = self.write_throughput_fn.unwrap();
+        let mut active_counts = None;
+        if let Some(total_bw) = self.total_bw {
+            let counts = Rc::new(Cell::new((0, 0)));
+            let read_fn = Rc::new(read_throughput_fn);
+            let write_fn = Rc::new(write_throughput_fn);
+            read_throughput_fn =
+                make_capped_throughput_fn(total_bw, read_fn.clone(), write_fn.clone(), counts.clone(), |c| c.1);
+            write_throughput_fn = make_capped_throughput_fn(total_bw, write_fn, read_fn, counts.clone(), |c| c.0);
+            active_counts = Some(counts);
+        }
+        if let Some(time) = self.fail_after {
+            ctx.emit_self(DiskDeath {}, (time - ctx.time()).max(0.));
+        }
+        let mut read_factor_fn = self.read_factor_fn;
+        let mut write_factor_fn = self.write_factor_fn;
+        if let Some(seek_penalty_fn) = self.seek_penalty_fn {
+            let seek_penalty_fn = Rc::new(seek_penalty_fn);
+            let position = Rc::new(Cell::new(None));
+            read_factor_fn = boxed!(SeekFactorFn {
+                inner: read_factor_fn,
+                seek_penalty_fn: seek_penalty_fn.clone(),
+                position: position.clone(),
+            });
+            write_factor_fn = boxed!(SeekFactorFn {
+                inner: write_factor_fn,
+                seek_penalty_fn,
+                position,
+            });
+        }
+        let capacity = self.capacity.unwrap();
+        let mut usage = None;
+        if let Some(utilization_factor_fn) = self.utilization_factor_fn {
+            let utilization_factor_fn = Rc::new(utilization_factor_fn);
+            let disk_usage = Rc::new(Cell::new((capacity, 0)));
+            read_factor_fn = boxed!(UtilizationAwareFactorFn {
+                inner: read_factor_fn,
+                utilization_factor_fn: utilization_factor_fn.clone(),
+                usage: disk_usage.clone(),
+            });
+            write_factor_fn = boxed!(UtilizationAwareFactorFn {
+                inner: write_factor_fn,
+                utilization_factor_fn,
+                usage: disk_usage.clone(),
+            });
+            usage = Some(disk_usage);
+        }
         Disk {
-            capacity: self.capacity.unwrap(),
+            capacity,
             used: 0,
-            read_throughput_model: FairThroughputSharingModel::new(
-                self.read_throughput_fn.unwrap(),
-                self.read_factor_fn,
+            read_throughput_model: DiskThroughputModel::new(
+                read_throughput_fn,
+                read_factor_fn,
+                self.fairness,
+                self.priorities,
             ),
-            write_throughput_model: FairThroughputSharingModel::new(
-                self.write_throughput_fn.unwrap(),
-                self.write_factor_fn,
+            write_throughput_model: DiskThroughputModel::new(
+                write_throughput_fn,
+                write_factor_fn,
+                self.fairness,
+                self.priorities,
             ),
+            active_counts,
+            usage,
             next_request_id: 0,
             next_read_event: u64::MAX,
             next_write_event: u64::MAX,
+            read_latency: self.read_latency,
+            write_latency: self.write_latency,
+            callbacks: HashMap::new(),
+            progress_interval: self.progress_interval,
+            progress_tick_scheduled: false,
+            imported_progress: HashMap::new(),
+            imported_volumes: HashMap::new(),
+            read_modify_writes: HashSet::new(),
+            write_backs: HashMap::new(),
+            overwrites: HashSet::new(),
+            batches: HashMap::new(),
+            batch_ids: HashMap::new(),
+            flushes: Vec::new(),
+            deadlines: HashMap::new(),
+            watermark: None,
+            failure_rate: self.failure_rate,
+            fail_after: self.fail_after,
+            track_written_bytes: self.track_written_bytes,
+            max_queue_depth: self.max_queue_depth,
+            queued: VecDeque::new(),
             ctx,
         }
     }
 }
 
+// Limits the throughput of operations of one type so that together with the other type it does not exceed the cap.
+fn make_capped_throughput_fn(
+    total_bw: f64,
+    own_fn: Rc<ResourceThroughputFn>,
+    other_fn: Rc<ResourceThroughputFn>,
+    counts: Rc<Cell<(usize, usize)>>,
+    other_count: fn((usize, usize)) -> usize,
+) -> ResourceThroughputFn {
+    boxed!(move |count| {
+        let own = own_fn(count);
+        let other_count = other_count(counts.get());
+        let other = if other_count > 0 { other_fn(other_count) } else { 0. };
+        if own + other <= total_bw {
+            return own;
+        }
+        let fair_share = total_bw * count as f64 / (count + other_count) as f64;
+        own.min(fair_share.max(total_bw - other))
+    })
+}
+
+// Applies the seek penalty on top of the user-defined factor and tracks the position of the disk head.
+struct SeekFactorFn {
+    inner: Box<dyn ActivityFactorFn<DiskActivity>>,
+    seek_penalty_fn: Rc<SeekPenaltyFn>,
+    position: Rc<Cell<Option<u64>>>,
+}
+
+impl ActivityFactorFn<DiskActivity> for SeekFactorFn {
+    fn get_factor(&mut self, item: &DiskActivity, ctx: &mut SimulationContext) -> f64 {
+        let factor = self.inner.get_factor(item, ctx);
+        match item.offset {
+            Some(offset) => {
+                let penalty = match self.position.get() {
+                    Some(position) => (self.seek_penalty_fn)(position, item),
+                    None => 1.,
+                };
+                self.position.set(Some(offset + item.size));
+                factor * penalty
+            }
+            None => factor,
+        }
+    }
+}
+
+// Applies the utilization factor on top of the user-defined factor.
+// The disk capacity and usage are synchronized by the disk before inserting operations.
+struct UtilizationAwareFactorFn {
+    inner: Box<dyn ActivityFactorFn<DiskActivity>>,
+    utilization_factor_fn: Rc<UtilizationFactorFn>,
+    usage: Rc<Cell<(u64, u64)>>,
+}
+
+impl ActivityFactorFn<DiskActivity> for UtilizationAwareFactorFn {
+    fn get_factor(&mut self, item: &DiskActivity, ctx: &mut SimulationContext) -> f64 {
+        let (capacity, used) = self.usage.get();
+        let info = StorageInfo {
+            capacity,
+            used_space: used,
+            free_space: capacity - used,
+        };
+        self.inner.get_factor(item, ctx) * (self.utilization_factor_fn)(&info)
+    }
+}
+
 ///////////////////////////////////////////////////////////////////////////////
 
 /// Represents a disk.
@@ -172,9 +700,38 @@ pub struct Disk {
     pub(in crate::disk) used: u64,
     pub(in crate::disk) read_throughput_model: DiskThroughputModel,
     pub(in crate::disk) write_throughput_model: DiskThroughputModel,
+    pub(in crate::disk) active_counts: Option<Rc<Cell<(usize, usize)>>>,
+    // capacity and used space shared with the utilization factor function
+    pub(in crate::disk) usage: Option<Rc<Cell<(u64, u64)>>>,
     pub(in crate::disk) next_request_id: u64,
     pub(in crate::disk) next_read_event: u64,
     pub(in crate::disk) next_write_event: u64,
+    pub(in crate::disk) read_latency: f64,
+    pub(in crate::disk) write_latency: f64,
+    pub(in crate::disk) callbacks: HashMap<u64, DiskCallback>,
+    pub(in crate::disk) progress_interval: Option<f64>,
+    pub(in crate::disk) progress_tick_scheduled: bool,
+    // amount of data processed on other disks for imported operations
+    pub(in crate::disk) imported_progress: HashMap<u64, u64>,
+    // amount of data remaining at the time of import, which was inserted into the throughput model
+    pub(in crate::disk) imported_volumes: HashMap<u64, f64>,
+    // outstanding read-modify-write requests and those of them waiting for the read latency before the write phase
+    pub(in crate::disk) read_modify_writes: HashSet<u64>,
+    pub(in crate::disk) write_backs: HashMap<u64, DiskActivity>,
+    // outstanding overwrites, which do not reserve disk space
+    pub(in crate::disk) overwrites: HashSet<u64>,
+    // batches are identified by the request id of their first operation
+    pub(in crate::disk) batches: HashMap<u64, DiskBatch>,
+    pub(in crate::disk) batch_ids: HashMap<u64, u64>,
+    pub(in crate::disk) flushes: Vec<DiskFlush>,
+    pub(in crate::disk) deadlines: HashMap<u64, DiskDeadline>,
+    pub(in crate::disk) watermark: Option<DiskWatermark>,
+    pub(in crate::disk) failure_rate: f64,
+    pub(in crate::disk) fail_after: Option<f64>,
+    pub(in crate::disk) track_written_bytes: bool,
+    pub(in crate::disk) max_queue_depth: Option<usize>,
+    // operations waiting for a free slot when the queue depth is limited, in the order of submission
+    pub(in crate::disk) queued: VecDeque<QueuedDiskActivity>,
     pub(in crate::disk) ctx: SimulationContext,
 }
 
@@ -199,34 +756,725 @@ impl Disk {
         }
     }
 
+    // Operations completing at the same time are processed together within a single event.
     fn on_read_completed(&mut self) {
-        let (_, activity) = self.read_throughput_model.pop().unwrap();
-        self.ctx.emit_now(
-            DataReadCompleted {
-                request_id: activity.request_id,
-                size: activity.size,
-            },
-            activity.requester,
-        );
+        for (_, activity) in self.read_throughput_model.pop_all_next() {
+            self.complete_read(activity);
+        }
         self.schedule_next_read_event();
+        self.update_shared_bandwidth();
+        self.admit_queued();
+    }
+
+    fn complete_read(&mut self, activity: DiskActivity) {
+        self.imported_progress.remove(&activity.request_id);
+        self.imported_volumes.remove(&activity.request_id);
+        if let Some(batch_id) = self.batch_ids.remove(&activity.request_id) {
+            self.on_batch_activity_done(batch_id, None, self.read_latency);
+        } else if self.read_modify_writes.contains(&activity.request_id) {
+            self.ctx.emit_self(
+                DiskWriteBackDue {
+                    request_id: activity.request_id,
+                },
+                self.read_latency,
+            );
+            self.write_backs.insert(activity.request_id, activity);
+        } else if self.misses_deadline(activity.request_id, self.read_latency) {
+            // the request is failed when its deadline expires
+            log_debug!(self.ctx, "Read {} misses its deadline", activity.request_id);
+        } else {
+            self.ctx.emit(
+                DataReadCompleted {
+                    request_id: activity.request_id,
+                    size: activity.size,
+                    start_time: activity.submit_time,
+                    completion_time: self.ctx.time() + self.read_latency,
+                    tag: activity.tag,
+                },
+                activity.requester,
+                self.read_latency,
+            );
+            self.complete_callback(&activity, self.read_latency);
+        }
+    }
+
+    // Returns true if the operation finishing now with given latency completes after its deadline (if any).
+    // Otherwise, the deadline is removed.
+    fn misses_deadline(&mut self, request_id: u64, latency: f64) -> bool {
+        match self.deadlines.get(&request_id) {
+            Some(d) if self.ctx.time() + latency > d.deadline => true,
+            Some(_) => {
+                self.clear_deadline(request_id);
+                false
+            }
+            None => false,
+        }
+    }
+
+    fn clear_deadline(&mut self, request_id: u64) {
+        if let Some(d) = self.deadlines.remove(&request_id) {
+            self.ctx.cancel_event(d.event_id);
+        }
+    }
+
+    fn on_deadline_expired(&mut self, request_id: u64) {
+        if let Some(d) = self.deadlines.remove(&request_id) {
+            // the operation is either still in progress or finished too late to meet the deadline
+            let _ = self.cancel(request_id);
+            self.fail_read(request_id, d.requester, format!("deadline {} exceeded", d.deadline));
+        }
     }
 
     fn on_write_completed(&mut self) {
-        let (_, activity) = self.write_throughput_model.pop().unwrap();
-        self.ctx.emit_now(
-            DataWriteCompleted {
-                request_id: activity.request_id,
-                size: activity.size,
+        for (_, activity) in self.write_throughput_model.pop_all_next() {
+            self.complete_write(activity);
+        }
+        self.schedule_next_write_event();
+        self.update_shared_bandwidth();
+        self.admit_queued();
+    }
+
+    fn complete_write(&mut self, activity: DiskActivity) {
+        self.imported_progress.remove(&activity.request_id);
+        self.imported_volumes.remove(&activity.request_id);
+        self.overwrites.remove(&activity.request_id);
+        if let Some(batch_id) = self.batch_ids.remove(&activity.request_id) {
+            self.on_batch_activity_done(batch_id, None, self.write_latency);
+        } else if self.read_modify_writes.remove(&activity.request_id) {
+            self.ctx.emit(
+                DataReadModifyWriteCompleted {
+                    request_id: activity.request_id,
+                    size: activity.size,
+                    start_time: activity.submit_time,
+                    completion_time: self.ctx.time() + self.write_latency,
+                },
+                activity.requester,
+                self.write_latency,
+            );
+        } else {
+            self.ctx.emit(
+                DataWriteCompleted {
+                    request_id: activity.request_id,
+                    size: activity.size,
+                    start_time: activity.submit_time,
+                    completion_time: self.ctx.time() + self.write_latency,
+                    tag: activity.tag,
+                },
+                activity.requester,
+                self.write_latency,
+            );
+            self.complete_callback(&activity, self.write_latency);
+        }
+        self.on_write_finished(activity.request_id, self.write_latency);
+    }
+
+    // Removes finished write from the pending flushes and completes the flushes which have nothing left to wait for.
+    fn on_write_finished(&mut self, request_id: u64, latency: f64) {
+        let mut completed = Vec::new();
+        self.flushes.retain_mut(|flush| {
+            flush.pending.remove(&request_id);
+            if flush.pending.is_empty() {
+                completed.push((flush.request_id, flush.requester));
+            }
+            !flush.pending.is_empty()
+        });
+        for (request_id, requester) in completed {
+            self.ctx.emit(DataFlushCompleted { request_id }, requester, latency);
+        }
+    }
+
+    // Completes all pending flushes after the outstanding writes are failed or exported.
+    fn complete_flushes(&mut self) {
+        for flush in std::mem::take(&mut self.flushes) {
+            self.ctx.emit_now(
+                DataFlushCompleted {
+                    request_id: flush.request_id,
+                },
+                flush.requester,
+            );
+        }
+    }
+
+    // The write phase of read-modify-write request is started after the read phase is completed,
+    // unless the request was cancelled or exported in the meantime.
+    fn on_write_back_due(&mut self, request_id: u64) {
+        if let Some(activity) = self.write_backs.remove(&request_id) {
+            self.start_write(activity);
+        }
+    }
+
+    fn sync_usage(&self) {
+        if let Some(usage) = &self.usage {
+            usage.set((self.capacity, self.used));
+        }
+    }
+
+    // Notifies the watermark listener if the used space has crossed the watermarks since the last check.
+    fn check_watermark(&mut self) {
+        let (used_space, free_space) = (self.used, self.capacity - self.used);
+        if let Some(watermark) = &mut self.watermark {
+            if !watermark.low_space && used_space > watermark.high {
+                watermark.low_space = true;
+                log_debug!(self.ctx, "Used space {} is above high watermark", used_space);
+                self.ctx
+                    .emit_now(StorageLowSpace { used_space, free_space }, watermark.listener);
+            } else if watermark.low_space && used_space < watermark.low {
+                watermark.low_space = false;
+                log_debug!(self.ctx, "Used space {} is below low watermark", used_space);
+                self.ctx
+                    .emit_now(StorageSpaceRecovered { used_space, free_space }, watermark.listener);
+            }
+        }
+    }
+
+    fn start_read(&mut self, activity: DiskActivity) {
+        let size = activity.size as f64;
+        self.start_activity(DiskOperation::Read, activity, size);
+    }
+
+    fn start_write(&mut self, activity: DiskActivity) {
+        let size = activity.size as f64;
+        self.start_activity(DiskOperation::Write, activity, size);
+    }
+
+    fn start_activity(&mut self, operation: DiskOperation, activity: DiskActivity, remaining_size: f64) {
+        if !self.has_free_slot() {
+            log_debug!(self.ctx, "Queued operation with request id {}", activity.request_id);
+            self.queued.push_back(QueuedDiskActivity {
+                operation,
+                activity,
+                remaining_size,
+            });
+            return;
+        }
+        self.sync_usage();
+        match operation {
+            DiskOperation::Read => {
+                self.read_throughput_model
+                    .insert(activity, remaining_size, &mut self.ctx);
+                self.ctx.cancel_event(self.next_read_event);
+                self.schedule_next_read_event();
+            }
+            DiskOperation::Write => {
+                self.write_throughput_model
+                    .insert(activity, remaining_size, &mut self.ctx);
+                self.ctx.cancel_event(self.next_write_event);
+                self.schedule_next_write_event();
+            }
+        }
+        self.update_shared_bandwidth();
+        self.schedule_progress_tick();
+    }
+
+    // Returns true if one more operation can be served without exceeding the queue depth.
+    fn has_free_slot(&self) -> bool {
+        self.max_queue_depth
+            .is_none_or(|depth| self.read_throughput_model.len() + self.write_throughput_model.len() < depth)
+    }
+
+    // Starts the queued operations while there are free slots.
+    fn admit_queued(&mut self) {
+        while self.has_free_slot() {
+            let Some(queued) = self.queued.pop_front() else {
+                break;
+            };
+            self.start_activity(queued.operation, queued.activity, queued.remaining_size);
+        }
+    }
+
+    // Moves all queued operations to the throughput models regardless of the queue depth,
+    // so that they are failed along with the in-flight ones.
+    fn release_queued(&mut self) {
+        for queued in std::mem::take(&mut self.queued) {
+            match queued.operation {
+                DiskOperation::Read => {
+                    self.read_throughput_model
+                        .insert(queued.activity, queued.remaining_size, &mut self.ctx)
+                }
+                DiskOperation::Write => {
+                    self.write_throughput_model
+                        .insert(queued.activity, queued.remaining_size, &mut self.ctx)
+                }
+            }
+        }
+    }
+
+    // Cancelled operations are excluded from the batch.
+    fn on_batch_activity_done(&mut self, batch_id: u64, cancelled: Option<&DiskActivity>, latency: f64) {
+        let batch = self.batches.get_mut(&batch_id).unwrap();
+        if let Some(activity) = cancelled {
+            batch.request_ids.retain(|id| *id != activity.request_id);
+            batch.total_size -= activity.size;
+        }
+        batch.remaining -= 1;
+        if batch.remaining > 0 {
+            return;
+        }
+        let batch = self.batches.remove(&batch_id).unwrap();
+        if batch.request_ids.is_empty() {
+            return;
+        }
+        match batch.operation {
+            DiskOperation::Read => self.ctx.emit(
+                DataBatchReadCompleted {
+                    request_ids: batch.request_ids,
+                    total_size: batch.total_size,
+                },
+                batch.requester,
+                latency,
+            ),
+            DiskOperation::Write => self.ctx.emit(
+                DataBatchWriteCompleted {
+                    request_ids: batch.request_ids,
+                    total_size: batch.total_size,
+                },
+                batch.requester,
+                latency,
+            ),
+        };
+    }
+
+    // All operations are inserted into the throughput model before the next completion event is rescheduled.
+    // The operations exceeding the queue depth are queued.
+    fn submit_batch(&mut self, operation: DiskOperation, sizes: &[u64], request_ids: &[u64], requester: Id) {
+        let batch_id = request_ids[0];
+        self.sync_usage();
+        for (&size, &request_id) in sizes.iter().zip(request_ids) {
+            let activity = DiskActivity {
+                request_id,
+                requester,
+                size,
+                priority: 0,
+                submit_time: self.ctx.time(),
+                offset: None,
+                tag: None,
+            };
+            if !self.has_free_slot() {
+                self.queued.push_back(QueuedDiskActivity {
+                    operation,
+                    activity,
+                    remaining_size: size as f64,
+                });
+            } else {
+                match operation {
+                    DiskOperation::Read => self.read_throughput_model.insert(activity, size as f64, &mut self.ctx),
+                    DiskOperation::Write => self.write_throughput_model.insert(activity, size as f64, &mut self.ctx),
+                }
+            }
+            self.batch_ids.insert(request_id, batch_id);
+        }
+        self.batches.insert(
+            batch_id,
+            DiskBatch {
+                operation,
+                requester,
+                request_ids: request_ids.to_vec(),
+                total_size: sizes.iter().sum(),
+                remaining: sizes.len(),
             },
-            activity.requester,
         );
+        match operation {
+            DiskOperation::Read => {
+                self.ctx.cancel_event(self.next_read_event);
+                self.schedule_next_read_event();
+            }
+            DiskOperation::Write => {
+                self.ctx.cancel_event(self.next_write_event);
+                self.schedule_next_write_event();
+            }
+        }
+        self.update_shared_bandwidth();
+        self.schedule_progress_tick();
+    }
+
+    fn schedule_progress_tick(&mut self) {
+        if let Some(interval) = self.progress_interval {
+            if !self.progress_tick_scheduled {
+                self.ctx.emit_self(DiskProgressTick {}, interval);
+                self.progress_tick_scheduled = true;
+            }
+        }
+    }
+
+    // Since throughput is continuously reallocated between operations,
+    // the amount of processed data is obtained from the throughput models at each tick.
+    fn on_progress_tick(&mut self) {
+        self.progress_tick_scheduled = false;
+        let time = self.ctx.time();
+        for (activity, done) in self.read_throughput_model.progress(time) {
+            self.ctx.emit_now(
+                DataReadProgress {
+                    request_id: activity.request_id,
+                    bytes_done: self.bytes_done(activity, done),
+                    total: activity.size,
+                },
+                activity.requester,
+            );
+        }
+        for (activity, done) in self.write_throughput_model.progress(time) {
+            self.ctx.emit_now(
+                DataWriteProgress {
+                    request_id: activity.request_id,
+                    bytes_done: self.bytes_done(activity, done),
+                    total: activity.size,
+                },
+                activity.requester,
+            );
+        }
+        if self.read_throughput_model.len() + self.write_throughput_model.len() > 0 {
+            self.schedule_progress_tick();
+        }
+    }
+
+    fn bytes_done(&self, activity: &DiskActivity, done: f64) -> u64 {
+        let before = self.imported_progress.get(&activity.request_id).copied().unwrap_or(0);
+        before + ((activity.size - before) as f64 * done).round() as u64
+    }
+
+    // With the combined bandwidth cap, a change in the set of operations of one type affects
+    // the throughput of the other type, so both models are updated.
+    fn update_shared_bandwidth(&mut self) {
+        if let Some(counts) = &self.active_counts {
+            counts.set((self.read_throughput_model.len(), self.write_throughput_model.len()));
+            let time = self.ctx.time();
+            self.read_throughput_model.update_throughput(time);
+            self.write_throughput_model.update_throughput(time);
+            self.ctx.cancel_event(self.next_read_event);
+            self.schedule_next_read_event();
+            self.ctx.cancel_event(self.next_write_event);
+            self.schedule_next_write_event();
+        }
+    }
+
+    // Operation latency is applied after the operation leaves the throughput model,
+    // so that it does not hold bandwidth and each operation observes the latency independently.
+    fn complete_callback(&mut self, activity: &DiskActivity, latency: f64) {
+        if latency == 0. {
+            self.run_callback(activity.request_id, Ok(activity.size));
+        } else if self.callbacks.contains_key(&activity.request_id) {
+            self.ctx.emit_self(
+                DiskCallbackDue {
+                    request_id: activity.request_id,
+                    result: Ok(activity.size),
+                },
+                latency,
+            );
+        }
+    }
+
+    fn run_callback(&mut self, request_id: u64, result: Result<u64, String>) {
+        if let Some(callback) = self.callbacks.remove(&request_id) {
+            callback(result);
+        }
+    }
+
+    /// Submits data read request and returns unique request id.
+    ///
+    /// Works like [`Storage::read()`], but additionally invokes `callback` with the read size on completion
+    /// or with the error message on failure. The callback is invoked at the same simulation time
+    /// as the corresponding event is delivered to the requester. Since the callback is called from the disk
+    /// event handler, it must not access the disk itself.
+    pub fn read_with_callback<F>(&mut self, size: u64, requester: Id, callback: F) -> u64
+    where
+        F: FnOnce(Result<u64, String>) + 'static,
+    {
+        self.submit_read(size, requester, 0, None, None, Some(Box::new(callback)))
+    }
+
+    /// Submits data write request and returns unique request id.
+    ///
+    /// Works like [`Storage::write()`], but additionally invokes `callback` with the written size on completion
+    /// or with the error message on failure. The callback is invoked at the same simulation time
+    /// as the corresponding event is delivered to the requester. Since the callback is called from the disk
+    /// event handler, it must not access the disk itself.
+    pub fn write_with_callback<F>(&mut self, size: u64, requester: Id, callback: F) -> u64
+    where
+        F: FnOnce(Result<u64, String>) + 'static,
+    {
+        self.submit_write(size, requester, 0, None, None, Some(Box::new(callback)))
+    }
+
+    /// Submits data read request and returns the future resolved on its completion or failure.
+    ///
+    /// The requester also receives the usual completion or failure event. See [`DiskFuture`] for details.
+    pub fn read_async(&mut self, size: u64, requester: Id) -> DiskFuture {
+        let state = Rc::new(RefCell::new(DiskFutureState::default()));
+        let request_id = self.read_with_callback(size, requester, make_future_callback(state.clone()));
+        DiskFuture { request_id, state }
+    }
+
+    /// Submits data write request and returns the future resolved on its completion or failure.
+    ///
+    /// The requester also receives the usual completion or failure event. See [`DiskFuture`] for details.
+    pub fn write_async(&mut self, size: u64, requester: Id) -> DiskFuture {
+        let state = Rc::new(RefCell::new(DiskFutureState::default()));
+        let request_id = self.write_with_callback(size, requester, make_future_callback(state.clone()));
+        DiskFuture { request_id, state }
+    }
+
+    /// Rejects write request without submitting it and returns unique request id.
+    ///
+    /// Used by storage models built on top of disk to enforce their own write constraints.
+    pub(crate) fn reject_write(&mut self, requester: Id, error: String) -> u64 {
+        let request_id = self.make_unique_request_id();
+        self.fail_write(request_id, requester, error);
+        request_id
+    }
+
+    /// Rejects a batch of write requests without submitting it and returns unique request ids.
+    pub(crate) fn reject_write_batch(&mut self, count: usize, requester: Id, error: String) -> Vec<u64> {
+        let request_ids: Vec<u64> = (0..count).map(|_| self.make_unique_request_id()).collect();
+        self.fail_write_batch(request_ids.clone(), requester, error);
+        request_ids
+    }
+
+    /// Rejects read-modify-write request without submitting it and returns unique request id.
+    pub(crate) fn reject_read_modify_write(&mut self, requester: Id, error: String) -> u64 {
+        let request_id = self.make_unique_request_id();
+        self.fail_read_modify_write(request_id, requester, error);
+        request_id
+    }
+
+    fn fail_read_modify_write(&mut self, request_id: u64, requester: Id, error: String) {
+        log_error!(self.ctx, "Failed read-modify-write: {}", error);
+        self.ctx
+            .emit_now(DataReadModifyWriteFailed { request_id, error }, requester);
+    }
+
+    fn fail_read(&mut self, request_id: u64, requester: Id, error: String) {
+        log_error!(self.ctx, "Failed reading: {}", error,);
+        if self.callbacks.contains_key(&request_id) {
+            self.ctx.emit_self_now(DiskCallbackDue {
+                request_id,
+                result: Err(error.clone()),
+            });
+        }
+        self.ctx.emit_now(DataReadFailed { request_id, error }, requester);
+    }
+
+    fn fail_read_batch(&mut self, request_ids: Vec<u64>, requester: Id, error: String) {
+        log_error!(self.ctx, "Failed reading batch: {}", error);
+        self.ctx.emit_now(DataBatchReadFailed { request_ids, error }, requester);
+    }
+
+    fn fail_write_batch(&mut self, request_ids: Vec<u64>, requester: Id, error: String) {
+        log_error!(self.ctx, "Failed writing batch: {}", error);
+        self.ctx
+            .emit_now(DataBatchWriteFailed { request_ids, error }, requester);
+    }
+
+    // Injected faults are checked only for otherwise valid requests. The random generator is not used
+    // when the failure rate is zero, so that disks without failure injection do not affect other random draws.
+    fn injected_fault(&self) -> Option<String> {
+        if let Some(time) = self.fail_after {
+            if self.ctx.time() >= time {
+                return Some(format!("injected fault: disk is dead since time {}", time));
+            }
+        }
+        if self.failure_rate > 0. && self.ctx.rand() < self.failure_rate {
+            return Some("injected fault: random operation failure".to_string());
+        }
+        None
+    }
+
+    // Fails all outstanding operations. Each batch is failed as a whole.
+    fn on_disk_death(&mut self) {
+        let time = self.ctx.time();
+        let error = format!("injected fault: disk is dead since time {}", time);
+        log_error!(self.ctx, "Disk is dead");
+        self.release_queued();
+        self.ctx.cancel_event(self.next_read_event);
+        self.ctx.cancel_event(self.next_write_event);
+        for (activity, _) in self.read_throughput_model.drain(time) {
+            self.clear_deadline(activity.request_id);
+            if self.read_modify_writes.remove(&activity.request_id) {
+                self.fail_read_modify_write(activity.request_id, activity.requester, error.clone());
+            } else if !self.batch_ids.contains_key(&activity.request_id) {
+                self.fail_read(activity.request_id, activity.requester, error.clone());
+            }
+        }
+        for activity in self.drain_write_backs() {
+            self.read_modify_writes.remove(&activity.request_id);
+            self.fail_read_modify_write(activity.request_id, activity.requester, error.clone());
+        }
+        for (activity, _) in self.write_throughput_model.drain(time) {
+            if self.read_modify_writes.remove(&activity.request_id) {
+                self.fail_read_modify_write(activity.request_id, activity.requester, error.clone());
+                continue;
+            }
+            if !self.overwrites.remove(&activity.request_id) {
+                self.used -= activity.size;
+            }
+            if !self.batch_ids.contains_key(&activity.request_id) {
+                self.fail_write(activity.request_id, activity.requester, error.clone());
+            }
+        }
+        self.check_watermark();
+        let mut batches: Vec<DiskBatch> = self.batches.drain().map(|(_, batch)| batch).collect();
+        batches.sort_by_key(|batch| batch.request_ids[0]);
+        for batch in batches {
+            match batch.operation {
+                DiskOperation::Read => self.fail_read_batch(batch.request_ids, batch.requester, error.clone()),
+                DiskOperation::Write => self.fail_write_batch(batch.request_ids, batch.requester, error.clone()),
+            }
+        }
+        self.batch_ids.clear();
+        self.imported_progress.clear();
+        self.imported_volumes.clear();
+        self.complete_flushes();
+        self.update_shared_bandwidth();
+    }
+
+    // Returns read-modify-write requests waiting for the write phase in the order of submission.
+    fn drain_write_backs(&mut self) -> Vec<DiskActivity> {
+        let mut activities: Vec<DiskActivity> = self.write_backs.drain().map(|(_, activity)| activity).collect();
+        activities.sort_by_key(|activity| activity.request_id);
+        activities
+    }
+
+    fn fail_write(&mut self, request_id: u64, requester: Id, error: String) {
+        log_error!(self.ctx, "Failed writing: {}", error,);
+        if self.callbacks.contains_key(&request_id) {
+            self.ctx.emit_self_now(DiskCallbackDue {
+                request_id,
+                result: Err(error.clone()),
+            });
+        }
+        self.ctx.emit_now(DataWriteFailed { request_id, error }, requester);
+    }
+
+    /// Changes disk capacity, e.g. to model thin-provisioned or elastic volumes.
+    ///
+    /// Subsequent requests are checked against the new capacity.
+    /// Fails if the new capacity is less than the currently used space.
+    pub fn set_capacity(&mut self, new_capacity: u64) -> Result<(), String> {
+        if new_capacity < self.used {
+            return Err(format!(
+                "new capacity {} is less than used space {}",
+                new_capacity, self.used
+            ));
+        }
+        log_debug!(self.ctx, "Changed capacity from {} to {}", self.capacity, new_capacity);
+        self.capacity = new_capacity;
+        Ok(())
+    }
+
+    /// Sets watermarks of used space, crossing of which is reported to `listener`.
+    ///
+    /// When the used space rises above `high`, the listener receives [`StorageLowSpace`] event.
+    /// After that, when the used space drops below `low`, the listener receives [`StorageSpaceRecovered`] event,
+    /// and so on. So each event is emitted once per crossing, and the fluctuations of used space between
+    /// the watermarks do not produce any events. The space reserved by outstanding writes counts as used.
+    /// If the used space is already above `high`, [`StorageLowSpace`] is emitted at once.
+    /// Fails if `low` is greater than `high`.
+    pub fn set_watermark(&mut self, low: u64, high: u64, listener: Id) -> Result<(), String> {
+        if low > high {
+            return Err(format!("low watermark {} is greater than high watermark {}", low, high));
+        }
+        self.watermark = Some(DiskWatermark {
+            low,
+            high,
+            listener,
+            low_space: false,
+        });
+        self.check_watermark();
+        Ok(())
+    }
+
+    /// Limits the throughput received by all operations of given requester to `max_bw`, e.g. to enforce QoS.
+    ///
+    /// The limit applies separately to reads and writes and holds even if the disk has spare capacity,
+    /// which is shared between the other requesters instead. If the combined throughput is capped
+    /// with [`DiskBuilder::total_bw()`], the limit is applied to the requester's share of the capped throughput,
+    /// so the requester receives the smaller of the two. The limit can be removed by setting it to `f64::INFINITY`.
+    /// Requester limits are supported only with [`FairnessMode::PerRequester`].
+    pub fn set_requester_limit(&mut self, requester: Id, max_bw: f64) -> Result<(), String> {
+        if max_bw <= 0. {
+            return Err(format!("invalid requester limit: {}", max_bw));
+        }
+        let time = self.ctx.time();
+        self.read_throughput_model
+            .set_requester_limit(requester, max_bw, time)?;
+        self.write_throughput_model
+            .set_requester_limit(requester, max_bw, time)?;
+        log_debug!(self.ctx, "Limited throughput of requester {} to {}", requester, max_bw);
+        self.ctx.cancel_event(self.next_read_event);
+        self.schedule_next_read_event();
+        self.ctx.cancel_event(self.next_write_event);
         self.schedule_next_write_event();
+        Ok(())
     }
-}
 
-/// Storage model implementation for disk.
-impl Storage for Disk {
-    fn read(&mut self, size: u64, requester: Id) -> u64 {
+    /// Submits data read request with given priority and returns unique request id.
+    ///
+    /// Works like [`Storage::read()`], which submits requests with zero priority.
+    /// If priorities are enabled for the disk (see [`DiskBuilder::enable_priorities()`]), the operation receives
+    /// a share of bandwidth proportional to `priority + 1`. Otherwise, the priority is ignored.
+    pub fn read_with_priority(&mut self, size: u64, requester: Id, priority: u8) -> u64 {
+        self.submit_read(size, requester, priority, None, None, None)
+    }
+
+    /// Submits data read request starting at given position on the disk and returns unique request id.
+    ///
+    /// Works like [`Storage::read()`], but the operation throughput depends on the seek penalty
+    /// if it is set for the disk (see [`DiskBuilder::seek_penalty_fn()`]).
+    pub fn read_at(&mut self, offset: u64, size: u64, requester: Id) -> u64 {
+        self.submit_read(size, requester, 0, Some(offset), None, None)
+    }
+
+    /// Submits data read request with user-defined tag and returns unique request id.
+    ///
+    /// Works like [`Storage::read()`], but the tag is passed back in `DataReadCompleted` event,
+    /// so that the requester can correlate the completion with its own context without keeping a map by request id.
+    pub fn read_tagged(&mut self, size: u64, requester: Id, tag: u64) -> u64 {
+        self.submit_read(size, requester, 0, None, Some(tag), None)
+    }
+
+    /// Submits data read request which must be completed by given `deadline` and returns unique request id.
+    ///
+    /// Works like [`Storage::read()`], but if the read is not completed by the deadline (specified as absolute
+    /// simulation time and including the read latency), it is removed from the disk and the requester receives
+    /// `DataReadFailed` event at the deadline instead of `DataReadCompleted`. Since the read progress depends
+    /// on the other operations submitted later, the decision is made when the deadline expires.
+    /// The deadline is dropped if the read is cancelled or exported with [`Disk::export_outstanding()`].
+    pub fn read_with_deadline(&mut self, size: u64, requester: Id, deadline: f64) -> u64 {
+        let request_id = self.submit_read(size, requester, 0, None, None, None);
+        let started = self.read_throughput_model.items().any(|a| a.request_id == request_id);
+        if started || self.queued.iter().any(|q| q.activity.request_id == request_id) {
+            let event_id = self
+                .ctx
+                .emit_self(DiskDeadlineExpired { request_id }, (deadline - self.ctx.time()).max(0.));
+            self.deadlines.insert(
+                request_id,
+                DiskDeadline {
+                    requester,
+                    deadline,
+                    event_id,
+                },
+            );
+        }
+        request_id
+    }
+
+    // Returns the maximum size of read operation, see `DiskBuilder::track_written_bytes()`.
+    fn readable_size(&self) -> u64 {
+        if self.track_written_bytes {
+            self.used
+        } else {
+            self.capacity
+        }
+    }
+
+    // The callback is registered under the allocated request id before the request can fail or complete.
+    fn submit_read(
+        &mut self,
+        size: u64,
+        requester: Id,
+        priority: u8,
+        offset: Option<u64>,
+        tag: Option<u64>,
+        callback: Option<DiskCallback>,
+    ) -> u64 {
         log_debug!(
             self.ctx,
             "Received read request, size: {}, requester: {}",
@@ -234,31 +1482,103 @@ impl Storage for Disk {
             requester
         );
         let request_id = self.make_unique_request_id();
-        if size > self.capacity {
+        if let Some(callback) = callback {
+            self.callbacks.insert(request_id, callback);
+        }
+        if size > self.readable_size() {
             let error = format!(
                 "requested read size is {} but only {} is available",
-                size, self.capacity
+                size,
+                self.readable_size()
             );
-            log_error!(self.ctx, "Failed reading: {}", error,);
-            self.ctx.emit_now(DataReadFailed { request_id, error }, requester);
+            self.fail_read(request_id, requester, error);
+        } else if let Some(error) = self.injected_fault() {
+            self.fail_read(request_id, requester, error);
         } else {
-            self.read_throughput_model.insert(
-                DiskActivity {
-                    request_id,
-                    requester,
-                    size,
-                },
-                size as f64,
-                &mut self.ctx,
-            );
-            self.ctx.cancel_event(self.next_read_event);
-            self.schedule_next_read_event();
+            self.start_read(DiskActivity {
+                request_id,
+                requester,
+                size,
+                priority,
+                submit_time: self.ctx.time(),
+                offset,
+                tag,
+            });
         }
         request_id
     }
 
-    fn write(&mut self, size: u64, requester: Id) -> u64 {
+    /// Submits data write request with given priority and returns unique request id.
+    ///
+    /// Works like [`Storage::write()`], which submits requests with zero priority.
+    /// If priorities are enabled for the disk (see [`DiskBuilder::enable_priorities()`]), the operation receives
+    /// a share of bandwidth proportional to `priority + 1`. Otherwise, the priority is ignored.
+    pub fn write_with_priority(&mut self, size: u64, requester: Id, priority: u8) -> u64 {
+        self.submit_write(size, requester, priority, None, None, None)
+    }
+
+    /// Submits data write request starting at given position on the disk and returns unique request id.
+    ///
+    /// Works like [`Storage::write()`], but the operation throughput depends on the seek penalty
+    /// if it is set for the disk (see [`DiskBuilder::seek_penalty_fn()`]).
+    pub fn write_at(&mut self, offset: u64, size: u64, requester: Id) -> u64 {
+        self.submit_write(size, requester, 0, Some(offset), None, None)
+    }
+
+    /// Submits data write request with user-defined tag and returns unique request id.
+    ///
+    /// Works like [`Storage::write()`], but the tag is passed back in `DataWriteCompleted` event.
+    pub fn write_tagged(&mut self, size: u64, requester: Id, tag: u64) -> u64 {
+        self.submit_write(size, requester, 0, None, Some(tag), None)
+    }
+
+    /// Submits request to overwrite previously written data and returns unique request id.
+    ///
+    /// Works like [`Storage::write()`] and consumes the same write bandwidth, but does not reserve disk space,
+    /// since the data being rewritten is already accounted in the used space. Accordingly, a cancelled or failed
+    /// overwrite does not release any space. Fails if `size` exceeds the used space of the disk.
+    pub fn overwrite(&mut self, size: u64, requester: Id) -> u64 {
+        let request_id = self.make_unique_request_id();
+        log_debug!(
+            self.ctx,
+            "Received overwrite request, size: {}, requester: {}",
+            size,
+            requester
+        );
+        if size > self.used {
+            let error = format!("requested overwrite size is {} but only {} is written", size, self.used);
+            self.fail_write(request_id, requester, error);
+        } else if let Some(error) = self.injected_fault() {
+            self.fail_write(request_id, requester, error);
+        } else {
+            self.overwrites.insert(request_id);
+            self.start_write(DiskActivity {
+                request_id,
+                requester,
+                size,
+                priority: 0,
+                submit_time: self.ctx.time(),
+                offset: None,
+                tag: None,
+            });
+        }
+        request_id
+    }
+
+    // The callback is registered under the allocated request id before the request can fail or complete.
+    fn submit_write(
+        &mut self,
+        size: u64,
+        requester: Id,
+        priority: u8,
+        offset: Option<u64>,
+        tag: Option<u64>,
+        callback: Option<DiskCallback>,
+    ) -> u64 {
         let request_id = self.make_unique_request_id();
+        if let Some(callback) = callback {
+            self.callbacks.insert(request_id, callback);
+        }
         log_debug!(
             self.ctx,
             "Received write request, size: {}, requester: {}",
@@ -268,28 +1588,453 @@ impl Storage for Disk {
         let available = self.capacity - self.used;
         if available < size {
             let error = format!("requested write size is {} but only {} is available", size, available);
-            log_error!(self.ctx, "Failed writing: {}", error,);
-            self.ctx.emit_now(DataWriteFailed { request_id, error }, requester);
+            self.fail_write(request_id, requester, error);
+        } else if let Some(error) = self.injected_fault() {
+            self.fail_write(request_id, requester, error);
         } else {
             self.used += size;
-            self.write_throughput_model.insert(
-                DiskActivity {
-                    request_id,
-                    requester,
-                    size,
-                },
-                size as f64,
-                &mut self.ctx,
-            );
+            self.check_watermark();
+            self.start_write(DiskActivity {
+                request_id,
+                requester,
+                size,
+                priority,
+                submit_time: self.ctx.time(),
+                offset,
+                tag,
+            });
+        }
+        request_id
+    }
+
+    /// Returns the number of read operations currently served by the disk.
+    pub fn active_read_count(&self) -> usize {
+        self.read_throughput_model.len()
+    }
+
+    /// Returns the number of write operations currently served by the disk.
+    pub fn active_write_count(&self) -> usize {
+        self.write_throughput_model.len()
+    }
+
+    /// Returns the number of operations waiting for a free slot, see [`DiskBuilder::max_queue_depth()`].
+    pub fn queued_operation_count(&self) -> usize {
+        self.queued.len()
+    }
+
+    /// Returns the total size of read operations currently served by the disk.
+    pub fn pending_read_size(&self) -> u64 {
+        self.read_throughput_model.items().map(|a| a.size).sum()
+    }
+
+    /// Returns the total size of write operations currently served by the disk.
+    pub fn pending_write_size(&self) -> u64 {
+        self.write_throughput_model.items().map(|a| a.size).sum()
+    }
+
+    /// Cancels outstanding read or write operation with given request id.
+    ///
+    /// The operation stops consuming disk bandwidth, its requester will not receive a completion event
+    /// and its callback (if any) is discarded. The space reserved by a cancelled write is released.
+    /// Fails if there is no such outstanding operation, e.g. it is already completed.
+    pub fn cancel(&mut self, request_id: u64) -> Result<(), String> {
+        let time = self.ctx.time();
+        let activity = if let Some((activity, _)) =
+            self.read_throughput_model.remove(|a| a.request_id == request_id, time)
+        {
+            self.ctx.cancel_event(self.next_read_event);
+            self.schedule_next_read_event();
+            activity
+        } else if let Some((activity, _)) = self.write_throughput_model.remove(|a| a.request_id == request_id, time) {
+            if !self.read_modify_writes.contains(&request_id) && !self.overwrites.remove(&request_id) {
+                self.used -= activity.size;
+            }
             self.ctx.cancel_event(self.next_write_event);
             self.schedule_next_write_event();
+            activity
+        } else if let Some(activity) = self.write_backs.remove(&request_id) {
+            activity
+        } else if let Some(pos) = self.queued.iter().position(|q| q.activity.request_id == request_id) {
+            let queued = self.queued.remove(pos).unwrap();
+            if queued.operation == DiskOperation::Write
+                && !self.read_modify_writes.contains(&request_id)
+                && !self.overwrites.remove(&request_id)
+            {
+                self.used -= queued.activity.size;
+            }
+            queued.activity
+        } else {
+            return Err(format!("no outstanding operation with request id {}", request_id));
+        };
+        self.update_shared_bandwidth();
+        self.admit_queued();
+        if let Some(batch_id) = self.batch_ids.remove(&request_id) {
+            self.on_batch_activity_done(batch_id, Some(&activity), 0.);
+        }
+        self.callbacks.remove(&request_id);
+        self.imported_progress.remove(&request_id);
+        self.imported_volumes.remove(&request_id);
+        self.read_modify_writes.remove(&request_id);
+        self.clear_deadline(request_id);
+        self.on_write_finished(request_id, 0.);
+        self.check_watermark();
+        log_debug!(self.ctx, "Cancelled operation with request id {}", request_id);
+        Ok(())
+    }
+
+    /// Removes all outstanding operations from the disk and returns them along with their remaining work.
+    ///
+    /// The exported operations will not be completed on this disk, and the space reserved by the exported writes
+    /// is released. Callbacks registered for the exported operations are discarded.
+    /// The returned list can be passed to [`Disk::import_outstanding()`] of another disk.
+    pub fn export_outstanding(&mut self) -> Vec<OutstandingDiskActivity> {
+        let time = self.ctx.time();
+        self.ctx.cancel_event(self.next_read_event);
+        self.ctx.cancel_event(self.next_write_event);
+        // the volumes in the throughput models are scaled by the activity factors,
+        // so the remaining amount of data is obtained from the progress of operations
+        let mut activities = Vec::new();
+        for operation in [DiskOperation::Read, DiskOperation::Write] {
+            if operation == DiskOperation::Write {
+                for activity in self.drain_write_backs() {
+                    let size = activity.size as f64;
+                    activities.push((operation, activity, size));
+                }
+            }
+            let model = match operation {
+                DiskOperation::Read => &mut self.read_throughput_model,
+                DiskOperation::Write => &mut self.write_throughput_model,
+            };
+            let done: HashMap<u64, f64> = model.progress(time).map(|(a, done)| (a.request_id, done)).collect();
+            for (activity, _) in model.drain(time) {
+                let volume = self.inserted_volume(&activity);
+                let remaining_size = volume * (1. - done[&activity.request_id]);
+                activities.push((operation, activity, remaining_size));
+            }
+        }
+        for queued in std::mem::take(&mut self.queued) {
+            activities.push((queued.operation, queued.activity, queued.remaining_size));
+        }
+        let mut result = Vec::new();
+        for (operation, activity, remaining_size) in activities {
+            let request_id = activity.request_id;
+            self.callbacks.remove(&request_id);
+            self.clear_deadline(request_id);
+            let read_modify_write = self.read_modify_writes.remove(&request_id);
+            let overwrite = self.overwrites.remove(&request_id);
+            if operation == DiskOperation::Write && !read_modify_write && !overwrite {
+                self.used -= activity.size;
+            }
+            let batch = self.batch_ids.get(&request_id).map(|batch_id| {
+                let batch = &self.batches[batch_id];
+                OutstandingDiskBatch {
+                    request_ids: batch.request_ids.clone(),
+                    total_size: batch.total_size,
+                }
+            });
+            result.push(OutstandingDiskActivity {
+                operation,
+                request_id,
+                requester: activity.requester,
+                size: activity.size,
+                remaining_size,
+                priority: activity.priority,
+                submit_time: activity.submit_time,
+                offset: activity.offset,
+                read_modify_write,
+                overwrite,
+                tag: activity.tag,
+                batch,
+            });
+        }
+        self.imported_progress.clear();
+        self.imported_volumes.clear();
+        self.batches.clear();
+        self.batch_ids.clear();
+        self.complete_flushes();
+        self.update_shared_bandwidth();
+        self.check_watermark();
+        log_debug!(self.ctx, "Exported {} outstanding operations", result.len());
+        result
+    }
+
+    // Returns the amount of data inserted into the throughput model for the operation, before scaling.
+    fn inserted_volume(&self, activity: &DiskActivity) -> f64 {
+        self.imported_volumes
+            .get(&activity.request_id)
+            .copied()
+            .unwrap_or(activity.size as f64)
+    }
+
+    // Returns true if the request id is used by an operation which is not finished yet,
+    // including the operations waiting for latency, callback or deadline.
+    fn is_outstanding(&self, request_id: u64) -> bool {
+        self.read_throughput_model
+            .items()
+            .chain(self.write_throughput_model.items())
+            .any(|a| a.request_id == request_id)
+            || self.queued.iter().any(|q| q.activity.request_id == request_id)
+            || self.write_backs.contains_key(&request_id)
+            || self.read_modify_writes.contains(&request_id)
+            || self.callbacks.contains_key(&request_id)
+            || self.deadlines.contains_key(&request_id)
+    }
+
+    /// Inserts operations exported from another disk with [`Disk::export_outstanding()`].
+    ///
+    /// The operations keep their original request ids, and their requesters will receive completion events
+    /// from this disk. The request ids issued by this disk afterwards are greater than the imported ones.
+    /// Batches are completed when all their imported operations are completed.
+    /// Fails without importing anything if there is not enough free space for the imported writes
+    /// or some imported request id is used by an outstanding operation or batch of this disk.
+    pub fn import_outstanding(&mut self, activities: Vec<OutstandingDiskActivity>) -> Result<(), String> {
+        let mut request_ids = HashSet::new();
+        for a in activities.iter() {
+            if !request_ids.insert(a.request_id) || self.is_outstanding(a.request_id) {
+                return Err(format!(
+                    "operation with request id {} is already outstanding",
+                    a.request_id
+                ));
+            }
+            if let Some(batch) = &a.batch {
+                if self.batches.contains_key(&batch.request_ids[0]) {
+                    return Err(format!(
+                        "batch with request id {} is already outstanding",
+                        batch.request_ids[0]
+                    ));
+                }
+            }
+        }
+        let write_size: u64 = activities
+            .iter()
+            .filter(|a| a.operation == DiskOperation::Write && !a.read_modify_write && !a.overwrite)
+            .map(|a| a.size)
+            .sum();
+        if write_size > self.free_space() {
+            return Err(format!(
+                "imported writes size is {} but only {} is available",
+                write_size,
+                self.free_space()
+            ));
+        }
+        log_debug!(self.ctx, "Imported {} outstanding operations", activities.len());
+        let max_request_id = activities
+            .iter()
+            .flat_map(|a| a.batch.iter().flat_map(|b| b.request_ids.iter()).chain([&a.request_id]))
+            .max();
+        if let Some(max_request_id) = max_request_id {
+            self.next_request_id = self.next_request_id.max(max_request_id + 1);
+        }
+        self.used += write_size;
+        self.sync_usage();
+        self.check_watermark();
+        for a in activities {
+            let activity = DiskActivity {
+                request_id: a.request_id,
+                requester: a.requester,
+                size: a.size,
+                priority: a.priority,
+                submit_time: a.submit_time,
+                offset: a.offset,
+                tag: a.tag,
+            };
+            if a.read_modify_write {
+                self.read_modify_writes.insert(a.request_id);
+            }
+            if a.overwrite {
+                self.overwrites.insert(a.request_id);
+            }
+            if let Some(batch) = a.batch {
+                let batch_id = batch.request_ids[0];
+                self.batches
+                    .entry(batch_id)
+                    .or_insert_with(|| DiskBatch {
+                        operation: a.operation,
+                        requester: a.requester,
+                        request_ids: batch.request_ids,
+                        total_size: batch.total_size,
+                        remaining: 0,
+                    })
+                    .remaining += 1;
+                self.batch_ids.insert(a.request_id, batch_id);
+            }
+            self.imported_volumes.insert(a.request_id, a.remaining_size);
+            if self.progress_interval.is_some() {
+                let before = (a.size as f64 - a.remaining_size).max(0.).round() as u64;
+                self.imported_progress.insert(a.request_id, before);
+            }
+            if !self.has_free_slot() {
+                self.queued.push_back(QueuedDiskActivity {
+                    operation: a.operation,
+                    activity,
+                    remaining_size: a.remaining_size,
+                });
+                continue;
+            }
+            match a.operation {
+                DiskOperation::Read => self
+                    .read_throughput_model
+                    .insert(activity, a.remaining_size, &mut self.ctx),
+                DiskOperation::Write => self
+                    .write_throughput_model
+                    .insert(activity, a.remaining_size, &mut self.ctx),
+            }
+        }
+        self.ctx.cancel_event(self.next_read_event);
+        self.schedule_next_read_event();
+        self.ctx.cancel_event(self.next_write_event);
+        self.schedule_next_write_event();
+        self.update_shared_bandwidth();
+        if self.read_throughput_model.len() + self.write_throughput_model.len() > 0 {
+            self.schedule_progress_tick();
+        }
+        Ok(())
+    }
+}
+
+/// Storage model implementation for disk.
+impl Storage for Disk {
+    fn read(&mut self, size: u64, requester: Id) -> u64 {
+        self.read_with_priority(size, requester, 0)
+    }
+
+    fn write(&mut self, size: u64, requester: Id) -> u64 {
+        self.write_with_priority(size, requester, 0)
+    }
+
+    fn read_modify_write(&mut self, size: u64, requester: Id) -> u64 {
+        log_debug!(
+            self.ctx,
+            "Received read-modify-write request, size: {}, requester: {}",
+            size,
+            requester
+        );
+        let request_id = self.make_unique_request_id();
+        if size > self.readable_size() {
+            let error = format!(
+                "requested read-modify-write size is {} but only {} is available",
+                size,
+                self.readable_size()
+            );
+            self.fail_read_modify_write(request_id, requester, error);
+        } else if let Some(error) = self.injected_fault() {
+            self.fail_read_modify_write(request_id, requester, error);
+        } else {
+            self.read_modify_writes.insert(request_id);
+            self.start_read(DiskActivity {
+                request_id,
+                requester,
+                size,
+                priority: 0,
+                submit_time: self.ctx.time(),
+                offset: None,
+                tag: None,
+            });
         }
         request_id
     }
 
+    fn read_batch(&mut self, sizes: &[u64], requester: Id) -> Vec<u64> {
+        log_debug!(
+            self.ctx,
+            "Received batch read request, count: {}, requester: {}",
+            sizes.len(),
+            requester
+        );
+        let request_ids: Vec<u64> = sizes.iter().map(|_| self.make_unique_request_id()).collect();
+        if sizes.is_empty() {
+            return request_ids;
+        }
+        if let Some(size) = sizes.iter().find(|size| **size > self.readable_size()) {
+            let error = format!(
+                "requested read size is {} but only {} is available",
+                size,
+                self.readable_size()
+            );
+            self.fail_read_batch(request_ids.clone(), requester, error);
+        } else if let Some(error) = self.injected_fault() {
+            self.fail_read_batch(request_ids.clone(), requester, error);
+        } else {
+            self.submit_batch(DiskOperation::Read, sizes, &request_ids, requester);
+        }
+        request_ids
+    }
+
+    fn write_batch(&mut self, sizes: &[u64], requester: Id) -> Vec<u64> {
+        log_debug!(
+            self.ctx,
+            "Received batch write request, count: {}, requester: {}",
+            sizes.len(),
+            requester
+        );
+        if sizes.is_empty() {
+            return Vec::new();
+        }
+        let total_size: u64 = sizes.iter().sum();
+        let available = self.capacity - self.used;
+        if available < total_size {
+            let error = format!(
+                "requested batch write size is {} but only {} is available",
+                total_size, available
+            );
+            return self.reject_write_batch(sizes.len(), requester, error);
+        }
+        if let Some(error) = self.injected_fault() {
+            return self.reject_write_batch(sizes.len(), requester, error);
+        }
+        let request_ids: Vec<u64> = sizes.iter().map(|_| self.make_unique_request_id()).collect();
+        self.used += total_size;
+        self.check_watermark();
+        self.submit_batch(DiskOperation::Write, sizes, &request_ids, requester);
+        request_ids
+    }
+
+    /// The flush waits for the writes in the throughput model or in the queue and the read-modify-write requests
+    /// in any of their phases. The cancelled writes are not waited for, while the failure of the disk
+    /// or export of its outstanding operations completes all pending flushes.
+    fn flush(&mut self, requester: Id) -> u64 {
+        log_debug!(self.ctx, "Received flush request, requester: {}", requester);
+        let request_id = self.make_unique_request_id();
+        let pending: HashSet<u64> = self
+            .write_throughput_model
+            .items()
+            .map(|activity| activity.request_id)
+            .chain(
+                self.queued
+                    .iter()
+                    .filter(|q| q.operation == DiskOperation::Write)
+                    .map(|q| q.activity.request_id),
+            )
+            .chain(self.read_modify_writes.iter().copied())
+            .collect();
+        if pending.is_empty() {
+            self.ctx.emit_now(DataFlushCompleted { request_id }, requester);
+        } else {
+            self.flushes.push(DiskFlush {
+                request_id,
+                requester,
+                pending,
+            });
+        }
+        request_id
+    }
+
+    fn estimated_read_time(&self, size: u64) -> f64 {
+        self.read_throughput_model
+            .estimate_completion_time(size as f64, self.ctx.time())
+            + self.read_latency
+    }
+
+    fn estimated_write_time(&self, size: u64) -> f64 {
+        self.write_throughput_model
+            .estimate_completion_time(size as f64, self.ctx.time())
+            + self.write_latency
+    }
+
     fn mark_free(&mut self, size: u64) -> Result<(), String> {
         if size <= self.used {
             self.used -= size;
+            self.check_watermark();
             return Ok(());
         }
         Err(format!("invalid size: {}", size))
@@ -329,6 +2074,21 @@ impl EventHandler for Disk {
             DiskWriteActivityCompleted {} => {
                 self.on_write_completed();
             }
+            DiskDeath {} => {
+                self.on_disk_death();
+            }
+            DiskProgressTick {} => {
+                self.on_progress_tick();
+            }
+            DiskWriteBackDue { request_id } => {
+                self.on_write_back_due(request_id);
+            }
+            DiskCallbackDue { request_id, result } => {
+                self.run_callback(request_id, result);
+            }
+            DiskDeadlineExpired { request_id } => {
+                self.on_deadline_expired(request_id);
+            }
         })
     }
 }
@@ -0,0 +1,403 @@
+//! Throughput sharing models used by disk.
+
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+use serde::{Deserialize, Serialize};
+use sugars::boxed;
+
+use dslab_core::component::Id;
+use dslab_core::SimulationContext;
+use dslab_models::throughput_sharing::{
+    ActivityFactorFn, ConstantFactorFn, FairThroughputSharingModel, ResourceThroughputFn, ThroughputSharingModel,
+};
+
+use crate::disk::DiskActivity;
+
+/// Defines how disk throughput is shared between concurrent operations.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FairnessMode {
+    /// Throughput is shared equally between all active operations.
+    PerActivity,
+    /// Throughput is shared equally between requesters with active operations,
+    /// and the share of each requester is shared equally between its operations.
+    PerRequester,
+}
+
+// Activities of each requester are kept in a separate model whose throughput is the requester's share
+// of the total throughput. The shares are recomputed on each change in the set of active operations.
+pub(crate) struct PerRequesterModel {
+    models: BTreeMap<Id, FairThroughputSharingModel<DiskActivity>>,
+    throughput_function: Rc<ResourceThroughputFn>,
+    factor_function: Box<dyn ActivityFactorFn<DiskActivity>>,
+    // total number of activities and number of requesters
+    counts: Rc<Cell<(usize, usize)>>,
+    // maximum throughput of requesters and current throughput shares of active requesters
+    limits: BTreeMap<Id, f64>,
+    shares: Rc<RefCell<BTreeMap<Id, f64>>>,
+}
+
+impl PerRequesterModel {
+    fn new(
+        throughput_function: ResourceThroughputFn,
+        factor_function: Box<dyn ActivityFactorFn<DiskActivity>>,
+    ) -> Self {
+        Self {
+            models: BTreeMap::new(),
+            throughput_function: Rc::new(throughput_function),
+            factor_function,
+            counts: Rc::new(Cell::new((0, 0))),
+            limits: BTreeMap::new(),
+            shares: Rc::new(RefCell::new(BTreeMap::new())),
+        }
+    }
+
+    fn requester_share(throughput_function: &ResourceThroughputFn, counts: (usize, usize)) -> f64 {
+        if counts.1 == 0 {
+            0.
+        } else {
+            throughput_function(counts.0) / counts.1 as f64
+        }
+    }
+
+    // Computes the shares of requesters with given numbers of activities. The requesters whose fair share
+    // exceeds their limit receive the limit, and the rest of throughput is shared between the other requesters.
+    fn compute_shares(&self, counts: &BTreeMap<Id, usize>) -> BTreeMap<Id, f64> {
+        let total_count = counts.values().sum();
+        self.counts.set((total_count, counts.len()));
+        let mut shares = BTreeMap::new();
+        if counts.is_empty() {
+            return shares;
+        }
+        let mut remaining = (self.throughput_function)(total_count);
+        let mut unlimited: Vec<Id> = counts.keys().copied().collect();
+        while !unlimited.is_empty() {
+            let share = remaining / unlimited.len() as f64;
+            let (limited, rest): (Vec<Id>, Vec<Id>) = unlimited
+                .iter()
+                .partition(|requester| self.limits.get(requester).is_some_and(|limit| *limit < share));
+            if limited.is_empty() {
+                shares.extend(rest.into_iter().map(|requester| (requester, share)));
+                break;
+            }
+            for requester in limited {
+                remaining -= self.limits[&requester];
+                shares.insert(requester, self.limits[&requester]);
+            }
+            unlimited = rest;
+        }
+        shares
+    }
+
+    fn set_shares(&mut self, new_activity_of: Option<Id>) {
+        let mut counts: BTreeMap<Id, usize> = self.models.iter().map(|(id, m)| (*id, m.len())).collect();
+        if let Some(requester) = new_activity_of {
+            *counts.entry(requester).or_default() += 1;
+        }
+        counts.retain(|_, count| *count > 0);
+        *self.shares.borrow_mut() = self.compute_shares(&counts);
+    }
+
+    fn update_shares(&mut self, time: f64) {
+        self.set_shares(None);
+        for model in self.models.values_mut() {
+            model.update_throughput(time);
+        }
+    }
+
+    fn set_limit(&mut self, requester: Id, max_bw: f64, time: f64) {
+        self.limits.insert(requester, max_bw);
+        self.update_shares(time);
+    }
+
+    fn insert(&mut self, item: DiskActivity, volume: f64, ctx: &mut SimulationContext) {
+        let volume = volume / self.factor_function.get_factor(&item, ctx);
+        let time = ctx.time();
+        // bring existing models up to date before changing the shares
+        for model in self.models.values_mut() {
+            model.update_throughput(time);
+        }
+        let requester = item.requester;
+        let shares = self.shares.clone();
+        self.models.entry(requester).or_insert_with(|| {
+            FairThroughputSharingModel::new(
+                boxed!(move |_| shares.borrow().get(&requester).copied().unwrap_or(0.)),
+                boxed!(ConstantFactorFn::new(1.)),
+            )
+        });
+        self.set_shares(Some(requester));
+        self.models.get_mut(&item.requester).unwrap().insert(item, volume, ctx);
+        self.update_shares(time);
+    }
+
+    fn next_requester(&self) -> Option<Id> {
+        self.models
+            .iter()
+            .filter_map(|(requester, model)| model.peek().map(|(time, _)| (time, *requester)))
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .map(|(_, requester)| requester)
+    }
+
+    // Only the simultaneous completions of the same requester are popped together, since the completion times
+    // computed by models of different requesters may differ slightly due to floating-point errors.
+    fn pop_all_next(&mut self) -> Vec<(f64, DiskActivity)> {
+        let Some(requester) = self.next_requester() else {
+            return Vec::new();
+        };
+        let model = self.models.get_mut(&requester).unwrap();
+        let result = model.pop_all_next();
+        if model.is_empty() {
+            self.models.remove(&requester);
+        }
+        self.update_shares(result[0].0);
+        result
+    }
+
+    fn peek(&self) -> Option<(f64, &DiskActivity)> {
+        let requester = self.next_requester()?;
+        self.models[&requester].peek()
+    }
+
+    fn remove<P>(&mut self, predicate: P, time: f64) -> Option<(DiskActivity, f64)>
+    where
+        P: Fn(&DiskActivity) -> bool,
+    {
+        let requester = *self.models.iter().find(|(_, model)| model.items().any(&predicate))?.0;
+        for model in self.models.values_mut() {
+            model.update_throughput(time);
+        }
+        let model = self.models.get_mut(&requester).unwrap();
+        let removed = model.remove(predicate, time);
+        if model.is_empty() {
+            self.models.remove(&requester);
+        }
+        self.update_shares(time);
+        removed
+    }
+
+    fn drain(&mut self, time: f64) -> Vec<(DiskActivity, f64)> {
+        let mut result: Vec<_> = self.models.values_mut().flat_map(|model| model.drain(time)).collect();
+        result.sort_by(|a, b| a.1.total_cmp(&b.1));
+        self.models.clear();
+        self.update_shares(time);
+        result
+    }
+
+    // Approximates the completion time of an operation from a new requester, assuming that
+    // the shares of other requesters do not change.
+    fn estimate_completion_time(&self, volume: f64) -> f64 {
+        let (count, requesters) = self.counts.get();
+        volume / Self::requester_share(&self.throughput_function, (count + 1, requesters + 1))
+    }
+}
+
+fn priority_weight(item: &DiskActivity) -> f64 {
+    item.priority as f64 + 1.
+}
+
+// Applies the priority weight on top of the user-defined factor.
+struct PriorityFactorFn {
+    inner: Box<dyn ActivityFactorFn<DiskActivity>>,
+}
+
+impl ActivityFactorFn<DiskActivity> for PriorityFactorFn {
+    fn get_factor(&mut self, item: &DiskActivity, ctx: &mut SimulationContext) -> f64 {
+        self.inner.get_factor(item, ctx) * priority_weight(item)
+    }
+}
+
+// Weighted sharing is reduced to the fair sharing model: the volume of each activity is divided by its weight,
+// and the throughput per activity is replaced with the throughput per unit of weight. The total weight
+// of activities must be updated before each change in the model, so that the throughput is computed correctly.
+pub(crate) struct WeightedModel {
+    model: FairThroughputSharingModel<DiskActivity>,
+    total_weight: Rc<Cell<f64>>,
+}
+
+impl WeightedModel {
+    fn new(
+        throughput_function: ResourceThroughputFn,
+        factor_function: Box<dyn ActivityFactorFn<DiskActivity>>,
+    ) -> Self {
+        let total_weight = Rc::new(Cell::new(0.));
+        let weight = total_weight.clone();
+        Self {
+            model: FairThroughputSharingModel::new(
+                boxed!(move |count| throughput_function(count) * count as f64 / weight.get()),
+                boxed!(PriorityFactorFn { inner: factor_function }),
+            ),
+            total_weight,
+        }
+    }
+
+    fn insert(&mut self, item: DiskActivity, volume: f64, ctx: &mut SimulationContext) {
+        self.total_weight.set(self.total_weight.get() + priority_weight(&item));
+        self.model.insert(item, volume, ctx);
+    }
+
+    fn pop_all_next(&mut self) -> Vec<(f64, DiskActivity)> {
+        let weight: f64 = self
+            .model
+            .peek_all_next()
+            .into_iter()
+            .map(|(_, item)| priority_weight(item))
+            .sum();
+        self.total_weight.set(self.total_weight.get() - weight);
+        self.model.pop_all_next()
+    }
+
+    fn remove<P>(&mut self, predicate: P, time: f64) -> Option<(DiskActivity, f64)>
+    where
+        P: Fn(&DiskActivity) -> bool,
+    {
+        let weight = priority_weight(self.model.items().find(|item| predicate(item))?);
+        self.total_weight.set(self.total_weight.get() - weight);
+        self.model
+            .remove(predicate, time)
+            .map(|(item, remaining)| (item, remaining * weight))
+    }
+
+    fn drain(&mut self, time: f64) -> Vec<(DiskActivity, f64)> {
+        self.total_weight.set(0.);
+        self.model
+            .drain(time)
+            .into_iter()
+            .map(|(item, remaining)| {
+                let weight = priority_weight(&item);
+                (item, remaining * weight)
+            })
+            .collect()
+    }
+
+    // Assumes that the new activity has zero priority.
+    fn estimate_completion_time(&self, volume: f64, time: f64) -> f64 {
+        let total_weight = self.total_weight.get();
+        self.total_weight.set(total_weight + 1.);
+        let result = self.model.estimate_completion_time(volume, time);
+        self.total_weight.set(total_weight);
+        result
+    }
+}
+
+/// Throughput sharing model of disk operations of one type.
+pub(crate) enum DiskThroughputModel {
+    PerActivity(FairThroughputSharingModel<DiskActivity>),
+    PerRequester(PerRequesterModel),
+    Weighted(WeightedModel),
+}
+
+impl DiskThroughputModel {
+    pub(crate) fn new(
+        throughput_function: ResourceThroughputFn,
+        factor_function: Box<dyn ActivityFactorFn<DiskActivity>>,
+        fairness: FairnessMode,
+        priorities: bool,
+    ) -> Self {
+        match fairness {
+            FairnessMode::PerActivity if priorities => {
+                Self::Weighted(WeightedModel::new(throughput_function, factor_function))
+            }
+            FairnessMode::PerActivity => {
+                Self::PerActivity(FairThroughputSharingModel::new(throughput_function, factor_function))
+            }
+            FairnessMode::PerRequester => {
+                Self::PerRequester(PerRequesterModel::new(throughput_function, factor_function))
+            }
+        }
+    }
+
+    pub(crate) fn insert(&mut self, item: DiskActivity, volume: f64, ctx: &mut SimulationContext) {
+        match self {
+            Self::PerActivity(model) => model.insert(item, volume, ctx),
+            Self::PerRequester(model) => model.insert(item, volume, ctx),
+            Self::Weighted(model) => model.insert(item, volume, ctx),
+        }
+    }
+
+    /// Removes all operations completing at the next completion time.
+    pub(crate) fn pop_all_next(&mut self) -> Vec<(f64, DiskActivity)> {
+        match self {
+            Self::PerActivity(model) => model.pop_all_next(),
+            Self::PerRequester(model) => model.pop_all_next(),
+            Self::Weighted(model) => model.pop_all_next(),
+        }
+    }
+
+    pub(crate) fn peek(&self) -> Option<(f64, &DiskActivity)> {
+        match self {
+            Self::PerActivity(model) => model.peek(),
+            Self::PerRequester(model) => model.peek(),
+            Self::Weighted(model) => model.model.peek(),
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        match self {
+            Self::PerActivity(model) => model.len(),
+            Self::PerRequester(model) => model.models.values().map(|m| m.len()).sum(),
+            Self::Weighted(model) => model.model.len(),
+        }
+    }
+
+    pub(crate) fn items(&self) -> Box<dyn Iterator<Item = &DiskActivity> + '_> {
+        match self {
+            Self::PerActivity(model) => Box::new(model.items()),
+            Self::PerRequester(model) => Box::new(model.models.values().flat_map(|m| m.items())),
+            Self::Weighted(model) => Box::new(model.model.items()),
+        }
+    }
+
+    pub(crate) fn progress(&self, time: f64) -> Box<dyn Iterator<Item = (&DiskActivity, f64)> + '_> {
+        match self {
+            Self::PerActivity(model) => Box::new(model.progress(time)),
+            Self::PerRequester(model) => Box::new(model.models.values().flat_map(move |m| m.progress(time))),
+            Self::Weighted(model) => Box::new(model.model.progress(time)),
+        }
+    }
+
+    pub(crate) fn remove<P>(&mut self, predicate: P, time: f64) -> Option<(DiskActivity, f64)>
+    where
+        P: Fn(&DiskActivity) -> bool,
+    {
+        match self {
+            Self::PerActivity(model) => model.remove(predicate, time),
+            Self::PerRequester(model) => model.remove(predicate, time),
+            Self::Weighted(model) => model.remove(predicate, time),
+        }
+    }
+
+    pub(crate) fn drain(&mut self, time: f64) -> Vec<(DiskActivity, f64)> {
+        match self {
+            Self::PerActivity(model) => model.drain(time),
+            Self::PerRequester(model) => model.drain(time),
+            Self::Weighted(model) => model.drain(time),
+        }
+    }
+
+    pub(crate) fn update_throughput(&mut self, time: f64) {
+        match self {
+            Self::PerActivity(model) => model.update_throughput(time),
+            Self::PerRequester(model) => model.update_shares(time),
+            Self::Weighted(model) => model.model.update_throughput(time),
+        }
+    }
+
+    pub(crate) fn set_requester_limit(&mut self, requester: Id, max_bw: f64, time: f64) -> Result<(), String> {
+        match self {
+            Self::PerRequester(model) => {
+                model.set_limit(requester, max_bw, time);
+                Ok(())
+            }
+            _ => Err("requester limits are supported only with per-requester fairness".to_string()),
+        }
+    }
+
+    pub(crate) fn estimate_completion_time(&self, volume: f64, time: f64) -> f64 {
+        match self {
+            Self::PerActivity(model) => model.estimate_completion_time(volume, time),
+            Self::PerRequester(model) => model.estimate_completion_time(volume),
+            Self::Weighted(model) => model.estimate_completion_time(volume, time),
+        }
+    }
+}
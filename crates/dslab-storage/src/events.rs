@@ -2,6 +2,22 @@
 
 use serde::Serialize;
 
+use crate::storage::{OpKind, RequestId};
+
+// Implements `typed_request_id()` for events carrying the id of a single request of given kind.
+macro_rules! impl_typed_request_id {
+    ($($event:ident => $op:expr),+ $(,)?) => {
+        $(
+            impl $event {
+                /// Returns the request id combined with the kind of request.
+                pub fn typed_request_id(&self) -> RequestId {
+                    RequestId::new($op, self.request_id)
+                }
+            }
+        )+
+    };
+}
+
 // Storage events
 
 #[derive(Clone, Serialize)]
@@ -11,6 +27,12 @@ pub struct DataReadCompleted {
     pub request_id: u64,
     /// Size of data read from storage.
     pub size: u64,
+    /// Time when the request was submitted.
+    pub start_time: f64,
+    /// Time when the request was completed, i.e. this event is delivered.
+    pub completion_time: f64,
+    /// Tag passed with the request to [`crate::disk::Disk::read_tagged()`], if any.
+    pub tag: Option<u64>,
 }
 
 #[derive(Clone, Serialize)]
@@ -22,6 +44,17 @@ pub struct DataReadFailed {
     pub error: String,
 }
 
+#[derive(Clone, Serialize)]
+/// Reports progress of storage read request. Source: storage, destination: requester.
+pub struct DataReadProgress {
+    /// Request id returned by [`crate::storage::Storage::read()`] method.
+    pub request_id: u64,
+    /// Size of data read so far.
+    pub bytes_done: u64,
+    /// Total size of data to read.
+    pub total: u64,
+}
+
 #[derive(Clone, Serialize)]
 /// Corresponds to completion of storage write request. Source: storage, destination: requester.
 pub struct DataWriteCompleted {
@@ -29,6 +62,12 @@ pub struct DataWriteCompleted {
     pub request_id: u64,
     /// Size of data written to storage.
     pub size: u64,
+    /// Time when the request was submitted.
+    pub start_time: f64,
+    /// Time when the request was completed, i.e. this event is delivered.
+    pub completion_time: f64,
+    /// Tag passed with the request to [`crate::disk::Disk::write_tagged()`], if any.
+    pub tag: Option<u64>,
 }
 
 #[derive(Clone, Serialize)]
@@ -40,6 +79,116 @@ pub struct DataWriteFailed {
     pub error: String,
 }
 
+#[derive(Clone, Serialize)]
+/// Reports progress of storage write request. Source: storage, destination: requester.
+pub struct DataWriteProgress {
+    /// Request id returned by [`crate::storage::Storage::write()`] method.
+    pub request_id: u64,
+    /// Size of data written so far.
+    pub bytes_done: u64,
+    /// Total size of data to write.
+    pub total: u64,
+}
+
+#[derive(Clone, Serialize)]
+/// Corresponds to completion of both phases of storage read-modify-write request.
+/// Source: storage, destination: requester.
+pub struct DataReadModifyWriteCompleted {
+    /// Request id returned by [`crate::storage::Storage::read_modify_write()`] method.
+    pub request_id: u64,
+    /// Size of data read and written back to storage.
+    pub size: u64,
+    /// Time when the request was submitted.
+    pub start_time: f64,
+    /// Time when the request was completed, i.e. this event is delivered.
+    pub completion_time: f64,
+}
+
+#[derive(Clone, Serialize)]
+/// Corresponds to failure of storage read-modify-write request. Source: storage, destination: requester.
+pub struct DataReadModifyWriteFailed {
+    /// Request id returned by [`crate::storage::Storage::read_modify_write()`] method.
+    pub request_id: u64,
+    /// Reason of failure.
+    pub error: String,
+}
+
+#[derive(Clone, Serialize)]
+/// Corresponds to completion of storage flush request, i.e. completion of all writes which were in progress
+/// at the time of the request. Source: storage, destination: requester.
+pub struct DataFlushCompleted {
+    /// Request id returned by [`crate::storage::Storage::flush()`] method.
+    pub request_id: u64,
+}
+
+impl_typed_request_id!(
+    DataReadCompleted => OpKind::Read,
+    DataReadFailed => OpKind::Read,
+    DataReadProgress => OpKind::Read,
+    DataWriteCompleted => OpKind::Write,
+    DataWriteFailed => OpKind::Write,
+    DataWriteProgress => OpKind::Write,
+    DataReadModifyWriteCompleted => OpKind::ReadModifyWrite,
+    DataReadModifyWriteFailed => OpKind::ReadModifyWrite,
+    DataFlushCompleted => OpKind::Flush,
+);
+
+#[derive(Clone, Serialize)]
+/// Signals that the used space of the disk has risen above the high watermark.
+/// Source: disk, destination: watermark listener (see [`crate::disk::Disk::set_watermark()`]).
+pub struct StorageLowSpace {
+    /// Used space of the disk.
+    pub used_space: u64,
+    /// Free space of the disk.
+    pub free_space: u64,
+}
+
+#[derive(Clone, Serialize)]
+/// Signals that the used space of the disk has dropped below the low watermark after [`StorageLowSpace`].
+/// Source: disk, destination: watermark listener (see [`crate::disk::Disk::set_watermark()`]).
+pub struct StorageSpaceRecovered {
+    /// Used space of the disk.
+    pub used_space: u64,
+    /// Free space of the disk.
+    pub free_space: u64,
+}
+
+#[derive(Clone, Serialize)]
+/// Corresponds to completion of all read requests of a batch. Source: storage, destination: requester.
+pub struct DataBatchReadCompleted {
+    /// Request ids returned by [`crate::storage::Storage::read_batch()`] method.
+    pub request_ids: Vec<u64>,
+    /// Total size of data read from storage.
+    pub total_size: u64,
+}
+
+#[derive(Clone, Serialize)]
+/// Corresponds to failure of batch read request. Source: storage, destination: requester.
+pub struct DataBatchReadFailed {
+    /// Request ids returned by [`crate::storage::Storage::read_batch()`] method.
+    pub request_ids: Vec<u64>,
+    /// Reason of failure.
+    pub error: String,
+}
+
+#[derive(Clone, Serialize)]
+/// Corresponds to completion of all write requests of a batch. Source: storage, destination: requester.
+pub struct DataBatchWriteCompleted {
+    /// Request ids returned by [`crate::storage::Storage::write_batch()`] method.
+    pub request_ids: Vec<u64>,
+    /// Total size of data written to storage.
+    pub total_size: u64,
+}
+
+#[derive(Clone, Serialize)]
+/// Corresponds to failure of batch write request. Source: storage, destination: requester.
+pub struct DataBatchWriteFailed {
+    /// Request ids returned by [`crate::storage::Storage::write_batch()`] method.
+    pub request_ids: Vec<u64>,
+    /// Reason of failure.
+    pub error: String,
+}
+
 // File events
 
 #[derive(Clone, Serialize)]
@@ -304,6 +304,7 @@ impl EventHandler for FileSystem {
             DataReadCompleted {
                 request_id: disk_request_id,
                 size,
+                ..
             } => {
                 let key = (event.src, disk_request_id);
                 if let Some((request_id, requester, file_path)) = self.requests.get(&key) {
@@ -364,6 +365,7 @@ impl EventHandler for FileSystem {
             DataWriteCompleted {
                 request_id: disk_request_id,
                 size,
+                ..
             } => {
                 let key = (event.src, disk_request_id);
                 if let Some((request_id, requester, file_path)) = self.requests.get(&key) {
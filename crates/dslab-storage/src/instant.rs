@@ -0,0 +1,253 @@
+//! Storage model which completes all requests instantly.
+//!
+//! It is intended to be used as a test double for the components working with the [`Storage`] trait,
+//! when the delays of storage operations do not matter. All requests are completed without delay,
+//! but the used space is tracked and the capacity limits are enforced in the same way as in the
+//! [disk model](crate::disk), so that the failure paths of the requesters can be tested too.
+
+use dslab_core::component::Id;
+use dslab_core::context::SimulationContext;
+use dslab_core::log_debug;
+
+use crate::events::{
+    DataBatchReadCompleted, DataBatchReadFailed, DataBatchWriteCompleted, DataBatchWriteFailed, DataFlushCompleted,
+    DataReadCompleted, DataReadFailed, DataReadModifyWriteCompleted, DataReadModifyWriteFailed, DataWriteCompleted,
+    DataWriteFailed,
+};
+use crate::storage::{Storage, StorageInfo};
+
+/// Storage with given capacity, which completes all requests instantly.
+///
+/// The completion and failure events are emitted to the requester with zero delay, so the storage does not
+/// need to be registered as event handler.
+pub struct InstantStorage {
+    capacity: u64,
+    used: u64,
+    next_request_id: u64,
+    ctx: SimulationContext,
+}
+
+impl InstantStorage {
+    /// Creates new instant storage with given capacity.
+    pub fn new(capacity: u64, ctx: SimulationContext) -> Self {
+        Self {
+            capacity,
+            used: 0,
+            next_request_id: 0,
+            ctx,
+        }
+    }
+
+    fn make_unique_request_id(&mut self) -> u64 {
+        let request_id = self.next_request_id;
+        self.next_request_id += 1;
+        request_id
+    }
+
+    fn emit_read_completed(&mut self, request_id: u64, size: u64, requester: Id) {
+        let time = self.ctx.time();
+        self.ctx.emit_now(
+            DataReadCompleted {
+                request_id,
+                size,
+                start_time: time,
+                completion_time: time,
+                tag: None,
+            },
+            requester,
+        );
+    }
+
+    fn emit_write_completed(&mut self, request_id: u64, size: u64, requester: Id) {
+        let time = self.ctx.time();
+        self.ctx.emit_now(
+            DataWriteCompleted {
+                request_id,
+                size,
+                start_time: time,
+                completion_time: time,
+                tag: None,
+            },
+            requester,
+        );
+    }
+}
+
+impl Storage for InstantStorage {
+    fn read(&mut self, size: u64, requester: Id) -> u64 {
+        log_debug!(
+            self.ctx,
+            "Received read request, size: {}, requester: {}",
+            size,
+            requester
+        );
+        let request_id = self.make_unique_request_id();
+        if size > self.capacity {
+            let error = format!(
+                "requested read size is {} but only {} is available",
+                size, self.capacity
+            );
+            self.ctx.emit_now(DataReadFailed { request_id, error }, requester);
+        } else {
+            self.emit_read_completed(request_id, size, requester);
+        }
+        request_id
+    }
+
+    fn write(&mut self, size: u64, requester: Id) -> u64 {
+        log_debug!(
+            self.ctx,
+            "Received write request, size: {}, requester: {}",
+            size,
+            requester
+        );
+        let request_id = self.make_unique_request_id();
+        let available = self.free_space();
+        if available < size {
+            let error = format!("requested write size is {} but only {} is available", size, available);
+            self.ctx.emit_now(DataWriteFailed { request_id, error }, requester);
+        } else {
+            self.used += size;
+            self.emit_write_completed(request_id, size, requester);
+        }
+        request_id
+    }
+
+    fn read_modify_write(&mut self, size: u64, requester: Id) -> u64 {
+        log_debug!(
+            self.ctx,
+            "Received read-modify-write request, size: {}, requester: {}",
+            size,
+            requester
+        );
+        let request_id = self.make_unique_request_id();
+        if size > self.capacity {
+            let error = format!(
+                "requested read-modify-write size is {} but only {} is available",
+                size, self.capacity
+            );
+            self.ctx
+                .emit_now(DataReadModifyWriteFailed { request_id, error }, requester);
+        } else {
+            let time = self.ctx.time();
+            self.ctx.emit_now(
+                DataReadModifyWriteCompleted {
+                    request_id,
+                    size,
+                    start_time: time,
+                    completion_time: time,
+                },
+                requester,
+            );
+        }
+        request_id
+    }
+
+    fn read_batch(&mut self, sizes: &[u64], requester: Id) -> Vec<u64> {
+        let request_ids: Vec<u64> = sizes.iter().map(|_| self.make_unique_request_id()).collect();
+        if sizes.is_empty() {
+            return request_ids;
+        }
+        if let Some(size) = sizes.iter().find(|size| **size > self.capacity) {
+            let error = format!(
+                "requested read size is {} but only {} is available",
+                size, self.capacity
+            );
+            self.ctx.emit_now(
+                DataBatchReadFailed {
+                    request_ids: request_ids.clone(),
+                    error,
+                },
+                requester,
+            );
+        } else {
+            self.ctx.emit_now(
+                DataBatchReadCompleted {
+                    request_ids: request_ids.clone(),
+                    total_size: sizes.iter().sum(),
+                },
+                requester,
+            );
+        }
+        request_ids
+    }
+
+    fn write_batch(&mut self, sizes: &[u64], requester: Id) -> Vec<u64> {
+        let request_ids: Vec<u64> = sizes.iter().map(|_| self.make_unique_request_id()).collect();
+        if sizes.is_empty() {
+            return request_ids;
+        }
+        let total_size: u64 = sizes.iter().sum();
+        let available = self.free_space();
+        if available < total_size {
+            let error = format!(
+                "requested batch write size is {} but only {} is available",
+                total_size, available
+            );
+            self.ctx.emit_now(
+                DataBatchWriteFailed {
+                    request_ids: request_ids.clone(),
+                    error,
+                },
+                requester,
+            );
+        } else {
+            self.used += total_size;
+            self.ctx.emit_now(
+                DataBatchWriteCompleted {
+                    request_ids: request_ids.clone(),
+                    total_size,
+                },
+                requester,
+            );
+        }
+        request_ids
+    }
+
+    /// Since the writes are completed instantly, there is nothing to wait for and the flush is completed immediately.
+    fn flush(&mut self, requester: Id) -> u64 {
+        let request_id = self.make_unique_request_id();
+        self.ctx.emit_now(DataFlushCompleted { request_id }, requester);
+        request_id
+    }
+
+    fn estimated_read_time(&self, _size: u64) -> f64 {
+        0.
+    }
+
+    fn estimated_write_time(&self, _size: u64) -> f64 {
+        0.
+    }
+
+    fn mark_free(&mut self, size: u64) -> Result<(), String> {
+        if size <= self.used {
+            self.used -= size;
+            return Ok(());
+        }
+        Err(format!("invalid size: {}", size))
+    }
+
+    fn used_space(&self) -> u64 {
+        self.used
+    }
+
+    fn free_space(&self) -> u64 {
+        self.capacity - self.used
+    }
+
+    fn capacity(&self) -> u64 {
+        self.capacity
+    }
+
+    fn id(&self) -> Id {
+        self.ctx.id()
+    }
+
+    fn info(&self) -> StorageInfo {
+        StorageInfo {
+            capacity: self.capacity(),
+            used_space: self.used_space(),
+            free_space: self.free_space(),
+        }
+    }
+}
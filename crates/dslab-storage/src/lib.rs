@@ -1,10 +1,18 @@
 #![warn(missing_docs)]
 #![doc = include_str!("../README.md")]
 
+pub mod cache;
 pub mod disk;
+mod disk_throughput;
 pub mod events;
 pub mod fs;
+pub mod instant;
+pub mod mirror;
+mod multi_disk;
+pub mod pool;
+pub mod ssd;
 pub mod storage;
+pub mod striped;
 
 #[cfg(test)]
 mod tests;
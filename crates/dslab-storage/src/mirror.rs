@@ -0,0 +1,424 @@
+//! Storage model for a logical volume mirrored over several disks (RAID-1).
+//!
+//! Each write is performed on all live mirrors and is completed when the slowest of them finishes.
+//! Each read is served by a single mirror chosen to balance the load, i.e. the mirror with the smallest
+//! estimated read time, so that concurrent reads are spread over the mirrors. The capacity of the volume
+//! is equal to the capacity of its smallest member.
+//!
+//! A mirror can be marked as failed with [`MirrorStorage::fail_mirror()`]. A mirror is also considered failed
+//! when any of its operations fails, e.g. after the death of its disk. The failed mirror is excluded from the volume:
+//! reads are served only by the surviving mirrors (the outstanding reads are resubmitted to them), and writes
+//! are performed only on the surviving mirrors. The requests fail only if there are no live mirrors left.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use dslab_core::cast;
+use dslab_core::component::Id;
+use dslab_core::context::SimulationContext;
+use dslab_core::event::Event;
+use dslab_core::handler::EventHandler;
+use dslab_core::{log_debug, log_error};
+
+use crate::disk::Disk;
+use crate::events::{
+    DataFlushCompleted, DataReadCompleted, DataReadFailed, DataReadModifyWriteCompleted, DataReadModifyWriteFailed,
+    DataWriteCompleted, DataWriteFailed,
+};
+use crate::multi_disk::{emit_completed, emit_failed, MultiDiskFlushes, MultiDiskOperation};
+use crate::storage::{Storage, StorageInfo};
+
+// Single request or batch of requests, which is completed when all its parts on mirrors are completed.
+struct MirrorRequest {
+    operation: MultiDiskOperation,
+    requester: Id,
+    request_ids: Vec<u64>,
+    batch: bool,
+    total_size: u64,
+    start_time: f64,
+    // outstanding parts as (disk index, disk request id, size)
+    outstanding: Vec<(usize, u64, u64)>,
+}
+
+/// Represents a logical volume mirrored over several disks.
+pub struct MirrorStorage {
+    disks: Vec<Rc<RefCell<Disk>>>,
+    failed: Vec<bool>,
+    used: u64,
+    requests: HashMap<u64, MirrorRequest>,
+    // mapping (disk index, disk request id) -> id of the request (or batch) the part belongs to
+    parts: HashMap<(usize, u64), u64>,
+    flushes: MultiDiskFlushes,
+    next_request_id: u64,
+    ctx: SimulationContext,
+}
+
+impl MirrorStorage {
+    /// Creates mirrored storage over given disks.
+    ///
+    /// The disks should be registered as simulation handlers and should not be used directly afterwards.
+    pub fn new(disks: Vec<Rc<RefCell<Disk>>>, ctx: SimulationContext) -> Self {
+        assert!(!disks.is_empty(), "mirrored storage needs at least one disk");
+        Self {
+            failed: vec![false; disks.len()],
+            disks,
+            used: 0,
+            requests: HashMap::new(),
+            parts: HashMap::new(),
+            flushes: MultiDiskFlushes::default(),
+            next_request_id: 0,
+            ctx,
+        }
+    }
+
+    /// Returns the number of mirrors, including the failed ones.
+    pub fn mirror_count(&self) -> usize {
+        self.disks.len()
+    }
+
+    /// Returns the number of live mirrors.
+    pub fn live_mirror_count(&self) -> usize {
+        self.failed.iter().filter(|failed| !**failed).count()
+    }
+
+    /// Returns whether the mirror with given index is failed.
+    pub fn is_mirror_failed(&self, index: usize) -> bool {
+        self.failed[index]
+    }
+
+    /// Marks the mirror with given index as failed and excludes it from the volume.
+    ///
+    /// The outstanding operations on this mirror are cancelled. The affected reads are resubmitted
+    /// to the surviving mirrors, while the affected writes are completed once the surviving mirrors finish them.
+    pub fn fail_mirror(&mut self, index: usize) {
+        if self.failed[index] {
+            return;
+        }
+        log_error!(self.ctx, "Mirror {} is failed", index);
+        self.failed[index] = true;
+        let mut lost: Vec<((usize, u64), u64)> = self
+            .parts
+            .iter()
+            .filter(|(part, _)| part.0 == index)
+            .map(|(part, id)| (*part, *id))
+            .collect();
+        lost.sort();
+        for (part, id) in lost {
+            self.parts.remove(&part);
+            // the part may have already finished on disk, in which case its event is ignored
+            if self.disks[index].borrow_mut().cancel(part.1).is_err() {
+                log_debug!(self.ctx, "Part {} on mirror {} is already finished", part.1, index);
+            }
+            self.on_part_lost(id, part);
+        }
+    }
+
+    fn make_unique_request_id(&mut self) -> u64 {
+        let request_id = self.next_request_id;
+        self.next_request_id += 1;
+        request_id
+    }
+
+    fn live_mirrors(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.disks.len()).filter(|i| !self.failed[*i])
+    }
+
+    // Chooses the live mirror with the smallest estimated read time.
+    fn choose_read_mirror(&self, size: u64) -> Option<usize> {
+        self.live_mirrors()
+            .map(|i| (self.disks[i].borrow().estimated_read_time(size), i))
+            .min_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)))
+            .map(|(_, i)| i)
+    }
+
+    fn check_request(&self, operation: MultiDiskOperation, total_size: u64, max_size: u64) -> Result<(), String> {
+        if self.live_mirror_count() == 0 {
+            return Err("there are no live mirrors".to_string());
+        }
+        match operation {
+            MultiDiskOperation::Write if total_size > self.free_space() => Err(format!(
+                "requested write size is {} but only {} is available",
+                total_size,
+                self.free_space()
+            )),
+            MultiDiskOperation::Read | MultiDiskOperation::ReadModifyWrite if max_size > self.capacity() => {
+                Err(format!(
+                    "requested read size is {} but only {} is available",
+                    max_size,
+                    self.capacity()
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    // Submits the parts of requests with given sizes to mirrors and returns the ids of these requests.
+    fn submit(&mut self, operation: MultiDiskOperation, sizes: &[u64], requester: Id, batch: bool) -> Vec<u64> {
+        let request_ids: Vec<u64> = sizes.iter().map(|_| self.make_unique_request_id()).collect();
+        let total_size = sizes.iter().sum();
+        let max_size = sizes.iter().copied().max().unwrap_or(0);
+        if let Err(error) = self.check_request(operation, total_size, max_size) {
+            log_error!(self.ctx, "Failed request: {}", error);
+            emit_failed(&mut self.ctx, operation, request_ids.clone(), batch, requester, error);
+            return request_ids;
+        }
+        if operation == MultiDiskOperation::Write {
+            self.used += total_size;
+        }
+        let id = request_ids[0];
+        self.requests.insert(
+            id,
+            MirrorRequest {
+                operation,
+                requester,
+                request_ids: request_ids.clone(),
+                batch,
+                total_size,
+                start_time: self.ctx.time(),
+                outstanding: Vec::new(),
+            },
+        );
+        for size in sizes {
+            if operation == MultiDiskOperation::Read {
+                let mirror = self.choose_read_mirror(*size).unwrap();
+                self.submit_part(id, mirror, *size);
+            } else {
+                for mirror in self.live_mirrors().collect::<Vec<_>>() {
+                    self.submit_part(id, mirror, *size);
+                }
+            }
+        }
+        request_ids
+    }
+
+    fn submit_part(&mut self, id: u64, mirror: usize, size: u64) {
+        let self_id = self.ctx.id();
+        let request = self.requests.get_mut(&id).unwrap();
+        let mut disk = self.disks[mirror].borrow_mut();
+        let disk_request_id = match request.operation {
+            MultiDiskOperation::Read => disk.read(size, self_id),
+            MultiDiskOperation::Write => disk.write(size, self_id),
+            MultiDiskOperation::ReadModifyWrite => disk.read_modify_write(size, self_id),
+        };
+        request.outstanding.push((mirror, disk_request_id, size));
+        self.parts.insert((mirror, disk_request_id), id);
+    }
+
+    fn on_part_completed(&mut self, disk_id: Id, disk_request_id: u64) {
+        let mirror = match self.disk_index(disk_id) {
+            Some(mirror) => mirror,
+            None => return,
+        };
+        if let Some(id) = self.parts.remove(&(mirror, disk_request_id)) {
+            let request = self.requests.get_mut(&id).unwrap();
+            request
+                .outstanding
+                .retain(|part| (part.0, part.1) != (mirror, disk_request_id));
+            if request.outstanding.is_empty() {
+                self.complete(id);
+            }
+        }
+    }
+
+    fn on_part_failed(&mut self, disk_id: Id, disk_request_id: u64, error: String) {
+        let mirror = match self.disk_index(disk_id) {
+            Some(mirror) => mirror,
+            None => return,
+        };
+        if self.parts.contains_key(&(mirror, disk_request_id)) {
+            log_error!(self.ctx, "Operation on mirror {} failed: {}", mirror, error);
+            self.fail_mirror(mirror);
+        }
+    }
+
+    // Handles the part of request which will not be completed because its mirror is failed.
+    fn on_part_lost(&mut self, id: u64, part: (usize, u64)) {
+        let request = self.requests.get_mut(&id).unwrap();
+        let idx = request.outstanding.iter().position(|p| (p.0, p.1) == part).unwrap();
+        let (_, _, size) = request.outstanding.remove(idx);
+        let (operation, done) = (request.operation, request.outstanding.is_empty());
+        if self.live_mirror_count() == 0 {
+            self.fail_request(id, "there are no live mirrors".to_string());
+        } else if operation == MultiDiskOperation::Read {
+            let mirror = self.choose_read_mirror(size).unwrap();
+            self.submit_part(id, mirror, size);
+        } else if done {
+            self.complete(id);
+        }
+    }
+
+    fn complete(&mut self, id: u64) {
+        let request = self.requests.remove(&id).unwrap();
+        emit_completed(
+            &mut self.ctx,
+            request.operation,
+            request.request_ids,
+            request.batch,
+            request.total_size,
+            request.start_time,
+            request.requester,
+        );
+    }
+
+    fn fail_request(&mut self, id: u64, error: String) {
+        let request = self.requests.remove(&id).unwrap();
+        for (mirror, disk_request_id, _) in request.outstanding {
+            self.parts.remove(&(mirror, disk_request_id));
+            self.disks[mirror].borrow_mut().cancel(disk_request_id).ok();
+        }
+        if request.operation == MultiDiskOperation::Write {
+            self.used -= request.total_size;
+        }
+        log_error!(self.ctx, "Failed request: {}", error);
+        emit_failed(
+            &mut self.ctx,
+            request.operation,
+            request.request_ids,
+            request.batch,
+            request.requester,
+            error,
+        );
+    }
+
+    fn disk_index(&self, disk_id: Id) -> Option<usize> {
+        self.disks.iter().position(|disk| disk.borrow().id() == disk_id)
+    }
+}
+
+/// Storage model implementation for mirrored volume.
+impl Storage for MirrorStorage {
+    fn read(&mut self, size: u64, requester: Id) -> u64 {
+        log_debug!(
+            self.ctx,
+            "Received read request, size: {}, requester: {}",
+            size,
+            requester
+        );
+        self.submit(MultiDiskOperation::Read, &[size], requester, false)[0]
+    }
+
+    fn write(&mut self, size: u64, requester: Id) -> u64 {
+        log_debug!(
+            self.ctx,
+            "Received write request, size: {}, requester: {}",
+            size,
+            requester
+        );
+        self.submit(MultiDiskOperation::Write, &[size], requester, false)[0]
+    }
+
+    /// Each live mirror performs read-modify-write of its own copy of the data.
+    fn read_modify_write(&mut self, size: u64, requester: Id) -> u64 {
+        log_debug!(
+            self.ctx,
+            "Received read-modify-write request, size: {}, requester: {}",
+            size,
+            requester
+        );
+        self.submit(MultiDiskOperation::ReadModifyWrite, &[size], requester, false)[0]
+    }
+
+    fn read_batch(&mut self, sizes: &[u64], requester: Id) -> Vec<u64> {
+        if sizes.is_empty() {
+            return Vec::new();
+        }
+        self.submit(MultiDiskOperation::Read, sizes, requester, true)
+    }
+
+    fn write_batch(&mut self, sizes: &[u64], requester: Id) -> Vec<u64> {
+        if sizes.is_empty() {
+            return Vec::new();
+        }
+        self.submit(MultiDiskOperation::Write, sizes, requester, true)
+    }
+
+    fn flush(&mut self, requester: Id) -> u64 {
+        log_debug!(self.ctx, "Received flush request, requester: {}", requester);
+        let request_id = self.make_unique_request_id();
+        let self_id = self.ctx.id();
+        let parts = (self.live_mirrors().collect::<Vec<_>>())
+            .into_iter()
+            .map(|i| (i, self.disks[i].borrow_mut().flush(self_id)))
+            .collect();
+        self.flushes.submit(&mut self.ctx, request_id, requester, parts);
+        request_id
+    }
+
+    fn estimated_read_time(&self, size: u64) -> f64 {
+        self.live_mirrors()
+            .map(|i| self.disks[i].borrow().estimated_read_time(size))
+            .fold(f64::NAN, f64::min)
+    }
+
+    fn estimated_write_time(&self, size: u64) -> f64 {
+        self.live_mirrors()
+            .map(|i| self.disks[i].borrow().estimated_write_time(size))
+            .fold(f64::NAN, f64::max)
+    }
+
+    fn mark_free(&mut self, size: u64) -> Result<(), String> {
+        if size > self.used {
+            return Err(format!("invalid size: {}", size));
+        }
+        for mirror in self.live_mirrors().collect::<Vec<_>>() {
+            self.disks[mirror].borrow_mut().mark_free(size)?;
+        }
+        self.used -= size;
+        Ok(())
+    }
+
+    fn used_space(&self) -> u64 {
+        self.used
+    }
+
+    fn free_space(&self) -> u64 {
+        self.capacity() - self.used
+    }
+
+    fn capacity(&self) -> u64 {
+        self.disks.iter().map(|disk| disk.borrow().capacity()).min().unwrap()
+    }
+
+    fn id(&self) -> Id {
+        self.ctx.id()
+    }
+
+    fn info(&self) -> StorageInfo {
+        StorageInfo {
+            capacity: self.capacity(),
+            used_space: self.used_space(),
+            free_space: self.free_space(),
+        }
+    }
+}
+
+impl EventHandler for MirrorStorage {
+    fn on(&mut self, event: Event) {
+        cast!(match event.data {
+            DataReadCompleted { request_id, .. } => {
+                self.on_part_completed(event.src, request_id);
+            }
+            DataWriteCompleted { request_id, .. } => {
+                self.on_part_completed(event.src, request_id);
+            }
+            DataFlushCompleted { request_id } => {
+                if let Some(disk_idx) = self.disk_index(event.src) {
+                    self.flushes.on_part_completed(&mut self.ctx, disk_idx, request_id);
+                }
+            }
+            DataReadModifyWriteCompleted { request_id, .. } => {
+                self.on_part_completed(event.src, request_id);
+            }
+            DataReadFailed { request_id, error } => {
+                self.on_part_failed(event.src, request_id, error);
+            }
+            DataWriteFailed { request_id, error } => {
+                self.on_part_failed(event.src, request_id, error);
+            }
+            DataReadModifyWriteFailed { request_id, error } => {
+                self.on_part_failed(event.src, request_id, error);
+            }
+        })
+    }
+}
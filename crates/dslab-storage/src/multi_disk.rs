@@ -0,0 +1,147 @@
+//! Common parts of storage models composed of several disks.
+
+use std::collections::HashMap;
+
+use dslab_core::component::Id;
+use dslab_core::context::SimulationContext;
+
+use crate::events::{
+    DataBatchReadCompleted, DataBatchReadFailed, DataBatchWriteCompleted, DataBatchWriteFailed, DataFlushCompleted,
+    DataReadCompleted, DataReadFailed, DataReadModifyWriteCompleted, DataReadModifyWriteFailed, DataWriteCompleted,
+    DataWriteFailed,
+};
+
+/// Type of request submitted to storage composed of several disks.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum MultiDiskOperation {
+    Read,
+    Write,
+    ReadModifyWrite,
+}
+
+/// Emits event corresponding to completion of a single request or batch of requests.
+///
+/// Read-modify-write requests are not submitted in batches.
+pub(crate) fn emit_completed(
+    ctx: &mut SimulationContext,
+    operation: MultiDiskOperation,
+    request_ids: Vec<u64>,
+    batch: bool,
+    total_size: u64,
+    start_time: f64,
+    requester: Id,
+) {
+    let request_id = request_ids[0];
+    let completion_time = ctx.time();
+    match (operation, batch) {
+        (MultiDiskOperation::Read, false) => ctx.emit_now(
+            DataReadCompleted {
+                request_id,
+                size: total_size,
+                start_time,
+                completion_time,
+                tag: None,
+            },
+            requester,
+        ),
+        (MultiDiskOperation::Write, false) => ctx.emit_now(
+            DataWriteCompleted {
+                request_id,
+                size: total_size,
+                start_time,
+                completion_time,
+                tag: None,
+            },
+            requester,
+        ),
+        (MultiDiskOperation::ReadModifyWrite, _) => ctx.emit_now(
+            DataReadModifyWriteCompleted {
+                request_id,
+                size: total_size,
+                start_time,
+                completion_time,
+            },
+            requester,
+        ),
+        (MultiDiskOperation::Read, true) => ctx.emit_now(
+            DataBatchReadCompleted {
+                request_ids,
+                total_size,
+            },
+            requester,
+        ),
+        (MultiDiskOperation::Write, true) => ctx.emit_now(
+            DataBatchWriteCompleted {
+                request_ids,
+                total_size,
+            },
+            requester,
+        ),
+    };
+}
+
+/// Emits event corresponding to failure of a single request or batch of requests.
+pub(crate) fn emit_failed(
+    ctx: &mut SimulationContext,
+    operation: MultiDiskOperation,
+    request_ids: Vec<u64>,
+    batch: bool,
+    requester: Id,
+    error: String,
+) {
+    let request_id = request_ids[0];
+    match (operation, batch) {
+        (MultiDiskOperation::Read, false) => ctx.emit_now(DataReadFailed { request_id, error }, requester),
+        (MultiDiskOperation::Write, false) => ctx.emit_now(DataWriteFailed { request_id, error }, requester),
+        (MultiDiskOperation::ReadModifyWrite, _) => {
+            ctx.emit_now(DataReadModifyWriteFailed { request_id, error }, requester)
+        }
+        (MultiDiskOperation::Read, true) => ctx.emit_now(DataBatchReadFailed { request_ids, error }, requester),
+        (MultiDiskOperation::Write, true) => ctx.emit_now(DataBatchWriteFailed { request_ids, error }, requester),
+    };
+}
+
+/// Tracks flush requests, which are completed when the flushes of all member disks are completed.
+///
+/// Since the parts of outstanding requests are submitted to member disks without delay, flushing the disks
+/// is enough to wait for all writes in progress at the time of the request.
+#[derive(Default)]
+pub(crate) struct MultiDiskFlushes {
+    // mapping request id -> (requester, number of outstanding disk flushes)
+    requests: HashMap<u64, (Id, usize)>,
+    // mapping (disk index, disk request id) -> request id
+    parts: HashMap<(usize, u64), u64>,
+}
+
+impl MultiDiskFlushes {
+    /// Registers flush request with given flushes of member disks as (disk index, disk request id).
+    pub(crate) fn submit(
+        &mut self,
+        ctx: &mut SimulationContext,
+        request_id: u64,
+        requester: Id,
+        parts: Vec<(usize, u64)>,
+    ) {
+        if parts.is_empty() {
+            ctx.emit_now(DataFlushCompleted { request_id }, requester);
+            return;
+        }
+        self.requests.insert(request_id, (requester, parts.len()));
+        for part in parts {
+            self.parts.insert(part, request_id);
+        }
+    }
+
+    /// Handles completion of member disk flush and completes the request if it was the last one.
+    pub(crate) fn on_part_completed(&mut self, ctx: &mut SimulationContext, disk_idx: usize, disk_request_id: u64) {
+        if let Some(request_id) = self.parts.remove(&(disk_idx, disk_request_id)) {
+            let (requester, remaining) = self.requests.get_mut(&request_id).unwrap();
+            *remaining -= 1;
+            if *remaining == 0 {
+                let requester = *requester;
+                self.requests.remove(&request_id);
+                ctx.emit_now(DataFlushCompleted { request_id }, requester);
+            }
+        }
+    }
+}
@@ -0,0 +1,498 @@
+//! Storage model for a pool of independent disks.
+//!
+//! Unlike [striped](crate::striped) and [mirrored](crate::mirror) volumes, each request is routed entirely
+//! to a single member [disk](crate::disk) chosen by the [`PlacementPolicy`], which makes it possible to study
+//! data placement strategies. A batch of requests is routed to a single disk as a whole. The events of member disks
+//! are forwarded to the original requesters with the request ids returned by the pool.
+//!
+//! The pool does not track where the data is stored, so reads are routed by the policy in the same way as writes.
+
+use std::cell::RefCell;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+
+use dslab_core::cast;
+use dslab_core::component::Id;
+use dslab_core::context::SimulationContext;
+use dslab_core::event::Event;
+use dslab_core::handler::EventHandler;
+use dslab_core::log_debug;
+
+use crate::disk::Disk;
+use crate::events::*;
+use crate::multi_disk::MultiDiskFlushes;
+use crate::storage::{Storage, StorageInfo};
+
+/// Current load of a member disk, which is passed to the placement policy.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DiskLoad {
+    /// Number of read operations currently served by the disk.
+    pub active_reads: usize,
+    /// Number of write operations currently served by the disk.
+    pub active_writes: usize,
+    /// Total size of read operations currently served by the disk.
+    pub pending_read_size: u64,
+    /// Total size of write operations currently served by the disk.
+    pub pending_write_size: u64,
+    /// Amount of free space on the disk.
+    pub free_space: u64,
+    /// Disk capacity.
+    pub capacity: u64,
+}
+
+impl DiskLoad {
+    fn of(disk: &Disk) -> Self {
+        Self {
+            active_reads: disk.active_read_count(),
+            active_writes: disk.active_write_count(),
+            pending_read_size: disk.pending_read_size(),
+            pending_write_size: disk.pending_write_size(),
+            free_space: disk.free_space(),
+            capacity: disk.capacity(),
+        }
+    }
+}
+
+/// Chooses the member disk of [`StoragePool`] for each incoming request.
+pub trait PlacementPolicy {
+    /// Returns the index of the disk to which the request (or batch of requests) with given total size
+    /// submitted by `requester` should be routed. The loads of the disks are passed in the order of their indices.
+    fn select_disk(&mut self, size: u64, requester: Id, loads: &[DiskLoad]) -> usize;
+}
+
+/// Routes the requests to the disks in round-robin order.
+#[derive(Default)]
+pub struct RoundRobinPlacement {
+    next: usize,
+}
+
+impl RoundRobinPlacement {
+    /// Creates new round-robin policy starting from the first disk.
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+impl PlacementPolicy for RoundRobinPlacement {
+    fn select_disk(&mut self, _size: u64, _requester: Id, loads: &[DiskLoad]) -> usize {
+        let disk = self.next % loads.len();
+        self.next = disk + 1;
+        disk
+    }
+}
+
+/// Routes each request to the disk with the smallest total size of operations in progress,
+/// ties are broken in favor of the disk with the smallest index.
+#[derive(Default)]
+pub struct LeastLoadedPlacement {}
+
+impl LeastLoadedPlacement {
+    /// Creates new least loaded policy.
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+impl PlacementPolicy for LeastLoadedPlacement {
+    fn select_disk(&mut self, _size: u64, _requester: Id, loads: &[DiskLoad]) -> usize {
+        (0..loads.len())
+            .min_by_key(|&i| loads[i].pending_read_size + loads[i].pending_write_size)
+            .unwrap()
+    }
+}
+
+/// Routes all requests of the same requester to the same disk chosen by the hash of the requester id.
+#[derive(Default)]
+pub struct RequesterHashPlacement {}
+
+impl RequesterHashPlacement {
+    /// Creates new requester hash policy.
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+impl PlacementPolicy for RequesterHashPlacement {
+    fn select_disk(&mut self, _size: u64, requester: Id, loads: &[DiskLoad]) -> usize {
+        let mut h = DefaultHasher::default();
+        requester.hash(&mut h);
+        (h.finish() % loads.len() as u64) as usize
+    }
+}
+
+// Request (or batch of requests) routed to a member disk.
+struct PoolRequest {
+    requester: Id,
+    request_ids: Vec<u64>,
+}
+
+/// Represents a pool of disks, each request to which is served by a single disk chosen by the placement policy.
+pub struct StoragePool {
+    disks: Vec<Rc<RefCell<Disk>>>,
+    policy: Box<dyn PlacementPolicy>,
+    // mapping (disk index, id of the first disk request) -> request (or batch) submitted to the pool
+    requests: HashMap<(usize, u64), PoolRequest>,
+    flushes: MultiDiskFlushes,
+    next_request_id: u64,
+    ctx: SimulationContext,
+}
+
+impl StoragePool {
+    /// Creates storage pool over given disks with given placement policy.
+    ///
+    /// The disks should be registered as simulation handlers and should not be used directly afterwards.
+    pub fn new(disks: Vec<Rc<RefCell<Disk>>>, policy: Box<dyn PlacementPolicy>, ctx: SimulationContext) -> Self {
+        assert!(!disks.is_empty(), "storage pool needs at least one disk");
+        Self {
+            disks,
+            policy,
+            requests: HashMap::new(),
+            flushes: MultiDiskFlushes::default(),
+            next_request_id: 0,
+            ctx,
+        }
+    }
+
+    /// Returns the number of member disks.
+    pub fn disk_count(&self) -> usize {
+        self.disks.len()
+    }
+
+    /// Returns the current loads of member disks.
+    pub fn loads(&self) -> Vec<DiskLoad> {
+        self.disks.iter().map(|disk| DiskLoad::of(&disk.borrow())).collect()
+    }
+
+    /// Marks previously used space of given `size` on the member disk with given index as free.
+    pub fn mark_free_on(&mut self, disk_idx: usize, size: u64) -> Result<(), String> {
+        self.disks
+            .get(disk_idx)
+            .ok_or(format!("invalid disk index: {}", disk_idx))?
+            .borrow_mut()
+            .mark_free(size)
+    }
+
+    fn make_unique_request_id(&mut self) -> u64 {
+        let request_id = self.next_request_id;
+        self.next_request_id += 1;
+        request_id
+    }
+
+    // Routes the request (or batch) to the disk chosen by the policy and returns the ids of requests in the pool.
+    fn submit<F>(&mut self, sizes: &[u64], requester: Id, submit: F) -> Vec<u64>
+    where
+        F: FnOnce(&mut Disk, Id) -> Vec<u64>,
+    {
+        let request_ids: Vec<u64> = sizes.iter().map(|_| self.make_unique_request_id()).collect();
+        let loads = self.loads();
+        let disk_idx = self.policy.select_disk(sizes.iter().sum(), requester, &loads);
+        assert!(
+            disk_idx < self.disks.len(),
+            "placement policy returned invalid disk index"
+        );
+        log_debug!(
+            self.ctx,
+            "Routing requests {:?} of requester {} to disk {}",
+            request_ids,
+            requester,
+            disk_idx
+        );
+        let self_id = self.ctx.id();
+        let disk_request_ids = submit(&mut self.disks[disk_idx].borrow_mut(), self_id);
+        self.requests.insert(
+            (disk_idx, disk_request_ids[0]),
+            PoolRequest {
+                requester,
+                request_ids: request_ids.clone(),
+            },
+        );
+        request_ids
+    }
+
+    fn disk_index(&self, disk_id: Id) -> Option<usize> {
+        self.disks.iter().position(|disk| disk.borrow().id() == disk_id)
+    }
+
+    // Returns the request to which the disk request belongs, the request is removed unless `keep` is set.
+    fn take_request(&mut self, disk_id: Id, disk_request_id: u64, keep: bool) -> Option<PoolRequest> {
+        let key = (self.disk_index(disk_id)?, disk_request_id);
+        if keep {
+            let request = self.requests.get(&key)?;
+            return Some(PoolRequest {
+                requester: request.requester,
+                request_ids: request.request_ids.clone(),
+            });
+        }
+        self.requests.remove(&key)
+    }
+}
+
+/// Storage model implementation for storage pool.
+impl Storage for StoragePool {
+    fn read(&mut self, size: u64, requester: Id) -> u64 {
+        self.submit(&[size], requester, |disk, id| vec![disk.read(size, id)])[0]
+    }
+
+    fn write(&mut self, size: u64, requester: Id) -> u64 {
+        self.submit(&[size], requester, |disk, id| vec![disk.write(size, id)])[0]
+    }
+
+    fn read_modify_write(&mut self, size: u64, requester: Id) -> u64 {
+        self.submit(&[size], requester, |disk, id| vec![disk.read_modify_write(size, id)])[0]
+    }
+
+    fn read_batch(&mut self, sizes: &[u64], requester: Id) -> Vec<u64> {
+        if sizes.is_empty() {
+            return Vec::new();
+        }
+        self.submit(sizes, requester, |disk, id| disk.read_batch(sizes, id))
+    }
+
+    fn write_batch(&mut self, sizes: &[u64], requester: Id) -> Vec<u64> {
+        if sizes.is_empty() {
+            return Vec::new();
+        }
+        self.submit(sizes, requester, |disk, id| disk.write_batch(sizes, id))
+    }
+
+    fn flush(&mut self, requester: Id) -> u64 {
+        log_debug!(self.ctx, "Received flush request, requester: {}", requester);
+        let request_id = self.make_unique_request_id();
+        let self_id = self.ctx.id();
+        let parts = self
+            .disks
+            .iter()
+            .enumerate()
+            .map(|(i, disk)| (i, disk.borrow_mut().flush(self_id)))
+            .collect();
+        self.flushes.submit(&mut self.ctx, request_id, requester, parts);
+        request_id
+    }
+
+    /// Since the pool does not track where the data is stored, the space is released from the member disks
+    /// in the order of their indices. Use [`StoragePool::mark_free_on()`] to release the space on a specific disk.
+    fn mark_free(&mut self, size: u64) -> Result<(), String> {
+        if size > self.used_space() {
+            return Err(format!("invalid size: {}", size));
+        }
+        let mut remaining = size;
+        for disk in &self.disks {
+            let mut disk = disk.borrow_mut();
+            let part = remaining.min(disk.used_space());
+            disk.mark_free(part)?;
+            remaining -= part;
+        }
+        Ok(())
+    }
+
+    fn used_space(&self) -> u64 {
+        self.disks.iter().map(|disk| disk.borrow().used_space()).sum()
+    }
+
+    fn free_space(&self) -> u64 {
+        self.disks.iter().map(|disk| disk.borrow().free_space()).sum()
+    }
+
+    fn capacity(&self) -> u64 {
+        self.disks.iter().map(|disk| disk.borrow().capacity()).sum()
+    }
+
+    fn id(&self) -> Id {
+        self.ctx.id()
+    }
+
+    fn info(&self) -> StorageInfo {
+        StorageInfo {
+            capacity: self.capacity(),
+            used_space: self.used_space(),
+            free_space: self.free_space(),
+        }
+    }
+}
+
+impl EventHandler for StoragePool {
+    fn on(&mut self, event: Event) {
+        cast!(match event.data {
+            DataReadCompleted {
+                request_id,
+                size,
+                start_time,
+                completion_time,
+                tag,
+            } => {
+                if let Some(request) = self.take_request(event.src, request_id, false) {
+                    self.ctx.emit_now(
+                        DataReadCompleted {
+                            request_id: request.request_ids[0],
+                            size,
+                            start_time,
+                            completion_time,
+                            tag,
+                        },
+                        request.requester,
+                    );
+                }
+            }
+            DataWriteCompleted {
+                request_id,
+                size,
+                start_time,
+                completion_time,
+                tag,
+            } => {
+                if let Some(request) = self.take_request(event.src, request_id, false) {
+                    self.ctx.emit_now(
+                        DataWriteCompleted {
+                            request_id: request.request_ids[0],
+                            size,
+                            start_time,
+                            completion_time,
+                            tag,
+                        },
+                        request.requester,
+                    );
+                }
+            }
+            DataReadModifyWriteCompleted {
+                request_id,
+                size,
+                start_time,
+                completion_time,
+            } => {
+                if let Some(request) = self.take_request(event.src, request_id, false) {
+                    self.ctx.emit_now(
+                        DataReadModifyWriteCompleted {
+                            request_id: request.request_ids[0],
+                            size,
+                            start_time,
+                            completion_time,
+                        },
+                        request.requester,
+                    );
+                }
+            }
+            DataReadFailed { request_id, error } => {
+                if let Some(request) = self.take_request(event.src, request_id, false) {
+                    self.ctx.emit_now(
+                        DataReadFailed {
+                            request_id: request.request_ids[0],
+                            error,
+                        },
+                        request.requester,
+                    );
+                }
+            }
+            DataWriteFailed { request_id, error } => {
+                if let Some(request) = self.take_request(event.src, request_id, false) {
+                    self.ctx.emit_now(
+                        DataWriteFailed {
+                            request_id: request.request_ids[0],
+                            error,
+                        },
+                        request.requester,
+                    );
+                }
+            }
+            DataReadModifyWriteFailed { request_id, error } => {
+                if let Some(request) = self.take_request(event.src, request_id, false) {
+                    self.ctx.emit_now(
+                        DataReadModifyWriteFailed {
+                            request_id: request.request_ids[0],
+                            error,
+                        },
+                        request.requester,
+                    );
+                }
+            }
+            DataReadProgress {
+                request_id,
+                bytes_done,
+                total,
+            } => {
+                if let Some(request) = self.take_request(event.src, request_id, true) {
+                    self.ctx.emit_now(
+                        DataReadProgress {
+                            request_id: request.request_ids[0],
+                            bytes_done,
+                            total,
+                        },
+                        request.requester,
+                    );
+                }
+            }
+            DataWriteProgress {
+                request_id,
+                bytes_done,
+                total,
+            } => {
+                if let Some(request) = self.take_request(event.src, request_id, true) {
+                    self.ctx.emit_now(
+                        DataWriteProgress {
+                            request_id: request.request_ids[0],
+                            bytes_done,
+                            total,
+                        },
+                        request.requester,
+                    );
+                }
+            }
+            DataBatchReadCompleted {
+                request_ids,
+                total_size,
+            } => {
+                if let Some(request) = self.take_request(event.src, request_ids[0], false) {
+                    self.ctx.emit_now(
+                        DataBatchReadCompleted {
+                            request_ids: request.request_ids,
+                            total_size,
+                        },
+                        request.requester,
+                    );
+                }
+            }
+            DataBatchWriteCompleted {
+                request_ids,
+                total_size,
+            } => {
+                if let Some(request) = self.take_request(event.src, request_ids[0], false) {
+                    self.ctx.emit_now(
+                        DataBatchWriteCompleted {
+                            request_ids: request.request_ids,
+                            total_size,
+                        },
+                        request.requester,
+                    );
+                }
+            }
+            DataBatchReadFailed { request_ids, error } => {
+                if let Some(request) = self.take_request(event.src, request_ids[0], false) {
+                    self.ctx.emit_now(
+                        DataBatchReadFailed {
+                            request_ids: request.request_ids,
+                            error,
+                        },
+                        request.requester,
+                    );
+                }
+            }
+            DataBatchWriteFailed { request_ids, error } => {
+                if let Some(request) = self.take_request(event.src, request_ids[0], false) {
+                    self.ctx.emit_now(
+                        DataBatchWriteFailed {
+                            request_ids: request.request_ids,
+                            error,
+                        },
+                        request.requester,
+                    );
+                }
+            }
+            DataFlushCompleted { request_id } => {
+                if let Some(disk_idx) = self.disk_index(event.src) {
+                    self.flushes.on_part_completed(&mut self.ctx, disk_idx, request_id);
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,302 @@
+//! Storage model for solid-state drive (SSD).
+//!
+//! It extends the [disk model](crate::disk) with the write amplification and wear of flash memory.
+//! Each written byte results in `write_amplification_factor` bytes physically written to the flash memory,
+//! which correspondingly slows down the write operations. The total amount of physically written data
+//! can be limited by the write budget (endurance) of the drive. Once the budget is exhausted,
+//! all subsequent write requests fail.
+//!
+//! A part of the raw capacity can be reserved for over-provisioning, in which case it is not available for user data.
+
+use dslab_core::component::Id;
+use dslab_core::context::SimulationContext;
+use dslab_core::event::Event;
+use dslab_core::handler::EventHandler;
+use dslab_models::throughput_sharing::{ConstantFactorFn, ResourceThroughputFn};
+use sugars::boxed;
+
+use crate::disk::{Disk, DiskBuilder};
+use crate::storage::{Storage, StorageInfo};
+
+/// SSD builder. This is a type for convenient SSD setup.
+///
+/// After SSD settings are filled, [`SsdBuilder::build()`] should be called with [`SimulationContext`] to build an SSD.
+pub struct SsdBuilder {
+    disk_builder: DiskBuilder,
+    capacity: Option<u64>,
+    over_provisioning: f64,
+    write_amplification_factor: f64,
+    write_budget: Option<u64>,
+}
+
+impl Default for SsdBuilder {
+    /// Creates default SSD builder.
+    ///
+    /// May be incomplete. User should fill required SSD settings using other functions.
+    fn default() -> Self {
+        Self {
+            disk_builder: DiskBuilder::new(),
+            capacity: None,
+            over_provisioning: 0.,
+            write_amplification_factor: 1.,
+            write_budget: None,
+        }
+    }
+}
+
+impl SsdBuilder {
+    /// Same as [`SsdBuilder::default()`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates SSD builder and fills it with given raw capacity, read and write bandwidth values.
+    pub fn simple(capacity: u64, read_bw: f64, write_bw: f64) -> Self {
+        Self::new()
+            .capacity(capacity)
+            .constant_read_bw(read_bw)
+            .constant_write_bw(write_bw)
+    }
+
+    /// Sets raw capacity of the SSD, including the over-provisioned space.
+    pub fn capacity(mut self, capacity: u64) -> Self {
+        self.capacity.replace(capacity);
+        self
+    }
+
+    /// Sets read bandwidth to be constant with given value.
+    pub fn constant_read_bw(mut self, read_bw: f64) -> Self {
+        self.disk_builder = self.disk_builder.constant_read_bw(read_bw);
+        self
+    }
+
+    /// Sets write bandwidth to be constant with given value.
+    ///
+    /// The bandwidth corresponds to physical writes, so the observed write bandwidth is reduced
+    /// by the write amplification factor.
+    pub fn constant_write_bw(mut self, write_bw: f64) -> Self {
+        self.disk_builder = self.disk_builder.constant_write_bw(write_bw);
+        self
+    }
+
+    /// Sets custom throughput function for read operations.
+    pub fn read_throughput_fn(mut self, read_throughput_fn: ResourceThroughputFn) -> Self {
+        self.disk_builder = self.disk_builder.read_throughput_fn(read_throughput_fn);
+        self
+    }
+
+    /// Sets custom throughput function for physical write operations.
+    pub fn write_throughput_fn(mut self, write_throughput_fn: ResourceThroughputFn) -> Self {
+        self.disk_builder = self.disk_builder.write_throughput_fn(write_throughput_fn);
+        self
+    }
+
+    /// Sets fixed latency added to completion time of each read operation.
+    pub fn read_latency(mut self, read_latency: f64) -> Self {
+        self.disk_builder = self.disk_builder.read_latency(read_latency);
+        self
+    }
+
+    /// Sets fixed latency added to completion time of each write operation.
+    pub fn write_latency(mut self, write_latency: f64) -> Self {
+        self.disk_builder = self.disk_builder.write_latency(write_latency);
+        self
+    }
+
+    /// Sets the fraction of raw capacity reserved for over-provisioning.
+    pub fn over_provisioning(mut self, over_provisioning: f64) -> Self {
+        assert!(
+            (0. ..1.).contains(&over_provisioning),
+            "over-provisioning must be in [0, 1)"
+        );
+        self.over_provisioning = over_provisioning;
+        self
+    }
+
+    /// Sets the ratio of physically written data to data written by user.
+    pub fn write_amplification_factor(mut self, write_amplification_factor: f64) -> Self {
+        assert!(
+            write_amplification_factor >= 1.,
+            "write amplification factor must be at least 1"
+        );
+        self.write_amplification_factor = write_amplification_factor;
+        self
+    }
+
+    /// Sets the maximum amount of data which can be physically written to the SSD during its lifetime.
+    pub fn write_budget(mut self, write_budget: u64) -> Self {
+        self.write_budget.replace(write_budget);
+        self
+    }
+
+    /// Builds SSD from given builder and simulation context.
+    ///
+    /// Panics on invalid or incomplete SSD settings.
+    pub fn build(self, ctx: SimulationContext) -> Ssd {
+        let raw_capacity = self.capacity.unwrap();
+        let reserved = (raw_capacity as f64 * self.over_provisioning).round() as u64;
+        let disk = self
+            .disk_builder
+            .capacity(raw_capacity - reserved)
+            .write_factor_fn(boxed!(ConstantFactorFn::new(1. / self.write_amplification_factor)))
+            .build(ctx);
+        Ssd {
+            disk,
+            raw_capacity,
+            write_amplification_factor: self.write_amplification_factor,
+            write_budget: self.write_budget,
+            total_bytes_written: 0,
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// Represents a solid-state drive.
+///
+/// Should be created using [`SsdBuilder`].
+pub struct Ssd {
+    disk: Disk,
+    raw_capacity: u64,
+    write_amplification_factor: f64,
+    write_budget: Option<u64>,
+    total_bytes_written: u64,
+}
+
+impl Ssd {
+    fn physical_size(&self, size: u64) -> u64 {
+        (size as f64 * self.write_amplification_factor).round() as u64
+    }
+
+    /// Returns the total amount of data physically written to the SSD, including the write amplification.
+    pub fn total_bytes_written(&self) -> u64 {
+        self.total_bytes_written
+    }
+
+    /// Returns the amount of data which can still be physically written to the SSD,
+    /// or `None` if the write budget is not limited.
+    pub fn remaining_write_budget(&self) -> Option<u64> {
+        self.write_budget
+            .map(|budget| budget.saturating_sub(self.total_bytes_written))
+    }
+
+    /// Returns the write amplification factor.
+    pub fn write_amplification_factor(&self) -> f64 {
+        self.write_amplification_factor
+    }
+
+    /// Returns raw capacity of the SSD, including the over-provisioned space.
+    pub fn raw_capacity(&self) -> u64 {
+        self.raw_capacity
+    }
+}
+
+/// Storage model implementation for SSD.
+impl Storage for Ssd {
+    fn read(&mut self, size: u64, requester: Id) -> u64 {
+        self.disk.read(size, requester)
+    }
+
+    fn write(&mut self, size: u64, requester: Id) -> u64 {
+        let physical_size = self.physical_size(size);
+        if let Some(remaining) = self.remaining_write_budget() {
+            if physical_size > remaining {
+                let error = format!(
+                    "write budget is exhausted: requested write needs {} but only {} is remaining",
+                    physical_size, remaining
+                );
+                return self.disk.reject_write(requester, error);
+            }
+        }
+        let used = self.disk.used_space();
+        let request_id = self.disk.write(size, requester);
+        if self.disk.used_space() > used {
+            self.total_bytes_written += physical_size;
+        }
+        request_id
+    }
+
+    fn read_modify_write(&mut self, size: u64, requester: Id) -> u64 {
+        let physical_size = self.physical_size(size);
+        if let Some(remaining) = self.remaining_write_budget() {
+            if physical_size > remaining {
+                let error = format!(
+                    "write budget is exhausted: requested read-modify-write needs {} but only {} is remaining",
+                    physical_size, remaining
+                );
+                return self.disk.reject_read_modify_write(requester, error);
+            }
+        }
+        let active = self.disk.active_read_count();
+        let request_id = self.disk.read_modify_write(size, requester);
+        if self.disk.active_read_count() > active {
+            self.total_bytes_written += physical_size;
+        }
+        request_id
+    }
+
+    fn read_batch(&mut self, sizes: &[u64], requester: Id) -> Vec<u64> {
+        self.disk.read_batch(sizes, requester)
+    }
+
+    fn write_batch(&mut self, sizes: &[u64], requester: Id) -> Vec<u64> {
+        let physical_size: u64 = sizes.iter().map(|size| self.physical_size(*size)).sum();
+        if let Some(remaining) = self.remaining_write_budget() {
+            if physical_size > remaining {
+                let error = format!(
+                    "write budget is exhausted: requested batch write needs {} but only {} is remaining",
+                    physical_size, remaining
+                );
+                return self.disk.reject_write_batch(sizes.len(), requester, error);
+            }
+        }
+        let used = self.disk.used_space();
+        let request_ids = self.disk.write_batch(sizes, requester);
+        if self.disk.used_space() > used {
+            self.total_bytes_written += physical_size;
+        }
+        request_ids
+    }
+
+    fn flush(&mut self, requester: Id) -> u64 {
+        self.disk.flush(requester)
+    }
+
+    fn estimated_read_time(&self, size: u64) -> f64 {
+        self.disk.estimated_read_time(size)
+    }
+
+    fn estimated_write_time(&self, size: u64) -> f64 {
+        self.disk.estimated_write_time(self.physical_size(size))
+    }
+
+    fn mark_free(&mut self, size: u64) -> Result<(), String> {
+        self.disk.mark_free(size)
+    }
+
+    fn used_space(&self) -> u64 {
+        self.disk.used_space()
+    }
+
+    fn free_space(&self) -> u64 {
+        self.disk.free_space()
+    }
+
+    fn capacity(&self) -> u64 {
+        self.disk.capacity()
+    }
+
+    fn id(&self) -> Id {
+        self.disk.id()
+    }
+
+    fn info(&self) -> StorageInfo {
+        self.disk.info()
+    }
+}
+
+impl EventHandler for Ssd {
+    fn on(&mut self, event: Event) {
+        self.disk.on(event);
+    }
+}
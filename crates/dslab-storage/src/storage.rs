@@ -13,11 +13,56 @@ pub struct StorageInfo {
     pub free_space: u64,
 }
 
+/// Kind of storage request.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum OpKind {
+    /// Read request.
+    Read,
+    /// Write request.
+    Write,
+    /// Read-modify-write request.
+    ReadModifyWrite,
+    /// Flush request.
+    Flush,
+}
+
+/// Request id combined with the kind of request.
+///
+/// Allows to track outstanding requests of different kinds in a single map without relying on the storage
+/// to draw the ids of all requests from a single sequence. Can be obtained from the storage events with
+/// `typed_request_id()` method, e.g. [`crate::events::DataReadCompleted::typed_request_id()`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct RequestId {
+    /// Kind of request.
+    pub op: OpKind,
+    /// Request id returned by the storage.
+    pub id: u64,
+}
+
+impl RequestId {
+    /// Creates typed request id.
+    pub fn new(op: OpKind, id: u64) -> Self {
+        Self { op, id }
+    }
+
+    /// Returns the raw request id as returned by the storage.
+    pub fn raw(&self) -> u64 {
+        self.id
+    }
+}
+
+impl From<RequestId> for u64 {
+    fn from(request_id: RequestId) -> Self {
+        request_id.id
+    }
+}
+
 /// A trait for modeling an abstract storage resource, i.e. any device, system or service for storing data.
 ///
 /// The main operations are reading and writing data to/from storage.
 /// Implementations should model the delays associated with these operations.
 /// The trait also includes operations to get information about the storage, including its capacity, current usage, etc.
+/// The kind of request can be attached to the returned request ids with [`RequestId`].
 pub trait Storage {
     /// Submits data read request and returns unique request id.
     ///
@@ -36,6 +81,58 @@ pub trait Storage {
     /// Note that the returned request id is unique only within the current storage.
     fn write(&mut self, size: u64, requester: Id) -> u64;
 
+    /// Submits read-modify-write request and returns unique request id.
+    ///
+    /// Models reading data of given `size` immediately followed by writing the same amount of data
+    /// to the same place, e.g. an update of a database page. The write starts only after the read is completed,
+    /// and the component specified in `requester` will receive a single `DataReadModifyWriteCompleted` event
+    /// when both phases are completed. Since the data is overwritten in place, the used space does not change.
+    /// If the size is larger than the storage capacity, `DataReadModifyWriteFailed` event will be immediately
+    /// emitted instead.
+    fn read_modify_write(&mut self, size: u64, requester: Id) -> u64;
+
+    /// Submits a batch of data read requests and returns their unique request ids.
+    ///
+    /// The requests are processed concurrently, as if they were submitted one by one with [`Storage::read()`].
+    /// Instead of individual events, the component specified in `requester` will receive a single
+    /// `DataBatchReadCompleted` event upon completion of all requests.
+    /// If any of the read sizes is larger than the storage capacity, none of the requests are submitted
+    /// and `DataBatchReadFailed` event will be immediately emitted instead.
+    fn read_batch(&mut self, sizes: &[u64], requester: Id) -> Vec<u64>;
+
+    /// Submits a batch of data write requests and returns their unique request ids.
+    ///
+    /// The requests are processed concurrently, as if they were submitted one by one with [`Storage::write()`].
+    /// Instead of individual events, the component specified in `requester` will receive a single
+    /// `DataBatchWriteCompleted` event upon completion of all requests.
+    /// If there is not enough available storage space for all requests, none of them are submitted
+    /// and `DataBatchWriteFailed` event will be immediately emitted instead.
+    fn write_batch(&mut self, sizes: &[u64], requester: Id) -> Vec<u64>;
+
+    /// Submits flush request and returns unique request id.
+    ///
+    /// Models a durability barrier such as `fsync`: the component specified in `requester` will receive
+    /// `DataFlushCompleted` event when all write requests (including write phases of read-modify-write requests)
+    /// which are in progress at the time of the call are finished. The writes submitted later are not waited for.
+    /// If there are no writes in progress, the event is emitted immediately.
+    fn flush(&mut self, requester: Id) -> u64;
+
+    /// Returns the estimated time needed to read data of given `size` if the read is submitted now.
+    ///
+    /// The estimate accounts for the operations already in progress, but not for the operations submitted later.
+    /// The default implementation returns `f64::NAN`, which means that the storage does not provide estimates.
+    fn estimated_read_time(&self, _size: u64) -> f64 {
+        f64::NAN
+    }
+
+    /// Returns the estimated time needed to write data of given `size` if the write is submitted now.
+    ///
+    /// The estimate accounts for the operations already in progress, but not for the operations submitted later.
+    /// The default implementation returns `f64::NAN`, which means that the storage does not provide estimates.
+    fn estimated_write_time(&self, _size: u64) -> f64 {
+        f64::NAN
+    }
+
     /// Marks previously used storage space of given `size` as free.
     ///
     /// The `size` should not exceed the currently used storage space.
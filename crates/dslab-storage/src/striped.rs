@@ -0,0 +1,429 @@
+//! Storage model for a logical volume striped over several disks (JBOD with striping).
+//!
+//! The volume consists of a set of member [disks](crate::disk), and its capacity is the sum of their capacities.
+//! Each request is split into stripe units of fixed size, which are distributed over the member disks
+//! in round-robin order starting from the first disk. The units assigned to the same disk are submitted to it
+//! as a single operation, so the disks process their parts of the request in parallel.
+//! The requester receives a single event when the parts on all disks are completed.
+//!
+//! If a part fails on some disk, the requester receives a single failure event, the other outstanding parts
+//! of the request are cancelled, and the space taken by the written parts is released.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use dslab_core::cast;
+use dslab_core::component::Id;
+use dslab_core::context::SimulationContext;
+use dslab_core::event::Event;
+use dslab_core::handler::EventHandler;
+use dslab_core::{log_debug, log_error};
+
+use crate::disk::Disk;
+use crate::events::*;
+use crate::multi_disk::{emit_completed, emit_failed, MultiDiskFlushes, MultiDiskOperation};
+use crate::storage::{Storage, StorageInfo};
+
+// Single request or batch of requests, which is completed when all its parts on member disks are completed.
+struct StripedRequest {
+    operation: MultiDiskOperation,
+    requester: Id,
+    request_ids: Vec<u64>,
+    batch: bool,
+    total_size: u64,
+    start_time: f64,
+    // outstanding parts as (disk index, disk request id)
+    outstanding: Vec<(usize, u64)>,
+    // sizes of completed parts as (disk index, size)
+    completed: Vec<(usize, u64)>,
+    // failed request is kept until its parts which could not be cancelled are finished
+    failed: bool,
+}
+
+/// Represents a logical volume striped over several disks.
+pub struct StripedStorage {
+    disks: Vec<Rc<RefCell<Disk>>>,
+    stripe_size: u64,
+    requests: HashMap<u64, StripedRequest>,
+    // mapping (disk index, disk request id) -> id of the request (or batch) the part belongs to
+    parts: HashMap<(usize, u64), u64>,
+    flushes: MultiDiskFlushes,
+    next_request_id: u64,
+    ctx: SimulationContext,
+}
+
+impl StripedStorage {
+    /// Creates striped storage over given disks with given stripe unit size.
+    ///
+    /// The disks should be registered as simulation handlers and should not be used directly afterwards.
+    pub fn new(disks: Vec<Rc<RefCell<Disk>>>, stripe_size: u64, ctx: SimulationContext) -> Self {
+        assert!(!disks.is_empty(), "striped storage needs at least one disk");
+        assert!(stripe_size > 0, "stripe size must be positive");
+        Self {
+            disks,
+            stripe_size,
+            requests: HashMap::new(),
+            parts: HashMap::new(),
+            flushes: MultiDiskFlushes::default(),
+            next_request_id: 0,
+            ctx,
+        }
+    }
+
+    /// Returns the number of member disks.
+    pub fn disk_count(&self) -> usize {
+        self.disks.len()
+    }
+
+    /// Returns the stripe unit size.
+    pub fn stripe_size(&self) -> u64 {
+        self.stripe_size
+    }
+
+    /// Splits data of given size into the parts stored on each of the member disks.
+    ///
+    /// Full stripe units are assigned to the disks in round-robin order, and the remaining incomplete unit
+    /// is assigned to the next disk in this order.
+    pub fn split(&self, size: u64) -> Vec<u64> {
+        let n = self.disks.len() as u64;
+        let units = size / self.stripe_size;
+        let rest = size % self.stripe_size;
+        (0..n)
+            .map(|i| {
+                let mut part = (units / n + u64::from(i < units % n)) * self.stripe_size;
+                if i == units % n {
+                    part += rest;
+                }
+                part
+            })
+            .collect()
+    }
+
+    fn make_unique_request_id(&mut self) -> u64 {
+        let request_id = self.next_request_id;
+        self.next_request_id += 1;
+        request_id
+    }
+
+    // Checks that the parts of requests with given total sizes fit into the member disks.
+    fn check_parts(&self, operation: MultiDiskOperation, sizes: &[u64]) -> Result<(), String> {
+        let mut parts = vec![0; self.disks.len()];
+        for size in sizes {
+            for (total, part) in parts.iter_mut().zip(self.split(*size)) {
+                *total += part;
+            }
+        }
+        for (i, (disk, part)) in self.disks.iter().zip(parts).enumerate() {
+            let available = match operation {
+                MultiDiskOperation::Write => disk.borrow().free_space(),
+                MultiDiskOperation::Read | MultiDiskOperation::ReadModifyWrite => disk.borrow().capacity(),
+            };
+            if part > available {
+                return Err(format!(
+                    "requested part size on disk {} is {} but only {} is available",
+                    i, part, available
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    // Submits the parts of requests with given sizes to member disks and returns the ids of these requests.
+    fn submit(&mut self, operation: MultiDiskOperation, sizes: &[u64], requester: Id, batch: bool) -> Vec<u64> {
+        let request_ids: Vec<u64> = sizes.iter().map(|_| self.make_unique_request_id()).collect();
+        if let Err(error) = self.check_parts(operation, sizes) {
+            self.fail(operation, request_ids.clone(), batch, requester, error);
+            return request_ids;
+        }
+        let id = request_ids[0];
+        let self_id = self.ctx.id();
+        let mut outstanding = Vec::new();
+        for size in sizes {
+            for (i, part) in self.split(*size).into_iter().enumerate() {
+                if part == 0 {
+                    continue;
+                }
+                let mut disk = self.disks[i].borrow_mut();
+                let disk_request_id = match operation {
+                    MultiDiskOperation::Read => disk.read(part, self_id),
+                    MultiDiskOperation::Write => disk.write(part, self_id),
+                    MultiDiskOperation::ReadModifyWrite => disk.read_modify_write(part, self_id),
+                };
+                outstanding.push((i, disk_request_id));
+                self.parts.insert((i, disk_request_id), id);
+            }
+        }
+        self.requests.insert(
+            id,
+            StripedRequest {
+                operation,
+                requester,
+                request_ids: request_ids.clone(),
+                batch,
+                total_size: sizes.iter().sum(),
+                start_time: self.ctx.time(),
+                outstanding,
+                completed: Vec::new(),
+                failed: false,
+            },
+        );
+        if sizes.iter().sum::<u64>() == 0 {
+            self.complete(id);
+        }
+        request_ids
+    }
+
+    // Finds the request which the part belongs to and removes the part from its outstanding parts.
+    fn take_part(&mut self, disk_id: Id, disk_request_id: u64) -> Option<(usize, u64)> {
+        let disk_idx = self.disk_index(disk_id)?;
+        let id = self.parts.remove(&(disk_idx, disk_request_id))?;
+        let request = self.requests.get_mut(&id).unwrap();
+        request.outstanding.retain(|part| *part != (disk_idx, disk_request_id));
+        Some((disk_idx, id))
+    }
+
+    fn on_part_completed(&mut self, disk_id: Id, disk_request_id: u64, size: u64) {
+        if let Some((disk_idx, id)) = self.take_part(disk_id, disk_request_id) {
+            let request = self.requests.get_mut(&id).unwrap();
+            if !request.failed {
+                request.completed.push((disk_idx, size));
+                if request.outstanding.is_empty() {
+                    self.complete(id);
+                }
+                return;
+            }
+            if request.operation == MultiDiskOperation::Write {
+                self.disks[disk_idx].borrow_mut().mark_free(size).unwrap();
+            }
+            if request.outstanding.is_empty() {
+                self.requests.remove(&id);
+            }
+        }
+    }
+
+    fn complete(&mut self, id: u64) {
+        let request = self.requests.remove(&id).unwrap();
+        emit_completed(
+            &mut self.ctx,
+            request.operation,
+            request.request_ids,
+            request.batch,
+            request.total_size,
+            request.start_time,
+            request.requester,
+        );
+    }
+
+    // Cancels the outstanding parts of the failed request and releases the space taken by its written parts.
+    fn on_part_failed(&mut self, disk_id: Id, disk_request_id: u64, error: String) {
+        let (disk_idx, id) = match self.take_part(disk_id, disk_request_id) {
+            Some(part) => part,
+            None => return,
+        };
+        let request = self.requests.get_mut(&id).unwrap();
+        if !request.failed {
+            request.failed = true;
+            // the parts which are already finished on disk are handled on receiving their events
+            let disks = &self.disks;
+            let parts = &mut self.parts;
+            request.outstanding.retain(|part| {
+                if disks[part.0].borrow_mut().cancel(part.1).is_ok() {
+                    parts.remove(part);
+                    false
+                } else {
+                    true
+                }
+            });
+            if request.operation == MultiDiskOperation::Write {
+                for (disk_idx, size) in request.completed.drain(..) {
+                    self.disks[disk_idx].borrow_mut().mark_free(size).unwrap();
+                }
+            }
+            let (operation, request_ids, batch, requester) = (
+                request.operation,
+                request.request_ids.clone(),
+                request.batch,
+                request.requester,
+            );
+            self.fail(
+                operation,
+                request_ids,
+                batch,
+                requester,
+                format!("disk {} failed: {}", disk_idx, error),
+            );
+        }
+        if self.requests[&id].outstanding.is_empty() {
+            self.requests.remove(&id);
+        }
+    }
+
+    fn fail(
+        &mut self,
+        operation: MultiDiskOperation,
+        request_ids: Vec<u64>,
+        batch: bool,
+        requester: Id,
+        error: String,
+    ) {
+        log_error!(self.ctx, "Failed request: {}", error);
+        emit_failed(&mut self.ctx, operation, request_ids, batch, requester, error);
+    }
+
+    fn disk_index(&self, disk_id: Id) -> Option<usize> {
+        self.disks.iter().position(|disk| disk.borrow().id() == disk_id)
+    }
+
+    fn estimated_time<F>(&self, size: u64, estimate: F) -> f64
+    where
+        F: Fn(&Disk, u64) -> f64,
+    {
+        self.disks
+            .iter()
+            .zip(self.split(size))
+            .filter(|(_, part)| *part > 0)
+            .map(|(disk, part)| estimate(&disk.borrow(), part))
+            .fold(0., f64::max)
+    }
+}
+
+/// Storage model implementation for striped volume.
+impl Storage for StripedStorage {
+    fn read(&mut self, size: u64, requester: Id) -> u64 {
+        log_debug!(
+            self.ctx,
+            "Received read request, size: {}, requester: {}",
+            size,
+            requester
+        );
+        self.submit(MultiDiskOperation::Read, &[size], requester, false)[0]
+    }
+
+    fn write(&mut self, size: u64, requester: Id) -> u64 {
+        log_debug!(
+            self.ctx,
+            "Received write request, size: {}, requester: {}",
+            size,
+            requester
+        );
+        self.submit(MultiDiskOperation::Write, &[size], requester, false)[0]
+    }
+
+    fn read_modify_write(&mut self, size: u64, requester: Id) -> u64 {
+        log_debug!(
+            self.ctx,
+            "Received read-modify-write request, size: {}, requester: {}",
+            size,
+            requester
+        );
+        self.submit(MultiDiskOperation::ReadModifyWrite, &[size], requester, false)[0]
+    }
+
+    fn read_batch(&mut self, sizes: &[u64], requester: Id) -> Vec<u64> {
+        if sizes.is_empty() {
+            return Vec::new();
+        }
+        self.submit(MultiDiskOperation::Read, sizes, requester, true)
+    }
+
+    fn write_batch(&mut self, sizes: &[u64], requester: Id) -> Vec<u64> {
+        if sizes.is_empty() {
+            return Vec::new();
+        }
+        self.submit(MultiDiskOperation::Write, sizes, requester, true)
+    }
+
+    fn flush(&mut self, requester: Id) -> u64 {
+        log_debug!(self.ctx, "Received flush request, requester: {}", requester);
+        let request_id = self.make_unique_request_id();
+        let self_id = self.ctx.id();
+        let parts = self
+            .disks
+            .iter()
+            .enumerate()
+            .map(|(i, disk)| (i, disk.borrow_mut().flush(self_id)))
+            .collect();
+        self.flushes.submit(&mut self.ctx, request_id, requester, parts);
+        request_id
+    }
+
+    fn estimated_read_time(&self, size: u64) -> f64 {
+        self.estimated_time(size, |disk, part| disk.estimated_read_time(part))
+    }
+
+    fn estimated_write_time(&self, size: u64) -> f64 {
+        self.estimated_time(size, |disk, part| disk.estimated_write_time(part))
+    }
+
+    /// Releases the space on each disk according to the same split as used for writes.
+    fn mark_free(&mut self, size: u64) -> Result<(), String> {
+        let parts = self.split(size);
+        if self
+            .disks
+            .iter()
+            .zip(&parts)
+            .any(|(disk, part)| disk.borrow().used_space() < *part)
+        {
+            return Err(format!("invalid size: {}", size));
+        }
+        for (disk, part) in self.disks.iter().zip(parts) {
+            disk.borrow_mut().mark_free(part)?;
+        }
+        Ok(())
+    }
+
+    fn used_space(&self) -> u64 {
+        self.disks.iter().map(|disk| disk.borrow().used_space()).sum()
+    }
+
+    fn free_space(&self) -> u64 {
+        self.disks.iter().map(|disk| disk.borrow().free_space()).sum()
+    }
+
+    fn capacity(&self) -> u64 {
+        self.disks.iter().map(|disk| disk.borrow().capacity()).sum()
+    }
+
+    fn id(&self) -> Id {
+        self.ctx.id()
+    }
+
+    fn info(&self) -> StorageInfo {
+        StorageInfo {
+            capacity: self.capacity(),
+            used_space: self.used_space(),
+            free_space: self.free_space(),
+        }
+    }
+}
+
+impl EventHandler for StripedStorage {
+    fn on(&mut self, event: Event) {
+        cast!(match event.data {
+            DataReadCompleted { request_id, size, .. } => {
+                self.on_part_completed(event.src, request_id, size);
+            }
+            DataWriteCompleted { request_id, size, .. } => {
+                self.on_part_completed(event.src, request_id, size);
+            }
+            DataFlushCompleted { request_id } => {
+                if let Some(disk_idx) = self.disk_index(event.src) {
+                    self.flushes.on_part_completed(&mut self.ctx, disk_idx, request_id);
+                }
+            }
+            DataReadModifyWriteCompleted { request_id, size, .. } => {
+                self.on_part_completed(event.src, request_id, size);
+            }
+            DataReadFailed { request_id, error } => {
+                self.on_part_failed(event.src, request_id, error);
+            }
+            DataWriteFailed { request_id, error } => {
+                self.on_part_failed(event.src, request_id, error);
+            }
+            DataReadModifyWriteFailed { request_id, error } => {
+                self.on_part_failed(event.src, request_id, error);
+            }
+        })
+    }
+}
@@ -1,15 +1,24 @@
 use std::cell::RefCell;
+use std::future::Future;
 use std::rc::Rc;
+use std::task::{Context, Waker};
 
-use sugars::{rc, refcell};
+use sugars::{boxed, rc, refcell};
 
 use dslab_core::simulation::Simulation;
-use dslab_core::{cast, Event, EventHandler};
+use dslab_core::{cast, Event, EventHandler, Id, SimulationContext};
+use dslab_models::throughput_sharing::ConstantFactorFn;
 
-use crate::disk::{Disk, DiskBuilder};
+use crate::cache::{CachedStorage, WritePolicy};
+use crate::disk::{Disk, DiskActivity, DiskBuilder, DiskOperation, FairnessMode};
 use crate::events::*;
 use crate::fs::FileSystem;
-use crate::storage::{Storage, StorageInfo};
+use crate::instant::InstantStorage;
+use crate::mirror::MirrorStorage;
+use crate::pool::{LeastLoadedPlacement, PlacementPolicy, RequesterHashPlacement, RoundRobinPlacement, StoragePool};
+use crate::ssd::SsdBuilder;
+use crate::storage::{OpKind, RequestId, Storage, StorageInfo};
+use crate::striped::StripedStorage;
 
 ///////////////////////////////////////////////////////////////////////////////
 
@@ -104,10 +113,157 @@ impl EventHandler for Checker {
                     panic!();
                 }
             }
+            DataBatchWriteFailed { .. } => {
+                if self.expected_event_type != ExpectedEventType::DataWriteFailed {
+                    panic!();
+                }
+            }
+        })
+    }
+}
+
+// Records completed disk requests as (request id, completion time, source).
+struct Recorder {
+    completed: Vec<(u64, f64, Id)>,
+    // (request id, start time, completion time) reported in completion events
+    timings: Vec<(u64, f64, f64)>,
+    progress: Vec<(u64, f64, u64)>,
+    batches: Vec<(Vec<u64>, f64, u64)>,
+    failed: Vec<(u64, f64, String)>,
+    flushes: Vec<(u64, f64)>,
+    // (request id, tag) reported in read and write completion events
+    tags: Vec<(u64, Option<u64>)>,
+    // (whether the space is low, used space) reported in watermark events
+    watermarks: Vec<(bool, u64)>,
+    // typed request ids of received events carrying the id of a single request
+    typed_request_ids: Vec<RequestId>,
+    ctx: SimulationContext,
+}
+
+impl Recorder {
+    fn new(ctx: SimulationContext) -> Recorder {
+        Recorder {
+            completed: Vec::new(),
+            timings: Vec::new(),
+            progress: Vec::new(),
+            batches: Vec::new(),
+            failed: Vec::new(),
+            flushes: Vec::new(),
+            tags: Vec::new(),
+            watermarks: Vec::new(),
+            typed_request_ids: Vec::new(),
+            ctx,
+        }
+    }
+
+    fn typed_request_id(event: &Event) -> Option<RequestId> {
+        let data = &event.data;
+        data.downcast_ref::<DataReadCompleted>()
+            .map(|e| e.typed_request_id())
+            .or_else(|| data.downcast_ref::<DataReadFailed>().map(|e| e.typed_request_id()))
+            .or_else(|| data.downcast_ref::<DataReadProgress>().map(|e| e.typed_request_id()))
+            .or_else(|| data.downcast_ref::<DataWriteCompleted>().map(|e| e.typed_request_id()))
+            .or_else(|| data.downcast_ref::<DataWriteFailed>().map(|e| e.typed_request_id()))
+            .or_else(|| data.downcast_ref::<DataWriteProgress>().map(|e| e.typed_request_id()))
+            .or_else(|| {
+                data.downcast_ref::<DataReadModifyWriteCompleted>()
+                    .map(|e| e.typed_request_id())
+            })
+            .or_else(|| {
+                data.downcast_ref::<DataReadModifyWriteFailed>()
+                    .map(|e| e.typed_request_id())
+            })
+            .or_else(|| data.downcast_ref::<DataFlushCompleted>().map(|e| e.typed_request_id()))
+    }
+}
+
+impl EventHandler for Recorder {
+    fn on(&mut self, event: Event) {
+        if let Some(typed_request_id) = Self::typed_request_id(&event) {
+            self.typed_request_ids.push(typed_request_id);
+        }
+        cast!(match event.data {
+            DataReadCompleted {
+                request_id,
+                start_time,
+                completion_time,
+                tag,
+                ..
+            } => {
+                self.completed.push((request_id, self.ctx.time(), event.src));
+                self.timings.push((request_id, start_time, completion_time));
+                self.tags.push((request_id, tag));
+            }
+            DataWriteCompleted {
+                request_id,
+                start_time,
+                completion_time,
+                tag,
+                ..
+            } => {
+                self.completed.push((request_id, self.ctx.time(), event.src));
+                self.timings.push((request_id, start_time, completion_time));
+                self.tags.push((request_id, tag));
+            }
+            DataReadModifyWriteCompleted {
+                request_id,
+                start_time,
+                completion_time,
+                ..
+            } => {
+                self.completed.push((request_id, self.ctx.time(), event.src));
+                self.timings.push((request_id, start_time, completion_time));
+            }
+            DataReadProgress {
+                request_id, bytes_done, ..
+            } => {
+                self.progress.push((request_id, self.ctx.time(), bytes_done));
+            }
+            DataWriteProgress {
+                request_id, bytes_done, ..
+            } => {
+                self.progress.push((request_id, self.ctx.time(), bytes_done));
+            }
+            DataReadFailed { request_id, error } => {
+                self.failed.push((request_id, self.ctx.time(), error));
+            }
+            DataWriteFailed { request_id, error } => {
+                self.failed.push((request_id, self.ctx.time(), error));
+            }
+            DataReadModifyWriteFailed { request_id, error } => {
+                self.failed.push((request_id, self.ctx.time(), error));
+            }
+            DataBatchReadCompleted {
+                request_ids,
+                total_size,
+            } => {
+                self.batches.push((request_ids, self.ctx.time(), total_size));
+            }
+            DataBatchWriteCompleted {
+                request_ids,
+                total_size,
+            } => {
+                self.batches.push((request_ids, self.ctx.time(), total_size));
+            }
+            DataFlushCompleted { request_id } => {
+                self.flushes.push((request_id, self.ctx.time()));
+            }
+            StorageLowSpace { used_space, .. } => {
+                self.watermarks.push((true, used_space));
+            }
+            StorageSpaceRecovered { used_space, .. } => {
+                self.watermarks.push((false, used_space));
+            }
         })
     }
 }
 
+fn make_recorder(sim: &mut Simulation, name: &str) -> (Rc<RefCell<Recorder>>, Id) {
+    let recorder = rc!(refcell!(Recorder::new(sim.create_context(name))));
+    let id = sim.add_handler(name, recorder.clone());
+    (recorder, id)
+}
+
 ///////////////////////////////////////////////////////////////////////////////
 
 #[test]
@@ -473,6 +629,39 @@ fn disk_failed_read_bad_size() {
     sim.step_until_no_events();
 }
 
+// Read of more data than was written fails if written bytes are tracked
+#[test]
+fn disk_failed_read_unwritten_data() {
+    let mut sim = Simulation::new(SEED);
+
+    let (recorder, recorder_id) = make_recorder(&mut sim, "User");
+
+    let disk = rc!(refcell!(DiskBuilder::simple(
+        DISK_CAPACITY,
+        DISK_READ_BW,
+        DISK_WRITE_BW
+    )
+    .track_written_bytes()
+    .build(sim.create_context("Disk-1"))));
+    sim.add_handler("Disk-1", disk.clone());
+
+    let failed_read = disk.borrow_mut().read(10, recorder_id);
+    sim.step_until_no_events();
+    disk.borrow_mut().write(30, recorder_id);
+    sim.step_until_no_events();
+    let read = disk.borrow_mut().read(30, recorder_id);
+    let failed_rmw = disk.borrow_mut().read_modify_write(31, recorder_id);
+    sim.step_until_no_events();
+
+    let failed: Vec<u64> = recorder.borrow().failed.iter().map(|f| f.0).collect();
+    assert_eq!(failed, vec![failed_read, failed_rmw]);
+    assert_eq!(
+        recorder.borrow().failed[0].2,
+        "requested read size is 10 but only 0 is available"
+    );
+    assert_eq!(recorder.borrow().completed.last().unwrap().0, read);
+}
+
 // Write fails because of low disk capacity
 #[test]
 fn disk_failed_write_low_disk_capacity() {
@@ -518,3 +707,1576 @@ fn disk_write_after_spaced_marked_free() {
     assert!(disk.borrow().used_space() == 99);
     assert!(disk.borrow().free_space() == DISK_CAPACITY - 99);
 }
+
+// Outstanding reads are migrated to another disk and completed there
+#[test]
+fn disk_migrate_outstanding_reads() {
+    let mut sim = Simulation::new(SEED);
+
+    let (recorder, recorder_id) = make_recorder(&mut sim, "User");
+
+    let disk1 = make_simple_disk(&mut sim, "Disk-1");
+    let disk2 = make_simple_disk(&mut sim, "Disk-2");
+
+    let read1 = disk1.borrow_mut().read(100, recorder_id);
+    let read2 = disk1.borrow_mut().read(100, recorder_id);
+    sim.step_for_duration(0.5);
+
+    let outstanding = disk1.borrow_mut().export_outstanding();
+    assert_eq!(outstanding.len(), 2);
+    for activity in outstanding.iter() {
+        assert_eq!(activity.operation, DiskOperation::Read);
+        assert_eq!(activity.remaining_size, 75.);
+    }
+    assert!(disk2.borrow_mut().import_outstanding(outstanding).is_ok());
+    sim.step_until_no_events();
+
+    let completed = &recorder.borrow().completed;
+    assert_eq!(completed.len(), 2);
+    let disk2_id = disk2.borrow().id();
+    for (request_id, time, src) in completed.iter() {
+        assert!(*request_id == read1 || *request_id == read2);
+        assert_eq!(*time, 2.);
+        assert_eq!(*src, disk2_id);
+    }
+}
+
+// Migrated batches keep their membership and imported request ids are not reused by the destination disk
+#[test]
+fn disk_migrate_outstanding_batch() {
+    let mut sim = Simulation::new(SEED);
+
+    let (recorder, recorder_id) = make_recorder(&mut sim, "User");
+
+    let disk1 = make_simple_disk(&mut sim, "Disk-1");
+    let disk2 = make_simple_disk(&mut sim, "Disk-2");
+
+    let batch = disk1.borrow_mut().read_batch(&[10, 100], recorder_id);
+    let busy = disk2.borrow_mut().read(100, recorder_id);
+    assert_eq!(busy, batch[0]);
+    sim.step_for_duration(0.25);
+
+    let outstanding = disk1.borrow_mut().export_outstanding();
+    assert_eq!(outstanding.len(), 1);
+    assert_eq!(outstanding[0].request_id, batch[1]);
+    let exported_batch = outstanding[0].batch.as_ref().unwrap();
+    assert_eq!(exported_batch.request_ids, batch);
+    assert_eq!(exported_batch.total_size, 110);
+
+    let mut duplicate = outstanding.clone();
+    duplicate[0].request_id = busy;
+    assert!(disk2.borrow_mut().import_outstanding(duplicate).is_err());
+    assert!(disk2.borrow_mut().import_outstanding(outstanding).is_ok());
+    let read = disk2.borrow_mut().read(10, recorder_id);
+    assert!(read > batch[1]);
+    sim.step_until_no_events();
+
+    let recorder = recorder.borrow();
+    assert_eq!(recorder.batches.len(), 1);
+    assert_eq!(recorder.batches[0].0, batch);
+    assert_eq!(recorder.batches[0].2, 110);
+}
+
+// Remaining size of exported operations does not depend on the activity factors of the source disk
+#[test]
+fn disk_migrate_outstanding_with_factor() {
+    let mut sim = Simulation::new(SEED);
+
+    let (recorder, recorder_id) = make_recorder(&mut sim, "User");
+
+    let make_disk = |sim: &mut Simulation, name: &str| {
+        let disk = rc!(refcell!(DiskBuilder::simple(
+            DISK_CAPACITY,
+            DISK_READ_BW,
+            DISK_WRITE_BW
+        )
+        .read_factor_fn(boxed!(ConstantFactorFn::new(0.5)))
+        .build(sim.create_context(name))));
+        sim.add_handler(name, disk.clone());
+        disk
+    };
+    let disk1 = make_disk(&mut sim, "Disk-1");
+    let disk2 = make_disk(&mut sim, "Disk-2");
+
+    let read = disk1.borrow_mut().read(100, recorder_id);
+    sim.step_for_duration(1.);
+
+    let outstanding = disk1.borrow_mut().export_outstanding();
+    assert_eq!(outstanding.len(), 1);
+    assert!((outstanding[0].remaining_size - 50.).abs() < 1e-9);
+    assert!(disk2.borrow_mut().import_outstanding(outstanding).is_ok());
+    sim.step_until_no_events();
+
+    let completed = &recorder.borrow().completed;
+    assert_eq!(completed.len(), 1);
+    assert_eq!(completed[0].0, read);
+    assert!((completed[0].1 - 2.).abs() < 1e-9);
+}
+
+// Callbacks are invoked on read completion and failure
+#[test]
+fn disk_read_with_callback() {
+    let mut sim = Simulation::new(SEED);
+
+    let (_, recorder_id) = make_recorder(&mut sim, "User");
+
+    let disk = make_simple_disk(&mut sim, "Disk-1");
+
+    let results: Rc<RefCell<Vec<Result<u64, String>>>> = rc!(refcell!(Vec::new()));
+    let good_results = results.clone();
+    disk.borrow_mut()
+        .read_with_callback(50, recorder_id, move |result| good_results.borrow_mut().push(result));
+    let bad_results = results.clone();
+    disk.borrow_mut()
+        .read_with_callback(101, recorder_id, move |result| bad_results.borrow_mut().push(result));
+
+    sim.step_until_no_events();
+
+    assert_eq!(sim.time(), 50. / DISK_READ_BW);
+    let results = results.borrow();
+    assert_eq!(results.len(), 2);
+    assert!(results[0].is_err());
+    assert_eq!(results[1], Ok(50));
+}
+
+// Futures allow to write sequential I/O logic which is resumed when the disk operations complete
+#[test]
+fn disk_async_operations() {
+    let mut sim = Simulation::new(SEED);
+
+    let (recorder, recorder_id) = make_recorder(&mut sim, "User");
+
+    let disk = make_simple_disk(&mut sim, "Disk-1");
+
+    let results = rc!(refcell!(Vec::<(f64, Result<u64, String>)>::new()));
+    let client_disk = disk.clone();
+    let client_results = results.clone();
+    let client_ctx = sim.create_context("Client");
+    // the disk must not be borrowed while awaiting, so the future is obtained in a separate statement
+    let mut client = Box::pin(async move {
+        let write = client_disk.borrow_mut().write_async(50, recorder_id);
+        let result = write.await;
+        client_results.borrow_mut().push((client_ctx.time(), result));
+        let read = client_disk.borrow_mut().read_async(101, recorder_id);
+        let result = read.await;
+        client_results.borrow_mut().push((client_ctx.time(), result));
+        let read = client_disk.borrow_mut().read_async(25, recorder_id);
+        let result = read.await;
+        client_results.borrow_mut().push((client_ctx.time(), result));
+    });
+
+    // minimal executor which polls the client task after each simulation step
+    let mut cx = Context::from_waker(Waker::noop());
+    let mut done = client.as_mut().poll(&mut cx).is_ready();
+    while !done && sim.step() {
+        done = client.as_mut().poll(&mut cx).is_ready();
+    }
+
+    assert!(done);
+    assert_eq!(
+        *results.borrow(),
+        vec![
+            (0.5, Ok(50)),
+            (
+                0.5,
+                Err("requested read size is 101 but only 100 is available".to_string())
+            ),
+            (0.75, Ok(25)),
+        ]
+    );
+    // requester also receives the completion events
+    sim.step_until_no_events();
+    assert_eq!(recorder.borrow().completed.len(), 2);
+}
+
+// Each operation observes the disk latency independently
+#[test]
+fn disk_read_latency() {
+    let mut sim = Simulation::new(SEED);
+
+    let (recorder, recorder_id) = make_recorder(&mut sim, "User");
+
+    let disk = rc!(refcell!(DiskBuilder::simple(
+        DISK_CAPACITY,
+        DISK_READ_BW,
+        DISK_WRITE_BW
+    )
+    .read_latency(0.25)
+    .build(sim.create_context("Disk-1"))));
+    sim.add_handler("Disk-1", disk.clone());
+
+    let read1 = disk.borrow_mut().read(50, recorder_id);
+    let read2 = disk.borrow_mut().read(100, recorder_id);
+    sim.step_until_no_events();
+
+    // both reads share bandwidth until the first one is transferred at time 1, the second one is transferred at 1.5
+    let completed = &recorder.borrow().completed;
+    assert_eq!(completed.len(), 2);
+    assert_eq!((completed[0].0, completed[0].1), (read1, 1.25));
+    assert_eq!((completed[1].0, completed[1].1), (read2, 1.75));
+}
+
+// Operations not starting at the position after the previous operation with known offset get lower throughput
+#[test]
+fn disk_seek_penalty() {
+    let mut sim = Simulation::new(SEED);
+
+    let (recorder, recorder_id) = make_recorder(&mut sim, "User");
+
+    let disk = rc!(refcell!(DiskBuilder::simple(
+        DISK_CAPACITY,
+        DISK_READ_BW,
+        DISK_WRITE_BW
+    )
+    .seek_penalty_fn(boxed!(|position, activity: &DiskActivity| {
+        if activity.offset == Some(position) {
+            1.
+        } else {
+            0.5
+        }
+    }))
+    .build(sim.create_context("Disk-1"))));
+    sim.add_handler("Disk-1", disk.clone());
+
+    // the first operation is not penalized
+    disk.borrow_mut().read_at(0, 25, recorder_id);
+    sim.step_until_no_events();
+    // sequential
+    disk.borrow_mut().read_at(25, 25, recorder_id);
+    sim.step_until_no_events();
+    // random
+    disk.borrow_mut().read_at(75, 25, recorder_id);
+    sim.step_until_no_events();
+    // random, reads and writes share the position
+    disk.borrow_mut().write_at(50, 25, recorder_id);
+    sim.step_until_no_events();
+    // operations without offset are not penalized and do not change the position
+    disk.borrow_mut().read(25, recorder_id);
+    sim.step_until_no_events();
+    // sequential
+    disk.borrow_mut().read_at(75, 25, recorder_id);
+    sim.step_until_no_events();
+
+    let times: Vec<f64> = recorder.borrow().completed.iter().map(|c| c.1).collect();
+    assert_eq!(times, vec![0.25, 0.5, 1., 1.5, 1.75, 2.]);
+}
+
+// Operations started when the disk is more than half full get lower throughput
+#[test]
+fn disk_utilization_factor() {
+    let mut sim = Simulation::new(SEED);
+
+    let (recorder, recorder_id) = make_recorder(&mut sim, "User");
+
+    let disk = rc!(refcell!(DiskBuilder::simple(
+        DISK_CAPACITY,
+        DISK_READ_BW,
+        DISK_WRITE_BW
+    )
+    .utilization_factor_fn(boxed!(|info: &StorageInfo| {
+        if info.used_space * 2 > info.capacity {
+            0.5
+        } else {
+            1.
+        }
+    }))
+    .build(sim.create_context("Disk-1"))));
+    sim.add_handler("Disk-1", disk.clone());
+
+    // the space reserved by the write itself is taken into account
+    disk.borrow_mut().write(DISK_CAPACITY / 2, recorder_id);
+    sim.step_until_no_events();
+    disk.borrow_mut().read(25, recorder_id);
+    sim.step_until_no_events();
+    disk.borrow_mut().write(1, recorder_id);
+    sim.step_until_no_events();
+    disk.borrow_mut().read(25, recorder_id);
+    sim.step_until_no_events();
+    // freeing space restores the throughput
+    disk.borrow_mut().mark_free(DISK_CAPACITY / 2).unwrap();
+    disk.borrow_mut().read(25, recorder_id);
+    sim.step_until_no_events();
+
+    let times: Vec<f64> = recorder.borrow().completed.iter().map(|c| c.1).collect();
+    assert_eq!(times.len(), 5);
+    let durations: Vec<f64> = times.windows(2).map(|w| w[1] - w[0]).collect();
+    let read_time = 25. / DISK_READ_BW;
+    assert!((durations[0] - read_time).abs() < 1e-9);
+    assert!((durations[2] - 2. * read_time).abs() < 1e-9);
+    assert!((durations[3] - read_time).abs() < 1e-9);
+}
+
+fn jittered_read_durations(seed: u64, cv: f64) -> Vec<f64> {
+    let mut sim = Simulation::new(seed);
+
+    let (recorder, recorder_id) = make_recorder(&mut sim, "User");
+
+    let disk = rc!(refcell!(DiskBuilder::new()
+        .capacity(DISK_CAPACITY)
+        .bw_with_jitter(DISK_READ_BW, DISK_WRITE_BW, cv)
+        .build(sim.create_context("Disk-1"))));
+    sim.add_handler("Disk-1", disk.clone());
+
+    for _ in 0..50 {
+        let start = sim.time();
+        disk.borrow_mut().read(10, recorder_id);
+        sim.step_until_no_events();
+        assert!(sim.time() > start);
+    }
+
+    let recorder = recorder.borrow();
+    let mut durations = Vec::new();
+    let mut prev = 0.;
+    for (_, time, _) in recorder.completed.iter() {
+        durations.push(time - prev);
+        prev = *time;
+    }
+    durations
+}
+
+#[test]
+fn disk_bw_with_jitter() {
+    let read_time = 10. / DISK_READ_BW;
+    // zero variation is the same as constant bandwidth
+    assert!(jittered_read_durations(SEED, 0.)
+        .iter()
+        .all(|d| (d - read_time).abs() < 1e-9));
+
+    let durations = jittered_read_durations(SEED, 0.3);
+    assert!(durations.iter().any(|d| (d - read_time).abs() > 1e-3));
+    // factors are in [1 - 0.3 * sqrt(3), 1 + 0.3 * sqrt(3)]
+    let max_factor = 1. + 0.3 * 3f64.sqrt();
+    let min_factor = 1. - 0.3 * 3f64.sqrt();
+    assert!(durations
+        .iter()
+        .all(|d| *d >= read_time / max_factor - 1e-9 && *d <= read_time / min_factor + 1e-9));
+    // the same seed gives the same results
+    assert_eq!(durations, jittered_read_durations(SEED, 0.3));
+    assert_ne!(durations, jittered_read_durations(SEED + 1, 0.3));
+}
+
+// Completion events report submission and completion times, which are preserved on migration
+#[test]
+fn disk_completion_timings() {
+    let mut sim = Simulation::new(SEED);
+
+    let (recorder, recorder_id) = make_recorder(&mut sim, "User");
+
+    let disk1 = rc!(refcell!(DiskBuilder::simple(
+        DISK_CAPACITY,
+        DISK_READ_BW,
+        DISK_WRITE_BW
+    )
+    .write_latency(0.25)
+    .build(sim.create_context("Disk-1"))));
+    sim.add_handler("Disk-1", disk1.clone());
+    let disk2 = make_simple_disk(&mut sim, "Disk-2");
+
+    let read = disk1.borrow_mut().read(100, recorder_id);
+    sim.step_for_duration(0.5);
+    let write = disk1.borrow_mut().write(50, recorder_id);
+    let outstanding = disk1.borrow_mut().export_outstanding();
+    let (reads, writes): (Vec<_>, Vec<_>) = outstanding
+        .into_iter()
+        .partition(|a| a.operation == DiskOperation::Read);
+    assert!(disk2.borrow_mut().import_outstanding(reads).is_ok());
+    assert!(disk1.borrow_mut().import_outstanding(writes).is_ok());
+    sim.step_until_no_events();
+
+    let recorder = recorder.borrow();
+    assert_eq!(recorder.timings, vec![(read, 0., 1.), (write, 0.5, 1.25)]);
+    for ((request_id, _, completion_time), (completed_id, time, _)) in recorder.timings.iter().zip(&recorder.completed)
+    {
+        assert_eq!(request_id, completed_id);
+        assert_eq!(completion_time, time);
+    }
+}
+
+// Write phase of read-modify-write starts after the read phase, including its latency
+#[test]
+fn disk_read_modify_write() {
+    let mut sim = Simulation::new(SEED);
+
+    let (recorder, recorder_id) = make_recorder(&mut sim, "User");
+
+    let disk = rc!(refcell!(DiskBuilder::simple(
+        DISK_CAPACITY,
+        DISK_READ_BW,
+        DISK_WRITE_BW
+    )
+    .read_latency(0.25)
+    .build(sim.create_context("Disk-1"))));
+    sim.add_handler("Disk-1", disk.clone());
+
+    let rmw = disk.borrow_mut().read_modify_write(50, recorder_id);
+    let read = disk.borrow_mut().read(50, recorder_id);
+    let failed = disk.borrow_mut().read_modify_write(101, recorder_id);
+    sim.step_until_no_events();
+
+    // both reads are transferred at time 1, then the write phase takes another 0.5
+    let recorder_ref = recorder.borrow();
+    assert_eq!(recorder_ref.completed.len(), 2);
+    assert_eq!((recorder_ref.completed[0].0, recorder_ref.completed[0].1), (read, 1.25));
+    assert_eq!((recorder_ref.completed[1].0, recorder_ref.completed[1].1), (rmw, 1.75));
+    assert_eq!(recorder_ref.timings[1], (rmw, 0., 1.75));
+    assert_eq!(recorder_ref.failed.len(), 1);
+    assert_eq!(recorder_ref.failed[0].0, failed);
+    assert_eq!(disk.borrow().used_space(), 0);
+    drop(recorder_ref);
+
+    // the request can be cancelled while waiting for the write phase
+    let cancelled = disk.borrow_mut().read_modify_write(100, recorder_id);
+    sim.step_for_duration(1.1);
+    assert_eq!(
+        disk.borrow().active_read_count() + disk.borrow().active_write_count(),
+        0
+    );
+    assert!(disk.borrow_mut().cancel(cancelled).is_ok());
+    sim.step_until_no_events();
+    assert_eq!(disk.borrow().active_write_count(), 0);
+    assert_eq!(recorder.borrow().completed.len(), 2);
+}
+
+// Cancelled write releases its bandwidth and reserved space
+#[test]
+fn disk_overwrite() {
+    let mut sim = Simulation::new(SEED);
+
+    let (recorder, recorder_id) = make_recorder(&mut sim, "User");
+
+    let disk = make_simple_disk(&mut sim, "Disk-1");
+
+    let write = disk.borrow_mut().write(50, recorder_id);
+    sim.step_until_no_events();
+    // the overwrite consumes write bandwidth but does not change the used space
+    let overwrite = disk.borrow_mut().overwrite(50, recorder_id);
+    assert_eq!(disk.borrow().used_space(), 50);
+    sim.step_until_no_events();
+    assert_eq!(disk.borrow().used_space(), 50);
+    // only written data can be overwritten
+    let failed = disk.borrow_mut().overwrite(60, recorder_id);
+    sim.step_until_no_events();
+    // cancelled overwrite does not release space
+    let cancelled = disk.borrow_mut().overwrite(50, recorder_id);
+    sim.step_for_duration(0.1);
+    assert!(disk.borrow_mut().cancel(cancelled).is_ok());
+    sim.step_until_no_events();
+    assert_eq!(disk.borrow().used_space(), 50);
+
+    let recorder = recorder.borrow();
+    let completed: Vec<(u64, f64)> = recorder.completed.iter().map(|c| (c.0, c.1)).collect();
+    assert_eq!(completed, vec![(write, 0.5), (overwrite, 1.)]);
+    assert_eq!(recorder.failed.len(), 1);
+    assert_eq!(recorder.failed[0].0, failed);
+}
+
+#[test]
+fn disk_watermark() {
+    let mut sim = Simulation::new(SEED);
+
+    let (recorder, recorder_id) = make_recorder(&mut sim, "User");
+
+    let disk = make_simple_disk(&mut sim, "Disk-1");
+    assert!(disk.borrow_mut().set_watermark(70, 30, recorder_id).is_err());
+    disk.borrow_mut().set_watermark(30, 70, recorder_id).unwrap();
+
+    disk.borrow_mut().write(50, recorder_id);
+    disk.borrow_mut().write(30, recorder_id);
+    sim.step_until_no_events();
+    assert_eq!(recorder.borrow().watermarks, vec![(true, 80)]);
+
+    // fluctuations between the watermarks do not produce events
+    disk.borrow_mut().mark_free(20).unwrap();
+    disk.borrow_mut().write(15, recorder_id);
+    sim.step_until_no_events();
+    assert_eq!(recorder.borrow().watermarks, vec![(true, 80)]);
+
+    disk.borrow_mut().mark_free(50).unwrap();
+    disk.borrow_mut().mark_free(5).unwrap();
+    sim.step_until_no_events();
+    assert_eq!(recorder.borrow().watermarks, vec![(true, 80), (false, 25)]);
+
+    // the space reserved by outstanding writes counts as used
+    let write = disk.borrow_mut().write(60, recorder_id);
+    sim.step_for_duration(0.1);
+    disk.borrow_mut().cancel(write).unwrap();
+    sim.step_until_no_events();
+    assert_eq!(
+        recorder.borrow().watermarks,
+        vec![(true, 80), (false, 25), (true, 80), (false, 20)]
+    );
+}
+
+// Returns the completion times of four equal reads submitted at once to a disk with given queue depth.
+fn queue_depth_completion_times(depth: Option<usize>) -> Vec<f64> {
+    let mut sim = Simulation::new(SEED);
+
+    let (recorder, recorder_id) = make_recorder(&mut sim, "User");
+
+    let mut builder = DiskBuilder::simple(DISK_CAPACITY, DISK_READ_BW, DISK_WRITE_BW);
+    if let Some(depth) = depth {
+        builder = builder.max_queue_depth(depth);
+    }
+    let disk = rc!(refcell!(builder.build(sim.create_context("Disk-1"))));
+    sim.add_handler("Disk-1", disk.clone());
+
+    for _ in 0..4 {
+        disk.borrow_mut().read(25, recorder_id);
+    }
+    assert_eq!(disk.borrow().queued_operation_count(), 4 - depth.unwrap_or(4));
+    sim.step_until_no_events();
+
+    let completed = &recorder.borrow().completed;
+    completed.iter().map(|c| c.1).collect()
+}
+
+#[test]
+fn disk_max_queue_depth() {
+    // the total throughput is the same, but the operations are served one by one
+    let unbounded = queue_depth_completion_times(None);
+    assert_eq!(unbounded, vec![1., 1., 1., 1.]);
+    let sequential = queue_depth_completion_times(Some(1));
+    assert_eq!(sequential, vec![0.25, 0.5, 0.75, 1.]);
+    let half = queue_depth_completion_times(Some(2));
+    assert_eq!(half, vec![0.5, 0.5, 1., 1.]);
+
+    let mut sim = Simulation::new(SEED);
+
+    let (recorder, recorder_id) = make_recorder(&mut sim, "User");
+
+    let disk = rc!(refcell!(DiskBuilder::simple(
+        DISK_CAPACITY,
+        DISK_READ_BW,
+        DISK_WRITE_BW
+    )
+    .max_queue_depth(1)
+    .build(sim.create_context("Disk-1"))));
+    sim.add_handler("Disk-1", disk.clone());
+
+    // queued writes reserve space, which is released on cancellation
+    let write1 = disk.borrow_mut().write(50, recorder_id);
+    let write2 = disk.borrow_mut().write(20, recorder_id);
+    let write3 = disk.borrow_mut().write(30, recorder_id);
+    assert_eq!(disk.borrow().used_space(), 100);
+    assert_eq!(disk.borrow().queued_operation_count(), 2);
+    disk.borrow_mut().cancel(write2).unwrap();
+    assert_eq!(disk.borrow().used_space(), 80);
+    assert_eq!(disk.borrow().queued_operation_count(), 1);
+    sim.step_until_no_events();
+
+    let completed = &recorder.borrow().completed;
+    assert_eq!(completed.len(), 2);
+    assert_eq!((completed[0].0, completed[0].1), (write1, 0.5));
+    assert_eq!((completed[1].0, completed[1].1), (write3, 0.8));
+}
+
+#[test]
+fn disk_cancel_write() {
+    let mut sim = Simulation::new(SEED);
+
+    let (recorder, recorder_id) = make_recorder(&mut sim, "User");
+
+    let disk = make_simple_disk(&mut sim, "Disk-1");
+
+    let write1 = disk.borrow_mut().write(50, recorder_id);
+    let write2 = disk.borrow_mut().write(50, recorder_id);
+    assert_eq!(disk.borrow().used_space(), 100);
+    sim.step_for_duration(0.4);
+
+    assert!(disk.borrow_mut().cancel(write2).is_ok());
+    assert!(disk.borrow_mut().cancel(write2).is_err());
+    assert_eq!(disk.borrow().used_space(), 50);
+    sim.step_until_no_events();
+
+    // the remaining write gets the full bandwidth after cancellation
+    let completed = &recorder.borrow().completed;
+    assert_eq!(completed.len(), 1);
+    assert_eq!((completed[0].0, completed[0].1), (write1, 0.7));
+    assert!(disk.borrow_mut().cancel(write1).is_err());
+}
+
+// Disk reports the number and total size of operations in progress
+#[test]
+fn disk_active_operations() {
+    let mut sim = Simulation::new(SEED);
+
+    let (_, recorder_id) = make_recorder(&mut sim, "User");
+
+    let disk = make_simple_disk(&mut sim, "Disk-1");
+
+    disk.borrow_mut().read(10, recorder_id);
+    disk.borrow_mut().read(20, recorder_id);
+    disk.borrow_mut().write(30, recorder_id);
+    assert_eq!(disk.borrow().active_read_count(), 2);
+    assert_eq!(disk.borrow().pending_read_size(), 30);
+    assert_eq!(disk.borrow().active_write_count(), 1);
+    assert_eq!(disk.borrow().pending_write_size(), 30);
+
+    sim.step_for_duration(0.25);
+    assert_eq!(disk.borrow().active_read_count(), 1);
+    assert_eq!(disk.borrow().pending_read_size(), 20);
+    assert_eq!(disk.borrow().active_write_count(), 1);
+
+    sim.step_until_no_events();
+    assert_eq!(disk.borrow().active_read_count(), 0);
+    assert_eq!(disk.borrow().pending_read_size(), 0);
+    assert_eq!(disk.borrow().active_write_count(), 0);
+    assert_eq!(disk.borrow().pending_write_size(), 0);
+}
+
+// Reads and writes share the combined bandwidth cap
+#[test]
+fn disk_total_bandwidth_cap() {
+    let mut sim = Simulation::new(SEED);
+
+    let (recorder, recorder_id) = make_recorder(&mut sim, "User");
+
+    let disk = rc!(refcell!(DiskBuilder::simple(
+        DISK_CAPACITY,
+        DISK_READ_BW,
+        DISK_WRITE_BW
+    )
+    .total_bw(100.)
+    .build(sim.create_context("Disk-1"))));
+    sim.add_handler("Disk-1", disk.clone());
+
+    let read1 = disk.borrow_mut().read(25, recorder_id);
+    let read2 = disk.borrow_mut().read(25, recorder_id);
+    let write = disk.borrow_mut().write(50, recorder_id);
+    sim.step_until_no_events();
+
+    // reads get 2/3 of the cap until they complete at 0.75,
+    // then the write gets the whole cap for its remaining 25
+    let completed = &recorder.borrow().completed;
+    assert_eq!(completed.len(), 3);
+    assert_eq!(completed[0].0, read1);
+    assert_eq!(completed[1].0, read2);
+    assert_eq!(completed[2].0, write);
+    assert!((completed[0].1 - 0.75).abs() < 1e-9);
+    assert!((completed[1].1 - 0.75).abs() < 1e-9);
+    assert!((completed[2].1 - 1.).abs() < 1e-9);
+}
+
+// Estimated read time accounts for reads in progress
+#[test]
+fn disk_estimated_read_time() {
+    let mut sim = Simulation::new(SEED);
+
+    let (recorder, recorder_id) = make_recorder(&mut sim, "User");
+
+    let disk = make_simple_disk(&mut sim, "Disk-1");
+
+    assert_eq!(disk.borrow().estimated_read_time(50), 0.5);
+    disk.borrow_mut().read(30, recorder_id);
+    disk.borrow_mut().read(60, recorder_id);
+    // new read shares bandwidth with two others until 0.9, then with one until completion at 1.1
+    let estimate = disk.borrow().estimated_read_time(40);
+    assert!((estimate - 1.1).abs() < 1e-9);
+
+    let read = disk.borrow_mut().read(40, recorder_id);
+    sim.step_until_no_events();
+    let completed = &recorder.borrow().completed;
+    let (_, time, _) = completed.iter().find(|c| c.0 == read).unwrap();
+    assert!((time - estimate).abs() < 1e-9);
+}
+
+// With per-requester fairness, requesters get equal throughput regardless of the number of their operations
+#[test]
+fn disk_per_requester_fairness() {
+    let run = |fairness: FairnessMode| {
+        let mut sim = Simulation::new(SEED);
+
+        let (recorder1, recorder1_id) = make_recorder(&mut sim, "User-1");
+        let (recorder2, recorder2_id) = make_recorder(&mut sim, "User-2");
+
+        let disk = rc!(refcell!(DiskBuilder::simple(
+            DISK_CAPACITY,
+            DISK_READ_BW,
+            DISK_WRITE_BW
+        )
+        .fairness(fairness)
+        .build(sim.create_context("Disk-1"))));
+        sim.add_handler("Disk-1", disk.clone());
+
+        for _ in 0..3 {
+            disk.borrow_mut().read(20, recorder1_id);
+        }
+        disk.borrow_mut().read(60, recorder2_id);
+        sim.step_until_no_events();
+
+        let last_completion = |recorder: &Rc<RefCell<Recorder>>| {
+            let completed = &recorder.borrow().completed;
+            completed.iter().map(|c| c.1).fold(0., f64::max)
+        };
+        assert_eq!(recorder1.borrow().completed.len(), 3);
+        assert_eq!(recorder2.borrow().completed.len(), 1);
+        (last_completion(&recorder1), last_completion(&recorder2))
+    };
+
+    // each requester gets half of the bandwidth
+    let (time1, time2) = run(FairnessMode::PerRequester);
+    assert!((time1 - 1.2).abs() < 1e-9);
+    assert!((time2 - 1.2).abs() < 1e-9);
+
+    // the requester with more operations gets 3/4 of the bandwidth
+    let (time1, time2) = run(FairnessMode::PerActivity);
+    assert!((time1 - 0.8).abs() < 1e-9);
+    assert!((time2 - 1.2).abs() < 1e-9);
+}
+
+// Limited requester does not receive more than its limit, and the spare bandwidth goes to other requesters
+#[test]
+fn disk_requester_limit() {
+    let mut sim = Simulation::new(SEED);
+
+    let (recorder1, recorder1_id) = make_recorder(&mut sim, "User-1");
+    let (recorder2, recorder2_id) = make_recorder(&mut sim, "User-2");
+    let disk = rc!(refcell!(DiskBuilder::simple(
+        DISK_CAPACITY,
+        DISK_READ_BW,
+        DISK_WRITE_BW
+    )
+    .fairness(FairnessMode::PerRequester)
+    .build(sim.create_context("Disk-1"))));
+    sim.add_handler("Disk-1", disk.clone());
+
+    assert!(disk.borrow_mut().set_requester_limit(recorder1_id, 0.).is_err());
+    assert!(disk.borrow_mut().set_requester_limit(recorder1_id, 20.).is_ok());
+    disk.borrow_mut().read(10, recorder1_id);
+    disk.borrow_mut().read(10, recorder1_id);
+    disk.borrow_mut().read(80, recorder2_id);
+    sim.step_until_no_events();
+    assert_eq!(
+        recorder1.borrow().completed.iter().map(|c| c.1).collect::<Vec<_>>(),
+        vec![1., 1.]
+    );
+    assert_eq!(recorder2.borrow().completed[0].1, 1.);
+
+    // the limit holds even if the disk is idle otherwise
+    disk.borrow_mut().read(10, recorder1_id);
+    sim.step_until_no_events();
+    assert_eq!(recorder1.borrow().completed[2].1, 1.5);
+
+    // removing the limit in the middle of operation speeds it up
+    disk.borrow_mut().read(20, recorder1_id);
+    sim.step_for_duration(0.5);
+    assert!(disk
+        .borrow_mut()
+        .set_requester_limit(recorder1_id, f64::INFINITY)
+        .is_ok());
+    sim.step_until_no_events();
+    assert_eq!(recorder1.borrow().completed[3].1, 2.1);
+
+    let disk = make_simple_disk(&mut sim, "Disk-2");
+    assert!(disk.borrow_mut().set_requester_limit(recorder1_id, 20.).is_err());
+}
+
+// Progress of each operation reflects the bandwidth reallocated after completion of other operations
+#[test]
+fn disk_progress_events() {
+    let mut sim = Simulation::new(SEED);
+
+    let (recorder, recorder_id) = make_recorder(&mut sim, "User");
+
+    let disk = rc!(refcell!(DiskBuilder::simple(
+        DISK_CAPACITY,
+        DISK_READ_BW,
+        DISK_WRITE_BW
+    )
+    .progress_interval(0.25)
+    .build(sim.create_context("Disk-1"))));
+    sim.add_handler("Disk-1", disk.clone());
+
+    let read1 = disk.borrow_mut().read(20, recorder_id);
+    let read2 = disk.borrow_mut().read(70, recorder_id);
+    sim.step_until_no_events();
+
+    // the first read is completed at 0.4, after that the second one gets the whole bandwidth until 0.9
+    let progress = &recorder.borrow().progress;
+    assert_eq!(progress.len(), 4);
+    assert_eq!(progress[0], (read1, 0.25, 13));
+    assert_eq!(progress[1], (read2, 0.25, 13));
+    assert_eq!(progress[2], (read2, 0.5, 30));
+    assert_eq!(progress[3], (read2, 0.75, 55));
+    assert_eq!(recorder.borrow().completed.len(), 2);
+
+    // no progress events are sent by default
+    let mut sim = Simulation::new(SEED);
+    let (recorder, recorder_id) = make_recorder(&mut sim, "User");
+    let disk = make_simple_disk(&mut sim, "Disk-1");
+    disk.borrow_mut().read(20, recorder_id);
+    sim.step_until_no_events();
+    assert!(recorder.borrow().progress.is_empty());
+    assert_eq!(recorder.borrow().completed.len(), 1);
+}
+
+// SSD writes are slowed down by write amplification and fail once the write budget is exhausted
+#[test]
+fn ssd_write_amplification_and_wear() {
+    let mut sim = Simulation::new(SEED);
+
+    let (recorder, recorder_id) = make_recorder(&mut sim, "User");
+    let checker = rc!(refcell!(Checker::new(ExpectedEventType::DataWriteFailed)));
+    let checker_id = sim.add_handler("Checker", checker.clone());
+
+    let ssd = rc!(refcell!(SsdBuilder::simple(DISK_CAPACITY, DISK_READ_BW, DISK_WRITE_BW)
+        .over_provisioning(0.2)
+        .write_amplification_factor(2.)
+        .write_budget(100)
+        .build(sim.create_context("SSD-1"))));
+    sim.add_handler("SSD-1", ssd.clone());
+
+    assert_eq!(ssd.borrow().capacity(), 80);
+    assert_eq!(ssd.borrow().raw_capacity(), 100);
+    assert_eq!(ssd.borrow().estimated_write_time(20), 0.4);
+
+    let write1 = ssd.borrow_mut().write(20, recorder_id);
+    sim.step_until_no_events();
+    assert_eq!(recorder.borrow().completed[0].0, write1);
+    assert_eq!(recorder.borrow().completed[0].1, 0.4);
+    assert_eq!(ssd.borrow().total_bytes_written(), 40);
+    assert_eq!(ssd.borrow().remaining_write_budget(), Some(60));
+
+    ssd.borrow_mut().write(25, recorder_id);
+    sim.step_until_no_events();
+    assert!(ssd.borrow_mut().mark_free(45).is_ok());
+
+    // the remaining budget of 10 is not enough for this write even though there is enough free space
+    ssd.borrow_mut().write(10, checker_id);
+    sim.step_until_no_events();
+    assert_eq!(ssd.borrow().total_bytes_written(), 90);
+    assert_eq!(ssd.borrow().used_space(), 0);
+    assert_eq!(recorder.borrow().completed.len(), 2);
+}
+
+#[test]
+fn disk_set_capacity() {
+    let mut sim = Simulation::new(SEED);
+
+    let (recorder, recorder_id) = make_recorder(&mut sim, "User");
+    let checker = rc!(refcell!(Checker::new(ExpectedEventType::DataWriteFailed)));
+    let checker_id = sim.add_handler("Checker", checker);
+
+    let disk = make_simple_disk(&mut sim, "Disk-1");
+
+    disk.borrow_mut().write(DISK_CAPACITY, recorder_id);
+    sim.step_until_no_events();
+    assert_eq!(disk.borrow().free_space(), 0);
+
+    // write to a full disk fails
+    disk.borrow_mut().write(50, checker_id);
+    sim.step_until_no_events();
+
+    // cannot shrink below used space
+    assert!(disk.borrow_mut().set_capacity(DISK_CAPACITY - 1).is_err());
+    assert_eq!(disk.borrow().capacity(), DISK_CAPACITY);
+
+    assert!(disk.borrow_mut().set_capacity(DISK_CAPACITY + 50).is_ok());
+    assert_eq!(disk.borrow().free_space(), 50);
+    let write = disk.borrow_mut().write(50, recorder_id);
+    sim.step_until_no_events();
+    assert_eq!(recorder.borrow().completed.len(), 2);
+    assert_eq!(recorder.borrow().completed[1].0, write);
+    assert_eq!(disk.borrow().free_space(), 0);
+
+    assert!(disk.borrow_mut().set_capacity(DISK_CAPACITY + 50).is_ok());
+}
+
+// Batch is completed with a single event when all its operations are completed
+#[test]
+fn disk_batch_operations() {
+    let mut sim = Simulation::new(SEED);
+
+    let (recorder, recorder_id) = make_recorder(&mut sim, "User");
+    let checker = rc!(refcell!(Checker::new(ExpectedEventType::DataWriteFailed)));
+    let checker_id = sim.add_handler("Checker", checker);
+
+    let disk = make_simple_disk(&mut sim, "Disk-1");
+
+    let read_ids = disk.borrow_mut().read_batch(&[10, 20, 30], recorder_id);
+    let write_ids = disk.borrow_mut().write_batch(&[40, 10], recorder_id);
+    assert_eq!(read_ids, vec![0, 1, 2]);
+    assert_eq!(write_ids, vec![3, 4]);
+    assert_eq!(disk.borrow().used_space(), 50);
+    assert_eq!(disk.borrow().active_read_count(), 3);
+
+    // the batch does not fit into the remaining space, so none of its operations are submitted
+    let failed_ids = disk.borrow_mut().write_batch(&[30, 30], checker_id);
+    assert_eq!(failed_ids.len(), 2);
+    assert_eq!(disk.borrow().used_space(), 50);
+    sim.step_until_no_events();
+
+    let recorder = recorder.borrow();
+    assert!(recorder.completed.is_empty());
+    assert_eq!(recorder.batches.len(), 2);
+    assert_eq!(recorder.batches[0], (write_ids, 0.5, 50));
+    assert_eq!(recorder.batches[1], (read_ids, 0.6, 60));
+}
+
+// With priorities enabled, the bandwidth is shared in proportion to priority + 1
+#[test]
+fn disk_priorities() {
+    let run = |enable_priorities: bool| {
+        let mut sim = Simulation::new(SEED);
+
+        let (recorder, recorder_id) = make_recorder(&mut sim, "User");
+
+        let mut builder = DiskBuilder::simple(DISK_CAPACITY, DISK_READ_BW, DISK_WRITE_BW);
+        if enable_priorities {
+            builder = builder.enable_priorities();
+        }
+        let disk = rc!(refcell!(builder.build(sim.create_context("Disk-1"))));
+        sim.add_handler("Disk-1", disk.clone());
+
+        let low = disk.borrow_mut().read(50, recorder_id);
+        let high = disk.borrow_mut().read_with_priority(50, recorder_id, 3);
+        sim.step_until_no_events();
+
+        let completed = recorder.borrow().completed.clone();
+        assert_eq!(completed.len(), 2);
+        let time = |request_id| completed.iter().find(|c| c.0 == request_id).unwrap().1;
+        (time(low), time(high))
+    };
+
+    // the high priority read gets 80% of the bandwidth until its completion
+    assert_eq!(run(true), (1., 0.625));
+    // priorities are ignored by default
+    assert_eq!(run(false), (1., 1.));
+}
+
+// Injected failures are reproducible with the same seed
+#[test]
+fn disk_failure_rate() {
+    let run = |seed: u64| {
+        let mut sim = Simulation::new(seed);
+
+        let (recorder, recorder_id) = make_recorder(&mut sim, "User");
+
+        let disk = rc!(refcell!(DiskBuilder::simple(
+            DISK_CAPACITY,
+            DISK_READ_BW,
+            DISK_WRITE_BW
+        )
+        .failure_rate(0.5)
+        .build(sim.create_context("Disk-1"))));
+        sim.add_handler("Disk-1", disk.clone());
+
+        for _ in 0..20 {
+            disk.borrow_mut().read(1, recorder_id);
+        }
+        sim.step_until_no_events();
+
+        let recorder = recorder.borrow();
+        assert_eq!(recorder.completed.len() + recorder.failed.len(), 20);
+        assert!(recorder.failed.iter().all(|f| f.2.starts_with("injected fault")));
+        recorder.failed.iter().map(|f| f.0).collect::<Vec<_>>()
+    };
+
+    let failed = run(SEED);
+    assert!(!failed.is_empty() && failed.len() < 20);
+    assert_eq!(run(SEED), failed);
+}
+
+// After the disk death all outstanding and new operations fail
+#[test]
+fn disk_fail_after() {
+    let mut sim = Simulation::new(SEED);
+
+    let (recorder, recorder_id) = make_recorder(&mut sim, "User");
+
+    let disk = rc!(refcell!(DiskBuilder::simple(
+        DISK_CAPACITY,
+        DISK_READ_BW,
+        DISK_WRITE_BW
+    )
+    .fail_after(1.)
+    .build(sim.create_context("Disk-1"))));
+    sim.add_handler("Disk-1", disk.clone());
+
+    let read1 = disk.borrow_mut().read(50, recorder_id);
+    let write1 = disk.borrow_mut().write(100, recorder_id);
+    sim.step_until_no_events();
+
+    assert_eq!(recorder.borrow().completed.len(), 1);
+    assert_eq!(recorder.borrow().completed[0].0, read1);
+    assert_eq!(recorder.borrow().failed.len(), 1);
+    assert_eq!(recorder.borrow().failed[0].0, write1);
+    assert_eq!(recorder.borrow().failed[0].1, 1.);
+    assert_eq!(disk.borrow().used_space(), 0);
+
+    let read2 = disk.borrow_mut().read(50, recorder_id);
+    sim.step_until_no_events();
+    assert_eq!(recorder.borrow().failed.len(), 2);
+    assert_eq!(recorder.borrow().failed[1].0, read2);
+    assert!(recorder.borrow().failed[1].2.starts_with("injected fault"));
+}
+
+// Identical concurrent reads are completed together by a single internal event
+#[test]
+fn disk_simultaneous_completions() {
+    let mut sim = Simulation::new(SEED);
+
+    let (recorder, recorder_id) = make_recorder(&mut sim, "User");
+    let disk = make_simple_disk(&mut sim, "Disk-1");
+
+    let reads: Vec<u64> = (0..4).map(|_| disk.borrow_mut().read(25, recorder_id)).collect();
+    let submitted_events = sim.event_count();
+    sim.step_until_no_events();
+
+    let completed: Vec<(u64, f64)> = recorder.borrow().completed.iter().map(|c| (c.0, c.1)).collect();
+    assert_eq!(completed, reads.iter().map(|id| (*id, 1.)).collect::<Vec<_>>());
+    assert_eq!(sim.event_count(), submitted_events + reads.len() as u64);
+}
+
+// Flush waits only for the writes in progress at the time of the request
+#[test]
+fn disk_flush() {
+    let mut sim = Simulation::new(SEED);
+
+    let (recorder, recorder_id) = make_recorder(&mut sim, "User");
+    let disk = make_simple_disk(&mut sim, "Disk-1");
+
+    let empty_flush = disk.borrow_mut().flush(recorder_id);
+    let write1 = disk.borrow_mut().write(50, recorder_id);
+    let flush = disk.borrow_mut().flush(recorder_id);
+    sim.step_for_duration(0.25);
+    // the new write slows down the first one but is not waited for
+    let write2 = disk.borrow_mut().write(50, recorder_id);
+    let late_flush = disk.borrow_mut().flush(recorder_id);
+    sim.step_until_no_events();
+
+    let recorder = recorder.borrow();
+    assert_eq!(
+        recorder.flushes,
+        vec![(empty_flush, 0.), (flush, 0.75), (late_flush, 1.)]
+    );
+    let completed: Vec<(u64, f64)> = recorder.completed.iter().map(|c| (c.0, c.1)).collect();
+    assert_eq!(completed, vec![(write1, 0.75), (write2, 1.)]);
+}
+
+#[test]
+fn disk_tagged_requests() {
+    let mut sim = Simulation::new(SEED);
+
+    let (recorder, recorder_id) = make_recorder(&mut sim, "User");
+    let disk1 = make_simple_disk(&mut sim, "Disk-1");
+    let disk2 = make_simple_disk(&mut sim, "Disk-2");
+
+    let read = disk1.borrow_mut().read_tagged(10, recorder_id, 7);
+    let write = disk1.borrow_mut().write_tagged(20, recorder_id, 8);
+    let untagged = disk1.borrow_mut().read(30, recorder_id);
+    sim.step_for_duration(0.05);
+    // the tags are kept when outstanding operations are moved to another disk
+    let outstanding = disk1.borrow_mut().export_outstanding();
+    disk2.borrow_mut().import_outstanding(outstanding).unwrap();
+    sim.step_until_no_events();
+
+    let mut tags = recorder.borrow().tags.clone();
+    tags.sort();
+    assert_eq!(tags, vec![(read, Some(7)), (write, Some(8)), (untagged, None)]);
+}
+
+#[test]
+fn disk_read_deadline() {
+    let mut sim = Simulation::new(SEED);
+
+    let (recorder, recorder_id) = make_recorder(&mut sim, "User");
+    let disk = make_simple_disk(&mut sim, "Disk-1");
+
+    let in_time = disk.borrow_mut().read_with_deadline(50, recorder_id, 1.);
+    sim.step_until_no_events();
+    // the reads share the bandwidth, so the first one cannot complete by the deadline
+    let late = disk.borrow_mut().read_with_deadline(50, recorder_id, 1.1);
+    let other = disk.borrow_mut().read(50, recorder_id);
+    sim.step_until_no_events();
+
+    let recorder = recorder.borrow();
+    let completed: Vec<(u64, f64)> = recorder.completed.iter().map(|c| (c.0, c.1)).collect();
+    // the other read gets the whole bandwidth after the late one is removed
+    assert_eq!(completed, vec![(in_time, 0.5), (other, 1.3)]);
+    assert_eq!(recorder.failed.len(), 1);
+    assert_eq!(recorder.failed[0].0, late);
+    assert_eq!(recorder.failed[0].1, 1.1);
+    assert_eq!(disk.borrow().active_read_count(), 0);
+}
+
+fn make_striped_storage(sim: &mut Simulation, disks: Vec<Rc<RefCell<Disk>>>) -> Rc<RefCell<StripedStorage>> {
+    let storage = rc!(refcell!(StripedStorage::new(disks, 10, sim.create_context("Volume"))));
+    sim.add_handler("Volume", storage.clone());
+    storage
+}
+
+// Parts of a request are processed by member disks in parallel
+#[test]
+fn striped_storage_bandwidth_scaling() {
+    for disk_count in [1, 2, 4] {
+        let mut sim = Simulation::new(SEED);
+        let (recorder, recorder_id) = make_recorder(&mut sim, "User");
+        let disks = (0..disk_count)
+            .map(|i| {
+                let name = format!("Disk-{}", i);
+                let disk = rc!(refcell!(
+                    DiskBuilder::simple(1000, DISK_READ_BW, DISK_WRITE_BW).build(sim.create_context(&name))
+                ));
+                sim.add_handler(name, disk.clone());
+                disk
+            })
+            .collect();
+        let storage = make_striped_storage(&mut sim, disks);
+        assert_eq!(storage.borrow().capacity(), 1000 * disk_count);
+        assert_eq!(storage.borrow().estimated_read_time(400), 4. / disk_count as f64);
+
+        let read = storage.borrow_mut().read(400, recorder_id);
+        sim.step_until_no_events();
+
+        let recorder = recorder.borrow();
+        assert_eq!(recorder.completed.len(), 1);
+        assert_eq!(recorder.completed[0].0, read);
+        assert_eq!(recorder.completed[0].1, 4. / disk_count as f64);
+        assert_eq!(recorder.completed[0].2, storage.borrow().id());
+        assert_eq!(recorder.timings[0], (read, 0., 4. / disk_count as f64));
+    }
+}
+
+// Writes take space on all member disks, and failed writes do not take any space
+#[test]
+fn striped_storage_write() {
+    let mut sim = Simulation::new(SEED);
+
+    let (recorder, recorder_id) = make_recorder(&mut sim, "User");
+    let disk1 = make_simple_disk(&mut sim, "Disk-1");
+    let disk2 = make_simple_disk(&mut sim, "Disk-2");
+    let storage = make_striped_storage(&mut sim, vec![disk1.clone(), disk2.clone()]);
+
+    assert_eq!(storage.borrow().split(155), vec![80, 75]);
+    let write = storage.borrow_mut().write(155, recorder_id);
+    sim.step_until_no_events();
+    assert_eq!(recorder.borrow().completed[0].0, write);
+    assert_eq!(recorder.borrow().completed[0].1, 0.8);
+    assert_eq!(disk1.borrow().used_space(), 80);
+    assert_eq!(disk2.borrow().used_space(), 75);
+    assert_eq!(
+        storage.borrow().info(),
+        StorageInfo {
+            capacity: 200,
+            used_space: 155,
+            free_space: 45,
+        }
+    );
+
+    // the part on the first disk does not fit even though the total free space is enough
+    let failed = storage.borrow_mut().write(45, recorder_id);
+    sim.step_until_no_events();
+    assert_eq!(recorder.borrow().failed[0].0, failed);
+    assert_eq!(storage.borrow().used_space(), 155);
+
+    assert!(storage.borrow_mut().mark_free(155).is_ok());
+    assert_eq!(storage.borrow().used_space(), 0);
+    assert!(storage.borrow_mut().mark_free(10).is_err());
+}
+
+// Failure of a part on one disk cancels the other parts and releases their space
+#[test]
+fn striped_storage_disk_failure() {
+    let mut sim = Simulation::new(SEED);
+
+    let (recorder, recorder_id) = make_recorder(&mut sim, "User");
+    let disk1 = make_simple_disk(&mut sim, "Disk-1");
+    let disk2 = rc!(refcell!(DiskBuilder::simple(
+        DISK_CAPACITY,
+        DISK_READ_BW,
+        DISK_WRITE_BW
+    )
+    .fail_after(0.25)
+    .build(sim.create_context("Disk-2"))));
+    sim.add_handler("Disk-2", disk2.clone());
+    let storage = make_striped_storage(&mut sim, vec![disk1.clone(), disk2]);
+
+    let write = storage.borrow_mut().write(100, recorder_id);
+    sim.step_until_no_events();
+
+    let recorder = recorder.borrow();
+    assert!(recorder.completed.is_empty());
+    assert_eq!(recorder.failed.len(), 1);
+    assert_eq!((recorder.failed[0].0, recorder.failed[0].1), (write, 0.25));
+    assert_eq!(disk1.borrow().active_write_count(), 0);
+    assert_eq!(storage.borrow().used_space(), 0);
+}
+
+// Flush of the volume waits for the flushes of all member disks
+#[test]
+fn striped_storage_flush() {
+    let mut sim = Simulation::new(SEED);
+
+    let (recorder, recorder_id) = make_recorder(&mut sim, "User");
+    let disk1 = make_simple_disk(&mut sim, "Disk-1");
+    let disk2 = make_simple_disk(&mut sim, "Disk-2");
+    let storage = make_striped_storage(&mut sim, vec![disk1, disk2]);
+
+    storage.borrow_mut().write(155, recorder_id);
+    let flush = storage.borrow_mut().flush(recorder_id);
+    sim.step_until_no_events();
+
+    let recorder = recorder.borrow();
+    assert_eq!(recorder.flushes, vec![(flush, 0.8)]);
+    assert_eq!(recorder.flushes[0].1, recorder.completed[0].1);
+}
+
+fn make_mirror_storage(sim: &mut Simulation, disks: Vec<Rc<RefCell<Disk>>>) -> Rc<RefCell<MirrorStorage>> {
+    let storage = rc!(refcell!(MirrorStorage::new(disks, sim.create_context("Mirror"))));
+    sim.add_handler("Mirror", storage.clone());
+    storage
+}
+
+// Writes go to all mirrors, while concurrent reads are spread over the live mirrors
+#[test]
+fn mirror_storage_read_balancing() {
+    let mut sim = Simulation::new(SEED);
+
+    let (recorder, recorder_id) = make_recorder(&mut sim, "User");
+    let disk1 = make_simple_disk(&mut sim, "Disk-1");
+    let disk2 = make_simple_disk(&mut sim, "Disk-2");
+    let storage = make_mirror_storage(&mut sim, vec![disk1.clone(), disk2.clone()]);
+    assert_eq!(storage.borrow().capacity(), DISK_CAPACITY);
+
+    storage.borrow_mut().write(50, recorder_id);
+    sim.step_until_no_events();
+    assert_eq!(recorder.borrow().completed.len(), 1);
+    assert_eq!(recorder.borrow().completed[0].1, 0.5);
+    assert_eq!(storage.borrow().used_space(), 50);
+    assert_eq!(disk1.borrow().used_space(), 50);
+    assert_eq!(disk2.borrow().used_space(), 50);
+
+    // each mirror serves one of the reads
+    storage.borrow_mut().read(50, recorder_id);
+    storage.borrow_mut().read(50, recorder_id);
+    assert_eq!(disk1.borrow().active_read_count(), 1);
+    assert_eq!(disk2.borrow().active_read_count(), 1);
+    sim.step_until_no_events();
+    assert_eq!(recorder.borrow().completed[1].1, 1.);
+    assert_eq!(recorder.borrow().completed[2].1, 1.);
+
+    // only the surviving mirror serves reads after the failure
+    storage.borrow_mut().fail_mirror(0);
+    assert_eq!(storage.borrow().live_mirror_count(), 1);
+    storage.borrow_mut().read(50, recorder_id);
+    storage.borrow_mut().read(50, recorder_id);
+    assert_eq!(disk1.borrow().active_read_count(), 0);
+    sim.step_until_no_events();
+    assert_eq!(recorder.borrow().completed[3].1, 2.);
+    assert_eq!(recorder.borrow().completed[4].1, 2.);
+
+    assert!(storage.borrow_mut().mark_free(50).is_ok());
+    assert_eq!(storage.borrow().used_space(), 0);
+    assert_eq!(disk2.borrow().used_space(), 0);
+}
+
+// Reads from the dead mirror are resubmitted to the surviving one, and requests fail without live mirrors
+#[test]
+fn mirror_storage_disk_failure() {
+    let mut sim = Simulation::new(SEED);
+
+    let (recorder, recorder_id) = make_recorder(&mut sim, "User");
+    let disk1 = rc!(refcell!(DiskBuilder::simple(
+        DISK_CAPACITY,
+        DISK_READ_BW,
+        DISK_WRITE_BW
+    )
+    .fail_after(0.25)
+    .build(sim.create_context("Disk-1"))));
+    sim.add_handler("Disk-1", disk1.clone());
+    let disk2 = make_simple_disk(&mut sim, "Disk-2");
+    let storage = make_mirror_storage(&mut sim, vec![disk1, disk2]);
+
+    let write = storage.borrow_mut().write(50, recorder_id);
+    let read = storage.borrow_mut().read(50, recorder_id);
+    sim.step_until_no_events();
+
+    assert!(storage.borrow().is_mirror_failed(0));
+    assert!(recorder.borrow().failed.is_empty());
+    assert_eq!(recorder.borrow().completed.len(), 2);
+    assert_eq!(recorder.borrow().completed[0].0, write);
+    assert_eq!(recorder.borrow().completed[0].1, 0.5);
+    assert_eq!(recorder.borrow().completed[1].0, read);
+    assert_eq!(recorder.borrow().completed[1].1, 0.75);
+    assert_eq!(recorder.borrow().timings[1], (read, 0., 0.75));
+
+    storage.borrow_mut().fail_mirror(1);
+    let failed = storage.borrow_mut().read(10, recorder_id);
+    sim.step_until_no_events();
+    assert_eq!(recorder.borrow().failed.len(), 1);
+    assert_eq!(recorder.borrow().failed[0].0, failed);
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+fn make_storage_pool(
+    sim: &mut Simulation,
+    disk_count: usize,
+    policy: Box<dyn PlacementPolicy>,
+) -> (Rc<RefCell<StoragePool>>, Vec<Rc<RefCell<Disk>>>) {
+    let disks: Vec<_> = (0..disk_count)
+        .map(|i| make_simple_disk(sim, &format!("Disk-{}", i)))
+        .collect();
+    let pool = rc!(refcell!(StoragePool::new(
+        disks.clone(),
+        policy,
+        sim.create_context("Pool")
+    )));
+    sim.add_handler("Pool", pool.clone());
+    (pool, disks)
+}
+
+// Each request goes entirely to one disk, and the events are forwarded with the ids returned by the pool
+#[test]
+fn storage_pool_round_robin() {
+    let mut sim = Simulation::new(SEED);
+    let (recorder, recorder_id) = make_recorder(&mut sim, "User");
+    let (pool, disks) = make_storage_pool(&mut sim, 2, boxed!(RoundRobinPlacement::new()));
+
+    let writes: Vec<u64> = (0..4).map(|_| pool.borrow_mut().write(10, recorder_id)).collect();
+    let failed = pool.borrow_mut().write(DISK_CAPACITY, recorder_id);
+    let batch = pool.borrow_mut().read_batch(&[10, 10], recorder_id);
+    let flush = pool.borrow_mut().flush(recorder_id);
+    sim.step_until_no_events();
+
+    let recorder = recorder.borrow();
+    let mut completed: Vec<(u64, f64)> = recorder.completed.iter().map(|c| (c.0, c.1)).collect();
+    completed.sort_by_key(|c| c.0);
+    assert_eq!(
+        completed,
+        vec![(writes[0], 0.2), (writes[1], 0.2), (writes[2], 0.2), (writes[3], 0.2)]
+    );
+    assert!(recorder.completed.iter().all(|c| c.2 == pool.borrow().id()));
+    assert_eq!(recorder.failed.len(), 1);
+    assert_eq!(recorder.failed[0].0, failed);
+    assert_eq!(recorder.batches, vec![(batch, 0.2, 20)]);
+    assert_eq!(recorder.flushes, vec![(flush, 0.2)]);
+    for disk in disks {
+        assert_eq!(disk.borrow().used_space(), 20);
+    }
+    assert_eq!(pool.borrow().used_space(), 40);
+}
+
+#[test]
+fn storage_pool_placement_policies() {
+    let mut sim = Simulation::new(SEED);
+    let (_, recorder_id) = make_recorder(&mut sim, "User");
+    let (pool, disks) = make_storage_pool(&mut sim, 2, boxed!(LeastLoadedPlacement::new()));
+    pool.borrow_mut().write(50, recorder_id);
+    pool.borrow_mut().write(10, recorder_id);
+    pool.borrow_mut().write(10, recorder_id);
+    assert_eq!(disks[0].borrow().used_space(), 50);
+    assert_eq!(disks[1].borrow().used_space(), 20);
+    assert_eq!(pool.borrow().loads()[1].active_writes, 2);
+
+    let mut sim = Simulation::new(SEED);
+    let (_, recorder_id) = make_recorder(&mut sim, "User");
+    let (pool, disks) = make_storage_pool(&mut sim, 4, boxed!(RequesterHashPlacement::new()));
+    for _ in 0..3 {
+        pool.borrow_mut().write(10, recorder_id);
+    }
+    let used: Vec<u64> = disks.iter().map(|disk| disk.borrow().used_space()).collect();
+    assert_eq!(used.iter().filter(|u| **u == 30).count(), 1);
+    assert_eq!(used.iter().sum::<u64>(), 30);
+}
+
+fn make_cached_storage(
+    sim: &mut Simulation,
+    disk: Rc<RefCell<Disk>>,
+    write_policy: WritePolicy,
+) -> Rc<RefCell<CachedStorage<Disk>>> {
+    let cache = rc!(refcell!(CachedStorage::new(
+        disk,
+        50,
+        0.5,
+        write_policy,
+        sim.create_context("Cache")
+    )));
+    sim.add_handler("Cache", cache.clone());
+    cache
+}
+
+// Reads of cached items complete after the hit latency, and the least recently used items are evicted
+#[test]
+fn cached_storage_lru_eviction() {
+    let mut sim = Simulation::new(SEED);
+
+    let (recorder, recorder_id) = make_recorder(&mut sim, "User");
+    let disk = make_simple_disk(&mut sim, "Disk");
+    let cache = make_cached_storage(&mut sim, disk.clone(), WritePolicy::WriteThrough);
+
+    cache.borrow_mut().write(1, 25, recorder_id);
+    sim.step_until_no_events();
+    cache.borrow_mut().write(2, 25, recorder_id);
+    sim.step_until_no_events();
+    assert_eq!(cache.borrow().used_space(), 50);
+    assert_eq!(disk.borrow().used_space(), 50);
+
+    // hit
+    cache.borrow_mut().read(1, 25, recorder_id);
+    sim.step_until_no_events();
+    // evicts item 2, which is less recently used than item 1
+    cache.borrow_mut().write(3, 25, recorder_id);
+    sim.step_until_no_events();
+    assert!(cache.borrow().contains(1));
+    assert!(!cache.borrow().contains(2));
+    assert!(cache.borrow().contains(3));
+    // miss
+    cache.borrow_mut().read(2, 25, recorder_id);
+    sim.step_until_no_events();
+    // hit
+    cache.borrow_mut().read(3, 25, recorder_id);
+    sim.step_until_no_events();
+
+    let times: Vec<f64> = recorder.borrow().completed.iter().map(|c| c.1).collect();
+    assert_eq!(times, vec![0.25, 0.5, 1., 1.25, 1.5, 2.]);
+    assert_eq!(cache.borrow().hits(), 2);
+    assert_eq!(cache.borrow().misses(), 1);
+    assert_eq!(cache.borrow().hit_rate(), 2. / 3.);
+    assert_eq!(disk.borrow().used_space(), 75);
+}
+
+// Write-back cache writes dirty items to the backing storage only on eviction or flush
+#[test]
+fn cached_storage_write_back() {
+    let mut sim = Simulation::new(SEED);
+
+    let (recorder, recorder_id) = make_recorder(&mut sim, "User");
+    let disk = make_simple_disk(&mut sim, "Disk");
+    let cache = make_cached_storage(&mut sim, disk.clone(), WritePolicy::WriteBack);
+
+    cache.borrow_mut().write(1, 25, recorder_id);
+    cache.borrow_mut().write(2, 25, recorder_id);
+    sim.step_until_no_events();
+    assert_eq!(recorder.borrow().completed.len(), 2);
+    assert_eq!(recorder.borrow().completed[1].1, 0.5);
+    assert_eq!(disk.borrow().used_space(), 0);
+
+    // evicts dirty item 1
+    cache.borrow_mut().write(3, 25, recorder_id);
+    assert_eq!(disk.borrow().used_space(), 25);
+    sim.step_until_no_events();
+    assert_eq!(recorder.borrow().completed.len(), 3);
+
+    cache.borrow_mut().flush();
+    assert_eq!(disk.borrow().used_space(), 75);
+    sim.step_until_no_events();
+    // all items are clean now
+    cache.borrow_mut().flush();
+    assert_eq!(disk.borrow().used_space(), 75);
+
+    // item larger than the cache is written directly to the backing storage
+    cache.borrow_mut().write(4, 60, recorder_id);
+    sim.step_until_no_events();
+    assert_eq!(recorder.borrow().failed.len(), 1);
+    assert_eq!(recorder.borrow().completed.len(), 3);
+    assert_eq!(cache.borrow().hit_rate(), 0.);
+}
+
+#[test]
+fn cached_storage_write_back_item_larger_than_cache() {
+    let mut sim = Simulation::new(SEED);
+
+    let (recorder, recorder_id) = make_recorder(&mut sim, "User");
+    let disk = make_simple_disk(&mut sim, "Disk");
+    let cache = make_cached_storage(&mut sim, disk.clone(), WritePolicy::WriteBack);
+
+    // item is written to the backing storage exactly once
+    cache.borrow_mut().write(1, 60, recorder_id);
+    assert_eq!(disk.borrow().used_space(), 60);
+    sim.step_until_no_events();
+    assert_eq!(recorder.borrow().completed.len(), 1);
+    assert!(recorder.borrow().failed.is_empty());
+    assert!(!cache.borrow().contains(1));
+}
+
+#[test]
+fn cached_storage_write_back_replaced_by_item_larger_than_cache() {
+    let mut sim = Simulation::new(SEED);
+
+    let (recorder, recorder_id) = make_recorder(&mut sim, "User");
+    let disk = make_simple_disk(&mut sim, "Disk");
+    let cache = make_cached_storage(&mut sim, disk.clone(), WritePolicy::WriteBack);
+
+    cache.borrow_mut().write(1, 10, recorder_id);
+    assert_eq!(disk.borrow().used_space(), 0);
+    // dirty cached version of the item is written back, then the new one is written directly
+    cache.borrow_mut().write(1, 60, recorder_id);
+    assert_eq!(disk.borrow().used_space(), 70);
+    sim.step_until_no_events();
+    assert_eq!(recorder.borrow().completed.len(), 2);
+    assert!(recorder.borrow().failed.is_empty());
+    assert!(!cache.borrow().contains(1));
+    assert_eq!(cache.borrow().used_space(), 0);
+}
+
+#[test]
+fn instant_storage() {
+    let mut sim = Simulation::new(SEED);
+
+    let (recorder, recorder_id) = make_recorder(&mut sim, "User");
+    let mut storage = InstantStorage::new(DISK_CAPACITY, sim.create_context("Storage"));
+
+    sim.step_for_duration(1.);
+    let write = storage.write(60, recorder_id);
+    let failed_write = storage.write(50, recorder_id);
+    let read = storage.read(DISK_CAPACITY, recorder_id);
+    let failed_read = storage.read(DISK_CAPACITY + 1, recorder_id);
+    let batch = storage.write_batch(&[10, 20], recorder_id);
+    let flush = storage.flush(recorder_id);
+    assert_eq!(storage.used_space(), 90);
+    assert_eq!(storage.estimated_write_time(10), 0.);
+    sim.step_until_no_events();
+
+    let recorder = recorder.borrow();
+    assert_eq!(recorder.timings, vec![(write, 1., 1.), (read, 1., 1.)]);
+    let failed: Vec<(u64, f64)> = recorder.failed.iter().map(|f| (f.0, f.1)).collect();
+    assert_eq!(failed, vec![(failed_write, 1.), (failed_read, 1.)]);
+    assert_eq!(recorder.batches, vec![(batch, 1., 30)]);
+    assert_eq!(recorder.flushes, vec![(flush, 1.)]);
+    assert_eq!(sim.time(), 1.);
+}
+
+#[test]
+fn typed_request_ids() {
+    let mut sim = Simulation::new(SEED);
+
+    let (recorder, recorder_id) = make_recorder(&mut sim, "User");
+
+    let disk = make_simple_disk(&mut sim, "Disk-1");
+
+    let read = disk.borrow_mut().read(10, recorder_id);
+    let write = disk.borrow_mut().write(10, recorder_id);
+    let failed_read = disk.borrow_mut().read(DISK_CAPACITY + 1, recorder_id);
+    let failed_write = disk.borrow_mut().write(DISK_CAPACITY + 1, recorder_id);
+    let flush = disk.borrow_mut().flush(recorder_id);
+    sim.step_until_no_events();
+
+    let recorder = recorder.borrow();
+    assert_eq!(recorder.completed.len(), 2);
+    assert_eq!(recorder.failed.len(), 2);
+    let typed_request_ids = &recorder.typed_request_ids;
+    assert_eq!(typed_request_ids.len(), 5);
+    for expected in [
+        RequestId::new(OpKind::Read, read),
+        RequestId::new(OpKind::Write, write),
+        RequestId::new(OpKind::Read, failed_read),
+        RequestId::new(OpKind::Write, failed_write),
+        RequestId::new(OpKind::Flush, flush),
+    ] {
+        assert!(typed_request_ids.contains(&expected), "{:?} is not received", expected);
+    }
+    assert!(typed_request_ids.iter().all(|id| u64::from(*id) == id.raw()));
+
+    // the same raw id of requests of different kinds gives different typed ids
+    let read_failed = DataReadFailed {
+        request_id: write,
+        error: String::new(),
+    };
+    let write_failed = DataWriteFailed {
+        request_id: write,
+        error: String::new(),
+    };
+    assert_ne!(read_failed.typed_request_id(), write_failed.typed_request_id());
+}
@@ -40,7 +40,7 @@ fn main() {
 
     let mut sim = DagSimulation::new(
         123,
-        read_resource_configs(&args.system),
+        read_resource_configs(&args.system).unwrap_or_else(|e| panic!("{}", e)),
         read_network_config(&args.system),
         rc!(refcell!(SimpleScheduler::new())),
         Config {
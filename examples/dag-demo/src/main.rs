@@ -87,7 +87,7 @@ fn main() {
 
     let args = Args::parse();
     let dag = DAG::from_file(&args.dag, &ParserConfig::default());
-    let resource_configs = read_resource_configs(&args.system);
+    let resource_configs = read_resource_configs(&args.system).unwrap_or_else(|e| panic!("{}", e));
     let network_config = read_network_config(&args.system);
     let data_transfer_mode = match args.data_transfer_mode.as_str() {
         "via-master-node" => DataTransferMode::ViaMasterNode,
@@ -140,6 +140,11 @@ fn run_experiments(args: &Args) {
                     speed,
                     cores,
                     memory,
+                    numa: None,
+                    core_speeds: None,
+                    gpus: 0,
+                    gpu_memory: 0,
+                    power: None,
                 });
             }
         }
@@ -16,7 +16,7 @@ use dslab_faas::extra::azure_trace_2019::{
 use dslab_faas::extra::resolvers::{extra_coldstart_policy_resolver, extra_scheduler_resolver};
 use dslab_faas::parallel::parallel_simulation_raw;
 
-use crate::plot::plot_results;
+use crate::plot::{plot_results, MetricPoint, PlotConfig};
 
 #[derive(Serialize, Deserialize)]
 struct ExperimentConfig {
@@ -32,10 +32,13 @@ struct Args {
     /// Path to a simulation config in YAML format.
     #[arg(long)]
     config: String,
-    /// Plot output path.
+    /// Plot output path (PNG or SVG depending on the file extension).
     #[arg(long)]
     plot: String,
-    /// Dump final metrics to given file.
+    /// Number of runs with different random seeds for each configuration.
+    #[arg(long, default_value_t = 1)]
+    seeds: u64,
+    /// Dump final metrics (averaged over the runs) to given file.
     #[arg(long)]
     dump: Option<String>,
 }
@@ -49,41 +52,12 @@ fn main() {
     let rps_vec = (1..15).map(|x| x as f64).collect::<Vec<f64>>();
     let mut points = vec![Vec::with_capacity(rps_vec.len()); schedulers.len()];
     for rps in rps_vec.iter() {
-        let trace_config = Azure2019TraceConfig {
-            time_period: 60,
-            duration_generator: DurationGenerator::PrefittedLognormal,
-            start_generator: StartGenerator::PoissonFit,
-            app_preferences: vec![AppPreference::new(1, 0.02, 0.05), AppPreference::new(49, 0.45, 0.55)],
-            force_fixed_memory: Some(256),
-            rps: Some(*rps),
-            ..Default::default()
-        };
-        let trace = Box::new(process_azure_2019_trace(Path::new(&args.trace), trace_config));
-        println!(
-            "trace processed successfully, got {} invocations at {} RPS",
-            trace.trace_records.len(),
-            *rps
-        );
-        let configs: Vec<_> = schedulers
-            .iter()
-            .map(|x| {
-                let mut config = base_config.clone();
-                config.scheduler = x.to_string();
-                config
-            })
-            .collect();
-        let resolvers = ConfigParamResolvers {
-            coldstart_policy_resolver: Box::new(extra_coldstart_policy_resolver),
-            scheduler_resolver: Box::new(extra_scheduler_resolver),
-            ..Default::default()
-        };
-        let mut stats = parallel_simulation_raw(configs, resolvers, vec![trace], vec![1]);
-        for (i, s) in stats.drain(..).enumerate() {
-            let inv = s.global_stats.invocation_stats;
-            points[i].push([
-                inv.rel_total_slowdown.quantile(0.99) + 1.,
-                (inv.cold_starts as f64) / (inv.invocations as f64) * 100.,
-            ]);
+        let mut samples = vec![[Vec::new(), Vec::new()]; schedulers.len()];
+        for seed in 1..=args.seeds {
+            run_experiment(&args.trace, &schedulers, &base_config, *rps, seed, &mut samples);
+        }
+        for (i, s) in samples.iter().enumerate() {
+            points[i].push([MetricPoint::from_samples(&s[0]), MetricPoint::from_samples(&s[1])]);
         }
     }
     if let Some(s) = args.dump {
@@ -91,9 +65,55 @@ fn main() {
         writeln!(&mut out, "scheduler,rps,99% slowdown,cold start %").unwrap();
         for (sched, pts) in std::iter::zip(schedulers.iter(), points.iter()) {
             for (rps, pt) in std::iter::zip(rps_vec.iter(), pts.iter()) {
-                writeln!(&mut out, "{},{},{:.4},{:.4}", sched, rps, pt[0], pt[1]).unwrap();
+                writeln!(&mut out, "{},{},{:.4},{:.4}", sched, rps, pt[0].mean, pt[1].mean).unwrap();
             }
         }
     }
-    plot_results(&args.plot, &schedulers, &rps_vec, &points);
+    plot_results(&args.plot, &PlotConfig::default(), &schedulers, &rps_vec, &points);
+}
+
+/// Runs all schedulers on a trace generated with given RPS and seed, and appends the metrics to `samples`.
+fn run_experiment(
+    trace: &str,
+    schedulers: &[String],
+    base_config: &RawConfig,
+    rps: f64,
+    seed: u64,
+    samples: &mut [[Vec<f64>; 2]],
+) {
+    let trace_config = Azure2019TraceConfig {
+        time_period: 60,
+        duration_generator: DurationGenerator::PrefittedLognormal,
+        start_generator: StartGenerator::PoissonFit,
+        app_preferences: vec![AppPreference::new(1, 0.02, 0.05), AppPreference::new(49, 0.45, 0.55)],
+        force_fixed_memory: Some(256),
+        rps: Some(rps),
+        random_seed: seed,
+        ..Default::default()
+    };
+    let trace = Box::new(process_azure_2019_trace(Path::new(trace), trace_config));
+    println!(
+        "trace processed successfully, got {} invocations at {} RPS (seed {})",
+        trace.trace_records.len(),
+        rps,
+        seed
+    );
+    let configs: Vec<_> = schedulers
+        .iter()
+        .map(|x| {
+            let mut config = base_config.clone();
+            config.scheduler = x.to_string();
+            config
+        })
+        .collect();
+    let resolvers = ConfigParamResolvers {
+        coldstart_policy_resolver: Box::new(extra_coldstart_policy_resolver),
+        scheduler_resolver: Box::new(extra_scheduler_resolver),
+        ..Default::default()
+    };
+    let mut stats = parallel_simulation_raw(configs, resolvers, vec![trace], vec![seed]);
+    for (i, s) in stats.drain(..).enumerate() {
+        samples[i][0].push(s.relative_slowdown_percentile(99.));
+        samples[i][1].push(s.cold_start_fraction() * 100.);
+    }
 }
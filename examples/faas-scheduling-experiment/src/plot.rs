@@ -1,50 +1,167 @@
 use std::iter::zip;
 
+use plotters::coord::Shift;
 use plotters::prelude::*;
 
 const METRICS: &[&str] = &["99% relative slowdown", "cold start fraction (%)"];
 
-pub(crate) fn plot_results(plot: &str, labels: &[String], rps: &[f64], points: &[Vec<[f64; 2]>]) {
+/// Metric value aggregated over several runs.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct MetricPoint {
+    pub mean: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+impl MetricPoint {
+    /// Aggregates metric values obtained in several runs.
+    pub fn from_samples(samples: &[f64]) -> Self {
+        assert!(!samples.is_empty(), "at least one sample is required");
+        Self {
+            mean: samples.iter().sum::<f64>() / samples.len() as f64,
+            min: samples.iter().copied().fold(f64::MAX, f64::min),
+            max: samples.iter().copied().fold(f64::MIN, f64::max),
+        }
+    }
+}
+
+/// Position of the legend on the chart, converted to [`SeriesLabelPosition`] when the chart is drawn.
+#[derive(Clone, Copy, Debug)]
+#[allow(dead_code)]
+pub(crate) enum LegendPosition {
+    UpperLeft,
+    MiddleLeft,
+    LowerLeft,
+    UpperMiddle,
+    MiddleMiddle,
+    LowerMiddle,
+    UpperRight,
+    MiddleRight,
+    LowerRight,
+}
+
+impl From<LegendPosition> for SeriesLabelPosition {
+    fn from(position: LegendPosition) -> Self {
+        match position {
+            LegendPosition::UpperLeft => SeriesLabelPosition::UpperLeft,
+            LegendPosition::MiddleLeft => SeriesLabelPosition::MiddleLeft,
+            LegendPosition::LowerLeft => SeriesLabelPosition::LowerLeft,
+            LegendPosition::UpperMiddle => SeriesLabelPosition::UpperMiddle,
+            LegendPosition::MiddleMiddle => SeriesLabelPosition::MiddleMiddle,
+            LegendPosition::LowerMiddle => SeriesLabelPosition::LowerMiddle,
+            LegendPosition::UpperRight => SeriesLabelPosition::UpperRight,
+            LegendPosition::MiddleRight => SeriesLabelPosition::MiddleRight,
+            LegendPosition::LowerRight => SeriesLabelPosition::LowerRight,
+        }
+    }
+}
+
+/// Plot size and style settings.
+pub(crate) struct PlotConfig {
+    /// Image width in pixels.
+    pub width: u32,
+    /// Image height in pixels.
+    pub height: u32,
+    /// Font size of axis and legend labels.
+    pub font_size: u32,
+    /// Position of the legend.
+    pub legend_position: LegendPosition,
+}
+
+impl Default for PlotConfig {
+    fn default() -> Self {
+        Self {
+            width: 1600,
+            height: 900,
+            font_size: 20,
+            legend_position: LegendPosition::UpperLeft,
+        }
+    }
+}
+
+/// Plots the results to the given file. SVG is used if the file has `.svg` extension, otherwise PNG.
+///
+/// Each point is drawn at the mean value with a vertical error bar spanning the range of values over the runs.
+pub(crate) fn plot_results(
+    plot: &str,
+    config: &PlotConfig,
+    labels: &[String],
+    rps: &[f64],
+    points: &[Vec<[MetricPoint; 2]>],
+) {
+    let size = (config.width, config.height);
+    if plot.ends_with(".svg") {
+        draw_results(
+            SVGBackend::new(plot, size).into_drawing_area(),
+            config,
+            labels,
+            rps,
+            points,
+        );
+    } else {
+        draw_results(
+            BitMapBackend::new(plot, size).into_drawing_area(),
+            config,
+            labels,
+            rps,
+            points,
+        );
+    }
+}
+
+fn draw_results<DB: DrawingBackend>(
+    root_area: DrawingArea<DB, Shift>,
+    config: &PlotConfig,
+    labels: &[String],
+    rps: &[f64],
+    points: &[Vec<[MetricPoint; 2]>],
+) {
     let mut styles = Vec::with_capacity(labels.len());
     for i in 0..labels.len() {
         styles.push(Into::<ShapeStyle>::into(Palette99::pick(i)).filled());
     }
-    let root_area = BitMapBackend::new(plot, (1600, 900)).into_drawing_area();
     root_area.fill(&WHITE).unwrap();
     let tmp = root_area.split_vertically((50).percent());
     let areas: [_; 2] = [tmp.0, tmp.1];
     for idx in 0..2 {
         let max = points
             .iter()
-            .map(|v| v.iter().fold(0., |acc, x| f64::max(acc, x[idx])))
+            .map(|v| v.iter().fold(0., |acc, x| f64::max(acc, x[idx].max)))
             .fold(0., f64::max)
             * 1.1;
+        // the range must not be empty even if all values are zero, e.g. there are no cold starts at all
         let mut ctx = ChartBuilder::on(&areas[idx])
             .margin(20)
             .set_label_area_size(LabelAreaPosition::Left, 60)
             .set_label_area_size(LabelAreaPosition::Bottom, 40)
-            .build_cartesian_2d(rps[0]..rps.last().copied().unwrap(), 0.0..f64::min(max, 100.))
+            .build_cartesian_2d(rps[0]..rps.last().copied().unwrap(), 0.0..max.clamp(1., 100.))
             .unwrap();
         ctx.configure_mesh()
             .y_desc(METRICS[idx])
             .x_desc("requests per second")
-            .label_style(("sans-serif", 20))
+            .label_style(("sans-serif", config.font_size))
             .draw()
             .unwrap();
         for (i, pts) in points.iter().enumerate() {
             let style = styles[i];
             ctx.draw_series(
-                LineSeries::new(zip(rps.iter(), pts.iter()).map(|(x, y)| (*x, y[idx])), style).point_size(5),
+                LineSeries::new(zip(rps.iter(), pts.iter()).map(|(x, y)| (*x, y[idx].mean)), style).point_size(5),
             )
             .unwrap()
             .label(labels[i].clone())
             .legend(move |pos| Circle::new(pos, 5, style));
+            ctx.draw_series(
+                zip(rps.iter(), pts.iter())
+                    .filter(|(_, y)| y[idx].max > y[idx].min)
+                    .map(|(x, y)| ErrorBar::new_vertical(*x, y[idx].min, y[idx].mean, y[idx].max, style, 10)),
+            )
+            .unwrap();
         }
         ctx.configure_series_labels()
-            .position(SeriesLabelPosition::UpperLeft)
+            .position(config.legend_position.into())
             .border_style(BLACK)
             .background_style(WHITE.mix(0.8))
-            .label_font(("sans-serif", 20))
+            .label_font(("sans-serif", config.font_size))
             .draw()
             .unwrap();
     }
@@ -196,7 +196,7 @@ impl EventHandler for Worker {
             DataTransferCompleted { data } => {
                 self.on_data_transfer_completed(data);
             }
-            DataReadCompleted { request_id, size: _ } => {
+            DataReadCompleted { request_id, .. } => {
                 self.on_data_read_completed(request_id);
             }
             CompStarted { id, cores: _ } => {
@@ -205,7 +205,7 @@ impl EventHandler for Worker {
             CompFinished { id } => {
                 self.on_comp_finished(id);
             }
-            DataWriteCompleted { request_id, size: _ } => {
+            DataWriteCompleted { request_id, .. } => {
                 self.on_data_write_completed(request_id);
             }
         })
@@ -13,10 +13,10 @@ use dslab_faas::coldstart::{ColdStartPolicy, FixedTimeColdStartPolicy};
 use dslab_faas::config::{ConfigParamResolvers, RawConfig};
 use dslab_faas::extra::azure_trace_2019::{process_azure_2019_trace, AppPreference, Azure2019TraceConfig};
 use dslab_faas::extra::hybrid_histogram::HybridHistogramPolicy;
-use dslab_faas::parallel::parallel_simulation_raw;
+use dslab_faas::parallel::{parallel_simulation_raw, pareto_frontier, Objective};
 use dslab_faas::stats::SampleMetric;
 
-use crate::plot::plot_results;
+use crate::plot::{plot_results, PlotConfig};
 
 #[derive(Serialize, Deserialize)]
 struct ExperimentConfig {
@@ -51,7 +51,7 @@ struct Args {
     /// Path to a simulation config in YAML format.
     #[arg(long)]
     config: String,
-    /// Plot output path (if needed).
+    /// Plot output path (if needed), PNG or SVG depending on the file extension.
     #[arg(long)]
     plot: Option<String>,
     /// Dump final metrics to given file.
@@ -92,16 +92,19 @@ fn main() {
     let mut results = Vec::with_capacity(stats.len());
     for (i, s) in stats.drain(..).enumerate() {
         s.global_stats.print_summary(&policies[i]);
-        if args.plot.is_some() {
-            let mut apps: SampleMetric = Default::default();
-            for app_stats in s.app_stats.iter() {
-                apps.add((app_stats.cold_starts as f64) / (app_stats.invocations as f64) * 100.);
-            }
-            results.push((
-                apps.quantile(0.75),
-                s.global_stats.wasted_resource_time.get(0).unwrap().sum(),
-            ));
+        let mut apps: SampleMetric = Default::default();
+        for app_stats in s.app_stats.iter() {
+            apps.add((app_stats.cold_starts as f64) / (app_stats.invocations as f64) * 100.);
         }
+        results.push((
+            apps.quantile(0.75),
+            s.global_stats.wasted_resource_time.get(0).unwrap().sum(),
+        ));
+    }
+    let objectives: Vec<Objective<(f64, f64)>> = vec![Box::new(|r| r.0), Box::new(|r| r.1)];
+    println!("Pareto-optimal policies:");
+    for i in pareto_frontier(&results, &objectives) {
+        println!("- {}", policies[i]);
     }
     if let Some(s) = args.dump {
         let mut out = File::create(s).unwrap();
@@ -123,6 +126,6 @@ fn main() {
         for p in results.iter_mut() {
             p.1 = p.1 / base * 100.;
         }
-        plot_results(&plot, policies, results);
+        plot_results(&plot, &PlotConfig::default(), policies, results);
     }
 }
@@ -1,15 +1,115 @@
+use plotters::coord::Shift;
 use plotters::prelude::*;
 
-pub(crate) fn plot_results(plot: &str, mut labels: Vec<String>, mut points: Vec<(f64, f64)>) {
-    if let Some(ban) = labels.iter().position(|x| x.contains("unloading")) {
-        // can't plot no unloading policy because of infinite wasted memory
-        labels.remove(ban);
-        points.remove(ban);
+/// Points with wasted memory time exceeding the median by more than this factor are considered outliers.
+const OUTLIER_FACTOR: f64 = 5.;
+
+/// Position of the legend on the chart, converted to [`SeriesLabelPosition`] when the chart is drawn.
+#[derive(Clone, Copy, Debug)]
+#[allow(dead_code)]
+pub(crate) enum LegendPosition {
+    UpperLeft,
+    MiddleLeft,
+    LowerLeft,
+    UpperMiddle,
+    MiddleMiddle,
+    LowerMiddle,
+    UpperRight,
+    MiddleRight,
+    LowerRight,
+}
+
+impl From<LegendPosition> for SeriesLabelPosition {
+    fn from(position: LegendPosition) -> Self {
+        match position {
+            LegendPosition::UpperLeft => SeriesLabelPosition::UpperLeft,
+            LegendPosition::MiddleLeft => SeriesLabelPosition::MiddleLeft,
+            LegendPosition::LowerLeft => SeriesLabelPosition::LowerLeft,
+            LegendPosition::UpperMiddle => SeriesLabelPosition::UpperMiddle,
+            LegendPosition::MiddleMiddle => SeriesLabelPosition::MiddleMiddle,
+            LegendPosition::LowerMiddle => SeriesLabelPosition::LowerMiddle,
+            LegendPosition::UpperRight => SeriesLabelPosition::UpperRight,
+            LegendPosition::MiddleRight => SeriesLabelPosition::MiddleRight,
+            LegendPosition::LowerRight => SeriesLabelPosition::LowerRight,
+        }
+    }
+}
+
+/// Plot size and style settings.
+pub(crate) struct PlotConfig {
+    /// Image width in pixels.
+    pub width: u32,
+    /// Image height in pixels.
+    pub height: u32,
+    /// Font size of axis and legend labels.
+    pub font_size: u32,
+    /// Position of the legend.
+    pub legend_position: LegendPosition,
+}
+
+impl Default for PlotConfig {
+    fn default() -> Self {
+        Self {
+            width: 1600,
+            height: 900,
+            font_size: 20,
+            legend_position: LegendPosition::MiddleRight,
+        }
+    }
+}
+
+/// Plots the results to the given file. SVG is used if the file has `.svg` extension, otherwise PNG.
+pub(crate) fn plot_results(plot: &str, config: &PlotConfig, labels: Vec<String>, points: Vec<(f64, f64)>) {
+    let size = (config.width, config.height);
+    if plot.ends_with(".svg") {
+        draw_results(SVGBackend::new(plot, size).into_drawing_area(), config, labels, points);
+    } else {
+        draw_results(
+            BitMapBackend::new(plot, size).into_drawing_area(),
+            config,
+            labels,
+            points,
+        );
     }
+}
+
+/// Returns indices of the points whose wasted memory time is not finite or exceeds the median value
+/// by more than `OUTLIER_FACTOR` times (e.g. the policy that never unloads containers).
+///
+/// Such points would squeeze the rest of the plot, so they are drawn clamped to the top of the axis.
+pub(crate) fn find_outliers(points: &[(f64, f64)]) -> Vec<usize> {
+    let mut finite: Vec<f64> = points.iter().map(|p| p.1).filter(|x| x.is_finite()).collect();
+    finite.sort_by(f64::total_cmp);
+    // a zero median gives no scale to compare with, so only the infinite values are outliers then
+    let limit = finite
+        .get(finite.len() / 2)
+        .filter(|median| **median > 0.)
+        .map_or(f64::INFINITY, |median| median * OUTLIER_FACTOR);
+    points
+        .iter()
+        .enumerate()
+        .filter(|(_, p)| !p.1.is_finite() || p.1 > limit)
+        .map(|(i, _)| i)
+        .collect()
+}
+
+fn draw_results<DB: DrawingBackend>(
+    root_area: DrawingArea<DB, Shift>,
+    config: &PlotConfig,
+    labels: Vec<String>,
+    points: Vec<(f64, f64)>,
+) {
+    let outliers = find_outliers(&points);
+    let regular = || {
+        points
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !outliers.contains(i))
+            .map(|(_, p)| p)
+    };
     let max_cold_start = 1.01 * points.iter().fold(0., |acc, x| f64::max(x.0, acc));
-    let min_wasted_mem = points.iter().fold(f64::MAX, |acc, x| f64::min(x.1, acc)) - 1.;
-    let max_wasted_mem = points.iter().fold(0., |acc, x| f64::max(x.1, acc)) + 1.;
-    let root_area = BitMapBackend::new(plot, (1600, 900)).into_drawing_area();
+    let min_wasted_mem = regular().fold(f64::MAX, |acc, x| f64::min(x.1, acc)) - 1.;
+    let max_wasted_mem = regular().fold(0., |acc, x| f64::max(x.1, acc)) + 1.;
     root_area.fill(&WHITE).unwrap();
     let mut ctx = ChartBuilder::on(&root_area)
         .set_label_area_size(LabelAreaPosition::Left, 70)
@@ -19,12 +119,23 @@ pub(crate) fn plot_results(plot: &str, mut labels: Vec<String>, mut points: Vec<
     ctx.configure_mesh()
         .y_desc("normalized wasted memory time (%)")
         .x_desc("3rd quartile app cold start percentage (%)")
-        .label_style(("sans-serif", 20))
+        .label_style(("sans-serif", config.font_size))
         .draw()
         .unwrap();
     for i in 0..labels.len() {
-        if labels[i].contains("unloading") {
-            continue;
+        if outliers.contains(&i) {
+            eprintln!(
+                "warning: wasted memory time of policy \"{}\" ({:.1}%) is out of plot range, clamped to the axis",
+                labels[i], points[i].1
+            );
+            let style = Into::<ShapeStyle>::into(Palette99::pick(i)).stroke_width(3);
+            let annotation = format!("{}: {:.1}%", labels[i], points[i].1);
+            ctx.draw_series([EmptyElement::at((points[i].0, max_wasted_mem))
+                + Cross::new((0, 0), 8, style)
+                + Text::new(annotation, (12, 4), ("sans-serif", config.font_size))])
+                .unwrap()
+                .label(format!("{} (clamped)", labels[i]))
+                .legend(move |pos| Cross::new(pos, 8, style));
         } else if labels[i].contains("keepalive") {
             ctx.draw_series([TriangleMarker::new(points[i], 8, Palette99::pick(i))])
                 .unwrap()
@@ -42,9 +153,28 @@ pub(crate) fn plot_results(plot: &str, mut labels: Vec<String>, mut points: Vec<
         }
     }
     ctx.configure_series_labels()
-        .label_font(("sans-serif", 20))
+        .position(config.legend_position.into())
+        .label_font(("sans-serif", config.font_size))
         .border_style(BLACK)
         .background_style(WHITE.mix(0.8))
         .draw()
         .unwrap();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::find_outliers;
+
+    #[test]
+    fn test_find_outliers() {
+        let points = vec![(10., 100.), (5., 5000.), (8., 120.), (12., 90.), (1., f64::INFINITY)];
+        assert_eq!(find_outliers(&points), vec![1, 4]);
+        assert!(find_outliers(&points[..1]).is_empty());
+        assert!(find_outliers(&[]).is_empty());
+        // policies wasting no memory at all do not make the others outliers
+        assert_eq!(
+            find_outliers(&[(10., 0.), (15., 0.), (5., 3.), (1., f64::INFINITY)]),
+            vec![3]
+        );
+    }
+}
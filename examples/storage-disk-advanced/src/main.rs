@@ -120,13 +120,13 @@ impl DiskClient {
 impl EventHandler for DiskClient {
     fn on(&mut self, event: Event) {
         cast!(match event.data {
-            DataReadCompleted { request_id, size: _ } => {
+            DataReadCompleted { request_id, .. } => {
                 log_info!(self.ctx, "Read {} completed", request_id - 10);
             }
             DataReadFailed { request_id, error } => {
                 log_error!(self.ctx, "Read {} failed: {}", request_id - 10, error);
             }
-            DataWriteCompleted { request_id, size: _ } => {
+            DataWriteCompleted { request_id, .. } => {
                 log_info!(self.ctx, "Write {} completed", request_id,);
             }
             DataWriteFailed { request_id, error } => {
@@ -140,7 +140,7 @@ impl Runner {
 impl EventHandler for Runner {
     fn on(&mut self, event: Event) {
         cast!(match event.data {
-            DataReadCompleted { request_id, size } => {
+            DataReadCompleted { request_id, size, .. } => {
                 self.requests_count -= 1;
                 let (start_time, request_idx) = self.request_start_times.get(&(event.src, request_id)).unwrap();
                 log_info!(
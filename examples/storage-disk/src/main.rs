@@ -155,7 +155,7 @@ impl EventHandler for DiskClient {
                 }
                 self.ctx.emit_self(Step {}, 1.);
             }
-            DataReadCompleted { request_id, size } => {
+            DataReadCompleted { request_id, size, .. } => {
                 log_info!(
                     self.ctx,
                     "Step {}: Completed reading {} bytes from disk",
@@ -172,7 +172,7 @@ impl EventHandler for DiskClient {
                     error
                 );
             }
-            DataWriteCompleted { request_id, size } => {
+            DataWriteCompleted { request_id, size, .. } => {
                 log_info!(
                     self.ctx,
                     "Step {}: Completed writing {} bytes to disk",